@@ -184,7 +184,6 @@
 #![stable(feature = "rust1", since = "1.0.0")]
 
 use core::borrow::{Borrow, BorrowMut};
-#[cfg(not(no_global_oom_handling))]
 use core::clone::CloneToUninit;
 use core::cmp::Ordering;
 use core::error::{self, Error};
@@ -1716,6 +1715,33 @@ fn default() -> Self {
     }
 }
 
+impl<T: Clone, A: Allocator + Clone> Box<T, A> {
+    /// Returns a new box with a `clone()` of this box's contents, or an error
+    /// if the allocation fails.
+    ///
+    /// This is the fallible version of [`clone`](Clone::clone).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(fallible_collections)]
+    /// let x = Box::new(5);
+    /// let y = x.try_clone().expect("out of memory");
+    ///
+    /// assert_eq!(x, y);
+    /// ```
+    #[unstable(feature = "fallible_collections", issue = "48043")]
+    #[inline]
+    pub fn try_clone(&self) -> Result<Self, AllocError> {
+        // Pre-allocate memory to allow writing the cloned value directly.
+        let mut boxed = Self::try_new_uninit_in(self.1.clone())?;
+        unsafe {
+            (**self).clone_to_uninit(boxed.as_mut_ptr().cast());
+            Ok(boxed.assume_init())
+        }
+    }
+}
+
 #[cfg(not(no_global_oom_handling))]
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<T: Clone, A: Allocator + Clone> Clone for Box<T, A> {
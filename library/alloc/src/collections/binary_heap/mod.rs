@@ -689,6 +689,45 @@ pub fn push(&mut self, item: T) {
         unsafe { self.sift_up(0, old_len) };
     }
 
+    /// Pushes an item onto the binary heap, or returns an error if the
+    /// allocation needed to make room for it fails.
+    ///
+    /// This is the fallible version of [`push`]. On error, the heap is
+    /// unchanged and `item` is dropped.
+    ///
+    /// [`push`]: BinaryHeap::push
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(fallible_collections)]
+    /// use std::collections::BinaryHeap;
+    ///
+    /// let mut heap = BinaryHeap::new();
+    /// heap.try_push(3).expect("out of memory");
+    /// heap.try_push(5).expect("out of memory");
+    ///
+    /// assert_eq!(heap.peek(), Some(&5));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// The same as for [`push`].
+    #[unstable(feature = "fallible_collections", issue = "48043")]
+    pub fn try_push(&mut self, item: T) -> Result<(), TryReserveError> {
+        let old_len = self.len();
+        self.data.try_push(item)?;
+        // SAFETY: Since we pushed a new item it means that
+        //  old_len = self.len() - 1 < self.len()
+        unsafe { self.sift_up(0, old_len) };
+        Ok(())
+    }
+
     /// Consumes the `BinaryHeap` and returns a vector in sorted
     /// (ascending) order.
     ///
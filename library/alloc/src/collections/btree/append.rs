@@ -91,6 +91,8 @@ pub(super) fn bulk_push<I, A: Allocator + Clone>(
             // the appended elements even if advancing the iterator panicks.
             *length += 1;
         }
+        // Pushing doesn't bother to update subtree lengths along the way.
+        self.borrow_mut().recalc_all_subtree_lens();
         self.fix_right_border_of_plentiful();
     }
 }
@@ -18,6 +18,7 @@
 use super::search::SearchResult::*;
 use super::set_val::SetValZST;
 use crate::alloc::{Allocator, Global};
+use crate::collections::{TryFromIteratorIn, TryReserveError};
 use crate::vec::Vec;
 
 mod entry;
@@ -770,6 +771,63 @@ pub fn get_key_value<Q: ?Sized>(&self, k: &Q) -> Option<(&K, &V)>
         }
     }
 
+    /// Returns the key-value pair at the given position in the map, counting
+    /// from zero in ascending order of keys, or `None` if `index` is out of bounds.
+    ///
+    /// This takes time logarithmic in the size of the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_order_statistics)]
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// map.insert(3, "c");
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    /// assert_eq!(map.get_index(0), Some((&1, &"a")));
+    /// assert_eq!(map.get_index(2), Some((&3, &"c")));
+    /// assert_eq!(map.get_index(3), None);
+    /// ```
+    #[unstable(feature = "btree_order_statistics", issue = "none")]
+    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        let root_node = self.root.as_ref()?.reborrow();
+        root_node.find_kv_at_index(index).map(Handle::into_kv)
+    }
+
+    /// Returns the number of keys in the map that are less than the given key,
+    /// which is the position the key has or would have in the map.
+    ///
+    /// The key may be any borrowed form of the map's key type, but the ordering
+    /// on the borrowed form *must* match the ordering on the key type.
+    ///
+    /// This takes time logarithmic in the size of the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_order_statistics)]
+    /// use std::collections::BTreeMap;
+    ///
+    /// let map = BTreeMap::from([(10, "a"), (20, "b"), (30, "c")]);
+    /// assert_eq!(map.rank(&5), 0);
+    /// assert_eq!(map.rank(&20), 1);
+    /// assert_eq!(map.rank(&25), 2);
+    /// assert_eq!(map.rank(&35), 3);
+    /// ```
+    #[unstable(feature = "btree_order_statistics", issue = "none")]
+    pub fn rank<Q: ?Sized>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q> + Ord,
+        Q: Ord,
+    {
+        match &self.root {
+            Some(root) => root.reborrow().rank(key),
+            None => 0,
+        }
+    }
+
     /// Returns the first key-value pair in the map.
     /// The key in this pair is the minimum key in the map.
     ///
@@ -1033,6 +1091,37 @@ pub fn insert(&mut self, key: K, value: V) -> Option<V>
         }
     }
 
+    /// Inserts a key-value pair into the map, or returns an error if the
+    /// allocation of the nodes needed to make room for it fails.
+    ///
+    /// This is the fallible version of [`insert`]: if the map did have this key
+    /// present, the value is updated, and the old value is returned. On error,
+    /// the map is unchanged and the key and `value` are dropped.
+    ///
+    /// [`insert`]: BTreeMap::insert
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(fallible_collections)]
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// assert_eq!(map.try_insert_or_replace(37, "a").expect("out of memory"), None);
+    /// assert_eq!(map.try_insert_or_replace(37, "b").expect("out of memory"), Some("a"));
+    /// assert_eq!(map[&37], "b");
+    /// ```
+    #[unstable(feature = "fallible_collections", issue = "48043")]
+    pub fn try_insert_or_replace(&mut self, key: K, value: V) -> Result<Option<V>, TryReserveError>
+    where
+        K: Ord,
+    {
+        match self.entry(key) {
+            Occupied(mut entry) => Ok(Some(entry.insert(value))),
+            Vacant(entry) => entry.try_insert(value).map(|_| None),
+        }
+    }
+
     /// Tries to insert a key-value pair into the map, and returns
     /// a mutable reference to the value in the entry.
     ///
@@ -1294,6 +1383,45 @@ pub fn range_mut<T: ?Sized, R>(&mut self, range: R) -> RangeMut<'_, K, V>
         }
     }
 
+    /// Returns the number of elements in the map within the specified range,
+    /// accepting the same ranges as [`range`].
+    ///
+    /// This takes time logarithmic in the size of the map, rather than in the
+    /// number of elements in the range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_order_statistics)]
+    /// use std::collections::BTreeMap;
+    /// use std::ops::Bound::{Excluded, Included};
+    ///
+    /// let map: BTreeMap<i32, ()> = (0..100).map(|i| (i * 2, ())).collect();
+    /// assert_eq!(map.range_len(10..20), 5);
+    /// assert_eq!(map.range_len((Excluded(10), Included(20))), 5);
+    /// assert_eq!(map.range_len(..), 100);
+    /// assert_eq!(map.range_len(1000..), 0);
+    /// ```
+    ///
+    /// [`range`]: BTreeMap::range
+    #[unstable(feature = "btree_order_statistics", issue = "none")]
+    pub fn range_len<T: ?Sized, R>(&self, range: R) -> usize
+    where
+        T: Ord,
+        K: Borrow<T> + Ord,
+        R: RangeBounds<T>,
+    {
+        match &self.root {
+            Some(root) => root.reborrow().range_len(&range),
+            None => 0,
+        }
+    }
+
     /// Gets the given key's corresponding entry in the map for in-place manipulation.
     ///
     /// # Examples
@@ -1397,6 +1525,58 @@ pub fn split_off<Q: ?Sized + Ord>(&mut self, key: &Q) -> Self
         }
     }
 
+    /// Splits the collection into two at the given position. Returns everything
+    /// from the `at`-th element onwards, counting from zero in ascending order of
+    /// keys, and leaves the first `at` elements in `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_order_statistics)]
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut a = BTreeMap::from([(1, "a"), (2, "b"), (3, "c"), (17, "d"), (41, "e")]);
+    ///
+    /// let b = a.split_off_at_index(2);
+    ///
+    /// assert_eq!(a.len(), 2);
+    /// assert_eq!(b.len(), 3);
+    ///
+    /// assert_eq!(a[&1], "a");
+    /// assert_eq!(a[&2], "b");
+    ///
+    /// assert_eq!(b[&3], "c");
+    /// assert_eq!(b[&17], "d");
+    /// assert_eq!(b[&41], "e");
+    /// ```
+    #[unstable(feature = "btree_order_statistics", issue = "none")]
+    pub fn split_off_at_index(&mut self, at: usize) -> Self
+    where
+        A: Clone,
+    {
+        let total_num = self.len();
+        assert!(at <= total_num, "`at` split index (is {at}) should be <= len (is {total_num})");
+        if at == total_num {
+            return Self::new_in((*self.alloc).clone());
+        }
+
+        let left_root = self.root.as_mut().unwrap(); // unwrap succeeds because not empty
+
+        let right_root = left_root.split_off_at_index(at, (*self.alloc).clone());
+        self.length = at;
+
+        BTreeMap {
+            root: Some(right_root),
+            length: total_num - at,
+            alloc: self.alloc.clone(),
+            _marker: PhantomData,
+        }
+    }
+
     /// Creates an iterator that visits elements (key-value pairs) in the specified range in
     /// ascending key order and uses a closure to determine if an element
     /// should be removed.
@@ -2356,6 +2536,20 @@ fn extend_one(&mut self, (k, v): (K, V)) {
     }
 }
 
+#[unstable(feature = "fallible_collections", issue = "48043")]
+impl<K: Ord, V, A: Allocator + Clone> TryFromIteratorIn<(K, V), A> for BTreeMap<K, V, A> {
+    fn try_from_iter_in<I: IntoIterator<Item = (K, V)>>(
+        iter: I,
+        alloc: A,
+    ) -> Result<Self, TryReserveError> {
+        let mut map = BTreeMap::new_in(alloc);
+        for (k, v) in iter {
+            map.try_insert_or_replace(k, v)?;
+        }
+        Ok(map)
+    }
+}
+
 #[stable(feature = "extend_ref", since = "1.2.0")]
 impl<'a, K: Ord + Copy, V: Copy, A: Allocator + Clone> Extend<(&'a K, &'a V)>
     for BTreeMap<K, V, A>
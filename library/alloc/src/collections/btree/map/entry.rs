@@ -5,9 +5,10 @@
 use Entry::*;
 
 use super::super::borrow::DormantMutRef;
-use super::super::node::{Handle, NodeRef, marker};
+use super::super::node::{Handle, NodeRef, SpareNodes, marker};
 use super::BTreeMap;
 use crate::alloc::{Allocator, Global};
+use crate::collections::TryReserveError;
 
 /// A view into a single entry in a map, which may either be vacant or occupied.
 ///
@@ -396,12 +397,57 @@ pub fn insert(self, value: V) -> &'a mut V {
     /// assert_eq!(map["poneyland"], 37);
     /// ```
     #[unstable(feature = "btree_entry_insert", issue = "65225")]
-    pub fn insert_entry(mut self, value: V) -> OccupiedEntry<'a, K, V, A> {
+    pub fn insert_entry(self, value: V) -> OccupiedEntry<'a, K, V, A> {
+        let alloc = self.alloc.clone();
+        self.insert_entry_with(value, alloc)
+    }
+
+    /// Sets the value of the entry with the `VacantEntry`'s key, and returns a
+    /// mutable reference to it, or returns an error if the allocation of the
+    /// nodes needed to make room for it fails.
+    ///
+    /// This is the fallible version of [`insert`]. On error, the map is
+    /// unchanged and the key and `value` are dropped.
+    ///
+    /// [`insert`]: VacantEntry::insert
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(fallible_collections)]
+    /// use std::collections::BTreeMap;
+    /// use std::collections::btree_map::Entry;
+    ///
+    /// let mut map: BTreeMap<&str, u32> = BTreeMap::new();
+    ///
+    /// if let Entry::Vacant(o) = map.entry("poneyland") {
+    ///     o.try_insert(37).expect("out of memory");
+    /// }
+    /// assert_eq!(map["poneyland"], 37);
+    /// ```
+    #[unstable(feature = "fallible_collections", issue = "48043")]
+    pub fn try_insert(self, value: V) -> Result<&'a mut V, TryReserveError> {
+        // Allocate every node the insertion may need up front, so that it
+        // cannot fail once it has started changing the tree.
+        let (leaves, internals) = match &self.handle {
+            None => (1, 0),
+            Some(handle) => handle.reborrow().split_node_counts(),
+        };
+        let spare = SpareNodes::<K, V, A>::try_new(self.alloc.clone(), leaves, internals)?;
+        Ok(self.insert_entry_with(value, &spare).into_mut())
+    }
+
+    /// Inserts the entry, allocating any new nodes from `node_alloc`.
+    fn insert_entry_with<N: Allocator + Clone>(
+        mut self,
+        value: V,
+        node_alloc: N,
+    ) -> OccupiedEntry<'a, K, V, A> {
         let handle = match self.handle {
             None => {
                 // SAFETY: There is no tree yet so no reference to it exists.
                 let map = unsafe { self.dormant_map.reborrow() };
-                let root = map.root.insert(NodeRef::new_leaf(self.alloc.clone()).forget_type());
+                let root = map.root.insert(NodeRef::new_leaf(node_alloc).forget_type());
                 // SAFETY: We *just* created the root as a leaf, and we're
                 // stacking the new handle on the original borrow lifetime.
                 unsafe {
@@ -409,13 +455,13 @@ pub fn insert_entry(mut self, value: V) -> OccupiedEntry<'a, K, V, A> {
                     leaf.push_with_handle(self.key, value)
                 }
             }
-            Some(handle) => handle.insert_recursing(self.key, value, self.alloc.clone(), |ins| {
+            Some(handle) => handle.insert_recursing(self.key, value, node_alloc.clone(), |ins| {
                 drop(ins.left);
                 // SAFETY: Pushing a new root node doesn't invalidate
                 // handles to existing nodes.
                 let map = unsafe { self.dormant_map.reborrow() };
                 let root = map.root.as_mut().unwrap(); // same as ins.left
-                root.push_internal_level(self.alloc.clone()).push(ins.kv.0, ins.kv.1, ins.right)
+                root.push_internal_level(node_alloc).push(ins.kv.0, ins.kv.1, ins.right)
             }),
         };
 
@@ -1,11 +1,14 @@
 use core::assert_matches::assert_matches;
+use std::cell::Cell;
 use std::iter;
 use std::ops::Bound::{Excluded, Included, Unbounded};
 use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::ptr::NonNull;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering::SeqCst;
 
 use super::*;
+use crate::alloc::{AllocError, Layout};
 use crate::boxed::Box;
 use crate::fmt::Debug;
 use crate::rc::Rc;
@@ -38,7 +41,7 @@ fn test_all_refs<'a, T: 'a>(dummy: &mut T, iter: impl Iterator<Item = &'a mut T>
     }
 }
 
-impl<K, V> BTreeMap<K, V> {
+impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     // Panics if the map (or the code navigating it) is corrupted.
     fn check_invariants(&self) {
         if let Some(root) = &self.root {
@@ -49,8 +52,10 @@ fn check_invariants(&self) {
             assert!(root_node.ascend().is_err());
             root_node.assert_back_pointers();
 
-            // Check consistency of `length` with what navigation code encounters.
+            // Check consistency of `length` with what navigation code encounters,
+            // and with the subtree lengths recorded in the nodes.
             assert_eq!(self.length, root_node.calc_length());
+            assert_eq!(self.length, root_node.assert_subtree_lens());
 
             // Lastly, check the invariant causing the least harm.
             root_node.assert_min_len(if root_node.height() > 0 { 1 } else { 0 });
@@ -79,17 +84,6 @@ fn height(&self) -> Option<usize> {
         self.root.as_ref().map(node::Root::height)
     }
 
-    fn dump_keys(&self) -> String
-    where
-        K: Debug,
-    {
-        if let Some(root) = self.root.as_ref() {
-            root.reborrow().dump_keys()
-        } else {
-            String::from("not yet allocated")
-        }
-    }
-
     // Panics if the keys are not in strictly ascending order.
     fn assert_strictly_ascending(&self)
     where
@@ -103,6 +97,19 @@ fn assert_strictly_ascending(&self)
             }
         }
     }
+}
+
+impl<K, V> BTreeMap<K, V> {
+    fn dump_keys(&self) -> String
+    where
+        K: Debug,
+    {
+        if let Some(root) = self.root.as_ref() {
+            root.reborrow().dump_keys()
+        } else {
+            String::from("not yet allocated")
+        }
+    }
 
     // Transform the tree to minimize wasted space, obtaining fewer nodes that
     // are mostly filled up to their capacity. The same compact tree could have
@@ -2072,6 +2079,51 @@ fn test_try_insert() {
     assert_eq!(err.value, 200);
 }
 
+#[test]
+fn test_try_insert_or_replace() {
+    // Allows a limited number of allocations.
+    struct Limited(Cell<usize>);
+    unsafe impl Allocator for Limited {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            let left = self.0.get().checked_sub(1).ok_or(AllocError)?;
+            self.0.set(left);
+            Global.allocate(layout)
+        }
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            unsafe { Global.deallocate(ptr, layout) }
+        }
+    }
+
+    let alloc = Limited(Cell::new(0));
+    let mut map = BTreeMap::new_in(&alloc);
+    assert!(map.try_insert_or_replace(0, 0).is_err());
+    assert!(map.is_empty());
+
+    // Enough for the root leaf and for splitting it once, which also needs a
+    // new root. The map fills up until the next split.
+    alloc.0.set(3);
+    let mut len = 0;
+    while map.try_insert_or_replace(len, len).is_ok() {
+        len += 1;
+    }
+    assert!(len > node::CAPACITY);
+    assert_eq!(alloc.0.get(), 0);
+    assert_eq!(map.len(), len);
+    assert_eq!(map.height(), Some(1));
+    map.check();
+
+    // The failed insertion left no trace, and replacing a value doesn't allocate.
+    assert!(!map.contains_key(&len));
+    assert_eq!(map.try_insert_or_replace(0, 100), Ok(Some(0)));
+
+    alloc.0.set(usize::MAX);
+    for i in len..MIN_INSERTS_HEIGHT_2 {
+        assert_eq!(map.try_insert_or_replace(i, i), Ok(None));
+    }
+    assert_eq!(map.height(), Some(2));
+    map.check();
+}
+
 macro_rules! create_append_test {
     ($name:ident, $len:expr) => {
         #[test]
@@ -2270,6 +2322,101 @@ fn test_split_off_large_random_sorted() {
     assert!(right.into_iter().eq(data.into_iter().filter(|x| x.0 >= key)));
 }
 
+#[test]
+fn test_split_off_at_index() {
+    let mut rng = DeterministicRng::new();
+    for &len in &[0, 1, node::CAPACITY, MIN_INSERTS_HEIGHT_1, 75, MIN_INSERTS_HEIGHT_2, 300] {
+        let data = Vec::from_iter((0..len).map(|_| (rng.next(), ())));
+        let mut sorted = data.clone();
+        sorted.sort();
+        sorted.dedup();
+        let len = sorted.len();
+        for at in [0, 1, len / 3, len / 2, len.saturating_sub(1), len] {
+            if at > len {
+                continue;
+            }
+            let mut map = BTreeMap::from_iter(data.iter().copied());
+            let right = map.split_off_at_index(at);
+            map.check();
+            right.check();
+            assert_eq!(map.len(), at);
+            assert!(map.into_iter().eq(sorted[..at].iter().copied()));
+            assert!(right.into_iter().eq(sorted[at..].iter().copied()));
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "`at` split index (is 4) should be <= len (is 3)")]
+fn test_split_off_at_index_out_of_bounds() {
+    let mut map = BTreeMap::from([(1, ()), (2, ()), (3, ())]);
+    let _ = map.split_off_at_index(4);
+}
+
+#[test]
+fn test_get_index_and_rank() {
+    let mut rng = DeterministicRng::new();
+    let mut map = BTreeMap::new();
+    assert_eq!(map.get_index(0), None);
+    assert_eq!(map.rank(&0), 0);
+    for _ in 0..500 {
+        let key = rng.next() % 1000 * 2;
+        map.insert(key, key + 1);
+    }
+    map.check();
+    for (index, (key, value)) in map.iter().enumerate() {
+        assert_eq!(map.get_index(index), Some((key, value)));
+        assert_eq!(map.rank(key), index);
+        assert_eq!(map.rank(&(key + 1)), index + 1);
+    }
+    assert_eq!(map.get_index(map.len()), None);
+    assert_eq!(map.rank(&u32::MAX), map.len());
+}
+
+#[test]
+fn test_rank_after_removal() {
+    let mut map = BTreeMap::from_iter((0..MIN_INSERTS_HEIGHT_2).map(|i| (i, ())));
+    let mut expected = Vec::from_iter(0..MIN_INSERTS_HEIGHT_2);
+    while let Some(key) = expected.get(expected.len() / 3).copied() {
+        map.remove(&key);
+        expected.retain(|&k| k != key);
+        map.check();
+        for (index, key) in expected.iter().enumerate() {
+            assert_eq!(map.get_index(index), Some((key, &())));
+            assert_eq!(map.rank(key), index);
+        }
+    }
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_range_len() {
+    let size = 200;
+    let map = BTreeMap::from_iter((0..size).map(|i| (i * 2, ())));
+    map.check();
+    for i in (-2..=size * 2 + 2).step_by(3) {
+        for j in (i..=size * 2 + 2).step_by(5) {
+            assert_eq!(map.range_len(i..j), map.range(i..j).count());
+            assert_eq!(map.range_len(i..=j), map.range(i..=j).count());
+            assert_eq!(
+                map.range_len((Excluded(i), Included(j))),
+                map.range((Excluded(i), Included(j))).count()
+            );
+        }
+        assert_eq!(map.range_len(i..), map.range(i..).count());
+        assert_eq!(map.range_len(..i), map.range(..i).count());
+    }
+    assert_eq!(map.range_len::<i32, _>(..), map.len());
+    assert_eq!(BTreeMap::<i32, ()>::new().range_len(1..5), 0);
+}
+
+#[test]
+#[should_panic(expected = "range start is greater than range end in BTreeMap")]
+fn test_range_len_backwards() {
+    let map = BTreeMap::from_iter((0..5).map(|i| (i, i)));
+    let _ = map.range_len((Included(3), Included(2)));
+}
+
 #[test]
 #[cfg_attr(not(panic = "unwind"), ignore = "test requires unwinding support")]
 fn test_into_iter_drop_leak_height_0() {
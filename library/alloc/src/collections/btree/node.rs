@@ -31,13 +31,15 @@
 //   since leaf edges are empty and need no data representation. In an internal node,
 //   an edge both identifies a position and contains a pointer to a child node.
 
+use core::cell::Cell;
 use core::marker::PhantomData;
 use core::mem::{self, MaybeUninit};
 use core::ptr::{self, NonNull};
 use core::slice::SliceIndex;
 
-use crate::alloc::{Allocator, Layout};
+use crate::alloc::{AllocError, Allocator, Layout};
 use crate::boxed::Box;
+use crate::collections::{TryReserveError, TryReserveErrorKind};
 
 const B: usize = 6;
 pub(super) const CAPACITY: usize = 2 * B - 1;
@@ -97,6 +99,11 @@ fn new<A: Allocator + Clone>(alloc: A) -> Box<Self, A> {
 struct InternalNode<K, V> {
     data: LeafNode<K, V>,
 
+    /// The number of key-value pairs stored in this node and all of its
+    /// descendants. Kept exact by every operation that changes the shape of
+    /// the tree, except while such an operation is in progress.
+    subtree_len: usize,
+
     /// The pointers to the children of this node. `len + 1` of these are considered
     /// initialized and valid, except that near the end, while the tree is held
     /// through borrow type `Dying`, some of these pointers are dangling.
@@ -113,8 +120,10 @@ impl<K, V> InternalNode<K, V> {
     unsafe fn new<A: Allocator + Clone>(alloc: A) -> Box<Self, A> {
         unsafe {
             let mut node = Box::<Self, _>::new_uninit_in(alloc);
-            // We only need to initialize the data; the edges are MaybeUninit.
+            // We only need to initialize the data and the subtree length;
+            // the edges are MaybeUninit.
             LeafNode::init(&raw mut (*node.as_mut_ptr()).data);
+            (&raw mut (*node.as_mut_ptr()).subtree_len).write(0);
             node.assume_init()
         }
     }
@@ -225,6 +234,7 @@ fn from_new_leaf<A: Allocator + Clone>(leaf: Box<LeafNode<K, V>, A>) -> Self {
 impl<K, V> NodeRef<marker::Owned, K, V, marker::Internal> {
     fn new_internal<A: Allocator + Clone>(child: Root<K, V>, alloc: A) -> Self {
         let mut new_node = unsafe { InternalNode::new(alloc) };
+        new_node.subtree_len = child.subtree_len();
         new_node.edges[0].write(child.node);
         unsafe { NodeRef::from_new_internal(new_node, child.height + 1) }
     }
@@ -289,6 +299,18 @@ pub(super) fn height(&self) -> usize {
         self.height
     }
 
+    /// Returns the number of key-value pairs in the node and all of its
+    /// descendants.
+    pub(super) fn subtree_len(&self) -> usize {
+        if self.height == 0 {
+            self.len()
+        } else {
+            // SAFETY: a node with a non-zero height is an internal node. We only
+            // access the `subtree_len` field, for the same reason as in `len`.
+            unsafe { (*(Self::as_leaf_ptr(self) as *const InternalNode<K, V>)).subtree_len }
+        }
+    }
+
     /// Temporarily takes out another, immutable reference to the same node.
     pub(super) fn reborrow(&self) -> NodeRef<marker::Immut<'_>, K, V, Type> {
         NodeRef { height: self.height, node: self.node, _marker: PhantomData }
@@ -555,6 +577,58 @@ fn correct_all_childrens_parent_links(&mut self) {
         let len = self.len();
         unsafe { self.correct_childrens_parent_links(0..=len) };
     }
+
+    /// Recalculates the subtree length of the node from its own length and
+    /// the subtree lengths of its children.
+    pub(super) fn recalc_subtree_len(&mut self) {
+        let len = self.len();
+        let mut subtree_len = len;
+        for idx in 0..=len {
+            subtree_len +=
+                unsafe { Handle::new_edge(self.reborrow(), idx) }.descend().subtree_len();
+        }
+        // Write through a raw pointer to avoid invalidating other references to the node.
+        unsafe { (*NodeRef::as_internal_ptr(self)).subtree_len = subtree_len };
+    }
+}
+
+impl<'a, K, V, Type> NodeRef<marker::Mut<'a>, K, V, Type> {
+    /// Adds `delta` to the subtree length of every ancestor of the node,
+    /// without invalidating other references to the node or its ancestors.
+    pub(super) fn adjust_ancestors_subtree_len(&mut self, delta: isize) {
+        let mut parent = unsafe { (*Self::as_leaf_ptr(self)).parent };
+        while let Some(node) = parent {
+            let node = node.as_ptr();
+            unsafe {
+                (*node).subtree_len = (*node).subtree_len.wrapping_add_signed(delta);
+                parent = (*node).data.parent;
+            }
+        }
+    }
+}
+
+impl<'a, K: 'a, V: 'a> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
+    /// Recalculates the subtree length of every ancestor of the node, bottom-up.
+    pub(super) fn recalc_ancestors_subtree_len(self) {
+        let mut node = self;
+        while let Ok(parent) = node.ascend() {
+            let mut parent = parent.into_node();
+            parent.recalc_subtree_len();
+            node = parent.forget_type();
+        }
+    }
+
+    /// Recalculates the subtree length of every internal node in the subtree
+    /// headed by the node, bottom-up.
+    pub(super) fn recalc_all_subtree_lens(self) {
+        if let ForceResult::Internal(mut internal) = self.force() {
+            for idx in 0..=internal.len() {
+                let edge = unsafe { Handle::new_edge(internal.reborrow_mut(), idx) };
+                edge.descend().recalc_all_subtree_lens();
+            }
+            internal.recalc_subtree_len();
+        }
+    }
 }
 
 impl<'a, K: 'a, V: 'a> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
@@ -694,6 +768,7 @@ pub(super) fn push(&mut self, key: K, val: V, edge: Root<K, V>) {
             self.edge_area_mut(idx + 1).write(edge.node);
             Handle::new_edge(self.reborrow_mut(), idx + 1).correct_parent_link();
         }
+        self.as_internal_mut().subtree_len += 1 + edge.subtree_len();
     }
 }
 
@@ -1007,7 +1082,8 @@ fn insert_fit(&mut self, key: K, val: V, edge: Root<K, V>) {
 
     /// Inserts a new key-value pair and an edge that will go to the right of that new pair
     /// between this edge and the key-value pair to the right of this edge. This method splits
-    /// the node if there isn't enough room.
+    /// the node if there isn't enough room, and then recalculates the subtree lengths of both
+    /// halves.
     fn insert<A: Allocator + Clone>(
         mut self,
         key: K,
@@ -1033,6 +1109,8 @@ fn insert<A: Allocator + Clone>(
                 },
             };
             insertion_edge.insert_fit(key, val, edge);
+            result.left.recalc_subtree_len();
+            result.right.borrow_mut().recalc_subtree_len();
             Some(result)
         }
     }
@@ -1046,13 +1124,17 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, mark
     /// If the returned result is some `SplitResult`, the `left` field will be the root node.
     /// The returned pointer points to the inserted value, which in the case of `SplitResult`
     /// is in the `left` or `right` tree.
+    ///
+    /// Ancestors that do not split merely grow by one key-value pair, so we account for that
+    /// up front, and let any splits recalculate the subtree lengths of the nodes they create.
     pub(super) fn insert_recursing<A: Allocator + Clone>(
-        self,
+        mut self,
         key: K,
         value: V,
         alloc: A,
         split_root: impl FnOnce(SplitResult<'a, K, V, marker::LeafOrInternal>),
     ) -> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::KV> {
+        self.node.adjust_ancestors_subtree_len(1);
         let (mut split, handle) = match self.insert(key, value, alloc.clone()) {
             // SAFETY: we have finished splitting and can now re-awaken the
             // handle to the inserted element.
@@ -1081,6 +1163,120 @@ pub(super) fn insert_recursing<A: Allocator + Clone>(
     }
 }
 
+impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Immut<'a>, K, V, marker::Leaf>, marker::Edge> {
+    /// Counts the leaf and internal nodes that `insert_recursing` allocates when inserting
+    /// at this edge: one leaf if the leaf is full, plus one internal node for each full
+    /// ancestor, plus one for a new root if the splits reach the root.
+    pub(super) fn split_node_counts(&self) -> (usize, usize) {
+        let mut node = self.node.forget_type();
+        if node.len() < CAPACITY {
+            return (0, 0);
+        }
+        let mut internals = 0;
+        loop {
+            match node.ascend() {
+                Ok(parent) if parent.into_node().len() < CAPACITY => {
+                    return (1, internals);
+                }
+                Ok(parent) => {
+                    internals += 1;
+                    node = parent.into_node().forget_type();
+                }
+                Err(_) => return (1, internals + 1),
+            }
+        }
+    }
+}
+
+/// Nodes allocated ahead of an insertion, so that splitting nodes on the way up
+/// to the root cannot fail halfway through.
+///
+/// A reference to it is an `Allocator` that hands out these nodes, which lets the
+/// regular insertion code run on top of it unchanged. The nodes themselves come
+/// from `alloc`, so the tree can later free them like any other node.
+pub(super) struct SpareNodes<K, V, A: Allocator> {
+    alloc: A,
+    // Singly linked lists threaded through the first word of each unused node.
+    leaves: Cell<Option<NonNull<u8>>>,
+    internals: Cell<Option<NonNull<u8>>>,
+    _marker: PhantomData<(K, V)>,
+}
+
+impl<K, V, A: Allocator> SpareNodes<K, V, A> {
+    const LEAF: Layout = Layout::new::<LeafNode<K, V>>();
+    const INTERNAL: Layout = Layout::new::<InternalNode<K, V>>();
+
+    /// Allocates `leaves` leaf nodes and `internals` internal nodes from `alloc`.
+    pub(super) fn try_new(
+        alloc: A,
+        leaves: usize,
+        internals: usize,
+    ) -> Result<Self, TryReserveError> {
+        let spare = SpareNodes {
+            alloc,
+            leaves: Cell::new(None),
+            internals: Cell::new(None),
+            _marker: PhantomData,
+        };
+        for (list, layout, count) in
+            [(&spare.leaves, Self::LEAF, leaves), (&spare.internals, Self::INTERNAL, internals)]
+        {
+            for _ in 0..count {
+                let node = spare
+                    .alloc
+                    .allocate(layout)
+                    .map_err(|_| TryReserveErrorKind::AllocError { layout, non_exhaustive: () })?
+                    .cast::<u8>();
+                // SAFETY: every node is at least as large and as aligned as a pointer,
+                // since it starts with the parent link.
+                unsafe { node.cast::<Option<NonNull<u8>>>().write(list.get()) };
+                list.set(Some(node));
+            }
+        }
+        Ok(spare)
+    }
+
+    fn list(&self, layout: Layout) -> Option<&Cell<Option<NonNull<u8>>>> {
+        if layout == Self::LEAF {
+            Some(&self.leaves)
+        } else if layout == Self::INTERNAL {
+            Some(&self.internals)
+        } else {
+            None
+        }
+    }
+}
+
+#[unstable(feature = "allocator_api", issue = "32838")]
+unsafe impl<K, V, A: Allocator> Allocator for &SpareNodes<K, V, A> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let list = self.list(layout).ok_or(AllocError)?;
+        let node = list.get().ok_or(AllocError)?;
+        // SAFETY: the node is on the list, so its first word holds the next one.
+        list.set(unsafe { node.cast::<Option<NonNull<u8>>>().read() });
+        Ok(NonNull::slice_from_raw_parts(node, layout.size()))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { self.alloc.deallocate(ptr, layout) }
+    }
+}
+
+impl<K, V, A: Allocator> Drop for SpareNodes<K, V, A> {
+    fn drop(&mut self) {
+        for (list, layout) in [(&self.leaves, Self::LEAF), (&self.internals, Self::INTERNAL)] {
+            while let Some(node) = list.get() {
+                // SAFETY: the node is on the list, so it is unused and was allocated
+                // from `self.alloc` with `layout`.
+                unsafe {
+                    list.set(node.cast::<Option<NonNull<u8>>>().read());
+                    self.alloc.deallocate(node, layout);
+                }
+            }
+        }
+    }
+}
+
 impl<BorrowType: marker::BorrowType, K, V>
     Handle<NodeRef<BorrowType, K, V, marker::Internal>, marker::Edge>
 {
@@ -1435,6 +1631,7 @@ fn do_merge<
                 );
 
                 left_node.correct_childrens_parent_links(old_left_len + 1..new_left_len + 1);
+                left_node.recalc_subtree_len();
 
                 alloc.deallocate(right_node.node.cast(), Layout::new::<InternalNode<K, V>>());
             } else {
@@ -1570,6 +1767,8 @@ pub(super) fn bulk_steal_left(&mut self, count: usize) {
                     );
 
                     right.correct_childrens_parent_links(0..new_right_len + 1);
+                    left.recalc_subtree_len();
+                    right.recalc_subtree_len();
                 }
                 (ForceResult::Leaf(_), ForceResult::Leaf(_)) => {}
                 _ => unreachable!(),
@@ -1634,6 +1833,8 @@ pub(super) fn bulk_steal_right(&mut self, count: usize) {
 
                     left.correct_childrens_parent_links(old_left_len + 1..new_left_len + 1);
                     right.correct_childrens_parent_links(0..new_right_len + 1);
+                    left.recalc_subtree_len();
+                    right.recalc_subtree_len();
                 }
                 (ForceResult::Leaf(_), ForceResult::Leaf(_)) => {}
                 _ => unreachable!(),
@@ -1697,7 +1898,8 @@ pub(super) unsafe fn cast_to_leaf_unchecked(
 
 impl<'a, K, V> Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>, marker::Edge> {
     /// Move the suffix after `self` from one node to another one. `right` must be empty.
-    /// The first edge of `right` remains unchanged.
+    /// The first edge of `right` remains unchanged. The subtree lengths of both nodes
+    /// and of their ancestors are left for the caller to recalculate.
     pub(super) fn move_suffix(
         &mut self,
         right: &mut NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>,
@@ -17,6 +17,21 @@ pub(crate) fn assert_back_pointers(self) {
         }
     }
 
+    // Asserts that the subtree length recorded in each reachable internal node
+    // matches the number of key-value pairs actually below it, and returns the
+    // number of key-value pairs in the subtree.
+    pub(crate) fn assert_subtree_lens(self) -> usize {
+        let mut subtree_len = self.len();
+        if let ForceResult::Internal(node) = self.force() {
+            for idx in 0..=node.len() {
+                let edge = unsafe { Handle::new_edge(node, idx) };
+                subtree_len += edge.descend().assert_subtree_lens();
+            }
+        }
+        assert_eq!(self.subtree_len(), subtree_len);
+        subtree_len
+    }
+
     // Renders a multi-line display of the keys in order and in tree hierarchy,
     // picturing the tree growing sideways from its root on the left to its
     // leaves on the right.
@@ -94,6 +109,6 @@ fn test_partial_eq() {
 fn test_sizes() {
     assert_eq!(size_of::<LeafNode<(), ()>>(), 16);
     assert_eq!(size_of::<LeafNode<i64, i64>>(), 16 + CAPACITY * 2 * 8);
-    assert_eq!(size_of::<InternalNode<(), ()>>(), 16 + (CAPACITY + 1) * 8);
-    assert_eq!(size_of::<InternalNode<i64, i64>>(), 16 + (CAPACITY * 3 + 1) * 8);
+    assert_eq!(size_of::<InternalNode<(), ()>>(), 16 + 8 + (CAPACITY + 1) * 8);
+    assert_eq!(size_of::<InternalNode<i64, i64>>(), 16 + 8 + (CAPACITY * 3 + 1) * 8);
 }
@@ -0,0 +1,134 @@
+use core::borrow::Borrow;
+use core::ops::RangeBounds;
+
+use super::node::ForceResult::*;
+use super::node::{Handle, NodeRef, marker};
+use super::search::{SearchBound, check_range_bounds};
+
+impl<BorrowType: marker::BorrowType, K, V> NodeRef<BorrowType, K, V, marker::LeafOrInternal> {
+    /// Returns the number of key-value pairs in the subtree below the edge at
+    /// `edge_idx`, which is zero if the node is a leaf.
+    fn edge_subtree_len(&self, edge_idx: usize) -> usize {
+        match self.reborrow().force() {
+            Leaf(_) => 0,
+            Internal(node) => unsafe { Handle::new_edge(node, edge_idx) }.descend().subtree_len(),
+        }
+    }
+
+    /// Returns the number of key-value pairs in the subtree headed by the node
+    /// that lie to the left of the edge at `edge_idx`.
+    fn subtree_len_before_edge(&self, edge_idx: usize) -> usize {
+        edge_idx + (0..edge_idx).map(|idx| self.edge_subtree_len(idx)).sum::<usize>()
+    }
+
+    /// Given the position of a key-value pair in the subtree headed by the node,
+    /// finds the edge in the node where the pair is either found below, or found
+    /// immediately to the right of. Returns the index of that edge, and the
+    /// position of the pair relative to the subtree below the edge.
+    ///
+    /// `index` may be the subtree length itself, designating the end of the subtree.
+    pub(super) fn find_index_edge(&self, mut index: usize) -> (usize, usize) {
+        debug_assert!(index <= self.subtree_len());
+        let len = self.len();
+        for edge_idx in 0..len {
+            let child_len = self.edge_subtree_len(edge_idx);
+            if index <= child_len {
+                return (edge_idx, index);
+            }
+            index -= child_len + 1;
+        }
+        (len, index)
+    }
+
+    /// Looks up the key-value pair at a given position in the subtree headed
+    /// by the node, counting from zero in ascending order.
+    pub(super) fn find_kv_at_index(
+        mut self,
+        mut index: usize,
+    ) -> Option<Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>> {
+        if index >= self.subtree_len() {
+            return None;
+        }
+        loop {
+            let (edge_idx, child_index) = self.find_index_edge(index);
+            if child_index == self.edge_subtree_len(edge_idx) {
+                return Some(unsafe { Handle::new_kv(self, edge_idx) });
+            }
+            self = match unsafe { Handle::new_edge(self, edge_idx) }.force() {
+                Leaf(_) => unreachable!(),
+                Internal(edge) => edge.descend(),
+            };
+            index = child_index;
+        }
+    }
+}
+
+impl<'a, K: 'a, V: 'a> NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal> {
+    /// Returns the number of keys in the (sub)tree that are less than the given key.
+    ///
+    /// The result is meaningful only if the tree is ordered by key.
+    pub(super) fn rank<Q: ?Sized + Ord>(self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+    {
+        self.rank_of_lower_bound(SearchBound::Included(key))
+    }
+
+    /// Returns the number of keys in the (sub)tree that lie within the given range.
+    ///
+    /// As a diagnostic service, panics if the range specifies impossible bounds.
+    ///
+    /// The result is meaningful only if the tree is ordered by key.
+    pub(super) fn range_len<Q: ?Sized + Ord, R>(self, range: &R) -> usize
+    where
+        K: Borrow<Q>,
+        R: RangeBounds<Q>,
+    {
+        // Query the bounds only once, for the same reason as in `search_tree_for_bifurcation`.
+        let (start, end) = (range.start_bound(), range.end_bound());
+        check_range_bounds::<Q, V>(start, end);
+        let lower = self.rank_of_lower_bound(SearchBound::from_range(start));
+        let upper = self.rank_of_upper_bound(SearchBound::from_range(end));
+        // An `Ord` implementation violating its contract could make these cross.
+        upper.saturating_sub(lower)
+    }
+
+    /// Returns the number of keys in the (sub)tree to the left of the edge
+    /// matching the lower bound of a range.
+    fn rank_of_lower_bound<Q: ?Sized + Ord>(mut self, mut bound: SearchBound<&Q>) -> usize
+    where
+        K: Borrow<Q>,
+    {
+        let mut rank = 0;
+        loop {
+            let (edge_idx, child_bound) = self.find_lower_bound_index(bound);
+            rank += self.subtree_len_before_edge(edge_idx);
+            match unsafe { Handle::new_edge(self, edge_idx) }.force() {
+                Leaf(_) => return rank,
+                Internal(edge) => {
+                    self = edge.descend();
+                    bound = child_bound;
+                }
+            }
+        }
+    }
+
+    /// Clone of `rank_of_lower_bound` for the upper bound.
+    fn rank_of_upper_bound<Q: ?Sized + Ord>(mut self, mut bound: SearchBound<&Q>) -> usize
+    where
+        K: Borrow<Q>,
+    {
+        let mut rank = 0;
+        loop {
+            let (edge_idx, child_bound) = unsafe { self.find_upper_bound_index(bound, 0) };
+            rank += self.subtree_len_before_edge(edge_idx);
+            match unsafe { Handle::new_edge(self, edge_idx) }.force() {
+                Leaf(_) => return rank,
+                Internal(edge) => {
+                    self = edge.descend();
+                    bound = child_bound;
+                }
+            }
+        }
+    }
+}
@@ -29,6 +29,7 @@ fn remove_leaf_kv<F: FnOnce(), A: Allocator + Clone>(
         alloc: A,
     ) -> ((K, V), Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge>) {
         let (old_kv, mut pos) = self.remove();
+        unsafe { pos.reborrow_mut() }.into_node().adjust_ancestors_subtree_len(-1);
         let len = pos.reborrow().into_node().len();
         if len < MIN_LEN {
             let idx = pos.idx();
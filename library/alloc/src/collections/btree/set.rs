@@ -402,6 +402,40 @@ pub fn range<K: ?Sized, R>(&self, range: R) -> Range<'_, T>
         Range { iter: self.map.range(range) }
     }
 
+    /// Returns the number of elements in the set within the specified range,
+    /// accepting the same ranges as [`range`].
+    ///
+    /// This takes time logarithmic in the size of the set, rather than in the
+    /// number of elements in the range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_order_statistics)]
+    /// use std::collections::BTreeSet;
+    ///
+    /// let set: BTreeSet<i32> = (0..100).map(|i| i * 2).collect();
+    /// assert_eq!(set.range_len(10..20), 5);
+    /// assert_eq!(set.range_len(..=10), 6);
+    /// assert_eq!(set.range_len(1000..), 0);
+    /// ```
+    ///
+    /// [`range`]: BTreeSet::range
+    #[unstable(feature = "btree_order_statistics", issue = "none")]
+    pub fn range_len<K: ?Sized, R>(&self, range: R) -> usize
+    where
+        K: Ord,
+        T: Borrow<K> + Ord,
+        R: RangeBounds<K>,
+    {
+        self.map.range_len(range)
+    }
+
     /// Visits the elements representing the difference,
     /// i.e., the elements that are in `self` but not in `other`,
     /// in ascending order.
@@ -642,6 +676,57 @@ pub fn get<Q: ?Sized>(&self, value: &Q) -> Option<&T>
         self.map.get_key_value(value).map(|(k, _)| k)
     }
 
+    /// Returns the element at the given position in the set, counting from
+    /// zero in ascending order, or `None` if `index` is out of bounds.
+    ///
+    /// This takes time logarithmic in the size of the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_order_statistics)]
+    /// use std::collections::BTreeSet;
+    ///
+    /// let set = BTreeSet::from([30, 10, 20]);
+    /// assert_eq!(set.get_index(0), Some(&10));
+    /// assert_eq!(set.get_index(2), Some(&30));
+    /// assert_eq!(set.get_index(3), None);
+    /// ```
+    #[unstable(feature = "btree_order_statistics", issue = "none")]
+    pub fn get_index(&self, index: usize) -> Option<&T> {
+        self.map.get_index(index).map(|(k, _)| k)
+    }
+
+    /// Returns the number of elements in the set that are less than the given
+    /// value, which is the position the value has or would have in the set.
+    ///
+    /// The value may be any borrowed form of the set's element type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the element type.
+    ///
+    /// This takes time logarithmic in the size of the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_order_statistics)]
+    /// use std::collections::BTreeSet;
+    ///
+    /// let set = BTreeSet::from([10, 20, 30]);
+    /// assert_eq!(set.rank(&5), 0);
+    /// assert_eq!(set.rank(&20), 1);
+    /// assert_eq!(set.rank(&25), 2);
+    /// assert_eq!(set.rank(&35), 3);
+    /// ```
+    #[unstable(feature = "btree_order_statistics", issue = "none")]
+    pub fn rank<Q: ?Sized>(&self, value: &Q) -> usize
+    where
+        T: Borrow<Q> + Ord,
+        Q: Ord,
+    {
+        self.map.rank(value)
+    }
+
     /// Returns `true` if `self` has no elements in common with `other`.
     /// This is equivalent to checking for an empty intersection.
     ///
@@ -1187,6 +1272,35 @@ pub fn split_off<Q: ?Sized + Ord>(&mut self, value: &Q) -> Self
         BTreeSet { map: self.map.split_off(value) }
     }
 
+    /// Splits the collection into two at the given position. Returns everything
+    /// from the `at`-th element onwards, counting from zero in ascending order,
+    /// and leaves the first `at` elements in `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_order_statistics)]
+    /// use std::collections::BTreeSet;
+    ///
+    /// let mut a = BTreeSet::from([1, 2, 3, 17, 41]);
+    ///
+    /// let b = a.split_off_at_index(2);
+    ///
+    /// assert_eq!(a.into_iter().collect::<Vec<_>>(), [1, 2]);
+    /// assert_eq!(b.into_iter().collect::<Vec<_>>(), [3, 17, 41]);
+    /// ```
+    #[unstable(feature = "btree_order_statistics", issue = "none")]
+    pub fn split_off_at_index(&mut self, at: usize) -> Self
+    where
+        A: Clone,
+    {
+        BTreeSet { map: self.map.split_off_at_index(at) }
+    }
+
     /// Creates an iterator that visits elements in the specified range in ascending order and
     /// uses a closure to determine if an element should be removed.
     ///
@@ -826,6 +826,39 @@ fn test_split_off_large_random_sorted() {
     assert!(right.into_iter().eq(data.into_iter().filter(|x| *x >= key)));
 }
 
+#[test]
+fn test_split_off_at_index() {
+    let mut data = rand_data(529);
+    let mut set = BTreeSet::from_iter(data.clone());
+    data.sort();
+    let right = set.split_off_at_index(200);
+
+    assert!(set.into_iter().eq(data[..200].iter().copied()));
+    assert!(right.into_iter().eq(data[200..].iter().copied()));
+}
+
+#[test]
+fn test_order_statistics() {
+    let mut data = rand_data(300);
+    let set = BTreeSet::from_iter(data.clone());
+    data.sort();
+
+    for (index, value) in data.iter().enumerate() {
+        assert_eq!(set.get_index(index), Some(value));
+        assert_eq!(set.rank(value), index);
+        assert_eq!(set.range_len(..=value), index + 1);
+        assert_eq!(set.range_len(value..), data.len() - index);
+    }
+    assert_eq!(set.get_index(data.len()), None);
+}
+
+#[should_panic(expected = "range start and end are equal and excluded in BTreeSet")]
+#[test]
+fn test_range_len_panic() {
+    let set = BTreeSet::from([3, 5, 8]);
+    let _ = set.range_len((Excluded(&5), Excluded(&5)));
+}
+
 #[test]
 fn from_array() {
     let set = BTreeSet::from([1, 2, 3, 4]);
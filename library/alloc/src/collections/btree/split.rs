@@ -2,7 +2,7 @@
 use core::borrow::Borrow;
 
 use super::node::ForceResult::*;
-use super::node::Root;
+use super::node::{Handle, NodeRef, Root, marker};
 use super::search::SearchResult::*;
 
 impl<K, V> Root<K, V> {
@@ -13,16 +13,9 @@ pub(super) fn calc_split_length(
         root_a: &Root<K, V>,
         root_b: &Root<K, V>,
     ) -> (usize, usize) {
-        let (length_a, length_b);
-        if root_a.height() < root_b.height() {
-            length_a = root_a.reborrow().calc_length();
-            length_b = total_num - length_a;
-            debug_assert_eq!(length_b, root_b.reborrow().calc_length());
-        } else {
-            length_b = root_b.reborrow().calc_length();
-            length_a = total_num - length_b;
-            debug_assert_eq!(length_a, root_a.reborrow().calc_length());
-        }
+        let length_a = root_a.reborrow().subtree_len();
+        let length_b = total_num - length_a;
+        debug_assert_eq!(length_b, root_b.reborrow().calc_length());
         (length_a, length_b)
     }
 
@@ -38,6 +31,40 @@ pub(super) fn split_off<Q: ?Sized + Ord, A: Allocator + Clone>(
     ) -> Self
     where
         K: Borrow<Q>,
+    {
+        self.split_off_at_edges(alloc, |node| match node.search_node(key) {
+            // key is going to the right tree
+            Found(kv) => kv.left_edge(),
+            GoDown(edge) => edge,
+        })
+    }
+
+    /// Split off a tree with the key-value pairs at and after the given
+    /// position in the tree. `index` must not exceed the length of the tree.
+    /// If `self` respects all `BTreeMap` tree invariants, then both
+    /// `self` and the returned tree will respect those invariants.
+    pub(super) fn split_off_at_index<A: Allocator + Clone>(
+        &mut self,
+        mut index: usize,
+        alloc: A,
+    ) -> Self {
+        debug_assert!(index <= self.reborrow().subtree_len());
+        self.split_off_at_edges(alloc, |node| {
+            let (edge_idx, child_index) = node.find_index_edge(index);
+            index = child_index;
+            unsafe { Handle::new_edge(node, edge_idx) }
+        })
+    }
+
+    /// Split off a tree with the key-value pairs to the right of the edges
+    /// chosen by `split_edge`, which is called for each node on the way down
+    /// from the root to a leaf.
+    fn split_off_at_edges<A: Allocator + Clone, F>(&mut self, alloc: A, mut split_edge: F) -> Self
+    where
+        F: FnMut(
+            NodeRef<marker::Mut<'_>, K, V, marker::LeafOrInternal>,
+        )
+            -> Handle<NodeRef<marker::Mut<'_>, K, V, marker::LeafOrInternal>, marker::Edge>,
     {
         let left_root = self;
         let mut right_root = Root::new_pillar(left_root.height(), alloc.clone());
@@ -45,11 +72,7 @@ pub(super) fn split_off<Q: ?Sized + Ord, A: Allocator + Clone>(
         let mut right_node = right_root.borrow_mut();
 
         loop {
-            let mut split_edge = match left_node.search_node(key) {
-                // key is going to the right tree
-                Found(kv) => kv.left_edge(),
-                GoDown(edge) => edge,
-            };
+            let mut split_edge = split_edge(left_node);
 
             split_edge.move_suffix(&mut right_node);
 
@@ -58,7 +81,12 @@ pub(super) fn split_off<Q: ?Sized + Ord, A: Allocator + Clone>(
                     left_node = edge.descend();
                     right_node = node.first_edge().descend();
                 }
-                (Leaf(_), Leaf(_)) => break,
+                (Leaf(edge), Leaf(node)) => {
+                    // Only the nodes we moved suffixes between have changed size.
+                    edge.into_node().forget_type().recalc_ancestors_subtree_len();
+                    node.forget_type().recalc_ancestors_subtree_len();
+                    break;
+                }
                 _ => unreachable!(),
             }
         }
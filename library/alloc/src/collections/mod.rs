@@ -61,7 +61,7 @@ pub mod btree_set {
 pub use vec_deque::VecDeque;
 
 #[cfg(not(test))]
-use crate::alloc::{Layout, LayoutError};
+use crate::alloc::{Allocator, Layout, LayoutError};
 
 /// The error type for `try_reserve` methods.
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -166,6 +166,64 @@ fn fmt(
     }
 }
 
+/// Conversion from an [`Iterator`] into a collection in a given allocator,
+/// reporting allocation failure instead of aborting.
+///
+/// This is the fallible counterpart of [`FromIterator`], used by
+/// [`TryCollectIn::try_collect_in`].
+#[unstable(feature = "fallible_collections", issue = "48043")]
+#[cfg(not(test))]
+pub trait TryFromIteratorIn<T, A: Allocator>: Sized {
+    /// Creates a collection in `alloc` from the items of `iter`, or returns an
+    /// error if an allocation fails.
+    fn try_from_iter_in<I: IntoIterator<Item = T>>(
+        iter: I,
+        alloc: A,
+    ) -> Result<Self, TryReserveError>;
+}
+
+/// Collecting an [`Iterator`] into a collection without aborting on allocation
+/// failure.
+///
+/// This trait is implemented for every iterator.
+#[unstable(feature = "fallible_collections", issue = "48043")]
+#[cfg(not(test))]
+pub trait TryCollectIn: Iterator + Sized {
+    /// Transforms the iterator into a collection in `alloc`, or returns an
+    /// error if an allocation fails.
+    ///
+    /// This is the fallible version of [`Iterator::collect`]. On error, the
+    /// items collected so far are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api, fallible_collections)]
+    /// use std::alloc::Global;
+    /// use std::collections::{BTreeMap, TryCollectIn};
+    ///
+    /// let squares: Vec<u32> = (1..4).map(|x| x * x).try_collect_in(Global).unwrap();
+    /// assert_eq!(squares, [1, 4, 9]);
+    ///
+    /// let map: BTreeMap<_, _> = [(2, 'b'), (1, 'a')].into_iter().try_collect_in(Global).unwrap();
+    /// assert_eq!(map.into_iter().collect::<Vec<_>>(), [(1, 'a'), (2, 'b')]);
+    /// ```
+    fn try_collect_in<C, A>(self, alloc: A) -> Result<C, TryReserveError>
+    where
+        C: TryFromIteratorIn<Self::Item, A>,
+        A: Allocator,
+    {
+        C::try_from_iter_in(self, alloc)
+    }
+}
+
+#[unstable(feature = "fallible_collections", issue = "48043")]
+#[cfg(not(test))]
+impl<I: Iterator> TryCollectIn for I {}
+
+#[cfg(test)]
+pub use realalloc::collections::TryFromIteratorIn;
+
 /// An intermediate trait for specialization of `Extend`.
 #[doc(hidden)]
 #[cfg(not(no_global_oom_handling))]
@@ -20,7 +20,7 @@
 use core::{fmt, ptr, slice};
 
 use crate::alloc::{Allocator, Global};
-use crate::collections::{TryReserveError, TryReserveErrorKind};
+use crate::collections::{TryFromIteratorIn, TryReserveError, TryReserveErrorKind};
 use crate::raw_vec::RawVec;
 use crate::vec::Vec;
 
@@ -101,6 +101,36 @@ pub struct VecDeque<
     buf: RawVec<T, A>,
 }
 
+impl<T: Clone, A: Allocator + Clone> VecDeque<T, A> {
+    /// Returns a copy of the deque, or an error if allocating it fails.
+    ///
+    /// This is the fallible version of [`clone`](Clone::clone).
+    ///
+    /// # Errors
+    ///
+    /// If the allocator reports a failure, then an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(fallible_collections)]
+    /// use std::collections::VecDeque;
+    ///
+    /// let deq = VecDeque::from([1, 2, 3]);
+    /// assert_eq!(deq.try_clone(), Ok(deq.clone()));
+    /// ```
+    #[unstable(feature = "fallible_collections", issue = "48043")]
+    pub fn try_clone(&self) -> Result<Self, TryReserveError> {
+        let mut deq = Self::new_in(self.allocator().clone());
+        deq.try_reserve_exact(self.len())?;
+        for value in self {
+            // There is room for all elements, so this doesn't grow.
+            let _ = deq.push_back_mut(value.clone());
+        }
+        Ok(deq)
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<T: Clone, A: Allocator + Clone> Clone for VecDeque<T, A> {
     #[track_caller]
@@ -1968,6 +1998,72 @@ pub fn push_back_mut(&mut self, value: T) -> &mut T {
         unsafe { self.buffer_write(self.to_physical_idx(len), value) }
     }
 
+    /// Prepends an element to the deque, or returns an error if the
+    /// allocation needed to make room for it fails.
+    ///
+    /// This is the fallible version of [`push_front`]. On error, the deque is
+    /// unchanged and `value` is dropped.
+    ///
+    /// [`push_front`]: VecDeque::push_front
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(fallible_collections)]
+    /// use std::collections::VecDeque;
+    ///
+    /// let mut d = VecDeque::from([1]);
+    /// d.try_push_front(2).expect("out of memory");
+    /// assert_eq!(d, [2, 1]);
+    /// ```
+    #[unstable(feature = "fallible_collections", issue = "48043")]
+    pub fn try_push_front(&mut self, value: T) -> Result<(), TryReserveError> {
+        if self.is_full() {
+            self.try_reserve(1)?;
+        }
+        // There is room for at least one more element, so this doesn't grow.
+        let _ = self.push_front_mut(value);
+        Ok(())
+    }
+
+    /// Appends an element to the back of the deque, or returns an error if
+    /// the allocation needed to make room for it fails.
+    ///
+    /// This is the fallible version of [`push_back`]. On error, the deque is
+    /// unchanged and `value` is dropped.
+    ///
+    /// [`push_back`]: VecDeque::push_back
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(fallible_collections)]
+    /// use std::collections::VecDeque;
+    ///
+    /// let mut d = VecDeque::from([1]);
+    /// d.try_push_back(2).expect("out of memory");
+    /// assert_eq!(d, [1, 2]);
+    /// ```
+    #[unstable(feature = "fallible_collections", issue = "48043")]
+    pub fn try_push_back(&mut self, value: T) -> Result<(), TryReserveError> {
+        if self.is_full() {
+            self.try_reserve(1)?;
+        }
+        // There is room for at least one more element, so this doesn't grow.
+        let _ = self.push_back_mut(value);
+        Ok(())
+    }
+
     #[inline]
     fn is_contiguous(&self) -> bool {
         // Do the calculation like this to avoid overflowing if len + head > usize::MAX
@@ -2130,6 +2226,47 @@ pub fn insert_mut(&mut self, index: usize, value: T) -> &mut T {
         }
     }
 
+    /// Inserts an element at `index` within the deque, shifting all elements
+    /// with indices greater than or equal to `index` towards the back, or
+    /// returns an error if the allocation needed to make room for it fails.
+    ///
+    /// This is the fallible version of [`insert`]. On error, the deque is
+    /// unchanged and `value` is dropped.
+    ///
+    /// [`insert`]: VecDeque::insert
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is strictly greater than the deque's length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(fallible_collections)]
+    /// use std::collections::VecDeque;
+    ///
+    /// let mut vec_deque = VecDeque::from(['a', 'b', 'c']);
+    /// vec_deque.try_insert(1, 'd').expect("out of memory");
+    /// assert_eq!(vec_deque, ['a', 'd', 'b', 'c']);
+    /// ```
+    #[unstable(feature = "fallible_collections", issue = "48043")]
+    #[track_caller]
+    pub fn try_insert(&mut self, index: usize, value: T) -> Result<(), TryReserveError> {
+        assert!(index <= self.len(), "index out of bounds");
+
+        if self.is_full() {
+            self.try_reserve(1)?;
+        }
+        // There is room for at least one more element, so this doesn't grow.
+        let _ = self.insert_mut(index, value);
+        Ok(())
+    }
+
     /// Removes and returns the element at `index` from the deque.
     /// Whichever end is closer to the removal point will be moved to make
     /// room, and all the affected elements will be moved to new positions.
@@ -3107,6 +3244,17 @@ fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> VecDeque<T> {
     }
 }
 
+#[unstable(feature = "fallible_collections", issue = "48043")]
+impl<T, A: Allocator> TryFromIteratorIn<T, A> for VecDeque<T, A> {
+    fn try_from_iter_in<I: IntoIterator<Item = T>>(
+        iter: I,
+        alloc: A,
+    ) -> Result<Self, TryReserveError> {
+        // Converting a `Vec` doesn't allocate.
+        Vec::try_from_iter_in(iter, alloc).map(VecDeque::from)
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<T, A: Allocator> IntoIterator for VecDeque<T, A> {
     type Item = T;
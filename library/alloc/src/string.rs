@@ -1341,6 +1341,170 @@ pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveE
         self.vec.try_reserve_exact(additional)
     }
 
+    /// Appends the given [`char`] to the end of this `String`, or returns an
+    /// error if the allocation needed to make room for it fails.
+    ///
+    /// This is the fallible version of [`push`]. On error, the string is
+    /// unchanged.
+    ///
+    /// [`push`]: String::push
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(fallible_collections)]
+    /// let mut s = String::from("abc");
+    /// s.try_push('1').expect("out of memory");
+    /// assert_eq!("abc1", s);
+    /// ```
+    #[inline]
+    #[unstable(feature = "fallible_collections", issue = "48043")]
+    pub fn try_push(&mut self, ch: char) -> Result<(), TryReserveError> {
+        self.try_push_str(ch.encode_utf8(&mut [0; char::MAX_LEN_UTF8]))
+    }
+
+    /// Appends a given string slice onto the end of this `String`, or returns
+    /// an error if the allocation needed to make room for it fails.
+    ///
+    /// This is the fallible version of [`push_str`]. On error, the string is
+    /// unchanged.
+    ///
+    /// [`push_str`]: String::push_str
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(fallible_collections)]
+    /// let mut s = String::from("foo");
+    /// s.try_push_str("bar").expect("out of memory");
+    /// assert_eq!("foobar", s);
+    /// ```
+    #[inline]
+    #[unstable(feature = "fallible_collections", issue = "48043")]
+    pub fn try_push_str(&mut self, string: &str) -> Result<(), TryReserveError> {
+        self.vec.try_extend_from_slice(string.as_bytes())
+    }
+
+    /// Inserts a character into this `String` at byte position `idx`, or
+    /// returns an error if the allocation needed to make room for it fails.
+    ///
+    /// This is the fallible version of [`insert`]. On error, the string is
+    /// unchanged.
+    ///
+    /// [`insert`]: String::insert
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the `String`'s length, or if it does not
+    /// lie on a [`char`] boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(fallible_collections)]
+    /// let mut s = String::from("fo");
+    /// s.try_insert(1, 'o').expect("out of memory");
+    /// assert_eq!("foo", s);
+    /// ```
+    #[inline]
+    #[track_caller]
+    #[unstable(feature = "fallible_collections", issue = "48043")]
+    pub fn try_insert(&mut self, idx: usize, ch: char) -> Result<(), TryReserveError> {
+        self.try_insert_str(idx, ch.encode_utf8(&mut [0; char::MAX_LEN_UTF8]))
+    }
+
+    /// Inserts a string slice into this `String` at byte position `idx`, or
+    /// returns an error if the allocation needed to make room for it fails.
+    ///
+    /// This is the fallible version of [`insert_str`]. On error, the string is
+    /// unchanged.
+    ///
+    /// [`insert_str`]: String::insert_str
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the `String`'s length, or if it does not
+    /// lie on a [`char`] boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(fallible_collections)]
+    /// let mut s = String::from("bar");
+    /// s.try_insert_str(0, "foo").expect("out of memory");
+    /// assert_eq!("foobar", s);
+    /// ```
+    #[track_caller]
+    #[unstable(feature = "fallible_collections", issue = "48043")]
+    pub fn try_insert_str(&mut self, idx: usize, string: &str) -> Result<(), TryReserveError> {
+        assert!(self.is_char_boundary(idx));
+
+        let len = self.len();
+        let amt = string.len();
+        self.try_reserve(amt)?;
+
+        // SAFETY: Move the bytes starting from `idx` to their new location `amt` bytes
+        // ahead. This is safe because sufficient capacity was just reserved, and `idx`
+        // is a char boundary.
+        unsafe {
+            ptr::copy(self.vec.as_ptr().add(idx), self.vec.as_mut_ptr().add(idx + amt), len - idx);
+        }
+
+        // SAFETY: Copy the new string slice into the vacated region if `idx != len`,
+        // or into the uninitialized spare capacity otherwise. The borrow checker
+        // ensures that the source and destination do not overlap.
+        unsafe {
+            ptr::copy_nonoverlapping(string.as_ptr(), self.vec.as_mut_ptr().add(idx), amt);
+        }
+
+        // SAFETY: Update the length to include the newly added bytes.
+        unsafe {
+            self.vec.set_len(len + amt);
+        }
+        Ok(())
+    }
+
+    /// Returns a copy of this `String`, or an error if allocating it fails.
+    ///
+    /// This is the fallible version of [`clone`](Clone::clone).
+    ///
+    /// # Errors
+    ///
+    /// If the allocator reports a failure, then an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(fallible_collections)]
+    /// let s = String::from("hello");
+    /// assert_eq!(s.try_clone().as_deref(), Ok("hello"));
+    /// ```
+    #[inline]
+    #[unstable(feature = "fallible_collections", issue = "48043")]
+    pub fn try_clone(&self) -> Result<String, TryReserveError> {
+        Ok(String { vec: self.vec.try_clone()? })
+    }
+
     /// Shrinks the capacity of this `String` to match its length.
     ///
     /// # Examples
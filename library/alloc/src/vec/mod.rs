@@ -71,7 +71,7 @@
 use crate::alloc::{Allocator, Global};
 use crate::borrow::{Cow, ToOwned};
 use crate::boxed::Box;
-use crate::collections::TryReserveError;
+use crate::collections::{TryFromIteratorIn, TryReserveError};
 use crate::raw_vec::RawVec;
 
 mod extract_if;
@@ -516,6 +516,32 @@ pub fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
         Self::try_with_capacity_in(capacity, Global)
     }
 
+    /// Creates a vector from the contents of an iterator, or returns an error
+    /// if an allocation fails.
+    ///
+    /// This is the fallible version of collecting into a `Vec`. See
+    /// [`try_from_iter_in`] to collect into a vector in another allocator.
+    ///
+    /// [`try_from_iter_in`]: Vec::try_from_iter_in
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(fallible_collections)]
+    /// let vec = Vec::try_from_iter((1..4).map(|x| x * x)).expect("out of memory");
+    /// assert_eq!(vec, [1, 4, 9]);
+    /// ```
+    #[inline]
+    #[unstable(feature = "fallible_collections", issue = "48043")]
+    pub fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, TryReserveError> {
+        Self::try_from_iter_in(iter, Global)
+    }
+
     /// Creates a `Vec<T>` directly from a pointer, a length, and a capacity.
     ///
     /// # Safety
@@ -2078,17 +2104,9 @@ pub fn insert(&mut self, index: usize, element: T) {
     #[track_caller]
     #[must_use = "if you don't need a reference to the value, use `Vec::insert` instead"]
     pub fn insert_mut(&mut self, index: usize, element: T) -> &mut T {
-        #[cold]
-        #[cfg_attr(not(feature = "panic_immediate_abort"), inline(never))]
-        #[track_caller]
-        #[optimize(size)]
-        fn assert_failed(index: usize, len: usize) -> ! {
-            panic!("insertion index (is {index}) should be <= len (is {len})");
-        }
-
         let len = self.len();
         if index > len {
-            assert_failed(index, len);
+            insert_assert_failed(index, len);
         }
 
         // space for the new element
@@ -2115,6 +2133,59 @@ fn assert_failed(index: usize, len: usize) -> ! {
         }
     }
 
+    /// Inserts an element at position `index` within the vector, shifting all
+    /// elements after it to the right, or returns an error if the allocation
+    /// needed to make room for it fails.
+    ///
+    /// This is the fallible version of [`insert`]. On error, the vector is
+    /// unchanged and `element` is dropped.
+    ///
+    /// [`insert`]: Vec::insert
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(fallible_collections)]
+    /// let mut vec = vec!['a', 'b', 'c'];
+    /// vec.try_insert(1, 'd').expect("out of memory");
+    /// assert_eq!(vec, ['a', 'd', 'b', 'c']);
+    /// ```
+    #[unstable(feature = "fallible_collections", issue = "48043")]
+    #[track_caller]
+    pub fn try_insert(&mut self, index: usize, element: T) -> Result<(), TryReserveError> {
+        let len = self.len();
+        if index > len {
+            insert_assert_failed(index, len);
+        }
+
+        // space for the new element
+        if len == self.buf.capacity() {
+            self.buf.try_reserve(len, 1)?;
+        }
+
+        unsafe {
+            // infallible
+            // The spot to put the new value
+            let p = self.as_mut_ptr().add(index);
+            if index < len {
+                // Shift everything over to make space.
+                ptr::copy(p, p.add(1), len - index);
+            }
+            ptr::write(p, element);
+            self.set_len(len + 1);
+        }
+        Ok(())
+    }
+
     /// Removes and returns the element at position `index` within the vector,
     /// shifting all elements after it to the left.
     ///
@@ -2643,6 +2714,120 @@ pub fn push_mut_within_capacity(&mut self, value: T) -> Result<&mut T, T> {
         }
     }
 
+    /// Appends an element to the back of a collection, or returns an error if
+    /// the allocation needed to make room for it fails.
+    ///
+    /// This is the fallible version of [`push`]. On error, the vector is
+    /// unchanged and `value` is dropped; use [`push_within_capacity`] together
+    /// with [`try_reserve`] to get the value back instead.
+    ///
+    /// [`push`]: Vec::push
+    /// [`push_within_capacity`]: Vec::push_within_capacity
+    /// [`try_reserve`]: Vec::try_reserve
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(fallible_collections)]
+    /// let mut vec = vec![1, 2];
+    /// vec.try_push(3).expect("out of memory");
+    /// assert_eq!(vec, [1, 2, 3]);
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// Takes amortized *O*(1) time, like [`push`].
+    #[inline]
+    #[unstable(feature = "fallible_collections", issue = "48043")]
+    pub fn try_push(&mut self, value: T) -> Result<(), TryReserveError> {
+        let len = self.len;
+        if len == self.buf.capacity() {
+            self.buf.try_reserve(len, 1)?;
+        }
+        unsafe {
+            ptr::write(self.as_mut_ptr().add(len), value);
+            self.len = len + 1;
+        }
+        Ok(())
+    }
+
+    /// Extends the vector with the contents of an iterator, or returns an
+    /// error if an allocation needed to make room for them fails.
+    ///
+    /// This is the fallible version of [`extend`]. On error, the elements
+    /// taken from the iterator so far remain in the vector.
+    ///
+    /// [`extend`]: Vec::extend
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(fallible_collections)]
+    /// let mut vec = vec![1];
+    /// vec.try_extend((2..5).map(|x| x * 10)).expect("out of memory");
+    /// assert_eq!(vec, [1, 20, 30, 40]);
+    /// ```
+    #[unstable(feature = "fallible_collections", issue = "48043")]
+    pub fn try_extend<I: IntoIterator<Item = T>>(
+        &mut self,
+        iter: I,
+    ) -> Result<(), TryReserveError> {
+        let mut iter = iter.into_iter();
+        while let Some(value) = iter.next() {
+            let len = self.len;
+            if len == self.buf.capacity() {
+                let (lower, _) = iter.size_hint();
+                self.buf.try_reserve(len, lower.saturating_add(1))?;
+            }
+            unsafe {
+                ptr::write(self.as_mut_ptr().add(len), value);
+                self.len = len + 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates a vector in the provided allocator from the contents of an
+    /// iterator, or returns an error if an allocation fails.
+    ///
+    /// This is the fallible version of collecting into a `Vec`.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api, fallible_collections)]
+    /// use std::alloc::System;
+    ///
+    /// let vec = Vec::try_from_iter_in((1..4).map(|x| x * x), System).expect("out of memory");
+    /// assert_eq!(vec, [1, 4, 9]);
+    /// ```
+    #[unstable(feature = "fallible_collections", issue = "48043")]
+    pub fn try_from_iter_in<I: IntoIterator<Item = T>>(
+        iter: I,
+        alloc: A,
+    ) -> Result<Self, TryReserveError> {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        let mut vec = Vec::try_with_capacity_in(lower, alloc)?;
+        vec.try_extend(iter)?;
+        Ok(vec)
+    }
+
     /// Removes the last element from a vector and returns it, or [`None`] if it
     /// is empty.
     ///
@@ -3262,6 +3447,68 @@ pub fn extend_from_slice(&mut self, other: &[T]) {
         self.spec_extend(other.iter())
     }
 
+    /// Clones and appends all elements in a slice to the `Vec`, or returns an
+    /// error if the allocation needed to make room for them fails.
+    ///
+    /// This is the fallible version of [`extend_from_slice`]. On error, the
+    /// vector is unchanged.
+    ///
+    /// [`extend_from_slice`]: Vec::extend_from_slice
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(fallible_collections)]
+    /// let mut vec = vec![1];
+    /// vec.try_extend_from_slice(&[2, 3, 4]).expect("out of memory");
+    /// assert_eq!(vec, [1, 2, 3, 4]);
+    /// ```
+    #[unstable(feature = "fallible_collections", issue = "48043")]
+    pub fn try_extend_from_slice(&mut self, other: &[T]) -> Result<(), TryReserveError> {
+        self.try_reserve(other.len())?;
+        for value in other {
+            let len = self.len;
+            // SAFETY: `try_reserve` made room for all of `other`. The length is
+            // updated after every element so that a panicking `clone` doesn't
+            // leak or double-drop anything.
+            unsafe {
+                ptr::write(self.as_mut_ptr().add(len), value.clone());
+                self.len = len + 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a copy of the vector, or an error if allocating it fails.
+    ///
+    /// This is the fallible version of [`clone`](Clone::clone).
+    ///
+    /// # Errors
+    ///
+    /// If the allocator reports a failure, then an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(fallible_collections)]
+    /// let vec = vec![String::from("a"), String::from("b")];
+    /// assert_eq!(vec.try_clone(), Ok(vec.clone()));
+    /// ```
+    #[unstable(feature = "fallible_collections", issue = "48043")]
+    pub fn try_clone(&self) -> Result<Self, TryReserveError>
+    where
+        A: Clone,
+    {
+        let mut vec = Vec::try_with_capacity_in(self.len(), self.allocator().clone())?;
+        vec.try_extend_from_slice(self)?;
+        Ok(vec)
+    }
+
     /// Given a range `src`, clones a slice of elements in that range and appends it to the end.
     ///
     /// `src` must be a range that can form a valid subslice of the `Vec`.
@@ -3406,6 +3653,14 @@ pub fn dedup(&mut self) {
 // Internal methods and functions
 ////////////////////////////////////////////////////////////////////////////////
 
+#[cold]
+#[cfg_attr(not(feature = "panic_immediate_abort"), inline(never))]
+#[track_caller]
+#[optimize(size)]
+fn insert_assert_failed(index: usize, len: usize) -> ! {
+    panic!("insertion index (is {index}) should be <= len (is {len})");
+}
+
 #[doc(hidden)]
 #[cfg(not(no_global_oom_handling))]
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -3580,6 +3835,17 @@ fn index_mut(&mut self, index: I) -> &mut Self::Output {
     }
 }
 
+#[unstable(feature = "fallible_collections", issue = "48043")]
+impl<T, A: Allocator> TryFromIteratorIn<T, A> for Vec<T, A> {
+    #[inline]
+    fn try_from_iter_in<I: IntoIterator<Item = T>>(
+        iter: I,
+        alloc: A,
+    ) -> Result<Self, TryReserveError> {
+        Vec::try_from_iter_in(iter, alloc)
+    }
+}
+
 /// Collects an iterator into a Vec, commonly called via [`Iterator::collect()`]
 ///
 /// # Allocation behavior
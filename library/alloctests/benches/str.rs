@@ -230,6 +230,39 @@ fn bench_contains_bad_simd(b: &mut Bencher) {
     })
 }
 
+#[bench]
+fn bench_find_short_long(b: &mut Bencher) {
+    let haystack = LONG_HAYSTACK;
+    let needle = "malesuada fames";
+
+    b.bytes = haystack.len() as u64;
+    b.iter(|| {
+        assert!(black_box(haystack).find(black_box(needle)).is_some());
+    })
+}
+
+#[bench]
+fn bench_find_absent_long(b: &mut Bencher) {
+    let haystack = LONG_HAYSTACK;
+    let needle = "english";
+
+    b.bytes = haystack.len() as u64;
+    b.iter(|| {
+        assert!(black_box(haystack).find(black_box(needle)).is_none());
+    })
+}
+
+#[bench]
+fn bench_match_indices_short_long(b: &mut Bencher) {
+    let haystack = LONG_HAYSTACK;
+    let needle = "sit amet";
+
+    b.bytes = haystack.len() as u64;
+    b.iter(|| {
+        assert_eq!(black_box(haystack).match_indices(black_box(needle)).count(), 8);
+    })
+}
+
 #[bench]
 fn bench_contains_equal(b: &mut Bencher) {
     let haystack = "Lorem ipsum dolor sit amet, consectetur adipiscing elit.";
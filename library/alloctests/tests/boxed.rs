@@ -2,6 +2,8 @@
 use core::cell::Cell;
 use core::mem::MaybeUninit;
 use core::ptr::NonNull;
+use std::rc::Rc;
+use std::sync::Arc;
 
 #[test]
 #[expect(dangling_pointers_from_temporaries)]
@@ -256,3 +258,18 @@ fn pin_coerce_unsized_box() {
         assert_eq!(pin_box_struct.as_ref().action(), "MyStruct");
     }
 }
+
+#[test]
+fn try_new() {
+    assert_eq!(*Box::try_new(5).unwrap(), 5);
+    assert_eq!(*Rc::try_new(5).unwrap(), 5);
+    assert_eq!(*Arc::try_new(5).unwrap(), 5);
+}
+
+#[test]
+fn try_clone() {
+    let x = Box::new(String::from("hello"));
+    let y = x.try_clone().unwrap();
+    assert_eq!(x, y);
+    assert_ne!(x.as_ptr(), y.as_ptr());
+}
@@ -179,6 +179,18 @@ fn test_peek_mut_pop() {
     assert_eq!(heap.peek(), Some(&9));
 }
 
+#[test]
+fn test_try_push() {
+    let mut heap = BinaryHeap::from(vec![2, 4, 9]);
+    heap.try_push(11).unwrap();
+    assert_eq!(heap.len(), 4);
+    assert_eq!(heap.peek(), Some(&11));
+    heap.try_push(5).unwrap();
+    assert_eq!(heap.len(), 5);
+    assert_eq!(heap.peek(), Some(&11));
+    assert_eq!(heap.into_sorted_vec(), [2, 4, 5, 9, 11]);
+}
+
 #[test]
 fn test_push() {
     let mut heap = BinaryHeap::from(vec![2, 4, 9]);
@@ -8,6 +8,7 @@
 #![feature(core_intrinsics)]
 #![feature(downcast_unchecked)]
 #![feature(exact_size_is_empty)]
+#![feature(fallible_collections)]
 #![feature(hashmap_internals)]
 #![feature(int_format_into)]
 #![feature(linked_list_cursors)]
@@ -1670,6 +1670,26 @@ fn strslice_issue_104726() {
     assert!(!haystack.contains(needle));
 }
 
+#[test]
+fn test_find_str_short_needles() {
+    let haystack = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. Suspendisse quis \
+                    lorem sit amet dolor ultricies condimentum. Praesent iaculis purus elit.";
+    for needle in ["sit", "amet", "elit.", "Praesent iaculis", "ultricies condimentum. Praesent"] {
+        let naive = (0..=haystack.len() - needle.len())
+            .find(|&i| haystack.as_bytes()[i..].starts_with(needle.as_bytes()));
+        assert_eq!(haystack.find(needle), naive, "needle: {needle:?}");
+    }
+    assert_eq!(haystack.find("english"), None);
+
+    // Matches are reported left to right and never overlap.
+    let haystack = "a".repeat(70);
+    let indices: Vec<_> = haystack.match_indices("aaa").map(|(i, _)| i).collect();
+    assert_eq!(indices, (0..67).step_by(3).collect::<Vec<_>>());
+    let haystack = format!("{}ab{}ab", "x".repeat(40), "y".repeat(40));
+    let indices: Vec<_> = haystack.match_indices("ab").map(|(i, _)| i).collect();
+    assert_eq!(indices, [40, 82]);
+}
+
 #[test]
 #[cfg_attr(miri, ignore)] // Miri is too slow
 fn test_strslice_contains() {
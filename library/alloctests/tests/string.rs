@@ -912,3 +912,22 @@ fn test_str_concat() {
     let s: String = format!("{a}{b}");
     assert_eq!(s.as_bytes()[9], 'd' as u8);
 }
+
+#[test]
+fn test_fallible_insertion() {
+    let mut s = String::new();
+    s.try_push('a').unwrap();
+    s.try_push('é').unwrap();
+    s.try_push_str("bc").unwrap();
+    s.try_insert(0, '¡').unwrap();
+    s.try_insert_str(3, "--").unwrap();
+    assert_eq!(s, "¡a--ébc");
+    assert_eq!(s.try_clone().unwrap(), s);
+}
+
+#[test]
+#[should_panic]
+fn test_try_insert_str_not_char_boundary() {
+    let mut s = String::from("é");
+    let _ = s.try_insert_str(1, "a");
+}
@@ -7,6 +7,7 @@
 use std::borrow::Cow;
 use std::cell::Cell;
 use std::collections::TryReserveErrorKind::*;
+use std::collections::{BTreeMap, TryCollectIn, VecDeque};
 use std::fmt::Debug;
 use std::hint;
 use std::iter::InPlaceIterable;
@@ -2704,3 +2705,71 @@ fn vec_null_ptr_roundtrip() {
     let new = roundtripped.with_addr(ptr.addr());
     unsafe { new.read() };
 }
+
+#[test]
+fn test_fallible_insertion() {
+    use core::alloc::AllocError;
+
+    // Allows a limited number of allocations.
+    struct Limited(Cell<usize>);
+    unsafe impl Allocator for Limited {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            let left = self.0.get().checked_sub(1).ok_or(AllocError)?;
+            self.0.set(left);
+            System.allocate(layout)
+        }
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            unsafe { System.deallocate(ptr, layout) }
+        }
+    }
+
+    let alloc = Limited(Cell::new(1));
+    let mut vec = Vec::new_in(&alloc);
+    for i in 1..=4 {
+        vec.try_push(i).unwrap();
+    }
+    assert_eq!(vec.capacity(), 4);
+
+    assert!(vec.try_push(5).is_err());
+    assert!(vec.try_insert(0, 0).is_err());
+    assert!(vec.try_extend_from_slice(&[5, 6]).is_err());
+    assert!(vec.try_extend(5..7).is_err());
+    assert!(vec.try_clone().is_err());
+    assert_eq!(vec, [1, 2, 3, 4]);
+
+    alloc.0.set(2);
+    vec.try_insert(0, 0).unwrap();
+    vec.try_extend_from_slice(&[5, 6]).unwrap();
+    assert_eq!(vec, [0, 1, 2, 3, 4, 5, 6]);
+    assert_eq!(vec.try_clone().unwrap(), vec);
+    assert_eq!(alloc.0.get(), 0);
+}
+
+#[test]
+fn test_try_from_iter() {
+    assert_eq!(Vec::try_from_iter((0..5).filter(|x| x % 2 == 0)), Ok(vec![0, 2, 4]));
+    assert_eq!(Vec::<u8>::try_from_iter([]), Ok(vec![]));
+
+    let mut vec = vec![1];
+    vec.try_extend(vec![2, 3]).unwrap();
+    assert_eq!(vec, [1, 2, 3]);
+
+    let err = Vec::<u16>::try_from_iter(std::iter::repeat_n(0, isize::MAX as usize)).unwrap_err();
+    assert_matches!(err.kind(), CapacityOverflow);
+}
+
+#[test]
+fn test_try_collect_in() {
+    let vec: Vec<_, _> = (0..5).filter(|x| x % 2 == 0).try_collect_in(System).unwrap();
+    assert_eq!(vec, [0, 2, 4]);
+    let deque: VecDeque<_, _> = (0..3).try_collect_in(System).unwrap();
+    assert_eq!(deque, [0, 1, 2]);
+    let map: BTreeMap<_, _, _> =
+        [(2, 'b'), (1, 'a'), (2, 'c')].into_iter().try_collect_in(System).unwrap();
+    assert!(map.into_iter().eq([(1, 'a'), (2, 'c')]));
+
+    let err = std::iter::repeat_n(0u16, isize::MAX as usize)
+        .try_collect_in::<Vec<_, _>, _>(System)
+        .unwrap_err();
+    assert_matches!(err.kind(), CapacityOverflow);
+}
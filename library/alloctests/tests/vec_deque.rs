@@ -1849,3 +1849,16 @@ fn test_truncate_front() {
     v.truncate_front(5);
     assert_eq!(v.as_slices(), ([2, 3, 4, 5, 6].as_slice(), [].as_slice()));
 }
+
+#[test]
+fn test_fallible_insertion() {
+    let mut d = VecDeque::with_capacity(3);
+    d.try_push_back(2).unwrap();
+    d.try_push_back(3).unwrap();
+    d.try_push_front(1).unwrap();
+    // Wraps around and grows.
+    d.try_push_front(0).unwrap();
+    d.try_insert(2, 9).unwrap();
+    assert_eq!(d, [0, 1, 9, 2, 3]);
+    assert_eq!(d.try_clone().unwrap(), d);
+}
@@ -0,0 +1,1088 @@
+use super::display_buffer::DisplayBuffer;
+use crate::error::Error;
+use crate::fmt::{self, Write};
+use crate::iter::FusedIterator;
+use crate::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// An IP network, either IPv4 or IPv6.
+///
+/// This enum can contain either an [`Ipv4Net`] or an [`Ipv6Net`], see their
+/// respective documentation for more details.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(ip_net)]
+/// use std::net::{IpAddr, IpNet, Ipv4Addr};
+///
+/// let net: IpNet = "10.0.0.0/8".parse().unwrap();
+///
+/// assert!(net.is_ipv4());
+/// assert!(net.contains(IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3))));
+/// assert_eq!(net.to_string(), "10.0.0.0/8");
+/// ```
+#[unstable(feature = "ip_net", issue = "none")]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum IpNet {
+    /// An IPv4 network.
+    #[unstable(feature = "ip_net", issue = "none")]
+    V4(#[unstable(feature = "ip_net", issue = "none")] Ipv4Net),
+    /// An IPv6 network.
+    #[unstable(feature = "ip_net", issue = "none")]
+    V6(#[unstable(feature = "ip_net", issue = "none")] Ipv6Net),
+}
+
+/// An IPv4 network, given by an address and a prefix length.
+///
+/// The prefix length is the number of leading bits of the address that
+/// identify the network. The remaining bits identify a host within the
+/// network. The address is stored as given, so an `Ipv4Net` can also describe
+/// an interface address together with its subnet, like `192.168.1.7/24`; use
+/// [`trunc`](Ipv4Net::trunc) to get the network itself.
+///
+/// # Textual representation
+///
+/// `Ipv4Net` provides a [`FromStr`] implementation for the CIDR notation
+/// described in [IETF RFC 4632], an IPv4 address followed by `/` and the prefix
+/// length in decimal, without leading zeros.
+///
+/// [IETF RFC 4632]: https://tools.ietf.org/html/rfc4632#section-3.1
+/// [`FromStr`]: crate::str::FromStr
+///
+/// # Examples
+///
+/// ```
+/// #![feature(ip_net)]
+/// use std::net::{Ipv4Addr, Ipv4Net};
+///
+/// let net = Ipv4Net::new(Ipv4Addr::new(192, 168, 1, 7), 24).unwrap();
+/// assert_eq!("192.168.1.7/24".parse(), Ok(net));
+///
+/// assert_eq!(net.network(), Ipv4Addr::new(192, 168, 1, 0));
+/// assert_eq!(net.broadcast(), Ipv4Addr::new(192, 168, 1, 255));
+/// assert!(net.contains(Ipv4Addr::new(192, 168, 1, 200)));
+/// ```
+#[unstable(feature = "ip_net", issue = "none")]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct Ipv4Net {
+    addr: Ipv4Addr,
+    prefix_len: u8,
+}
+
+/// An IPv6 network, given by an address and a prefix length.
+///
+/// See [`Ipv4Net`] for how the address and prefix length are interpreted.
+///
+/// # Textual representation
+///
+/// `Ipv6Net` provides a [`FromStr`] implementation for the notation described
+/// in [IETF RFC 4291], an IPv6 address followed by `/` and the prefix length
+/// in decimal, without leading zeros.
+///
+/// [IETF RFC 4291]: https://tools.ietf.org/html/rfc4291#section-2.3
+/// [`FromStr`]: crate::str::FromStr
+///
+/// # Examples
+///
+/// ```
+/// #![feature(ip_net)]
+/// use std::net::{Ipv6Addr, Ipv6Net};
+///
+/// let net = Ipv6Net::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 32).unwrap();
+/// assert_eq!("2001:db8::/32".parse(), Ok(net));
+///
+/// assert!(net.contains(Ipv6Addr::new(0x2001, 0xdb8, 1, 2, 3, 4, 5, 6)));
+/// ```
+#[unstable(feature = "ip_net", issue = "none")]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct Ipv6Net {
+    addr: Ipv6Addr,
+    prefix_len: u8,
+}
+
+/// An error returned when a prefix length is longer than the address it
+/// applies to.
+#[unstable(feature = "ip_net", issue = "none")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PrefixLenError;
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl fmt::Display for PrefixLenError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("invalid IP prefix length")
+    }
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl Error for PrefixLenError {}
+
+impl IpNet {
+    /// Creates a new IP network from an address and a prefix length.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PrefixLenError`] if `prefix_len` exceeds 32 for an IPv4
+    /// address or 128 for an IPv6 address.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[inline]
+    pub const fn new(addr: IpAddr, prefix_len: u8) -> Result<IpNet, PrefixLenError> {
+        match addr {
+            IpAddr::V4(addr) => match Ipv4Net::new(addr, prefix_len) {
+                Ok(net) => Ok(IpNet::V4(net)),
+                Err(e) => Err(e),
+            },
+            IpAddr::V6(addr) => match Ipv6Net::new(addr, prefix_len) {
+                Ok(net) => Ok(IpNet::V6(net)),
+                Err(e) => Err(e),
+            },
+        }
+    }
+
+    /// Returns the address this network was created with.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn addr(&self) -> IpAddr {
+        match self {
+            IpNet::V4(net) => IpAddr::V4(net.addr()),
+            IpNet::V6(net) => IpAddr::V6(net.addr()),
+        }
+    }
+
+    /// Returns the prefix length of this network.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn prefix_len(&self) -> u8 {
+        match self {
+            IpNet::V4(net) => net.prefix_len(),
+            IpNet::V6(net) => net.prefix_len(),
+        }
+    }
+
+    /// Returns the maximum prefix length for this network's address family,
+    /// 32 for IPv4 and 128 for IPv6.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn max_prefix_len(&self) -> u8 {
+        match self {
+            IpNet::V4(net) => net.max_prefix_len(),
+            IpNet::V6(net) => net.max_prefix_len(),
+        }
+    }
+
+    /// Returns the network mask, with the leading [`prefix_len`] bits set.
+    ///
+    /// [`prefix_len`]: IpNet::prefix_len
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn netmask(&self) -> IpAddr {
+        match self {
+            IpNet::V4(net) => IpAddr::V4(net.netmask()),
+            IpNet::V6(net) => IpAddr::V6(net.netmask()),
+        }
+    }
+
+    /// Returns the host mask, the inverse of the [`netmask`](IpNet::netmask).
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn hostmask(&self) -> IpAddr {
+        match self {
+            IpNet::V4(net) => IpAddr::V4(net.hostmask()),
+            IpNet::V6(net) => IpAddr::V6(net.hostmask()),
+        }
+    }
+
+    /// Returns the network address, the first address in this network.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn network(&self) -> IpAddr {
+        match self {
+            IpNet::V4(net) => IpAddr::V4(net.network()),
+            IpNet::V6(net) => IpAddr::V6(net.network()),
+        }
+    }
+
+    /// Returns the broadcast address, the last address in this network.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn broadcast(&self) -> IpAddr {
+        match self {
+            IpNet::V4(net) => IpAddr::V4(net.broadcast()),
+            IpNet::V6(net) => IpAddr::V6(net.broadcast()),
+        }
+    }
+
+    /// Returns this network with the host bits of its address cleared.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn trunc(&self) -> IpNet {
+        match self {
+            IpNet::V4(net) => IpNet::V4(net.trunc()),
+            IpNet::V6(net) => IpNet::V6(net.trunc()),
+        }
+    }
+
+    /// Returns the network one bit shorter than this one, or [`None`] if the
+    /// prefix length is already zero.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn supernet(&self) -> Option<IpNet> {
+        match self {
+            IpNet::V4(net) => match net.supernet() {
+                Some(net) => Some(IpNet::V4(net)),
+                None => None,
+            },
+            IpNet::V6(net) => match net.supernet() {
+                Some(net) => Some(IpNet::V6(net)),
+                None => None,
+            },
+        }
+    }
+
+    /// Returns [`true`] if `addr` is within this network. An address of the
+    /// other family is never contained.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn contains(&self, addr: IpAddr) -> bool {
+        match (self, addr) {
+            (IpNet::V4(net), IpAddr::V4(addr)) => net.contains(addr),
+            (IpNet::V6(net), IpAddr::V6(addr)) => net.contains(addr),
+            _ => false,
+        }
+    }
+
+    /// Returns [`true`] if `other` lies entirely within this network. A
+    /// network of the other family is never contained.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn contains_net(&self, other: &IpNet) -> bool {
+        match (self, other) {
+            (IpNet::V4(net), IpNet::V4(other)) => net.contains_net(other),
+            (IpNet::V6(net), IpNet::V6(other)) => net.contains_net(other),
+            _ => false,
+        }
+    }
+
+    /// Returns [`true`] if this is an IPv4 network.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn is_ipv4(&self) -> bool {
+        matches!(self, IpNet::V4(_))
+    }
+
+    /// Returns [`true`] if this is an IPv6 network.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn is_ipv6(&self) -> bool {
+        matches!(self, IpNet::V6(_))
+    }
+}
+
+impl Ipv4Net {
+    /// Creates a new IPv4 network from an address and a prefix length.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PrefixLenError`] if `prefix_len` exceeds 32.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(ip_net)]
+    /// use std::net::{Ipv4Addr, Ipv4Net};
+    ///
+    /// assert!(Ipv4Net::new(Ipv4Addr::new(10, 0, 0, 0), 8).is_ok());
+    /// assert!(Ipv4Net::new(Ipv4Addr::new(10, 0, 0, 0), 33).is_err());
+    /// ```
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[inline]
+    pub const fn new(addr: Ipv4Addr, prefix_len: u8) -> Result<Ipv4Net, PrefixLenError> {
+        if prefix_len > 32 {
+            return Err(PrefixLenError);
+        }
+        Ok(Ipv4Net { addr, prefix_len })
+    }
+
+    /// Returns the address this network was created with.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn addr(&self) -> Ipv4Addr {
+        self.addr
+    }
+
+    /// Returns the prefix length of this network.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Returns the maximum prefix length of an IPv4 network, 32.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn max_prefix_len(&self) -> u8 {
+        32
+    }
+
+    /// Returns the network mask, with the leading [`prefix_len`] bits set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(ip_net)]
+    /// use std::net::{Ipv4Addr, Ipv4Net};
+    ///
+    /// let net: Ipv4Net = "172.16.0.0/12".parse().unwrap();
+    /// assert_eq!(net.netmask(), Ipv4Addr::new(255, 240, 0, 0));
+    /// assert_eq!(net.hostmask(), Ipv4Addr::new(0, 15, 255, 255));
+    /// ```
+    ///
+    /// [`prefix_len`]: Ipv4Net::prefix_len
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn netmask(&self) -> Ipv4Addr {
+        Ipv4Addr::from_bits(self.netmask_bits())
+    }
+
+    /// Returns the host mask, the inverse of the [`netmask`](Ipv4Net::netmask).
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn hostmask(&self) -> Ipv4Addr {
+        Ipv4Addr::from_bits(!self.netmask_bits())
+    }
+
+    /// Returns the network address, the first address in this network.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn network(&self) -> Ipv4Addr {
+        Ipv4Addr::from_bits(self.addr.to_bits() & self.netmask_bits())
+    }
+
+    /// Returns the broadcast address, the last address in this network.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn broadcast(&self) -> Ipv4Addr {
+        Ipv4Addr::from_bits(self.addr.to_bits() | !self.netmask_bits())
+    }
+
+    /// Returns this network with the host bits of its address cleared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(ip_net)]
+    /// use std::net::Ipv4Net;
+    ///
+    /// let net: Ipv4Net = "192.168.1.7/24".parse().unwrap();
+    /// assert_eq!(net.trunc().to_string(), "192.168.1.0/24");
+    /// ```
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn trunc(&self) -> Ipv4Net {
+        Ipv4Net { addr: self.network(), prefix_len: self.prefix_len }
+    }
+
+    /// Returns the network one bit shorter than this one, or [`None`] if the
+    /// prefix length is already zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(ip_net)]
+    /// use std::net::Ipv4Net;
+    ///
+    /// let net: Ipv4Net = "10.1.0.0/16".parse().unwrap();
+    /// assert_eq!(net.supernet(), "10.0.0.0/15".parse().ok());
+    /// ```
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn supernet(&self) -> Option<Ipv4Net> {
+        if self.prefix_len == 0 {
+            return None;
+        }
+        Some(Ipv4Net { addr: self.addr, prefix_len: self.prefix_len - 1 }.trunc())
+    }
+
+    /// Returns [`true`] if `other` has the same prefix length and the two
+    /// networks together form their common [`supernet`](Ipv4Net::supernet).
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn is_sibling(&self, other: &Ipv4Net) -> bool {
+        self.prefix_len > 0
+            && self.prefix_len == other.prefix_len
+            && self.network().to_bits() != other.network().to_bits()
+            && Ipv4Net { addr: self.addr, prefix_len: self.prefix_len - 1 }.contains(other.addr)
+    }
+
+    /// Returns [`true`] if `addr` is within this network.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn contains(&self, addr: Ipv4Addr) -> bool {
+        addr.to_bits() & self.netmask_bits() == self.network().to_bits()
+    }
+
+    /// Returns [`true`] if `other` lies entirely within this network.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(ip_net)]
+    /// use std::net::Ipv4Net;
+    ///
+    /// let net: Ipv4Net = "10.0.0.0/8".parse().unwrap();
+    /// assert!(net.contains_net(&"10.20.0.0/16".parse().unwrap()));
+    /// assert!(!net.contains_net(&"0.0.0.0/0".parse().unwrap()));
+    /// ```
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn contains_net(&self, other: &Ipv4Net) -> bool {
+        self.prefix_len <= other.prefix_len && self.contains(other.addr)
+    }
+
+    /// Returns an iterator over the usable host addresses in this network.
+    ///
+    /// The network and broadcast addresses are excluded, except for `/31`
+    /// and `/32` networks, where every address is a host as per
+    /// [IETF RFC 3021].
+    ///
+    /// [IETF RFC 3021]: https://tools.ietf.org/html/rfc3021
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(ip_net)]
+    /// use std::net::{Ipv4Addr, Ipv4Net};
+    ///
+    /// let net: Ipv4Net = "192.168.0.0/30".parse().unwrap();
+    /// assert!(net.hosts().eq([Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2)]));
+    /// ```
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[inline]
+    pub fn hosts(&self) -> Ipv4Hosts {
+        let (start, end) = (self.network().to_bits(), self.broadcast().to_bits());
+        if self.prefix_len >= 31 {
+            Ipv4Hosts { start, end, done: false }
+        } else {
+            Ipv4Hosts { start: start + 1, end: end - 1, done: false }
+        }
+    }
+
+    /// Returns an iterator over the subnets of this network with the given
+    /// prefix length.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PrefixLenError`] if `new_prefix_len` is shorter than this
+    /// network's prefix length or exceeds 32.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(ip_net)]
+    /// use std::net::Ipv4Net;
+    ///
+    /// let net: Ipv4Net = "10.0.0.0/23".parse().unwrap();
+    /// let subnets: Vec<String> = net.subnets(24).unwrap().map(|net| net.to_string()).collect();
+    /// assert_eq!(subnets, ["10.0.0.0/24", "10.0.1.0/24"]);
+    /// ```
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[inline]
+    pub fn subnets(&self, new_prefix_len: u8) -> Result<Ipv4Subnets, PrefixLenError> {
+        if new_prefix_len < self.prefix_len || new_prefix_len > 32 {
+            return Err(PrefixLenError);
+        }
+        let last = Ipv4Net { addr: self.broadcast(), prefix_len: new_prefix_len }.network();
+        Ok(Ipv4Subnets {
+            start: self.network().to_bits(),
+            end: last.to_bits(),
+            prefix_len: new_prefix_len,
+            done: false,
+        })
+    }
+
+    /// Aggregates `networks` into the smallest set of networks covering the
+    /// same addresses.
+    ///
+    /// Networks contained in other networks are dropped, and sibling networks
+    /// are merged into their supernet as long as possible. The result is
+    /// sorted, moved to the front of `networks` and returned as a subslice;
+    /// the order of the remaining elements is unspecified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(ip_net)]
+    /// use std::net::Ipv4Net;
+    ///
+    /// let mut networks: Vec<Ipv4Net> = ["10.0.1.0/24", "10.0.0.0/24", "10.0.0.128/25", "10.1.0.0/16"]
+    ///     .iter()
+    ///     .map(|s| s.parse().unwrap())
+    ///     .collect();
+    /// let aggregated = Ipv4Net::aggregate(&mut networks);
+    /// assert_eq!(aggregated, ["10.0.0.0/23".parse().unwrap(), "10.1.0.0/16".parse().unwrap()]);
+    /// ```
+    #[unstable(feature = "ip_net", issue = "none")]
+    pub fn aggregate(networks: &mut [Ipv4Net]) -> &mut [Ipv4Net] {
+        for net in networks.iter_mut() {
+            *net = net.trunc();
+        }
+        let len = aggregate_sorted(networks, Ipv4Net::contains_net, Ipv4Net::is_sibling, |net| {
+            net.supernet()
+        });
+        &mut networks[..len]
+    }
+
+    #[inline]
+    const fn netmask_bits(&self) -> u32 {
+        if self.prefix_len == 0 { 0 } else { u32::MAX << (32 - self.prefix_len) }
+    }
+}
+
+impl Ipv6Net {
+    /// Creates a new IPv6 network from an address and a prefix length.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PrefixLenError`] if `prefix_len` exceeds 128.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[inline]
+    pub const fn new(addr: Ipv6Addr, prefix_len: u8) -> Result<Ipv6Net, PrefixLenError> {
+        if prefix_len > 128 {
+            return Err(PrefixLenError);
+        }
+        Ok(Ipv6Net { addr, prefix_len })
+    }
+
+    /// Returns the address this network was created with.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn addr(&self) -> Ipv6Addr {
+        self.addr
+    }
+
+    /// Returns the prefix length of this network.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Returns the maximum prefix length of an IPv6 network, 128.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn max_prefix_len(&self) -> u8 {
+        128
+    }
+
+    /// Returns the network mask, with the leading [`prefix_len`] bits set.
+    ///
+    /// [`prefix_len`]: Ipv6Net::prefix_len
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn netmask(&self) -> Ipv6Addr {
+        Ipv6Addr::from_bits(self.netmask_bits())
+    }
+
+    /// Returns the host mask, the inverse of the [`netmask`](Ipv6Net::netmask).
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn hostmask(&self) -> Ipv6Addr {
+        Ipv6Addr::from_bits(!self.netmask_bits())
+    }
+
+    /// Returns the network address, the first address in this network.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn network(&self) -> Ipv6Addr {
+        Ipv6Addr::from_bits(self.addr.to_bits() & self.netmask_bits())
+    }
+
+    /// Returns the last address in this network.
+    ///
+    /// IPv6 has no broadcast addresses, the name mirrors
+    /// [`Ipv4Net::broadcast`].
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn broadcast(&self) -> Ipv6Addr {
+        Ipv6Addr::from_bits(self.addr.to_bits() | !self.netmask_bits())
+    }
+
+    /// Returns this network with the host bits of its address cleared.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn trunc(&self) -> Ipv6Net {
+        Ipv6Net { addr: self.network(), prefix_len: self.prefix_len }
+    }
+
+    /// Returns the network one bit shorter than this one, or [`None`] if the
+    /// prefix length is already zero.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn supernet(&self) -> Option<Ipv6Net> {
+        if self.prefix_len == 0 {
+            return None;
+        }
+        Some(Ipv6Net { addr: self.addr, prefix_len: self.prefix_len - 1 }.trunc())
+    }
+
+    /// Returns [`true`] if `other` has the same prefix length and the two
+    /// networks together form their common [`supernet`](Ipv6Net::supernet).
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn is_sibling(&self, other: &Ipv6Net) -> bool {
+        self.prefix_len > 0
+            && self.prefix_len == other.prefix_len
+            && self.network().to_bits() != other.network().to_bits()
+            && Ipv6Net { addr: self.addr, prefix_len: self.prefix_len - 1 }.contains(other.addr)
+    }
+
+    /// Returns [`true`] if `addr` is within this network.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn contains(&self, addr: Ipv6Addr) -> bool {
+        addr.to_bits() & self.netmask_bits() == self.network().to_bits()
+    }
+
+    /// Returns [`true`] if `other` lies entirely within this network.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[must_use]
+    #[inline]
+    pub const fn contains_net(&self, other: &Ipv6Net) -> bool {
+        self.prefix_len <= other.prefix_len && self.contains(other.addr)
+    }
+
+    /// Returns an iterator over all addresses in this network.
+    ///
+    /// Unlike for [`Ipv4Net::hosts`], no addresses are excluded.
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[inline]
+    pub fn hosts(&self) -> Ipv6Hosts {
+        Ipv6Hosts { start: self.network().to_bits(), end: self.broadcast().to_bits(), done: false }
+    }
+
+    /// Returns an iterator over the subnets of this network with the given
+    /// prefix length.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PrefixLenError`] if `new_prefix_len` is shorter than this
+    /// network's prefix length or exceeds 128.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(ip_net)]
+    /// use std::net::Ipv6Net;
+    ///
+    /// let net: Ipv6Net = "2001:db8::/32".parse().unwrap();
+    /// let mut subnets = net.subnets(48).unwrap();
+    /// assert_eq!(subnets.next(), "2001:db8::/48".parse().ok());
+    /// assert_eq!(subnets.next(), "2001:db8:1::/48".parse().ok());
+    /// ```
+    #[unstable(feature = "ip_net", issue = "none")]
+    #[inline]
+    pub fn subnets(&self, new_prefix_len: u8) -> Result<Ipv6Subnets, PrefixLenError> {
+        if new_prefix_len < self.prefix_len || new_prefix_len > 128 {
+            return Err(PrefixLenError);
+        }
+        let last = Ipv6Net { addr: self.broadcast(), prefix_len: new_prefix_len }.network();
+        Ok(Ipv6Subnets {
+            start: self.network().to_bits(),
+            end: last.to_bits(),
+            prefix_len: new_prefix_len,
+            done: false,
+        })
+    }
+
+    /// Aggregates `networks` into the smallest set of networks covering the
+    /// same addresses.
+    ///
+    /// See [`Ipv4Net::aggregate`] for details.
+    #[unstable(feature = "ip_net", issue = "none")]
+    pub fn aggregate(networks: &mut [Ipv6Net]) -> &mut [Ipv6Net] {
+        for net in networks.iter_mut() {
+            *net = net.trunc();
+        }
+        let len = aggregate_sorted(networks, Ipv6Net::contains_net, Ipv6Net::is_sibling, |net| {
+            net.supernet()
+        });
+        &mut networks[..len]
+    }
+
+    #[inline]
+    const fn netmask_bits(&self) -> u128 {
+        if self.prefix_len == 0 { 0 } else { u128::MAX << (128 - self.prefix_len) }
+    }
+}
+
+/// Sorts the truncated `networks`, then drops contained networks and merges
+/// siblings in place. Returns the number of networks left at the front.
+fn aggregate_sorted<N: Copy + Ord>(
+    networks: &mut [N],
+    contains_net: impl Fn(&N, &N) -> bool,
+    is_sibling: impl Fn(&N, &N) -> bool,
+    supernet: impl Fn(&N) -> Option<N>,
+) -> usize {
+    // Sorting by address and then prefix length places every network right
+    // after the networks containing it.
+    networks.sort_unstable();
+
+    // `networks[..len]` is used as a stack of disjoint, sorted networks.
+    let mut len = 0;
+    for i in 0..networks.len() {
+        let net = networks[i];
+        if len > 0 && contains_net(&networks[len - 1], &net) {
+            continue;
+        }
+        networks[len] = net;
+        len += 1;
+
+        // A merged supernet may in turn be the sibling of the network
+        // before it.
+        while len >= 2 && is_sibling(&networks[len - 2], &networks[len - 1]) {
+            match supernet(&networks[len - 2]) {
+                Some(merged) => {
+                    networks[len - 2] = merged;
+                    len -= 1;
+                }
+                None => break,
+            }
+        }
+    }
+    len
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl fmt::Display for IpNet {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IpNet::V4(net) => net.fmt(fmt),
+            IpNet::V6(net) => net.fmt(fmt),
+        }
+    }
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl fmt::Debug for IpNet {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, fmt)
+    }
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl fmt::Display for Ipv4Net {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // If there are no alignment requirements, write the network directly to `f`.
+        // Otherwise, write it to a local buffer and then use `f.pad`.
+        if fmt.precision().is_none() && fmt.width().is_none() {
+            write!(fmt, "{}/{}", self.addr, self.prefix_len)
+        } else {
+            const LONGEST_IPV4_NET: &str = "255.255.255.255/32";
+
+            let mut buf = DisplayBuffer::<{ LONGEST_IPV4_NET.len() }>::new();
+            // Buffer is long enough for the longest possible IPv4 network, so this should never fail.
+            write!(buf, "{}/{}", self.addr, self.prefix_len).unwrap();
+
+            fmt.pad(buf.as_str())
+        }
+    }
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl fmt::Debug for Ipv4Net {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, fmt)
+    }
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl fmt::Display for Ipv6Net {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // If there are no alignment requirements, write the network directly to `f`.
+        // Otherwise, write it to a local buffer and then use `f.pad`.
+        if fmt.precision().is_none() && fmt.width().is_none() {
+            write!(fmt, "{}/{}", self.addr, self.prefix_len)
+        } else {
+            const LONGEST_IPV6_NET: &str = "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff/128";
+
+            let mut buf = DisplayBuffer::<{ LONGEST_IPV6_NET.len() }>::new();
+            // Buffer is long enough for the longest possible IPv6 network, so this should never fail.
+            write!(buf, "{}/{}", self.addr, self.prefix_len).unwrap();
+
+            fmt.pad(buf.as_str())
+        }
+    }
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl fmt::Debug for Ipv6Net {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, fmt)
+    }
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl From<Ipv4Net> for IpNet {
+    #[inline]
+    fn from(net: Ipv4Net) -> IpNet {
+        IpNet::V4(net)
+    }
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl From<Ipv6Net> for IpNet {
+    #[inline]
+    fn from(net: Ipv6Net) -> IpNet {
+        IpNet::V6(net)
+    }
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl From<Ipv4Addr> for Ipv4Net {
+    /// Creates a `/32` network containing only `addr`.
+    #[inline]
+    fn from(addr: Ipv4Addr) -> Ipv4Net {
+        Ipv4Net { addr, prefix_len: 32 }
+    }
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl From<Ipv6Addr> for Ipv6Net {
+    /// Creates a `/128` network containing only `addr`.
+    #[inline]
+    fn from(addr: Ipv6Addr) -> Ipv6Net {
+        Ipv6Net { addr, prefix_len: 128 }
+    }
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl From<IpAddr> for IpNet {
+    /// Creates a network containing only `addr`.
+    #[inline]
+    fn from(addr: IpAddr) -> IpNet {
+        match addr {
+            IpAddr::V4(addr) => IpNet::V4(addr.into()),
+            IpAddr::V6(addr) => IpNet::V6(addr.into()),
+        }
+    }
+}
+
+/// An iterator over the host addresses of an [`Ipv4Net`].
+///
+/// This `struct` is created by the [`Ipv4Net::hosts`] method.
+#[unstable(feature = "ip_net", issue = "none")]
+#[derive(Clone, Debug)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Ipv4Hosts {
+    start: u32,
+    end: u32,
+    done: bool,
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl Iterator for Ipv4Hosts {
+    type Item = Ipv4Addr;
+
+    fn next(&mut self) -> Option<Ipv4Addr> {
+        if self.done {
+            return None;
+        }
+        let addr = self.start;
+        if addr == self.end {
+            self.done = true;
+        } else {
+            self.start += 1;
+        }
+        Some(Ipv4Addr::from_bits(addr))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = if self.done { 0 } else { u64::from(self.end - self.start) + 1 };
+        match usize::try_from(len) {
+            Ok(len) => (len, Some(len)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl DoubleEndedIterator for Ipv4Hosts {
+    fn next_back(&mut self) -> Option<Ipv4Addr> {
+        if self.done {
+            return None;
+        }
+        let addr = self.end;
+        if addr == self.start {
+            self.done = true;
+        } else {
+            self.end -= 1;
+        }
+        Some(Ipv4Addr::from_bits(addr))
+    }
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl FusedIterator for Ipv4Hosts {}
+
+/// An iterator over the addresses of an [`Ipv6Net`].
+///
+/// This `struct` is created by the [`Ipv6Net::hosts`] method.
+#[unstable(feature = "ip_net", issue = "none")]
+#[derive(Clone, Debug)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Ipv6Hosts {
+    start: u128,
+    end: u128,
+    done: bool,
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl Iterator for Ipv6Hosts {
+    type Item = Ipv6Addr;
+
+    fn next(&mut self) -> Option<Ipv6Addr> {
+        if self.done {
+            return None;
+        }
+        let addr = self.start;
+        if addr == self.end {
+            self.done = true;
+        } else {
+            self.start += 1;
+        }
+        Some(Ipv6Addr::from_bits(addr))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            return (0, Some(0));
+        }
+        match usize::try_from(self.end - self.start).ok().and_then(|len| len.checked_add(1)) {
+            Some(len) => (len, Some(len)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl DoubleEndedIterator for Ipv6Hosts {
+    fn next_back(&mut self) -> Option<Ipv6Addr> {
+        if self.done {
+            return None;
+        }
+        let addr = self.end;
+        if addr == self.start {
+            self.done = true;
+        } else {
+            self.end -= 1;
+        }
+        Some(Ipv6Addr::from_bits(addr))
+    }
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl FusedIterator for Ipv6Hosts {}
+
+/// An iterator over the subnets of an [`Ipv4Net`].
+///
+/// This `struct` is created by the [`Ipv4Net::subnets`] method.
+#[unstable(feature = "ip_net", issue = "none")]
+#[derive(Clone, Debug)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Ipv4Subnets {
+    start: u32,
+    end: u32,
+    prefix_len: u8,
+    done: bool,
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl Iterator for Ipv4Subnets {
+    type Item = Ipv4Net;
+
+    fn next(&mut self) -> Option<Ipv4Net> {
+        if self.done {
+            return None;
+        }
+        let net = Ipv4Net { addr: Ipv4Addr::from_bits(self.start), prefix_len: self.prefix_len };
+        if self.start == self.end {
+            self.done = true;
+        } else {
+            self.start += 1 << (32 - self.prefix_len);
+        }
+        Some(net)
+    }
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl FusedIterator for Ipv4Subnets {}
+
+/// An iterator over the subnets of an [`Ipv6Net`].
+///
+/// This `struct` is created by the [`Ipv6Net::subnets`] method.
+#[unstable(feature = "ip_net", issue = "none")]
+#[derive(Clone, Debug)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Ipv6Subnets {
+    start: u128,
+    end: u128,
+    prefix_len: u8,
+    done: bool,
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl Iterator for Ipv6Subnets {
+    type Item = Ipv6Net;
+
+    fn next(&mut self) -> Option<Ipv6Net> {
+        if self.done {
+            return None;
+        }
+        let net = Ipv6Net { addr: Ipv6Addr::from_bits(self.start), prefix_len: self.prefix_len };
+        if self.start == self.end {
+            self.done = true;
+        } else {
+            self.start += 1 << (128 - self.prefix_len);
+        }
+        Some(net)
+    }
+}
+
+#[unstable(feature = "ip_net", issue = "none")]
+impl FusedIterator for Ipv6Subnets {}
@@ -8,11 +8,17 @@
 //!   [`Ipv6Addr`] are respectively IPv4 and IPv6 addresses
 //! * [`SocketAddr`] represents socket addresses of either IPv4 or IPv6; [`SocketAddrV4`]
 //!   and [`SocketAddrV6`] are respectively IPv4 and IPv6 socket addresses
+//! * [`IpNet`] represents IP networks of either IPv4 or IPv6; [`Ipv4Net`] and
+//!   [`Ipv6Net`] are respectively IPv4 and IPv6 networks in CIDR notation
 
 #![stable(feature = "ip_in_core", since = "1.77.0")]
 
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::ip_addr::{IpAddr, Ipv4Addr, Ipv6Addr, Ipv6MulticastScope};
+#[unstable(feature = "ip_net", issue = "none")]
+pub use self::ip_net::{
+    IpNet, Ipv4Hosts, Ipv4Net, Ipv4Subnets, Ipv6Hosts, Ipv6Net, Ipv6Subnets, PrefixLenError,
+};
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::parser::AddrParseError;
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -20,5 +26,6 @@
 
 mod display_buffer;
 mod ip_addr;
+mod ip_net;
 mod parser;
 mod socket_addr;
@@ -56,6 +56,11 @@ const fn memchr_aligned(x: u8, text: &[u8]) -> Option<usize> {
         if const {
             memchr_naive(x, text)
         } else {
+            #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+            if text.len() >= vectorized::Block::LEN {
+                return vectorized::memchr(x, text);
+            }
+
             // Scan for a single byte value by reading two `usize` words at a time.
             //
             // Split `text` in three parts
@@ -109,6 +114,11 @@ const fn memchr_aligned(x: u8, text: &[u8]) -> Option<usize> {
 /// Returns the last index matching the byte `x` in `text`.
 #[must_use]
 pub fn memrchr(x: u8, text: &[u8]) -> Option<usize> {
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    if text.len() >= vectorized::Block::LEN {
+        return vectorized::memrchr(x, text);
+    }
+
     // Scan for a single byte value by reading two `usize` words at a time.
     //
     // Split `text` in three parts:
@@ -159,3 +169,165 @@ pub fn memrchr(x: u8, text: &[u8]) -> Option<usize> {
     // Find the byte before the point the body loop stopped.
     text[..offset].iter().rposition(|elt| *elt == x)
 }
+
+/// Returns the first index matching either of the bytes `x1` or `x2` in `text`.
+#[inline]
+#[must_use]
+pub fn memchr2(x1: u8, x2: u8, text: &[u8]) -> Option<usize> {
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    if text.len() >= vectorized::Block::LEN {
+        return vectorized::memchr2(x1, x2, text);
+    }
+
+    text.iter().position(|&b| b == x1 || b == x2)
+}
+
+/// Returns the first index matching any of the bytes `x1`, `x2` or `x3` in `text`.
+#[inline]
+#[must_use]
+pub fn memchr3(x1: u8, x2: u8, x3: u8, text: &[u8]) -> Option<usize> {
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    if text.len() >= vectorized::Block::LEN {
+        return vectorized::memchr3(x1, x2, x3, text);
+    }
+
+    text.iter().position(|&b| b == x1 || b == x2 || b == x3)
+}
+
+/// Vectorized searches, used at runtime on x86_64, where SSE2 is part of the baseline.
+/// Other targets with baseline 128-bit vectors, like LoongArch with LSX, keep using the
+/// word-at-a-time search until they have been benchmarked.
+///
+/// All of them compare a whole `Block` of the text against splatted needles at once
+/// and only locate the exact index once a block reported a match. The text must be
+/// at least one block long: the unaligned remainder at the end (or the start, for
+/// `memrchr`) is handled by re-reading an overlapping block that ends flush with the
+/// text, which is fine since the bytes already covered are known not to match.
+///
+/// As in `str::pattern::simd_contains`, wider vectors are not used because they are
+/// not available without runtime feature detection, which core cannot do.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+mod vectorized {
+    use crate::simd::cmp::SimdPartialEq;
+    use crate::simd::mask8x16 as Mask;
+    pub(super) use crate::simd::u8x16 as Block;
+
+    #[inline]
+    pub(super) fn memchr(x: u8, text: &[u8]) -> Option<usize> {
+        let x = Block::splat(x);
+        forward_search(text, |block| block.simd_eq(x))
+    }
+
+    #[inline]
+    pub(super) fn memrchr(x: u8, text: &[u8]) -> Option<usize> {
+        let x = Block::splat(x);
+        reverse_search(text, |block| block.simd_eq(x))
+    }
+
+    #[inline]
+    pub(super) fn memchr2(x1: u8, x2: u8, text: &[u8]) -> Option<usize> {
+        let (x1, x2) = (Block::splat(x1), Block::splat(x2));
+        forward_search(text, |block| block.simd_eq(x1) | block.simd_eq(x2))
+    }
+
+    #[inline]
+    pub(super) fn memchr3(x1: u8, x2: u8, x3: u8, text: &[u8]) -> Option<usize> {
+        let (x1, x2, x3) = (Block::splat(x1), Block::splat(x2), Block::splat(x3));
+        forward_search(text, |block| block.simd_eq(x1) | block.simd_eq(x2) | block.simd_eq(x3))
+    }
+
+    /// Reads the block starting at `offset`.
+    ///
+    /// # Safety
+    ///
+    /// `offset + Block::LEN` must not exceed `text.len()`.
+    #[inline(always)]
+    unsafe fn load(text: &[u8], offset: usize) -> Block {
+        debug_assert!(offset + Block::LEN <= text.len());
+        // SAFETY: the caller guarantees that the whole block lies within `text`.
+        unsafe { text.as_ptr().add(offset).cast::<Block>().read_unaligned() }
+    }
+
+    /// Returns the index of the first byte in `text` for which `matches` sets the
+    /// corresponding lane.
+    #[inline(always)]
+    fn forward_search(text: &[u8], matches: impl Fn(Block) -> Mask) -> Option<usize> {
+        let len = text.len();
+        debug_assert!(len >= Block::LEN);
+        let first_in = |mask: Mask| mask.to_bitmask().trailing_zeros() as usize;
+
+        let mut offset = 0;
+        // Check two blocks per iteration, which keeps the loop-carried work to a
+        // single branch on the combined mask.
+        while offset + 2 * Block::LEN <= len {
+            // SAFETY: the loop condition guarantees two whole blocks at `offset`.
+            let (a, b) =
+                unsafe { (matches(load(text, offset)), matches(load(text, offset + Block::LEN))) };
+            if (a | b).any() {
+                return Some(if a.any() {
+                    offset + first_in(a)
+                } else {
+                    offset + Block::LEN + first_in(b)
+                });
+            }
+            offset += 2 * Block::LEN;
+        }
+        if offset + Block::LEN <= len {
+            // SAFETY: checked by the condition above.
+            let a = matches(unsafe { load(text, offset) });
+            if a.any() {
+                return Some(offset + first_in(a));
+            }
+            offset += Block::LEN;
+        }
+        if offset < len {
+            offset = len - Block::LEN;
+            // SAFETY: `text` is at least one block long.
+            let a = matches(unsafe { load(text, offset) });
+            if a.any() {
+                return Some(offset + first_in(a));
+            }
+        }
+        None
+    }
+
+    /// Returns the index of the last byte in `text` for which `matches` sets the
+    /// corresponding lane.
+    #[inline(always)]
+    fn reverse_search(text: &[u8], matches: impl Fn(Block) -> Mask) -> Option<usize> {
+        debug_assert!(text.len() >= Block::LEN);
+        let last_in = |mask: Mask| 63 - mask.to_bitmask().leading_zeros() as usize;
+
+        let mut end = text.len();
+        while end >= 2 * Block::LEN {
+            // SAFETY: the loop condition guarantees two whole blocks before `end`.
+            let (a, b) = unsafe {
+                (matches(load(text, end - 2 * Block::LEN)), matches(load(text, end - Block::LEN)))
+            };
+            if (a | b).any() {
+                return Some(if b.any() {
+                    end - Block::LEN + last_in(b)
+                } else {
+                    end - 2 * Block::LEN + last_in(a)
+                });
+            }
+            end -= 2 * Block::LEN;
+        }
+        if end >= Block::LEN {
+            // SAFETY: checked by the condition above.
+            let b = matches(unsafe { load(text, end - Block::LEN) });
+            if b.any() {
+                return Some(end - Block::LEN + last_in(b));
+            }
+            end -= Block::LEN;
+        }
+        if end > 0 {
+            // SAFETY: `text` is at least one block long.
+            let a = matches(unsafe { load(text, 0) });
+            if a.any() {
+                return Some(last_in(a));
+            }
+        }
+        None
+    }
+}
@@ -1689,3 +1689,70 @@ pub fn try_from_secs_f64(secs: f64) -> Result<Duration, TryFromFloatSecsError> {
         )
     }
 }
+
+/// Days between 0000-03-01 and 1970-01-01 in the proleptic Gregorian calendar.
+const DAYS_FROM_0000_03_01_TO_UNIX_EPOCH: i64 = 719_468;
+/// Days in a 400 year cycle of the Gregorian calendar.
+const DAYS_PER_ERA: i64 = 146_097;
+
+/// Converts a number of days since 1970-01-01 to a `(year, month, day)` date in
+/// the proleptic Gregorian calendar, with `month` and `day` starting at 1.
+///
+/// This follows Howard Hinnant's `civil_from_days` algorithm, shifting the
+/// start of the year to March so that the leap day is the last day of the year.
+/// `days` must be within `±2^62` so that the intermediate values don't
+/// overflow.
+#[unstable(feature = "time_civil_internals", reason = "exposed only for libstd", issue = "none")]
+#[doc(hidden)]
+#[inline]
+pub const fn days_to_civil(days: i64) -> (i64, u8, u8) {
+    let days = days + DAYS_FROM_0000_03_01_TO_UNIX_EPOCH;
+    let era = days.div_euclid(DAYS_PER_ERA);
+    // [0, 146096]
+    let day_of_era = days.rem_euclid(DAYS_PER_ERA);
+    // [0, 399]
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    // [0, 365], counting from March 1st
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    // [0, 11], counting from March
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u8;
+    let month = if month_from_march < 10 { month_from_march + 3 } else { month_from_march - 9 };
+    let year = era * 400 + year_of_era + if month <= 2 { 1 } else { 0 };
+    (year, month as u8, day)
+}
+
+/// Converts a `(year, month, day)` date in the proleptic Gregorian calendar to
+/// a number of days since 1970-01-01, the inverse of [`days_to_civil`].
+///
+/// Returns `None` if `month` or `day` is out of range for the given year.
+/// `year` must be within `±2^53` so that the intermediate values don't
+/// overflow.
+#[unstable(feature = "time_civil_internals", reason = "exposed only for libstd", issue = "none")]
+#[doc(hidden)]
+#[inline]
+pub const fn civil_to_days(year: i64, month: u8, day: u8) -> Option<i64> {
+    if month < 1 || month > 12 || day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+    let (month, day) = (month as i64, day as i64);
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    // [0, 399]
+    let year_of_era = year.rem_euclid(400);
+    // [0, 365], counting from March 1st
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    // [0, 146096]
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    Some(era * DAYS_PER_ERA + day_of_era - DAYS_FROM_0000_03_01_TO_UNIX_EPOCH)
+}
+
+const fn days_in_month(year: i64, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
@@ -171,3 +171,32 @@ fn fold_to_last(b: &mut Bencher) {
     let slice: &[i32] = &[0; 1024];
     b.iter(|| black_box(slice).iter().fold(None, |_, r| Some(NonNull::from(r))));
 }
+
+#[bench]
+fn memchr_absent_4k(b: &mut Bencher) {
+    let haystack = [b'a'; 4096];
+    b.bytes = haystack.len() as u64;
+    b.iter(|| core::slice::memchr::memchr(black_box(b'z'), black_box(&haystack)));
+}
+
+#[bench]
+fn memrchr_absent_4k(b: &mut Bencher) {
+    let haystack = [b'a'; 4096];
+    b.bytes = haystack.len() as u64;
+    b.iter(|| core::slice::memchr::memrchr(black_box(b'z'), black_box(&haystack)));
+}
+
+#[bench]
+fn memchr_at_end_64(b: &mut Bencher) {
+    let mut haystack = [b'a'; 64];
+    haystack[63] = b'z';
+    b.bytes = haystack.len() as u64;
+    b.iter(|| core::slice::memchr::memchr(black_box(b'z'), black_box(&haystack)));
+}
+
+#[bench]
+fn memchr3_absent_4k(b: &mut Bencher) {
+    let haystack = [b'a'; 4096];
+    b.bytes = haystack.len() as u64;
+    b.iter(|| core::slice::memchr::memchr3(b'x', b'y', black_box(b'z'), black_box(&haystack)));
+}
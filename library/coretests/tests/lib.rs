@@ -54,6 +54,7 @@
 #![feature(int_roundings)]
 #![feature(ip)]
 #![feature(ip_from)]
+#![feature(ip_net)]
 #![feature(is_ascii_octdigit)]
 #![feature(isolate_most_least_significant_one)]
 #![feature(iter_advance_by)]
@@ -0,0 +1,178 @@
+use core::net::{IpAddr, IpNet, Ipv4Addr, Ipv4Net, Ipv6Addr, Ipv6Net};
+
+fn v4(s: &str) -> Ipv4Net {
+    s.parse().unwrap()
+}
+
+fn v6(s: &str) -> Ipv6Net {
+    s.parse().unwrap()
+}
+
+#[test]
+fn parse_ipv4_net() {
+    assert_eq!(v4("192.168.1.7/24"), Ipv4Net::new(Ipv4Addr::new(192, 168, 1, 7), 24).unwrap());
+    assert_eq!(v4("0.0.0.0/0").prefix_len(), 0);
+    assert_eq!(v4("255.255.255.255/32").prefix_len(), 32);
+
+    assert!("192.168.1.0".parse::<Ipv4Net>().is_err());
+    assert!("192.168.1.0/".parse::<Ipv4Net>().is_err());
+    assert!("192.168.1.0/33".parse::<Ipv4Net>().is_err());
+    assert!("192.168.1.0/024".parse::<Ipv4Net>().is_err());
+    assert!("192.168.1.0/1000".parse::<Ipv4Net>().is_err());
+    assert!("192.168.1.0/24/24".parse::<Ipv4Net>().is_err());
+    assert!("::/0".parse::<Ipv4Net>().is_err());
+}
+
+#[test]
+fn parse_ipv6_net() {
+    let net = v6("2001:db8::1/64");
+    assert_eq!(net.addr(), Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+    assert_eq!(net.prefix_len(), 64);
+    assert_eq!(v6("::/128").prefix_len(), 128);
+
+    assert!("::/129".parse::<Ipv6Net>().is_err());
+    assert!("[::]/64".parse::<Ipv6Net>().is_err());
+    assert!("10.0.0.0/8".parse::<Ipv6Net>().is_err());
+}
+
+#[test]
+fn parse_ip_net() {
+    assert_eq!("10.0.0.0/8".parse(), Ok(IpNet::V4(v4("10.0.0.0/8"))));
+    assert_eq!("fe80::/10".parse(), Ok(IpNet::V6(v6("fe80::/10"))));
+    assert!("10.0.0.0/64".parse::<IpNet>().is_err());
+
+    let err = "foo".parse::<IpNet>().unwrap_err();
+    assert_eq!(err.to_string(), "invalid IP network syntax");
+}
+
+#[test]
+fn ip_net_to_string() {
+    assert_eq!(v4("192.168.1.7/24").to_string(), "192.168.1.7/24");
+    assert_eq!(v6("2001:db8:0:0::/32").to_string(), "2001:db8::/32");
+    assert_eq!(format!("{:>20}", v4("10.0.0.0/8")), "          10.0.0.0/8");
+    assert_eq!(format!("{:<10}|", v6("::/0")), "::/0      |");
+    assert_eq!(format!("{:?}", IpNet::from(v4("10.0.0.0/8"))), "10.0.0.0/8");
+}
+
+#[test]
+fn ipv4_net_masks() {
+    let net = v4("192.168.1.7/22");
+    assert_eq!(net.netmask(), Ipv4Addr::new(255, 255, 252, 0));
+    assert_eq!(net.hostmask(), Ipv4Addr::new(0, 0, 3, 255));
+    assert_eq!(net.network(), Ipv4Addr::new(192, 168, 0, 0));
+    assert_eq!(net.broadcast(), Ipv4Addr::new(192, 168, 3, 255));
+    assert_eq!(net.trunc(), v4("192.168.0.0/22"));
+
+    assert_eq!(v4("1.2.3.4/0").netmask(), Ipv4Addr::UNSPECIFIED);
+    assert_eq!(v4("1.2.3.4/32").netmask(), Ipv4Addr::BROADCAST);
+}
+
+#[test]
+fn ipv6_net_masks() {
+    let net = v6("2001:db8::1/64");
+    assert_eq!(net.netmask(), Ipv6Addr::new(0xffff, 0xffff, 0xffff, 0xffff, 0, 0, 0, 0));
+    assert_eq!(net.network(), Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0));
+    assert_eq!(net.broadcast(), Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0xffff, 0xffff, 0xffff, 0xffff));
+    assert_eq!(v6("::1/0").netmask(), Ipv6Addr::UNSPECIFIED);
+}
+
+#[test]
+fn ip_net_contains() {
+    let net = v4("10.0.0.0/8");
+    assert!(net.contains(Ipv4Addr::new(10, 255, 0, 1)));
+    assert!(!net.contains(Ipv4Addr::new(11, 0, 0, 0)));
+    assert!(net.contains_net(&v4("10.1.0.0/16")));
+    assert!(net.contains_net(&net));
+    assert!(!net.contains_net(&v4("0.0.0.0/0")));
+    assert!(v4("0.0.0.0/0").contains(Ipv4Addr::BROADCAST));
+
+    let net = IpNet::V6(v6("fe80::/10"));
+    assert!(net.contains(IpAddr::V6(Ipv6Addr::new(0xfebf, 0, 0, 0, 0, 0, 0, 1))));
+    assert!(!net.contains(IpAddr::V4(Ipv4Addr::UNSPECIFIED)));
+    assert!(!net.contains_net(&IpNet::V4(v4("0.0.0.0/0"))));
+}
+
+#[test]
+fn ip_net_supernet() {
+    assert_eq!(v4("10.1.0.0/16").supernet(), Some(v4("10.0.0.0/15")));
+    assert_eq!(v4("10.0.0.0/0").supernet(), None);
+    assert_eq!(v6("2001:db8:8000::/33").supernet(), Some(v6("2001:db8::/32")));
+
+    assert!(v4("10.0.0.0/24").is_sibling(&v4("10.0.1.0/24")));
+    assert!(!v4("10.0.1.0/24").is_sibling(&v4("10.0.2.0/24")));
+    assert!(!v4("10.0.0.0/24").is_sibling(&v4("10.0.0.0/24")));
+    assert!(!v4("10.0.0.0/24").is_sibling(&v4("10.0.1.0/25")));
+}
+
+#[test]
+fn ipv4_net_hosts() {
+    let net = v4("192.168.0.0/29");
+    assert_eq!(net.hosts().size_hint(), (6, Some(6)));
+    assert_eq!(net.hosts().next(), Some(Ipv4Addr::new(192, 168, 0, 1)));
+    assert_eq!(net.hosts().next_back(), Some(Ipv4Addr::new(192, 168, 0, 6)));
+
+    assert!(v4("10.0.0.0/31").hosts().eq([Ipv4Addr::new(10, 0, 0, 0), Ipv4Addr::new(10, 0, 0, 1)]));
+    assert!(v4("10.0.0.1/32").hosts().eq([Ipv4Addr::new(10, 0, 0, 1)]));
+
+    let mut hosts = v4("255.255.255.255/32").hosts();
+    assert_eq!(hosts.next(), Some(Ipv4Addr::BROADCAST));
+    assert_eq!(hosts.next(), None);
+    assert_eq!(hosts.next_back(), None);
+
+    let hosts = v4("0.0.0.0/0").hosts();
+    assert_eq!(hosts.clone().next(), Some(Ipv4Addr::new(0, 0, 0, 1)));
+    assert_eq!(hosts.last(), Some(Ipv4Addr::new(255, 255, 255, 254)));
+}
+
+#[test]
+fn ipv6_net_hosts() {
+    let mut hosts = v6("::/126").hosts();
+    assert_eq!(hosts.size_hint(), (4, Some(4)));
+    assert_eq!(hosts.next(), Some(Ipv6Addr::UNSPECIFIED));
+    assert_eq!(hosts.next_back(), Some(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 3)));
+    assert_eq!(hosts.count(), 2);
+
+    assert_eq!(v6("::/0").hosts().size_hint(), (usize::MAX, None));
+}
+
+#[test]
+fn ip_net_subnets() {
+    let subnets: Vec<_> = v4("10.0.0.0/22").subnets(24).unwrap().collect();
+    assert_eq!(
+        subnets,
+        [v4("10.0.0.0/24"), v4("10.0.1.0/24"), v4("10.0.2.0/24"), v4("10.0.3.0/24")]
+    );
+    assert!(v4("10.0.0.7/24").subnets(24).unwrap().eq([v4("10.0.0.0/24")]));
+    assert_eq!(v4("0.0.0.0/0").subnets(1).unwrap().count(), 2);
+    assert_eq!(v4("255.255.255.0/24").subnets(32).unwrap().last(), Some(v4("255.255.255.255/32")));
+    assert!(v4("10.0.0.0/24").subnets(23).is_err());
+    assert!(v4("10.0.0.0/24").subnets(33).is_err());
+
+    assert_eq!(v6("::/0").subnets(1).unwrap().last(), Some(v6("8000::/1")));
+    assert!(v6("::/64").subnets(129).is_err());
+}
+
+#[test]
+fn ip_net_aggregate() {
+    let mut nets = [
+        v4("10.0.3.0/24"),
+        v4("10.0.0.0/24"),
+        v4("10.0.1.0/24"),
+        v4("10.0.2.7/24"),
+        v4("10.0.1.128/25"),
+        v4("192.168.0.0/16"),
+        v4("10.0.4.0/24"),
+    ];
+    assert_eq!(
+        Ipv4Net::aggregate(&mut nets),
+        [v4("10.0.0.0/22"), v4("10.0.4.0/24"), v4("192.168.0.0/16")]
+    );
+
+    let mut nets = [v4("0.0.0.0/1"), v4("128.0.0.0/1")];
+    assert_eq!(Ipv4Net::aggregate(&mut nets), [v4("0.0.0.0/0")]);
+
+    let mut nets = [v6("2001:db8::/33"), v6("2001:db8:8000::/33"), v6("2001:db8::1/128")];
+    assert_eq!(Ipv6Net::aggregate(&mut nets), [v6("2001:db8::/32")]);
+
+    assert!(Ipv4Net::aggregate(&mut []).is_empty());
+}
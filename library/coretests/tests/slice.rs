@@ -1736,7 +1736,7 @@ fn select_nth_unstable_past_length() {
 }
 
 pub mod memchr {
-    use core::slice::memchr::{memchr, memrchr};
+    use core::slice::memchr::{memchr, memchr2, memchr3, memrchr};
 
     // test fallback implementations on all platforms
     #[test]
@@ -1819,6 +1819,55 @@ fn each_alignment_reversed() {
             assert_eq!(Some(pos - start), memrchr(needle, &data[start..]));
         }
     }
+
+    #[test]
+    fn each_position_and_length() {
+        // Cover every split between whole vectors and the overlapping remainder.
+        for len in 0..80 {
+            for pos in 0..len {
+                let mut data = vec![1u8; len];
+                data[pos] = 2;
+                assert_eq!(Some(pos), memchr(2, &data));
+                assert_eq!(Some(pos), memrchr(2, &data));
+                assert_eq!(None, memchr(3, &data));
+                assert_eq!(None, memrchr(3, &data));
+            }
+        }
+    }
+
+    #[test]
+    fn first_and_last_of_many() {
+        let mut data = [0u8; 100];
+        data[17] = b'x';
+        data[50] = b'x';
+        data[83] = b'x';
+        assert_eq!(Some(17), memchr(b'x', &data));
+        assert_eq!(Some(83), memrchr(b'x', &data));
+    }
+
+    #[test]
+    fn matches_two() {
+        assert_eq!(None, memchr2(b'a', b'b', b""));
+        assert_eq!(Some(2), memchr2(b'a', b'b', b"xxbxa"));
+        assert_eq!(None, memchr2(b'a', b'b', b"xyzxyzxyzxyzxyzxyzxyz"));
+        let mut data = [0u8; 70];
+        data[65] = b'a';
+        data[40] = b'b';
+        assert_eq!(Some(40), memchr2(b'a', b'b', &data));
+        assert_eq!(Some(65), memchr2(b'a', b'c', &data));
+    }
+
+    #[test]
+    fn matches_three() {
+        assert_eq!(None, memchr3(b'a', b'b', b'c', b""));
+        assert_eq!(Some(3), memchr3(b'a', b'b', b'c', b"xyzcba"));
+        let mut data = [0u8; 70];
+        data[69] = b'c';
+        assert_eq!(Some(69), memchr3(b'a', b'b', b'c', &data));
+        data[33] = b'a';
+        assert_eq!(Some(33), memchr3(b'a', b'b', b'c', &data));
+        assert_eq!(None, memchr3(b'x', b'y', b'z', &data));
+    }
 }
 
 #[test]
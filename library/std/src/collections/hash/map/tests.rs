@@ -1054,3 +1054,45 @@ fn build_hasher(&self) -> Self::Hasher {
     y.insert((), ());
     assert_eq!(y.len(), 1);
 }
+
+#[test]
+fn test_custom_allocator() {
+    use crate::alloc::{AllocError, Allocator, Global, Layout};
+    use crate::ptr::NonNull;
+    use crate::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingAlloc<'a>(&'a AtomicUsize);
+
+    unsafe impl Allocator for CountingAlloc<'_> {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.0.fetch_sub(1, Ordering::Relaxed);
+            unsafe { Global.deallocate(ptr, layout) }
+        }
+    }
+
+    let live = AtomicUsize::new(0);
+    let mut m = HashMap::new_in(CountingAlloc(&live));
+    assert_eq!(live.load(Ordering::Relaxed), 0);
+    for i in 0..100 {
+        m.insert(i, i * 2);
+    }
+    assert_eq!(live.load(Ordering::Relaxed), 1);
+    assert_eq!(m[&21], 42);
+    *m.entry(21).or_insert(0) += 1;
+    assert_eq!(m[&21], 43);
+
+    let drained = m.drain().count();
+    assert_eq!(drained, 100);
+    assert!(m.is_empty());
+
+    m.extend((0..10).map(|i| (i, i)));
+    let mut keys: Vec<_> = m.into_keys().collect();
+    keys.sort();
+    assert_eq!(keys, (0..10).collect::<Vec<_>>());
+    assert_eq!(live.load(Ordering::Relaxed), 0);
+}
@@ -525,3 +525,48 @@ fn test_insert_does_not_overwrite_the_value() {
             must be the same as first value pointer we inserted"
     );
 }
+
+#[test]
+fn test_custom_allocator() {
+    use crate::alloc::{AllocError, Allocator, Global, Layout};
+    use crate::ptr::NonNull;
+    use crate::sync::atomic::AtomicUsize;
+
+    struct CountingAlloc<'a>(&'a AtomicUsize);
+
+    unsafe impl Allocator for CountingAlloc<'_> {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.0.fetch_sub(1, Ordering::Relaxed);
+            unsafe { Global.deallocate(ptr, layout) }
+        }
+    }
+
+    let live_a = AtomicUsize::new(0);
+    let live_b = AtomicUsize::new(0);
+    let mut a = HashSet::with_capacity_in(4, CountingAlloc(&live_a));
+    let mut b = HashSet::new_in(CountingAlloc(&live_b));
+    assert_eq!(live_a.load(Ordering::Relaxed), 1);
+    assert_eq!(live_b.load(Ordering::Relaxed), 0);
+
+    a.extend([1, 2, 3]);
+    b.extend([2, 3, 4]);
+    let mut union: Vec<_> = a.union(&b).copied().collect();
+    union.sort();
+    assert_eq!(union, [1, 2, 3, 4]);
+    let mut intersection: Vec<_> = a.intersection(&b).copied().collect();
+    intersection.sort();
+    assert_eq!(intersection, [2, 3]);
+    assert!(!a.is_disjoint(&b));
+
+    let mut rest: Vec<_> = a.into_iter().collect();
+    rest.sort();
+    assert_eq!(rest, [1, 2, 3]);
+    assert_eq!(live_a.load(Ordering::Relaxed), 0);
+    drop(b);
+    assert_eq!(live_b.load(Ordering::Relaxed), 0);
+}
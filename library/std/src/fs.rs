@@ -48,8 +48,8 @@
 use crate::sync::Arc;
 use crate::sys::fs as fs_imp;
 use crate::sys_common::{AsInner, AsInnerMut, FromInner, IntoInner};
-use crate::time::SystemTime;
-use crate::{error, fmt};
+use crate::time::{Duration, SystemTime};
+use crate::{cmp, error, fmt};
 
 /// An object providing access to an open file on the filesystem.
 ///
@@ -198,6 +198,66 @@ pub enum TryLockError {
 #[stable(feature = "rust1", since = "1.0.0")]
 pub struct DirEntry(fs_imp::DirEntry);
 
+/// Iterator over the entries of a directory tree, recursively.
+///
+/// This iterator is returned from the [`walk_dir`] function of this module and
+/// will yield instances of <code>[io::Result]<[WalkDirEntry]></code>. Before
+/// iterating, the walk can be configured with the builder methods on this type.
+///
+/// By default, every directory is yielded before its contents (pre-order),
+/// symlinks are not followed, there is no depth limit, and entries within a
+/// directory are returned in platform and filesystem dependent order.
+///
+/// # Platform-specific behavior
+///
+/// On most Unix platforms, subdirectories are opened relative to the already
+/// opened parent directory (using `openat`) instead of by their full path. A
+/// concurrent rename of an ancestor, or replacing a directory by a symlink,
+/// therefore cannot redirect the walk outside of the tree that was started.
+/// Other platforms currently open each directory by its path.
+///
+/// Note that this [may change in the future][changes].
+///
+/// [changes]: io#platform-specific-behavior
+///
+/// # Errors
+///
+/// An [`Err`] is yielded when a directory cannot be opened or read, or when a
+/// filesystem loop is detected while following symlinks. Iteration can be
+/// continued after an error; the affected subtree is skipped.
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+pub struct WalkDir {
+    root: PathBuf,
+    max_depth: usize,
+    follow_symlinks: bool,
+    contents_first: bool,
+    same_file_system: bool,
+    sorter: Option<Box<dyn FnMut(&DirEntry, &DirEntry) -> cmp::Ordering + Send + Sync>>,
+    started: bool,
+    stack: Vec<WalkDirFrame>,
+    pending_error: Option<io::Error>,
+}
+
+/// Entries returned by the [`WalkDir`] iterator.
+///
+/// A `WalkDirEntry` is a [`DirEntry`] together with its depth below the root
+/// of the walk. Entries directly inside the root have a depth of 1.
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+pub struct WalkDirEntry {
+    entry: DirEntry,
+    depth: usize,
+}
+
+// One open directory on the `WalkDir` stack.
+struct WalkDirFrame {
+    stream: fs_imp::DirStream,
+    // When sorting, all entries are read up front and stored here in reverse order.
+    sorted: Option<Vec<io::Result<DirEntry>>>,
+    // The entry for this directory, held back until its contents have been
+    // yielded when walking contents-first.
+    deferred: Option<WalkDirEntry>,
+}
+
 /// Options and flags which can be used to configure how a file is opened.
 ///
 /// This builder exposes the ability to configure how a [`File`] is opened and
@@ -274,6 +334,169 @@ pub struct DirBuilder {
     recursive: bool,
 }
 
+/// An open directory on the filesystem that other files and directories can
+/// be opened, created, removed and renamed relative to.
+///
+/// Paths passed to the methods of `Dir` are resolved relative to the opened
+/// directory rather than the current working directory. Because the directory
+/// stays open, renaming or replacing it (or one of its ancestors) after it was
+/// opened does not change which directory the operations apply to, which
+/// avoids the time-of-check to time-of-use races that come with building
+/// paths by hand.
+///
+/// Note that `Dir` is not a sandbox: absolute paths and `..` components are
+/// resolved as usual and can refer to files outside of the directory.
+///
+/// # Platform-specific behavior
+///
+/// On most Unix platforms, `Dir` wraps a directory file descriptor and uses
+/// `openat`, `mkdirat`, `unlinkat`, `renameat`, `symlinkat` and `fstatat`. On
+/// Windows it wraps a directory handle and uses `NtCreateFile` relative to it;
+/// [`Dir::read_dir`] and [`Dir::symlink`] use the current path of that handle
+/// instead. Other platforms currently resolve paths against the path the
+/// directory was opened with.
+///
+/// Note that this [may change in the future][changes].
+///
+/// [changes]: io#platform-specific-behavior
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(dirfd)]
+/// use std::fs::{Dir, OpenOptions};
+/// use std::io::Write;
+///
+/// fn main() -> std::io::Result<()> {
+///     let dir = Dir::open("/var/lib/service")?;
+///     let mut file = dir.open_file_with(
+///         "state.tmp",
+///         OpenOptions::new().write(true).create(true).truncate(true),
+///     )?;
+///     file.write_all(b"ready")?;
+///     file.sync_all()?;
+///     dir.rename("state.tmp", &dir, "state")?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "dirfd", issue = "none")]
+pub struct Dir {
+    inner: fs_imp::Dir,
+}
+
+/// A builder used to atomically replace the contents of a file.
+///
+/// Instead of truncating the target file and writing to it in place, which
+/// leaves a partially written file behind if the process or system crashes,
+/// an `AtomicFile` writes to a new temporary file in the same directory. Once
+/// all data has been written, [`AtomicFileWriter::commit`] flushes the
+/// temporary file to disk, renames it over the target and then flushes the
+/// directory, so that readers either see the old or the new contents in full.
+///
+/// The [`write_atomic`] function is a convenience for the common case.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_atomic_write)]
+/// use std::fs::AtomicFile;
+/// use std::io::Write;
+///
+/// fn main() -> std::io::Result<()> {
+///     let mut file = AtomicFile::new().preserve_permissions(true).create("config.toml")?;
+///     writeln!(file, "[server]")?;
+///     writeln!(file, "port = 8080")?;
+///     file.commit()
+/// }
+/// ```
+#[unstable(feature = "fs_atomic_write", issue = "none")]
+#[derive(Clone, Debug)]
+pub struct AtomicFile {
+    preserve_permissions: bool,
+}
+
+/// A temporary file that atomically replaces its target when committed.
+///
+/// This is returned from [`AtomicFile::create`] and implements [`Write`] and
+/// [`Seek`]. If it is dropped without calling [`commit`], the temporary file is
+/// removed and the target is left untouched.
+///
+/// [`commit`]: AtomicFileWriter::commit
+#[unstable(feature = "fs_atomic_write", issue = "none")]
+pub struct AtomicFileWriter {
+    file: File,
+    temp_path: PathBuf,
+    path: PathBuf,
+    preserve_permissions: bool,
+    committed: bool,
+}
+
+/// Watches files and directories for changes.
+///
+/// Paths are added with [`watch`] or [`watch_recursive`], and changes to them
+/// are retrieved with [`next_event`] or [`next_event_timeout`].
+///
+/// # Platform-specific behavior
+///
+/// This currently uses inotify on Linux and Android. On other platforms the
+/// watched paths are scanned periodically and compared with the previous
+/// scan, so changes are only noticed after a short delay and renames are
+/// reported as a [`Remove`] followed by a [`Create`].
+/// Note that, this [may change in the future][changes].
+///
+/// [`watch`]: Watcher::watch
+/// [`watch_recursive`]: Watcher::watch_recursive
+/// [`next_event`]: Watcher::next_event
+/// [`next_event_timeout`]: Watcher::next_event_timeout
+/// [`Remove`]: WatchEventKind::Remove
+/// [`Create`]: WatchEventKind::Create
+/// [changes]: io#platform-specific-behavior
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_watcher)]
+/// use std::fs::Watcher;
+///
+/// fn main() -> std::io::Result<()> {
+///     let mut watcher = Watcher::new()?;
+///     watcher.watch_recursive("src")?;
+///     loop {
+///         let event = watcher.next_event()?;
+///         println!("{:?}: {}", event.kind(), event.path().display());
+///     }
+/// }
+/// ```
+#[unstable(feature = "fs_watcher", issue = "none")]
+pub struct Watcher(fs_imp::Watcher);
+
+/// A change reported by a [`Watcher`].
+#[unstable(feature = "fs_watcher", issue = "none")]
+#[derive(Clone)]
+pub struct WatchEvent(fs_imp::WatchEvent);
+
+/// The kind of change described by a [`WatchEvent`].
+#[unstable(feature = "fs_watcher", issue = "none")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum WatchEventKind {
+    /// A file or directory was created, or moved into a watched directory.
+    Create,
+    /// The contents or metadata of a file or directory changed.
+    Modify,
+    /// A file or directory was removed, or moved out of a watched directory.
+    Remove,
+    /// A file or directory was renamed within the watched paths.
+    ///
+    /// [`WatchEvent::path`] is the old path and [`WatchEvent::new_path`] is
+    /// the new one.
+    Rename,
+    /// Events were lost because too many changes happened at once.
+    ///
+    /// Any state derived from earlier events should be rebuilt from scratch.
+    Overflow,
+}
+
 /// Reads the entire contents of a file into a bytes vector.
 ///
 /// This is a convenience function for using [`File::open`] and [`read_to_end`]
@@ -387,6 +610,55 @@ fn inner(path: &Path, contents: &[u8]) -> io::Result<()> {
     inner(path.as_ref(), contents.as_ref())
 }
 
+/// Writes a slice as the entire contents of a file, atomically replacing the
+/// file if it already exists.
+///
+/// Unlike [`write`], which truncates the file and writes to it in place, this
+/// function writes the contents to a temporary file in the same directory and
+/// renames it over `path` once it has been flushed to disk. Other processes,
+/// and the file system after a crash, will see either the old or the new
+/// contents in full, but never a partially written file.
+///
+/// The file is created with default permissions, like [`File::create`]. Use
+/// [`AtomicFile`] to keep the permissions of the file being replaced, or to
+/// write the contents incrementally.
+///
+/// If `path` is a symlink, the symlink itself is replaced and its target is
+/// left unchanged.
+///
+/// # Platform-specific behavior
+///
+/// This function currently uses [`rename`] to replace the file. On Unix, the
+/// containing directory is synced after the rename so that the replacement
+/// itself is durable; other platforms do not support syncing a directory.
+/// Note that, this [may change in the future][changes].
+///
+/// [changes]: io#platform-specific-behavior
+///
+/// # Errors
+///
+/// This function returns an error if the temporary file cannot be created,
+/// written or synced, or if the rename fails, for example because `path` is
+/// a directory or because the user lacks permission to write to the
+/// containing directory. In that case the temporary file is removed and the
+/// original file is left unchanged.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_atomic_write)]
+/// use std::fs;
+///
+/// fn main() -> std::io::Result<()> {
+///     fs::write_atomic("state.json", br#"{"generation": 2}"#)?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_atomic_write", issue = "none")]
+pub fn write_atomic<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
+    AtomicFile::new().write(path, contents)
+}
+
 #[stable(feature = "file_lock", since = "1.89.0")]
 impl error::Error for TryLockError {}
 
@@ -2446,6 +2718,294 @@ fn as_inner(&self) -> &fs_imp::DirEntry {
     }
 }
 
+impl WalkDir {
+    /// Sets the maximum depth of entries yielded by the walk.
+    ///
+    /// Entries directly inside the root have a depth of 1, so `max_depth(1)`
+    /// yields the same entries as [`read_dir`]. A depth of 0 yields nothing.
+    /// Directories at the maximum depth are yielded but not descended into.
+    ///
+    /// The default is [`usize::MAX`], i.e. no limit.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Sets whether symlinks to directories are descended into.
+    ///
+    /// When enabled, the walk keeps track of the directories it is currently
+    /// inside of and yields an error of kind [`io::ErrorKind::FilesystemLoop`]
+    /// instead of descending into a directory a second time. The root path
+    /// itself is always followed if it is a symlink.
+    ///
+    /// Entries are still reported with the file type of the link itself, see
+    /// [`DirEntry::file_type`].
+    ///
+    /// The default is `false`.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn follow_symlinks(mut self, follow: bool) -> Self {
+        self.follow_symlinks = follow;
+        self
+    }
+
+    /// Sets whether a directory is yielded after its contents (post-order)
+    /// instead of before them (pre-order).
+    ///
+    /// Yielding contents first is useful, for example, to remove a tree
+    /// bottom-up.
+    ///
+    /// The default is `false`.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn contents_first(mut self, contents_first: bool) -> Self {
+        self.contents_first = contents_first;
+        self
+    }
+
+    /// Sets whether the walk stays on the filesystem of the root directory.
+    ///
+    /// When enabled, directories on other filesystems (mount points) are
+    /// yielded but not descended into.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This option currently compares the device ID on Unix and the volume
+    /// serial number on Windows. On other platforms, enabling it makes the
+    /// walk return an [`io::ErrorKind::Unsupported`] error for every
+    /// directory it would descend into.
+    ///
+    /// The default is `false`.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn same_file_system(mut self, same_file_system: bool) -> Self {
+        self.same_file_system = same_file_system;
+        self
+    }
+
+    /// Sorts the entries of each directory with the given comparison function
+    /// before yielding them.
+    ///
+    /// Sorting requires reading each directory completely when it is entered,
+    /// so it uses memory proportional to the largest directory being walked.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(fs_walk_dir)]
+    /// use std::fs;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     for entry in fs::walk_dir(".").sort_by(|a, b| a.file_name().cmp(&b.file_name())) {
+    ///         let entry = entry?;
+    ///         println!("{}{:?}", "  ".repeat(entry.depth() - 1), entry.file_name());
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn sort_by<F>(mut self, compare: F) -> Self
+    where
+        F: FnMut(&DirEntry, &DirEntry) -> cmp::Ordering + Send + Sync + 'static,
+    {
+        self.sorter = Some(Box::new(compare));
+        self
+    }
+
+    /// Sorts the entries of each directory by file name.
+    ///
+    /// This is a shorthand for [`sort_by`](WalkDir::sort_by) comparing
+    /// [`DirEntry::file_name`].
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn sort_by_file_name(self) -> Self {
+        self.sort_by(|a, b| a.file_name().cmp(&b.file_name()))
+    }
+
+    fn push_frame(&mut self, mut stream: fs_imp::DirStream, deferred: Option<WalkDirEntry>) {
+        let sorted = self.sorter.as_mut().map(|compare| {
+            let mut entries: Vec<_> = stream.by_ref().map(|entry| entry.map(DirEntry)).collect();
+            // Sorted in reverse so that entries can be popped off the end; errors
+            // go last so that they are yielded first.
+            entries.sort_by(|a, b| match (a, b) {
+                (Ok(a), Ok(b)) => compare(b, a),
+                (Ok(_), Err(_)) => cmp::Ordering::Less,
+                (Err(_), Ok(_)) => cmp::Ordering::Greater,
+                (Err(_), Err(_)) => cmp::Ordering::Equal,
+            });
+            entries
+        });
+        self.stack.push(WalkDirFrame { stream, sorted, deferred });
+    }
+
+    // Opens `entry` as a directory to descend into, or returns `None` if the
+    // walk should not descend into it.
+    fn open_child(&self, entry: &DirEntry) -> io::Result<Option<fs_imp::DirStream>> {
+        let file_type = entry.file_type()?;
+        let follow = if file_type.is_dir() {
+            false
+        } else if file_type.is_symlink() && self.follow_symlinks {
+            true
+        } else {
+            return Ok(None);
+        };
+
+        // The stack always contains at least the root when this is called.
+        let parent = &self.stack[self.stack.len() - 1].stream;
+        let Some(child) = parent.open_child(&entry.0, follow)? else {
+            return Ok(None);
+        };
+        if self.same_file_system && !child.is_same_device(&self.stack[0].stream)? {
+            return Ok(None);
+        }
+        if self.follow_symlinks && self.stack.iter().any(|frame| frame.stream.is_same_dir(&child)) {
+            return Err(io::const_error!(
+                io::ErrorKind::FilesystemLoop,
+                "filesystem loop detected while walking a directory tree",
+            ));
+        }
+        Ok(Some(child))
+    }
+}
+
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+impl Iterator for WalkDir {
+    type Item = io::Result<WalkDirEntry>;
+
+    fn next(&mut self) -> Option<io::Result<WalkDirEntry>> {
+        if !self.started {
+            self.started = true;
+            if self.max_depth == 0 {
+                return None;
+            }
+            match fs_imp::DirStream::open(&self.root) {
+                Ok(stream) => self.push_frame(stream, None),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+
+        if let Some(err) = self.pending_error.take() {
+            return Some(Err(err));
+        }
+
+        loop {
+            let frame = self.stack.last_mut()?;
+            let next = match &mut frame.sorted {
+                Some(sorted) => sorted.pop(),
+                None => frame.stream.next().map(|entry| entry.map(DirEntry)),
+            };
+            let entry = match next {
+                Some(Ok(entry)) => entry,
+                Some(Err(err)) => return Some(Err(err)),
+                None => match self.stack.pop().and_then(|frame| frame.deferred) {
+                    Some(entry) => return Some(Ok(entry)),
+                    None => continue,
+                },
+            };
+
+            let depth = self.stack.len();
+            if depth >= self.max_depth {
+                return Some(Ok(WalkDirEntry { entry, depth }));
+            }
+            match self.open_child(&entry) {
+                Ok(Some(child)) if self.contents_first => {
+                    self.push_frame(child, Some(WalkDirEntry { entry, depth }));
+                }
+                Ok(Some(child)) => {
+                    self.push_frame(child, None);
+                    return Some(Ok(WalkDirEntry { entry, depth }));
+                }
+                Ok(None) => return Some(Ok(WalkDirEntry { entry, depth })),
+                Err(err) => {
+                    // Report the entry itself first, then why it was not descended into.
+                    self.pending_error = Some(err);
+                    return Some(Ok(WalkDirEntry { entry, depth }));
+                }
+            }
+        }
+    }
+}
+
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+impl fmt::Debug for WalkDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WalkDir")
+            .field("root", &self.root)
+            .field("max_depth", &self.max_depth)
+            .field("follow_symlinks", &self.follow_symlinks)
+            .field("contents_first", &self.contents_first)
+            .field("same_file_system", &self.same_file_system)
+            .field("sorted", &self.sorter.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl WalkDirEntry {
+    /// Returns the depth of this entry below the root of the walk.
+    ///
+    /// Entries directly inside the root have a depth of 1.
+    #[must_use]
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the full path to the file that this entry represents.
+    ///
+    /// The full path is created by joining the path passed to [`walk_dir`]
+    /// with the names of the directories leading to this entry.
+    #[must_use]
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn path(&self) -> PathBuf {
+        self.entry.path()
+    }
+
+    /// Returns the file name of this entry without any leading path
+    /// component(s).
+    #[must_use]
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn file_name(&self) -> OsString {
+        self.entry.file_name()
+    }
+
+    /// Returns the file type for the file that this entry points at.
+    ///
+    /// This function will not traverse symlinks, see [`DirEntry::file_type`].
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn file_type(&self) -> io::Result<FileType> {
+        self.entry.file_type()
+    }
+
+    /// Returns the metadata for the file that this entry points at.
+    ///
+    /// This function will not traverse symlinks, see [`DirEntry::metadata`].
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        self.entry.metadata()
+    }
+
+    /// Returns a reference to the underlying [`DirEntry`].
+    #[must_use]
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn dir_entry(&self) -> &DirEntry {
+        &self.entry
+    }
+
+    /// Consumes this entry, returning the underlying [`DirEntry`].
+    #[must_use]
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn into_dir_entry(self) -> DirEntry {
+        self.entry
+    }
+}
+
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+impl fmt::Debug for WalkDirEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WalkDirEntry")
+            .field("path", &self.path())
+            .field("depth", &self.depth)
+            .finish()
+    }
+}
+
 /// Removes a file from the filesystem.
 ///
 /// Note that there is no
@@ -3100,6 +3660,69 @@ pub fn read_dir<P: AsRef<Path>>(path: P) -> io::Result<ReadDir> {
     fs_imp::read_dir(path.as_ref()).map(ReadDir)
 }
 
+/// Returns an iterator over the entries of a directory tree, recursively.
+///
+/// The returned [`WalkDir`] yields every file, directory and symlink below
+/// `path` (but not `path` itself) together with its depth. Use the builder
+/// methods on [`WalkDir`] to limit the depth, follow symlinks, yield
+/// directories after their contents, sort entries or stay on one filesystem.
+///
+/// The directory at `path` is only opened once iteration starts, so errors
+/// opening it are reported as the first item of the iterator.
+///
+/// # Platform-specific behavior
+///
+/// On most Unix platforms, this function uses `openat`, `fstat` and
+/// `fdopendir` to traverse the tree relative to open directory handles, the
+/// same way [`remove_dir_all`] does. Other platforms open each directory by
+/// path with [`read_dir`].
+///
+/// Note that this [may change in the future][changes].
+///
+/// [changes]: io#platform-specific-behavior
+///
+/// # Errors
+///
+/// The iterator yields an error in the following situations, but is not
+/// limited to just these cases:
+///
+/// * The provided `path` doesn't exist or is not a directory.
+/// * The process lacks permissions to view the contents of a directory.
+/// * A filesystem loop is detected while following symlinks.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_walk_dir)]
+/// use std::fs;
+///
+/// fn main() -> std::io::Result<()> {
+///     // Print every Rust source file below the current directory.
+///     for entry in fs::walk_dir(".").follow_symlinks(true) {
+///         let path = entry?.path();
+///         if path.extension().is_some_and(|ext| ext == "rs") {
+///             println!("{}", path.display());
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+#[doc(alias = "walkdir", alias = "find", alias = "nftw", alias = "fts_open")]
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+pub fn walk_dir<P: AsRef<Path>>(path: P) -> WalkDir {
+    WalkDir {
+        root: path.as_ref().to_path_buf(),
+        max_depth: usize::MAX,
+        follow_symlinks: false,
+        contents_first: false,
+        same_file_system: false,
+        sorter: None,
+        started: false,
+        stack: Vec::new(),
+        pending_error: None,
+    }
+}
+
 /// Changes the permissions found on a file or a directory.
 ///
 /// # Platform-specific behavior
@@ -3250,6 +3873,465 @@ fn as_inner_mut(&mut self) -> &mut fs_imp::DirBuilder {
     }
 }
 
+impl Dir {
+    /// Opens the directory at `path`.
+    ///
+    /// If `path` is relative, it is resolved relative to the current working
+    /// directory. Symlinks are followed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `path` does not exist, is not a
+    /// directory, or the user lacks permission to open it.
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Dir> {
+        fs_imp::Dir::open(path.as_ref()).map(|inner| Dir { inner })
+    }
+
+    /// Opens the directory at `path`, relative to this directory.
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn open_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<Dir> {
+        self.inner.open_dir(path.as_ref()).map(|inner| Dir { inner })
+    }
+
+    /// Opens the file at `path`, relative to this directory, in read-only mode.
+    ///
+    /// This is the equivalent of [`File::open`] for a path relative to this
+    /// directory.
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn open_file<P: AsRef<Path>>(&self, path: P) -> io::Result<File> {
+        self.open_file_with(path, OpenOptions::new().read(true))
+    }
+
+    /// Opens the file at `path`, relative to this directory, with the options
+    /// specified by `opts`.
+    ///
+    /// This is the equivalent of [`OpenOptions::open`] for a path relative to
+    /// this directory.
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn open_file_with<P: AsRef<Path>>(&self, path: P, opts: &OpenOptions) -> io::Result<File> {
+        self.inner.open_file(path.as_ref(), &opts.0).map(|inner| File { inner })
+    }
+
+    /// Creates a new, empty directory at `path`, relative to this directory.
+    ///
+    /// This is the equivalent of [`create_dir`] for a path relative to this
+    /// directory.
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn create_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.inner.create_dir(path.as_ref())
+    }
+
+    /// Removes the file at `path`, relative to this directory.
+    ///
+    /// This is the equivalent of [`remove_file`] for a path relative to this
+    /// directory.
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn remove_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.inner.remove_file(path.as_ref())
+    }
+
+    /// Removes the empty directory at `path`, relative to this directory.
+    ///
+    /// This is the equivalent of [`remove_dir`] for a path relative to this
+    /// directory.
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn remove_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.inner.remove_dir(path.as_ref())
+    }
+
+    /// Renames `from`, relative to this directory, to `to`, relative to
+    /// `to_dir`, replacing the original file if `to` already exists.
+    ///
+    /// `to_dir` may be the same directory as `self`. This is the equivalent of
+    /// [`rename`] for paths relative to two directories.
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Dir,
+        to: Q,
+    ) -> io::Result<()> {
+        self.inner.rename(from.as_ref(), &to_dir.inner, to.as_ref())
+    }
+
+    /// Queries the metadata of the file at `path`, relative to this directory,
+    /// traversing symlinks.
+    ///
+    /// This is the equivalent of [`metadata`] for a path relative to this
+    /// directory.
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        self.inner.metadata(path.as_ref()).map(Metadata)
+    }
+
+    /// Queries the metadata of the file at `path`, relative to this directory,
+    /// without following symlinks.
+    ///
+    /// This is the equivalent of [`symlink_metadata`] for a path relative to
+    /// this directory.
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn symlink_metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        self.inner.symlink_metadata(path.as_ref()).map(Metadata)
+    }
+
+    /// Returns an iterator over the entries within this directory.
+    ///
+    /// The paths of the returned entries are joined onto the path this
+    /// directory was opened with.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// On Windows, this lists the directory at the current path of the open
+    /// handle.
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn read_dir(&self) -> io::Result<ReadDir> {
+        self.inner.read_dir().map(ReadDir)
+    }
+
+    /// Creates a new symbolic link at `link`, relative to this directory,
+    /// pointing at `original`.
+    ///
+    /// `original` is stored in the link as-is and is not resolved relative to
+    /// this directory.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// On Windows, this creates a file symlink, like
+    /// [`os::windows::fs::symlink_file`], at the current path of the open
+    /// handle.
+    ///
+    /// [`os::windows::fs::symlink_file`]: crate::os::windows::fs::symlink_file
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn symlink<P: AsRef<Path>, Q: AsRef<Path>>(&self, original: P, link: Q) -> io::Result<()> {
+        self.inner.symlink(original.as_ref(), link.as_ref())
+    }
+}
+
+#[unstable(feature = "dirfd", issue = "none")]
+impl fmt::Debug for Dir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl AtomicFile {
+    /// Creates a new builder with the default options.
+    ///
+    /// By default, the permissions of the file being replaced are not kept.
+    #[unstable(feature = "fs_atomic_write", issue = "none")]
+    #[must_use]
+    pub fn new() -> AtomicFile {
+        AtomicFile { preserve_permissions: false }
+    }
+
+    /// Sets whether the permissions of an existing file at the target path are
+    /// applied to the replacement.
+    ///
+    /// The permissions are read when the file is committed. If no file exists
+    /// at that point, the replacement keeps the default permissions.
+    #[unstable(feature = "fs_atomic_write", issue = "none")]
+    pub fn preserve_permissions(&mut self, preserve: bool) -> &mut Self {
+        self.preserve_permissions = preserve;
+        self
+    }
+
+    /// Creates a temporary file next to `path` that will replace `path` once
+    /// it is committed.
+    ///
+    /// The temporary file is named after the file name of `path` with a random
+    /// suffix and is created with [`OpenOptions::create_new`], so an existing
+    /// file is never overwritten by accident.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `path` has no file name, or if the
+    /// temporary file cannot be created, for example because the containing
+    /// directory does not exist.
+    #[unstable(feature = "fs_atomic_write", issue = "none")]
+    pub fn create<P: AsRef<Path>>(&self, path: P) -> io::Result<AtomicFileWriter> {
+        self._create(path.as_ref())
+    }
+
+    fn _create(&self, path: &Path) -> io::Result<AtomicFileWriter> {
+        use crate::sync::atomic::{AtomicU64, Ordering};
+
+        // Mixed into the random suffix so that names never repeat within a process.
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        const MAX_ATTEMPTS: usize = 16;
+
+        let Some(file_name) = path.file_name() else {
+            return Err(io::const_error!(io::ErrorKind::InvalidInput, "path has no file name"));
+        };
+        let parent = path.parent().unwrap_or(Path::new(""));
+
+        for _ in 0..MAX_ATTEMPTS {
+            let suffix = crate::sys::random::hashmap_random_keys().0
+                ^ COUNTER.fetch_add(1, Ordering::Relaxed);
+            let mut temp_name = OsString::from(".");
+            temp_name.push(file_name);
+            temp_name.push(format!(".{suffix:016x}.tmp"));
+            let temp_path = parent.join(temp_name);
+
+            match OpenOptions::new().write(true).create_new(true).open(&temp_path) {
+                Ok(file) => {
+                    return Ok(AtomicFileWriter {
+                        file,
+                        temp_path,
+                        path: path.to_path_buf(),
+                        preserve_permissions: self.preserve_permissions,
+                        committed: false,
+                    });
+                }
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(err),
+            }
+        }
+        Err(io::const_error!(
+            io::ErrorKind::AlreadyExists,
+            "failed to find an unused name for the temporary file",
+        ))
+    }
+
+    /// Atomically replaces the file at `path` with `contents`.
+    ///
+    /// This is a convenience for [`create`](AtomicFile::create), writing all
+    /// of `contents` and [`commit`](AtomicFileWriter::commit).
+    #[unstable(feature = "fs_atomic_write", issue = "none")]
+    pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(&self, path: P, contents: C) -> io::Result<()> {
+        fn inner(this: &AtomicFile, path: &Path, contents: &[u8]) -> io::Result<()> {
+            let mut file = this.create(path)?;
+            file.write_all(contents)?;
+            file.commit()
+        }
+        inner(self, path.as_ref(), contents.as_ref())
+    }
+}
+
+#[unstable(feature = "fs_atomic_write", issue = "none")]
+impl Default for AtomicFile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AtomicFileWriter {
+    /// Returns a reference to the temporary file.
+    ///
+    /// This can be used to query metadata or change permissions before the
+    /// file is committed.
+    #[unstable(feature = "fs_atomic_write", issue = "none")]
+    #[must_use]
+    pub fn file(&self) -> &File {
+        &self.file
+    }
+
+    /// Returns the path of the temporary file.
+    #[unstable(feature = "fs_atomic_write", issue = "none")]
+    #[must_use]
+    pub fn temp_path(&self) -> &Path {
+        &self.temp_path
+    }
+
+    /// Replaces the target file with the temporary file.
+    ///
+    /// This syncs the temporary file to disk with [`File::sync_all`], applies
+    /// the permissions of the file being replaced if requested, renames the
+    /// temporary file over the target and finally syncs the containing
+    /// directory where supported.
+    ///
+    /// # Errors
+    ///
+    /// If an error occurs before the rename, the temporary file is removed
+    /// and the target is left unchanged. If only syncing the directory fails,
+    /// the file has already been replaced but the replacement may not be
+    /// durable yet.
+    #[unstable(feature = "fs_atomic_write", issue = "none")]
+    pub fn commit(mut self) -> io::Result<()> {
+        if self.preserve_permissions {
+            match metadata(&self.path) {
+                Ok(metadata) => self.file.set_permissions(metadata.permissions())?,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+        self.file.sync_all()?;
+        rename(&self.temp_path, &self.path)?;
+        self.committed = true;
+
+        let parent = match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        fs_imp::sync_dir(parent)
+    }
+}
+
+#[unstable(feature = "fs_atomic_write", issue = "none")]
+impl Drop for AtomicFileWriter {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = remove_file(&self.temp_path);
+        }
+    }
+}
+
+#[unstable(feature = "fs_atomic_write", issue = "none")]
+impl fmt::Debug for AtomicFileWriter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AtomicFileWriter")
+            .field("path", &self.path)
+            .field("temp_path", &self.temp_path)
+            .finish_non_exhaustive()
+    }
+}
+
+#[unstable(feature = "fs_atomic_write", issue = "none")]
+impl Write for AtomicFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf)
+    }
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        self.file.write_vectored(bufs)
+    }
+    #[inline]
+    fn is_write_vectored(&self) -> bool {
+        self.file.is_write_vectored()
+    }
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[unstable(feature = "fs_atomic_write", issue = "none")]
+impl Seek for AtomicFileWriter {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.file.seek(pos)
+    }
+    fn stream_position(&mut self) -> io::Result<u64> {
+        self.file.stream_position()
+    }
+}
+
+impl Watcher {
+    /// Creates a new watcher that isn't watching any paths yet.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the platform's notification
+    /// mechanism can't be initialized, for example because a per-user limit
+    /// was reached.
+    #[unstable(feature = "fs_watcher", issue = "none")]
+    pub fn new() -> io::Result<Watcher> {
+        fs_imp::Watcher::new().map(Watcher)
+    }
+
+    /// Starts watching a file or directory.
+    ///
+    /// If `path` is a directory, changes to its direct children are reported,
+    /// but changes inside its subdirectories are not. Use
+    /// [`watch_recursive`](Watcher::watch_recursive) for that.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `path` doesn't exist or can't be
+    /// watched.
+    #[unstable(feature = "fs_watcher", issue = "none")]
+    pub fn watch<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        self.0.watch(path.as_ref(), false)
+    }
+
+    /// Starts watching a directory and everything below it.
+    ///
+    /// Directories that are later created inside `path` are watched as well.
+    /// Files created in such a directory before it could be watched are
+    /// reported as [`Create`](WatchEventKind::Create) events.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `path` doesn't exist or if it or
+    /// one of its subdirectories can't be watched.
+    #[unstable(feature = "fs_watcher", issue = "none")]
+    pub fn watch_recursive<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        self.0.watch(path.as_ref(), true)
+    }
+
+    /// Stops watching a path that was passed to [`watch`](Watcher::watch) or
+    /// [`watch_recursive`](Watcher::watch_recursive).
+    ///
+    /// Events for this path that were already queued may still be returned.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error of kind [`io::ErrorKind::NotFound`]
+    /// if `path` isn't being watched.
+    #[unstable(feature = "fs_watcher", issue = "none")]
+    pub fn unwatch<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        self.0.unwatch(path.as_ref())
+    }
+
+    /// Blocks until the next change and returns it.
+    #[unstable(feature = "fs_watcher", issue = "none")]
+    pub fn next_event(&mut self) -> io::Result<WatchEvent> {
+        loop {
+            if let Some(event) = self.0.next_event(None)? {
+                return Ok(WatchEvent(event));
+            }
+        }
+    }
+
+    /// Blocks until the next change or until `timeout` has elapsed.
+    ///
+    /// Returns `Ok(None)` if no change happened within `timeout`. A zero
+    /// `timeout` only returns changes that are already available.
+    #[unstable(feature = "fs_watcher", issue = "none")]
+    pub fn next_event_timeout(&mut self, timeout: Duration) -> io::Result<Option<WatchEvent>> {
+        Ok(self.0.next_event(Some(timeout))?.map(WatchEvent))
+    }
+}
+
+#[unstable(feature = "fs_watcher", issue = "none")]
+impl fmt::Debug for Watcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl WatchEvent {
+    /// Returns the kind of change.
+    #[must_use]
+    #[unstable(feature = "fs_watcher", issue = "none")]
+    pub fn kind(&self) -> WatchEventKind {
+        self.0.kind
+    }
+
+    /// Returns the path of the file or directory that changed.
+    ///
+    /// For a [`Rename`](WatchEventKind::Rename) this is the old path. For an
+    /// [`Overflow`](WatchEventKind::Overflow) this is empty.
+    #[must_use]
+    #[unstable(feature = "fs_watcher", issue = "none")]
+    pub fn path(&self) -> &Path {
+        &self.0.path
+    }
+
+    /// Returns the new path of a [`Rename`](WatchEventKind::Rename), and
+    /// `None` for all other kinds of events.
+    #[must_use]
+    #[unstable(feature = "fs_watcher", issue = "none")]
+    pub fn new_path(&self) -> Option<&Path> {
+        self.0.new_path.as_deref()
+    }
+}
+
+#[unstable(feature = "fs_watcher", issue = "none")]
+impl fmt::Debug for WatchEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 /// Returns `Ok(true)` if the path points at an existing entity.
 ///
 /// This function will traverse symbolic links to query information about the
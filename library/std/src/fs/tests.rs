@@ -2084,3 +2084,352 @@ fn test_rename_junction() {
     // Junction links are always absolute so we just check the file name is correct.
     assert_eq!(fs::read_link(&dest).unwrap().file_name(), Some(not_exist.as_os_str()));
 }
+
+fn walk_dir_names(walk: fs::WalkDir) -> Vec<(String, usize)> {
+    walk.map(|entry| {
+        let entry = check!(entry);
+        (entry.file_name().into_string().unwrap(), entry.depth())
+    })
+    .collect()
+}
+
+#[test]
+fn walk_dir_sorted_pre_and_post_order() {
+    let tmpdir = tmpdir();
+    check!(fs::create_dir_all(tmpdir.join("a/b")));
+    check!(fs::create_dir_all(tmpdir.join("c")));
+    check!(File::create(tmpdir.join("a/b/f1")));
+    check!(File::create(tmpdir.join("a/f2")));
+    check!(File::create(tmpdir.join("c/f3")));
+
+    let pre = walk_dir_names(fs::walk_dir(tmpdir.path()).sort_by_file_name());
+    let expected = [("a", 1), ("b", 2), ("f1", 3), ("f2", 2), ("c", 1), ("f3", 2)];
+    assert_eq!(pre, expected.map(|(name, depth)| (name.to_string(), depth)));
+
+    let post = walk_dir_names(fs::walk_dir(tmpdir.path()).sort_by_file_name().contents_first(true));
+    let expected = [("f1", 3), ("b", 2), ("f2", 2), ("a", 1), ("f3", 2), ("c", 1)];
+    assert_eq!(post, expected.map(|(name, depth)| (name.to_string(), depth)));
+
+    let paths: Vec<_> = fs::walk_dir(tmpdir.path()).map(|entry| check!(entry).path()).collect();
+    assert_eq!(paths.len(), 6);
+    assert!(paths.contains(&tmpdir.join("a").join("b").join("f1")));
+}
+
+#[test]
+fn walk_dir_max_depth() {
+    let tmpdir = tmpdir();
+    check!(fs::create_dir_all(tmpdir.join("a/b/c")));
+
+    assert_eq!(fs::walk_dir(tmpdir.path()).max_depth(0).count(), 0);
+    let names = walk_dir_names(fs::walk_dir(tmpdir.path()).max_depth(2));
+    assert_eq!(names, [("a".to_string(), 1), ("b".to_string(), 2)]);
+}
+
+#[test]
+#[cfg(any(unix, windows))]
+fn walk_dir_same_file_system() {
+    let tmpdir = tmpdir();
+    check!(fs::create_dir_all(tmpdir.join("a/b")));
+
+    let names = walk_dir_names(fs::walk_dir(tmpdir.path()).same_file_system(true));
+    assert_eq!(names, [("a".to_string(), 1), ("b".to_string(), 2)]);
+}
+
+#[test]
+fn walk_dir_missing_root() {
+    let tmpdir = tmpdir();
+    let mut walk = fs::walk_dir(tmpdir.join("missing"));
+    assert_eq!(walk.next().unwrap().unwrap_err().kind(), ErrorKind::NotFound);
+    assert!(walk.next().is_none());
+}
+
+#[test]
+fn walk_dir_symlinks() {
+    let tmpdir = tmpdir();
+    if !got_symlink_permission(&tmpdir) {
+        return;
+    };
+
+    check!(fs::create_dir_all(tmpdir.join("d/e")));
+    check!(symlink_dir(tmpdir.join("d/e"), tmpdir.join("link")));
+    check!(symlink_dir(tmpdir.join("d"), tmpdir.join("d/e/loop")));
+
+    // Symlinks are yielded but not descended into by default.
+    let names = walk_dir_names(fs::walk_dir(tmpdir.path()).sort_by_file_name());
+    let expected = [("d", 1), ("e", 2), ("loop", 3), ("link", 1)];
+    assert_eq!(names, expected.map(|(name, depth)| (name.to_string(), depth)));
+
+    // When following symlinks, the loop back to `d` is reported as an error.
+    let entries: Vec<_> =
+        fs::walk_dir(tmpdir.path()).sort_by_file_name().follow_symlinks(true).collect();
+    let loops = entries
+        .iter()
+        .filter(|entry| matches!(entry, Err(e) if e.kind() == ErrorKind::FilesystemLoop))
+        .count();
+    assert_eq!(loops, 2);
+    let names: Vec<_> = entries
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| (entry.file_name().into_string().unwrap(), entry.depth()))
+        .collect();
+    let expected = [("d", 1), ("e", 2), ("loop", 3), ("link", 1), ("loop", 2), ("e", 3)];
+    assert_eq!(names, expected.map(|(name, depth)| (name.to_string(), depth)));
+}
+
+#[test]
+fn dir_relative_operations() {
+    let tmpdir = tmpdir();
+    let dir = check!(fs::Dir::open(tmpdir.path()));
+
+    check!(dir.create_dir("sub"));
+    let sub = check!(dir.open_dir("sub"));
+    let mut file = check!(sub.open_file_with("a", OpenOptions::new().write(true).create_new(true)));
+    check!(file.write_all(b"hello"));
+    drop(file);
+    assert!(tmpdir.join("sub").join("a").is_file());
+    assert_eq!(check!(sub.metadata("a")).len(), 5);
+    assert!(check!(dir.metadata("sub")).is_dir());
+
+    let mut contents = String::new();
+    check!(check!(sub.open_file("a")).read_to_string(&mut contents));
+    assert_eq!(contents, "hello");
+
+    // Renaming between two handles, then moving the directory, keeps both handles valid.
+    check!(sub.rename("a", &dir, "b"));
+    assert!(tmpdir.join("b").is_file());
+    check!(fs::rename(tmpdir.join("sub"), tmpdir.join("moved")));
+    check!(dir.rename("b", &sub, "c"));
+    assert!(tmpdir.join("moved").join("c").is_file());
+
+    let names: Vec<_> = check!(sub.read_dir()).map(|entry| check!(entry).file_name()).collect();
+    assert_eq!(names, ["c"]);
+
+    assert_eq!(dir.remove_dir("moved").unwrap_err().kind(), ErrorKind::DirectoryNotEmpty);
+    check!(sub.remove_file("c"));
+    check!(dir.remove_dir("moved"));
+    assert!(!tmpdir.join("moved").exists());
+    assert_eq!(dir.open_file("missing").unwrap_err().kind(), ErrorKind::NotFound);
+}
+
+#[test]
+#[cfg(windows)]
+fn dir_open_file_custom_flags() {
+    const FILE_FLAG_DELETE_ON_CLOSE: u32 = 0x04000000;
+
+    let tmpdir = tmpdir();
+    let dir = check!(fs::Dir::open(tmpdir.path()));
+    let file = check!(dir.open_file_with(
+        "temp",
+        OpenOptions::new().write(true).create_new(true).custom_flags(FILE_FLAG_DELETE_ON_CLOSE),
+    ));
+    assert!(tmpdir.join("temp").exists());
+    drop(file);
+    assert!(!tmpdir.join("temp").exists());
+}
+
+#[test]
+fn dir_symlink() {
+    let tmpdir = tmpdir();
+    if !got_symlink_permission(&tmpdir) {
+        return;
+    };
+
+    let dir = check!(fs::Dir::open(tmpdir.path()));
+    check!(File::create(tmpdir.join("target")));
+    check!(dir.symlink("target", "link"));
+    assert!(check!(dir.symlink_metadata("link")).file_type().is_symlink());
+    assert!(check!(dir.metadata("link")).is_file());
+    assert_eq!(check!(fs::read_link(tmpdir.join("link"))), Path::new("target"));
+}
+
+#[test]
+fn write_atomic_replaces_contents() {
+    let tmpdir = tmpdir();
+    let path = tmpdir.join("config");
+
+    check!(fs::write_atomic(&path, "first"));
+    assert_eq!(check!(fs::read_to_string(&path)), "first");
+    check!(fs::write_atomic(&path, "second"));
+    assert_eq!(check!(fs::read_to_string(&path)), "second");
+
+    // No temporary files are left behind.
+    assert_eq!(check!(fs::read_dir(tmpdir.path())).count(), 1);
+}
+
+#[test]
+fn atomic_file_uncommitted_leaves_target() {
+    let tmpdir = tmpdir();
+    let path = tmpdir.join("config");
+    check!(fs::write(&path, "original"));
+
+    let mut file = check!(fs::AtomicFile::new().create(&path));
+    check!(file.write_all(b"partial"));
+    let temp_path = file.temp_path().to_path_buf();
+    assert!(temp_path.exists());
+    assert_eq!(temp_path.parent(), path.parent());
+    drop(file);
+
+    assert!(!temp_path.exists());
+    assert_eq!(check!(fs::read_to_string(&path)), "original");
+    assert_eq!(check!(fs::read_dir(tmpdir.path())).count(), 1);
+}
+
+#[test]
+#[cfg(unix)]
+fn atomic_file_preserve_permissions() {
+    use crate::os::unix::fs::PermissionsExt;
+
+    let tmpdir = tmpdir();
+    let path = tmpdir.join("config");
+    check!(fs::write(&path, "original"));
+    check!(fs::set_permissions(&path, fs::Permissions::from_mode(0o640)));
+
+    check!(fs::AtomicFile::new().preserve_permissions(true).write(&path, "replaced"));
+    assert_eq!(check!(fs::read_to_string(&path)), "replaced");
+    assert_eq!(check!(fs::metadata(&path)).permissions().mode() & 0o777, 0o640);
+}
+
+#[test]
+fn write_atomic_no_file_name() {
+    let tmpdir = tmpdir();
+    let err = fs::write_atomic(tmpdir.join(".."), "contents").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+// Collects events until `done` returns true, failing if that takes too long.
+fn watch_until(
+    watcher: &mut fs::Watcher,
+    mut done: impl FnMut(&[fs::WatchEvent]) -> bool,
+) -> Vec<fs::WatchEvent> {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let mut events = Vec::new();
+    while !done(&events) {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match check!(watcher.next_event_timeout(remaining)) {
+            Some(event) => events.push(event),
+            None => panic!("timed out waiting for events, got {events:?}"),
+        }
+    }
+    events
+}
+
+#[test]
+fn watcher_create_and_remove() {
+    let tmpdir = tmpdir();
+    let path = tmpdir.join("file");
+    let mut watcher = check!(fs::Watcher::new());
+    check!(watcher.watch(tmpdir.path()));
+    assert!(check!(watcher.next_event_timeout(Duration::ZERO)).is_none());
+
+    check!(fs::write(&path, "contents"));
+    watch_until(&mut watcher, |events| {
+        events.iter().any(|e| e.kind() == fs::WatchEventKind::Create && e.path() == path)
+    });
+
+    check!(fs::remove_file(&path));
+    watch_until(&mut watcher, |events| {
+        events.iter().any(|e| e.kind() == fs::WatchEventKind::Remove && e.path() == path)
+    });
+
+    check!(watcher.unwatch(tmpdir.path()));
+    let err = watcher.unwatch(tmpdir.path()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+}
+
+#[test]
+fn watcher_watch_keeps_pending_changes() {
+    let tmpdir = tmpdir();
+    let first = tmpdir.join("first");
+    let second = tmpdir.join("second");
+    check!(fs::create_dir(&first));
+    check!(fs::create_dir(&second));
+    let mut watcher = check!(fs::Watcher::new());
+    check!(watcher.watch(&first));
+
+    // Changes made before another path is watched are still reported.
+    let path = first.join("file");
+    check!(fs::write(&path, "contents"));
+    check!(watcher.watch(&second));
+    watch_until(&mut watcher, |events| {
+        events.iter().any(|e| e.kind() == fs::WatchEventKind::Create && e.path() == path)
+    });
+}
+
+#[test]
+fn watcher_recursive() {
+    let tmpdir = tmpdir();
+    let mut watcher = check!(fs::Watcher::new());
+    check!(watcher.watch_recursive(tmpdir.path()));
+
+    let dir = tmpdir.join("a");
+    let file = dir.join("b");
+    check!(fs::create_dir(&dir));
+    check!(fs::write(&file, "contents"));
+    watch_until(&mut watcher, |events| {
+        events.iter().any(|e| e.kind() == fs::WatchEventKind::Create && e.path() == file)
+    });
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        let renamed = tmpdir.join("c");
+        check!(fs::rename(&dir, &renamed));
+        let events = watch_until(&mut watcher, |events| {
+            events.iter().any(|e| e.kind() == fs::WatchEventKind::Rename)
+        });
+        let rename = events.iter().find(|e| e.kind() == fs::WatchEventKind::Rename).unwrap();
+        assert_eq!(rename.path(), dir);
+        assert_eq!(rename.new_path(), Some(renamed.as_path()));
+
+        // Watches below the renamed directory report the new paths.
+        let file = renamed.join("b");
+        check!(fs::remove_file(&file));
+        watch_until(&mut watcher, |events| {
+            events.iter().any(|e| e.kind() == fs::WatchEventKind::Remove && e.path() == file)
+        });
+    }
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn watcher_recursive_move_out() {
+    let tmpdir = tmpdir();
+    let watched = tmpdir.join("watched");
+    let dir = watched.join("a");
+    check!(fs::create_dir_all(dir.join("b")));
+    let mut watcher = check!(fs::Watcher::new());
+    check!(watcher.watch_recursive(&watched));
+
+    let outside = tmpdir.join("outside");
+    check!(fs::rename(&dir, &outside));
+    watch_until(&mut watcher, |events| {
+        events.iter().any(|e| e.kind() == fs::WatchEventKind::Remove && e.path() == dir)
+    });
+
+    // The directories that left the tree are no longer watched.
+    check!(fs::write(outside.join("b").join("file"), "contents"));
+    check!(fs::write(outside.join("file"), "contents"));
+    let deadline = Instant::now() + Duration::from_millis(500);
+    let remaining = || deadline.saturating_duration_since(Instant::now());
+    while let Some(event) = check!(watcher.next_event_timeout(remaining())) {
+        assert_eq!(event.kind(), fs::WatchEventKind::Remove, "unexpected event {event:?}");
+    }
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn watcher_recursive_create_then_remove() {
+    let tmpdir = tmpdir();
+    let mut watcher = check!(fs::Watcher::new());
+    check!(watcher.watch_recursive(tmpdir.path()));
+
+    // The directory is gone by the time its creation is read; the events
+    // after it must still be delivered.
+    let gone = tmpdir.join("gone");
+    let after = tmpdir.join("after");
+    check!(fs::create_dir(&gone));
+    check!(fs::remove_dir(&gone));
+    check!(fs::write(&after, "contents"));
+    watch_until(&mut watcher, |events| {
+        events.iter().any(|e| e.kind() == fs::WatchEventKind::Create && e.path() == after)
+    });
+}
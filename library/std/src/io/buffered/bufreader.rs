@@ -2,6 +2,7 @@
 
 use buffer::Buffer;
 
+use crate::borrow::Cow;
 use crate::fmt;
 use crate::io::{
     self, BorrowedCursor, BufRead, DEFAULT_BUF_SIZE, IoSliceMut, Read, Seek, SeekFrom, SizeHint,
@@ -150,6 +151,93 @@ pub fn peek(&mut self, n: usize) -> io::Result<&[u8]> {
         }
         Ok(&self.buf.buffer()[..n])
     }
+
+    /// Reads the next record terminated by the delimiter `delim`, borrowing it
+    /// from the internal buffer when possible.
+    ///
+    /// The returned record does *not* include the delimiter. If the record and
+    /// its delimiter fit into the buffer, the record is returned as
+    /// [`Cow::Borrowed`] without copying it out of the buffer. Otherwise it is
+    /// collected into a vector as by [`read_until_slice`] and returned as
+    /// [`Cow::Owned`]. Either way the record and its delimiter are consumed.
+    ///
+    /// The last record may lack a delimiter. Returns `Ok(None)` once EOF is
+    /// reached.
+    ///
+    /// # Errors
+    ///
+    /// This function will ignore all instances of [`ErrorKind::Interrupted`] and
+    /// will otherwise return any errors returned by the underlying reader. Bytes
+    /// read before an error are kept in the buffer, unless the record had to be
+    /// copied out of it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `delim` is empty.
+    ///
+    /// [`read_until_slice`]: BufRead::read_until_slice
+    /// [`ErrorKind::Interrupted`]: io::ErrorKind::Interrupted
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(bufread_delimiters)]
+    /// use std::borrow::Cow;
+    /// use std::io::BufReader;
+    ///
+    /// let mut rdr = BufReader::with_capacity(8, &b"id=1\r\nname=long value\r\n"[..]);
+    ///
+    /// let record = rdr.next_record(b"\r\n")?.unwrap();
+    /// assert!(matches!(record, Cow::Borrowed(b"id=1")));
+    ///
+    /// // Too long to fit into the 8 byte buffer.
+    /// let record = rdr.next_record(b"\r\n")?.unwrap();
+    /// assert!(matches!(record, Cow::Owned(_)));
+    /// assert_eq!(&*record, b"name=long value");
+    ///
+    /// assert!(rdr.next_record(b"\r\n")?.is_none());
+    /// # std::io::Result::Ok(())
+    /// ```
+    #[unstable(feature = "bufread_delimiters", issue = "none")]
+    pub fn next_record(&mut self, delim: &[u8]) -> io::Result<Option<Cow<'_, [u8]>>> {
+        assert!(!delim.is_empty(), "delimiter must not be empty");
+        // How much of the buffered data is known not to contain the start of a delimiter.
+        let mut searched = 0;
+        let (record_len, used) = loop {
+            let available = self.buf.buffer();
+            if let Some(i) = io::find_delimiter(&available[searched..], delim) {
+                break (searched + i, searched + i + delim.len());
+            }
+            if available.len() == self.capacity() {
+                // The record doesn't fit into the buffer, so it has to be copied out.
+                let mut record = Vec::new();
+                self.read_until_slice(delim, &mut record)?;
+                if record.ends_with(delim) {
+                    record.truncate(record.len() - delim.len());
+                }
+                return Ok(Some(Cow::Owned(record)));
+            }
+            searched = available.len().saturating_sub(delim.len() - 1);
+
+            if self.buf.pos() > 0 {
+                self.buf.backshift();
+            }
+            match self.buf.read_more(&mut self.inner) {
+                Ok(0) => {
+                    // End of file, the remaining bytes form the last record.
+                    let len = self.buf.buffer().len();
+                    if len == 0 {
+                        return Ok(None);
+                    }
+                    break (len, len);
+                }
+                Ok(_) => {}
+                Err(ref e) if e.is_interrupted() => {}
+                Err(e) => return Err(e),
+            }
+        };
+        Ok(Some(Cow::Borrowed(&self.buf.claim(used)[..record_len])))
+    }
 }
 
 impl<R: ?Sized> BufReader<R> {
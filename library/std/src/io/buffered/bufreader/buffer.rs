@@ -102,6 +102,17 @@ pub fn consume_with<V>(&mut self, amt: usize, mut visitor: V) -> bool
         }
     }
 
+    /// Consumes up to `amt` bytes and returns them. The returned bytes stay in the buffer, so
+    /// they can be handed out without copying until the buffer is next filled.
+    #[inline]
+    pub fn claim(&mut self, amt: usize) -> &[u8] {
+        let start = self.pos;
+        self.consume(amt);
+        // SAFETY: `start..self.pos` is a subrange of the old `self.pos..self.filled`, which is
+        // initialized by the invariants of this type.
+        unsafe { self.buf.get_unchecked(start..self.pos).assume_init_ref() }
+    }
+
     #[inline]
     pub fn unconsume(&mut self, amt: usize) {
         self.pos = self.pos.saturating_sub(amt);
@@ -451,6 +451,54 @@ fn test_lines() {
     assert!(it.next().is_none());
 }
 
+#[test]
+fn test_next_record() {
+    use crate::borrow::Cow;
+
+    let in_buf: &[u8] = b"a\r\nbc\r\n\r\nlong record\r\nd";
+    let mut reader = BufReader::with_capacity(6, in_buf);
+    assert!(matches!(reader.next_record(b"\r\n").unwrap(), Some(Cow::Borrowed(b"a"))));
+    assert!(matches!(reader.next_record(b"\r\n").unwrap(), Some(Cow::Borrowed(b"bc"))));
+    assert!(matches!(reader.next_record(b"\r\n").unwrap(), Some(Cow::Borrowed(b""))));
+    // Doesn't fit into the buffer, so it has to be copied.
+    let record = reader.next_record(b"\r\n").unwrap().unwrap();
+    assert!(matches!(record, Cow::Owned(_)));
+    assert_eq!(&*record, b"long record");
+    // The last record has no delimiter.
+    assert!(matches!(reader.next_record(b"\r\n").unwrap(), Some(Cow::Borrowed(b"d"))));
+    assert!(reader.next_record(b"\r\n").unwrap().is_none());
+    assert!(reader.next_record(b"\r\n").unwrap().is_none());
+}
+
+#[test]
+fn test_next_record_short_reads() {
+    use crate::borrow::Cow;
+
+    // Hands out a single byte per read, so delimiters arrive split across reads.
+    struct OneByteReader(&'static [u8]);
+
+    impl Read for OneByteReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match (self.0.split_first(), buf.first_mut()) {
+                (Some((&byte, rest)), Some(out)) => {
+                    *out = byte;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    let mut reader = BufReader::with_capacity(4, OneByteReader(b"a::b:c::::"));
+    assert!(matches!(reader.next_record(b"::").unwrap(), Some(Cow::Borrowed(b"a"))));
+    let record = reader.next_record(b"::").unwrap().unwrap();
+    assert!(matches!(record, Cow::Owned(_)));
+    assert_eq!(&*record, b"b:c");
+    assert!(matches!(reader.next_record(b"::").unwrap(), Some(Cow::Borrowed(b""))));
+    assert!(reader.next_record(b"::").unwrap().is_none());
+}
+
 #[test]
 fn test_short_reads() {
     let inner = ShortReader { lengths: vec![0, 1, 2, 0, 1, 0] };
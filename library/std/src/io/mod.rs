@@ -2232,6 +2232,58 @@ fn read_until<R: BufRead + ?Sized>(r: &mut R, delim: u8, buf: &mut Vec<u8>) -> R
     }
 }
 
+/// Returns the index of the first occurrence of the non-empty `delim` in `haystack`.
+fn find_delimiter(haystack: &[u8], delim: &[u8]) -> Option<usize> {
+    let (&first, rest) = delim.split_first()?;
+    let mut offset = 0;
+    while let Some(i) = memchr::memchr(first, &haystack[offset..]) {
+        let start = offset + i;
+        if haystack[start + 1..].starts_with(rest) {
+            return Some(start);
+        }
+        offset = start + 1;
+    }
+    None
+}
+
+fn read_until_slice<R: BufRead + ?Sized>(
+    r: &mut R,
+    delim: &[u8],
+    buf: &mut Vec<u8>,
+) -> Result<usize> {
+    assert!(!delim.is_empty(), "delimiter must not be empty");
+    let start_len = buf.len();
+    let mut read = 0;
+    loop {
+        let (done, used) = {
+            let available = match r.fill_buf() {
+                Ok(n) => n,
+                Err(ref e) if e.is_interrupted() => continue,
+                Err(e) => return Err(e),
+            };
+            // The delimiter may straddle two reads, so also search the tail of what
+            // was already appended. Any match must end in the newly available bytes.
+            let overlap = cmp::min(delim.len() - 1, buf.len() - start_len);
+            let search_from = buf.len() - overlap;
+            buf.extend_from_slice(available);
+            match find_delimiter(&buf[search_from..], delim) {
+                Some(i) => {
+                    let end = search_from + i + delim.len();
+                    let used = end - (buf.len() - available.len());
+                    buf.truncate(end);
+                    (true, used)
+                }
+                None => (false, available.len()),
+            }
+        };
+        r.consume(used);
+        read += used;
+        if done || used == 0 {
+            return Ok(read);
+        }
+    }
+}
+
 fn skip_until<R: BufRead + ?Sized>(r: &mut R, delim: u8) -> Result<usize> {
     let mut read = 0;
     loop {
@@ -2455,6 +2507,64 @@ fn read_until(&mut self, byte: u8, buf: &mut Vec<u8>) -> Result<usize> {
         read_until(self, byte, buf)
     }
 
+    /// Reads all bytes into `buf` until the multi-byte delimiter `delim` or EOF
+    /// is reached.
+    ///
+    /// This is like [`read_until`], except that the delimiter is a byte sequence
+    /// such as `b"\r\n"`, which may be split across several reads from the
+    /// underlying stream. All bytes up to, and including, the delimiter (if found)
+    /// will be appended to `buf`.
+    ///
+    /// If successful, this function will return the total number of bytes read.
+    ///
+    /// This function is blocking and should be used carefully: it is possible for
+    /// an attacker to continuously send bytes without ever sending the delimiter
+    /// or EOF.
+    ///
+    /// # Errors
+    ///
+    /// This function will ignore all instances of [`ErrorKind::Interrupted`] and
+    /// will otherwise return any errors returned by [`fill_buf`].
+    ///
+    /// If an I/O error is encountered then all bytes read so far will be
+    /// present in `buf` and its length will have been adjusted appropriately.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `delim` is empty.
+    ///
+    /// [`read_until`]: BufRead::read_until
+    /// [`fill_buf`]: BufRead::fill_buf
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(bufread_delimiters)]
+    /// use std::io::{self, BufRead};
+    ///
+    /// let mut cursor = io::Cursor::new(b"HELO a\r\nQUIT\r\n");
+    /// let mut buf = vec![];
+    ///
+    /// let num_bytes = cursor.read_until_slice(b"\r\n", &mut buf)?;
+    /// assert_eq!(num_bytes, 8);
+    /// assert_eq!(buf, b"HELO a\r\n");
+    /// buf.clear();
+    ///
+    /// let num_bytes = cursor.read_until_slice(b"\r\n", &mut buf)?;
+    /// assert_eq!(num_bytes, 6);
+    /// assert_eq!(buf, b"QUIT\r\n");
+    /// buf.clear();
+    ///
+    /// // cursor is at EOF
+    /// let num_bytes = cursor.read_until_slice(b"\r\n", &mut buf)?;
+    /// assert_eq!(num_bytes, 0);
+    /// # io::Result::Ok(())
+    /// ```
+    #[unstable(feature = "bufread_delimiters", issue = "none")]
+    fn read_until_slice(&mut self, delim: &[u8], buf: &mut Vec<u8>) -> Result<usize> {
+        read_until_slice(self, delim, buf)
+    }
+
     /// Skips all bytes until the delimiter `byte` or EOF is reached.
     ///
     /// This function will read (and discard) bytes from the underlying stream until the
@@ -2624,6 +2734,44 @@ fn split(self, byte: u8) -> Split<Self>
         Split { buf: self, delim: byte }
     }
 
+    /// Returns an iterator over the contents of this reader split on the
+    /// multi-byte delimiter `delim`.
+    ///
+    /// The iterator returned from this function will return instances of
+    /// <code>[io::Result]<[Vec]\<u8>></code>. Each vector returned will *not* have
+    /// the delimiter at the end.
+    ///
+    /// This function will yield errors whenever [`read_until_slice`] would have
+    /// also yielded an error.
+    ///
+    /// # Panics
+    ///
+    /// The iterator panics on its first use if `delim` is empty.
+    ///
+    /// [io::Result]: self::Result "io::Result"
+    /// [`read_until_slice`]: BufRead::read_until_slice
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(bufread_delimiters)]
+    /// use std::io::{self, BufRead};
+    ///
+    /// let cursor = io::Cursor::new(b"lorem\0\0ipsum\0dolor\0\0");
+    ///
+    /// let mut split_iter = cursor.split_slice(b"\0\0").map(|l| l.unwrap());
+    /// assert_eq!(split_iter.next(), Some(b"lorem".to_vec()));
+    /// assert_eq!(split_iter.next(), Some(b"ipsum\0dolor".to_vec()));
+    /// assert_eq!(split_iter.next(), None);
+    /// ```
+    #[unstable(feature = "bufread_delimiters", issue = "none")]
+    fn split_slice(self, delim: &[u8]) -> SplitSlice<'_, Self>
+    where
+        Self: Sized,
+    {
+        SplitSlice { buf: self, delim }
+    }
+
     /// Returns an iterator over the lines of this reader.
     ///
     /// The iterator returned from this function will yield instances of
@@ -2660,6 +2808,44 @@ fn lines(self) -> Lines<Self>
     {
         Lines { buf: self }
     }
+
+    /// Returns an iterator over the lines of this reader, keeping their line
+    /// terminators.
+    ///
+    /// The iterator returned from this function will yield instances of
+    /// <code>[io::Result]<[String]></code>. Unlike with [`lines`], each string
+    /// returned keeps its newline byte (the `0xA` byte) or `CRLF` (`0xD`, `0xA`
+    /// bytes), so that concatenating the lines reproduces the input exactly. Only
+    /// the last line may lack a terminator.
+    ///
+    /// [io::Result]: self::Result "io::Result"
+    /// [`lines`]: BufRead::lines
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(bufread_delimiters)]
+    /// use std::io::{self, BufRead};
+    ///
+    /// let cursor = io::Cursor::new(b"lorem\nipsum\r\ndolor");
+    ///
+    /// let mut lines_iter = cursor.lines_with_terminator().map(|l| l.unwrap());
+    /// assert_eq!(lines_iter.next(), Some(String::from("lorem\n")));
+    /// assert_eq!(lines_iter.next(), Some(String::from("ipsum\r\n")));
+    /// assert_eq!(lines_iter.next(), Some(String::from("dolor")));
+    /// assert_eq!(lines_iter.next(), None);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Each line of the iterator has the same error semantics as [`BufRead::read_line`].
+    #[unstable(feature = "bufread_delimiters", issue = "none")]
+    fn lines_with_terminator(self) -> LinesWithTerminator<Self>
+    where
+        Self: Sized,
+    {
+        LinesWithTerminator { buf: self }
+    }
 }
 
 /// Adapter to chain together two readers.
@@ -3327,6 +3513,39 @@ fn next(&mut self) -> Option<Result<Vec<u8>>> {
     }
 }
 
+/// An iterator over the contents of an instance of `BufRead` split on a
+/// byte sequence.
+///
+/// This struct is generally created by calling [`split_slice`] on a `BufRead`.
+/// Please see the documentation of [`split_slice`] for more details.
+///
+/// [`split_slice`]: BufRead::split_slice
+#[unstable(feature = "bufread_delimiters", issue = "none")]
+#[derive(Debug)]
+pub struct SplitSlice<'a, B> {
+    buf: B,
+    delim: &'a [u8],
+}
+
+#[unstable(feature = "bufread_delimiters", issue = "none")]
+impl<B: BufRead> Iterator for SplitSlice<'_, B> {
+    type Item = Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Result<Vec<u8>>> {
+        let mut buf = Vec::new();
+        match self.buf.read_until_slice(self.delim, &mut buf) {
+            Ok(0) => None,
+            Ok(_n) => {
+                if buf.ends_with(self.delim) {
+                    buf.truncate(buf.len() - self.delim.len());
+                }
+                Some(Ok(buf))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
 /// An iterator over the lines of an instance of `BufRead`.
 ///
 /// This struct is generally created by calling [`lines`] on a `BufRead`.
@@ -3361,3 +3580,31 @@ fn next(&mut self) -> Option<Result<String>> {
         }
     }
 }
+
+/// An iterator over the lines of an instance of `BufRead`, including their
+/// line terminators.
+///
+/// This struct is generally created by calling [`lines_with_terminator`] on a
+/// `BufRead`. Please see the documentation of [`lines_with_terminator`] for more
+/// details.
+///
+/// [`lines_with_terminator`]: BufRead::lines_with_terminator
+#[unstable(feature = "bufread_delimiters", issue = "none")]
+#[derive(Debug)]
+pub struct LinesWithTerminator<B> {
+    buf: B,
+}
+
+#[unstable(feature = "bufread_delimiters", issue = "none")]
+impl<B: BufRead> Iterator for LinesWithTerminator<B> {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Result<String>> {
+        let mut buf = String::new();
+        match self.buf.read_line(&mut buf) {
+            Ok(0) => None,
+            Ok(_n) => Some(Ok(buf)),
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
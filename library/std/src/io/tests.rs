@@ -25,6 +25,50 @@ fn read_until() {
     assert_eq!(v, []);
 }
 
+#[test]
+fn read_until_slice() {
+    let mut buf = Cursor::new(&b"12"[..]);
+    let mut v = Vec::new();
+    assert_eq!(buf.read_until_slice(b"\r\n", &mut v).unwrap(), 2);
+    assert_eq!(v, b"12");
+
+    let mut buf = Cursor::new(&b"1\r2\r\n\r\n"[..]);
+    let mut v = Vec::new();
+    assert_eq!(buf.read_until_slice(b"\r\n", &mut v).unwrap(), 5);
+    assert_eq!(v, b"1\r2\r\n");
+    v.truncate(0);
+    assert_eq!(buf.read_until_slice(b"\r\n", &mut v).unwrap(), 2);
+    assert_eq!(v, b"\r\n");
+    v.truncate(0);
+    assert_eq!(buf.read_until_slice(b"\r\n", &mut v).unwrap(), 0);
+    assert_eq!(v, []);
+}
+
+#[test]
+fn read_until_slice_straddling_fills() {
+    // Every fill of the buffer ends in the middle of a delimiter.
+    let data = b"ab--cd---ef-g--";
+    for cap in 1..=data.len() {
+        let mut rdr = BufReader::with_capacity(cap, &data[..]);
+        let mut records = Vec::new();
+        loop {
+            let mut v = Vec::new();
+            if rdr.read_until_slice(b"--", &mut v).unwrap() == 0 {
+                break;
+            }
+            records.push(v);
+        }
+        assert_eq!(records, [&b"ab--"[..], b"cd--", b"-ef-g--"], "capacity {cap}");
+    }
+}
+
+#[test]
+#[should_panic = "delimiter must not be empty"]
+fn read_until_slice_empty_delimiter() {
+    let mut buf = Cursor::new(&b"12"[..]);
+    let _ = buf.read_until_slice(b"", &mut Vec::new());
+}
+
 #[test]
 fn skip_until() {
     let bytes: &[u8] = b"read\0ignore\0read\0ignore\0read\0ignore\0";
@@ -69,6 +113,22 @@ fn split() {
     assert!(s.next().is_none());
 }
 
+#[test]
+fn split_slice() {
+    let buf = Cursor::new(&b"1::2:3::"[..]);
+    let mut s = buf.split_slice(b"::");
+    assert_eq!(s.next().unwrap().unwrap(), b"1");
+    assert_eq!(s.next().unwrap().unwrap(), b"2:3");
+    assert!(s.next().is_none());
+
+    let buf = Cursor::new(&b"::::1:"[..]);
+    let mut s = buf.split_slice(b"::");
+    assert_eq!(s.next().unwrap().unwrap(), b"");
+    assert_eq!(s.next().unwrap().unwrap(), b"");
+    assert_eq!(s.next().unwrap().unwrap(), b"1:");
+    assert!(s.next().is_none());
+}
+
 #[test]
 fn read_line() {
     let mut buf = Cursor::new(&b"12"[..]);
@@ -102,6 +162,20 @@ fn lines() {
     assert!(s.next().is_none());
 }
 
+#[test]
+fn lines_with_terminator() {
+    let buf = Cursor::new(&b"12\r\n\n3"[..]);
+    let mut s = buf.lines_with_terminator();
+    assert_eq!(s.next().unwrap().unwrap(), "12\r\n");
+    assert_eq!(s.next().unwrap().unwrap(), "\n");
+    assert_eq!(s.next().unwrap().unwrap(), "3");
+    assert!(s.next().is_none());
+
+    let buf = Cursor::new(&b"\xff\n"[..]);
+    let mut s = buf.lines_with_terminator();
+    assert_eq!(s.next().unwrap().unwrap_err().kind(), io::ErrorKind::InvalidData);
+}
+
 #[test]
 fn buf_read_has_data_left() {
     let mut buf = Cursor::new(&b"abcd"[..]);
@@ -0,0 +1,382 @@
+//! Builders for sockets that need options applied before they are bound or
+//! connected.
+
+#[cfg(all(
+    test,
+    not(any(
+        target_os = "emscripten",
+        all(target_os = "wasi", target_env = "p1"),
+        target_os = "xous",
+        target_os = "trusty",
+    ))
+))]
+mod tests;
+
+use crate::collections::VecDeque;
+use crate::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+use crate::sys::net as net_imp;
+use crate::sys_common::{AsInnerMut, FromInner};
+use crate::time::Duration;
+use crate::{cmp, io};
+
+/// Generates the setters for the options shared by all socket builders.
+macro_rules! socket_options {
+    () => {
+        /// Sets the `SO_REUSEADDR` option on the socket.
+        ///
+        /// This allows binding to an address that is still in the `TIME_WAIT`
+        /// state after a previous socket was closed.
+        ///
+        /// [`TcpListener::bind`] already sets this option on all platforms
+        /// except Windows, where it allows other sockets to take over an
+        /// address that is actively in use. [`TcpListenerBuilder`] follows the
+        /// same default unless this method is called.
+        #[unstable(feature = "net_socket_builder", issue = "none")]
+        pub fn reuse_address(&mut self, reuse_address: bool) -> &mut Self {
+            self.opts.reuse_address = Some(reuse_address);
+            self
+        }
+
+        /// Sets the `SO_REUSEPORT` option on the socket.
+        ///
+        /// When set on every socket, this allows multiple sockets to be bound
+        /// to the same address and port, which the operating system then
+        /// balances incoming connections or datagrams between.
+        ///
+        /// This option is only supported on Linux, Android, Fuchsia, the BSDs
+        /// and Apple platforms. Elsewhere, setting it makes creating the socket
+        /// fail with an error of kind [`io::ErrorKind::Unsupported`].
+        #[unstable(feature = "net_socket_builder", issue = "none")]
+        pub fn reuse_port(&mut self, reuse_port: bool) -> &mut Self {
+            self.opts.reuse_port = Some(reuse_port);
+            self
+        }
+
+        /// Sets the `IPV6_V6ONLY` option on the socket.
+        ///
+        /// If set to `true`, a socket bound to an IPv6 address only
+        /// communicates over IPv6. Otherwise, a socket bound to the IPv6
+        /// unspecified address also accepts IPv4-mapped traffic. The option
+        /// is ignored for IPv4 addresses.
+        #[unstable(feature = "net_socket_builder", issue = "none")]
+        pub fn only_v6(&mut self, only_v6: bool) -> &mut Self {
+            self.opts.only_v6 = Some(only_v6);
+            self
+        }
+
+        /// Sets the size of the socket's send buffer, `SO_SNDBUF`.
+        ///
+        /// The operating system may round or clamp the size, and Linux doubles
+        /// it to leave room for bookkeeping overhead.
+        #[unstable(feature = "net_socket_builder", issue = "none")]
+        pub fn send_buffer_size(&mut self, size: usize) -> &mut Self {
+            self.opts.send_buffer_size = Some(size);
+            self
+        }
+
+        /// Sets the size of the socket's receive buffer, `SO_RCVBUF`.
+        ///
+        /// See [`send_buffer_size`](Self::send_buffer_size) for caveats.
+        #[unstable(feature = "net_socket_builder", issue = "none")]
+        pub fn recv_buffer_size(&mut self, size: usize) -> &mut Self {
+            self.opts.recv_buffer_size = Some(size);
+            self
+        }
+    };
+}
+
+/// Generates the setters for the TCP keepalive options.
+macro_rules! keepalive_options {
+    () => {
+        /// Enables or disables TCP keepalive probes, `SO_KEEPALIVE`.
+        #[unstable(feature = "net_socket_builder", issue = "none")]
+        pub fn keepalive(&mut self, keepalive: bool) -> &mut Self {
+            self.opts.keepalive = Some(keepalive);
+            self
+        }
+
+        /// Sets how long a connection has to be idle before keepalive probes
+        /// are sent.
+        ///
+        /// The time is rounded down to whole seconds, with a minimum of one
+        /// second. This option is supported on Linux, Android, Fuchsia,
+        /// FreeBSD, NetBSD, DragonFly BSD, Apple platforms and Windows 10
+        /// version 1709 and later.
+        #[unstable(feature = "net_socket_builder", issue = "none")]
+        pub fn keepalive_idle(&mut self, idle: Duration) -> &mut Self {
+            self.opts.keepalive_idle = Some(idle);
+            self
+        }
+
+        /// Sets the time between keepalive probes.
+        ///
+        /// The same rounding and platform support as for
+        /// [`keepalive_idle`](Self::keepalive_idle) applies.
+        #[unstable(feature = "net_socket_builder", issue = "none")]
+        pub fn keepalive_interval(&mut self, interval: Duration) -> &mut Self {
+            self.opts.keepalive_interval = Some(interval);
+            self
+        }
+    };
+}
+
+/// A builder for a [`TcpListener`] with options that have to be set before the
+/// socket is bound.
+///
+/// Options that are not set keep the defaults of [`TcpListener::bind`]. If an
+/// option can't be applied, binding fails instead of silently ignoring it.
+///
+/// # Examples
+///
+/// Run several listeners on the same port, letting the operating system
+/// distribute incoming connections between them:
+///
+/// ```no_run
+/// #![feature(net_socket_builder)]
+/// use std::net::TcpListenerBuilder;
+///
+/// let mut builder = TcpListenerBuilder::new();
+/// builder.reuse_port(true).backlog(1024);
+///
+/// let first = builder.bind("0.0.0.0:8080")?;
+/// let second = builder.bind("0.0.0.0:8080")?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+#[unstable(feature = "net_socket_builder", issue = "none")]
+pub struct TcpListenerBuilder {
+    opts: net_imp::SocketOptions,
+}
+
+impl TcpListenerBuilder {
+    /// Creates a builder with no options set.
+    #[unstable(feature = "net_socket_builder", issue = "none")]
+    #[must_use]
+    pub fn new() -> TcpListenerBuilder {
+        TcpListenerBuilder::default()
+    }
+
+    socket_options!();
+    keepalive_options!();
+
+    /// Sets the maximum length of the queue of pending connections.
+    ///
+    /// The operating system may silently cap this value, e.g. to
+    /// `net.core.somaxconn` on Linux. By default, the same backlog as in
+    /// [`TcpListener::bind`] is used.
+    #[unstable(feature = "net_socket_builder", issue = "none")]
+    pub fn backlog(&mut self, backlog: u32) -> &mut Self {
+        self.opts.backlog = Some(backlog);
+        self
+    }
+
+    /// Creates a new [`TcpListener`] bound to the specified address with the
+    /// configured options.
+    ///
+    /// Like [`TcpListener::bind`], every address yielded by `addr` is tried in
+    /// turn, and the error of the last attempt is returned if none succeeds.
+    #[unstable(feature = "net_socket_builder", issue = "none")]
+    pub fn bind<A: ToSocketAddrs>(&self, addr: A) -> io::Result<TcpListener> {
+        super::each_addr(addr, |addr| net_imp::TcpListener::bind_with(addr, &self.opts))
+            .map(TcpListener::from_inner)
+    }
+}
+
+/// A builder for a [`TcpStream`] with options that have to be set before the
+/// socket is connected.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(net_socket_builder)]
+/// use std::net::TcpStreamBuilder;
+/// use std::time::Duration;
+///
+/// let stream = TcpStreamBuilder::new()
+///     .nodelay(true)
+///     .keepalive(true)
+///     .keepalive_idle(Duration::from_secs(30))
+///     .connect("127.0.0.1:8080")?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+#[unstable(feature = "net_socket_builder", issue = "none")]
+pub struct TcpStreamBuilder {
+    opts: net_imp::SocketOptions,
+    happy_eyeballs: Option<Duration>,
+}
+
+impl TcpStreamBuilder {
+    /// Creates a builder with no options set.
+    #[unstable(feature = "net_socket_builder", issue = "none")]
+    #[must_use]
+    pub fn new() -> TcpStreamBuilder {
+        TcpStreamBuilder::default()
+    }
+
+    socket_options!();
+    keepalive_options!();
+
+    /// Sets the `TCP_NODELAY` option before connecting.
+    ///
+    /// See [`TcpStream::set_nodelay`] for details.
+    #[unstable(feature = "net_socket_builder", issue = "none")]
+    pub fn nodelay(&mut self, nodelay: bool) -> &mut Self {
+        self.opts.nodelay = Some(nodelay);
+        self
+    }
+
+    /// Races connection attempts to all addresses instead of trying them one
+    /// after another, as described in [RFC 8305] ("Happy Eyeballs").
+    ///
+    /// When enabled, [`connect`](Self::connect) resolves all addresses up
+    /// front and reorders them so that IPv6 and IPv4 addresses alternate,
+    /// starting with the family of the first address. It then starts a new
+    /// connection attempt every `attempt_delay`, or as soon as the previous
+    /// attempt failed, until one of them succeeds. This way, an unreachable
+    /// address only delays the connection by `attempt_delay` instead of the
+    /// operating system's connect timeout.
+    ///
+    /// RFC 8305 recommends an `attempt_delay` of 250 milliseconds. Delays
+    /// below 10 milliseconds are raised to 10 milliseconds.
+    ///
+    /// [RFC 8305]: https://www.rfc-editor.org/rfc/rfc8305
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// On platforms other than Unix and Windows, the addresses are tried one
+    /// after another in the interleaved order.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(net_socket_builder)]
+    /// use std::net::TcpStreamBuilder;
+    /// use std::time::Duration;
+    ///
+    /// let stream = TcpStreamBuilder::new()
+    ///     .happy_eyeballs(Duration::from_millis(250))
+    ///     .connect("example.com:80")?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[unstable(feature = "net_socket_builder", issue = "none")]
+    pub fn happy_eyeballs(&mut self, attempt_delay: Duration) -> &mut Self {
+        self.happy_eyeballs = Some(cmp::max(attempt_delay, MIN_ATTEMPT_DELAY));
+        self
+    }
+
+    /// Opens a TCP connection to a remote host with the configured options.
+    ///
+    /// Like [`TcpStream::connect`], every address yielded by `addr` is tried in
+    /// turn, and the error of the last attempt is returned if none succeeds.
+    /// See [`happy_eyeballs`](Self::happy_eyeballs) for racing the attempts
+    /// instead.
+    #[unstable(feature = "net_socket_builder", issue = "none")]
+    pub fn connect<A: ToSocketAddrs>(&self, addr: A) -> io::Result<TcpStream> {
+        if let Some(attempt_delay) = self.happy_eyeballs {
+            let addrs = interleave_families(addr.to_socket_addrs()?);
+            return net_imp::TcpStream::connect_happy_eyeballs(&addrs, &self.opts, attempt_delay)
+                .map(TcpStream::from_inner);
+        }
+        super::each_addr(addr, |addr| net_imp::TcpStream::connect_with(addr, &self.opts, None))
+            .map(TcpStream::from_inner)
+    }
+
+    /// Opens a TCP connection to a remote host with a timeout and the
+    /// configured options.
+    ///
+    /// See [`TcpStream::connect_timeout`] for details.
+    #[unstable(feature = "net_socket_builder", issue = "none")]
+    pub fn connect_timeout(&self, addr: &SocketAddr, timeout: Duration) -> io::Result<TcpStream> {
+        net_imp::TcpStream::connect_with(Ok(addr), &self.opts, Some(timeout))
+            .map(TcpStream::from_inner)
+    }
+}
+
+/// The minimum connection attempt delay recommended by RFC 8305, section 5.
+const MIN_ATTEMPT_DELAY: Duration = Duration::from_millis(10);
+
+/// Reorders `addrs` so that the address families alternate, starting with the
+/// family of the first address, as described in RFC 8305, section 4.
+fn interleave_families(addrs: impl Iterator<Item = SocketAddr>) -> Vec<SocketAddr> {
+    let (mut first, mut second) = (VecDeque::new(), VecDeque::new());
+    let mut first_is_v6 = None;
+    for addr in addrs {
+        if *first_is_v6.get_or_insert(addr.is_ipv6()) == addr.is_ipv6() {
+            first.push_back(addr);
+        } else {
+            second.push_back(addr);
+        }
+    }
+
+    let mut interleaved = Vec::with_capacity(first.len() + second.len());
+    while !first.is_empty() || !second.is_empty() {
+        interleaved.extend(first.pop_front());
+        interleaved.extend(second.pop_front());
+    }
+    interleaved
+}
+
+/// A builder for a [`UdpSocket`] with options that have to be set before the
+/// socket is bound.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(net_socket_builder)]
+/// use std::net::UdpSocketBuilder;
+///
+/// let socket = UdpSocketBuilder::new()
+///     .reuse_address(true)
+///     .recv_buffer_size(1 << 20)
+///     .bind("0.0.0.0:5353")?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+#[unstable(feature = "net_socket_builder", issue = "none")]
+pub struct UdpSocketBuilder {
+    opts: net_imp::SocketOptions,
+}
+
+impl UdpSocketBuilder {
+    /// Creates a builder with no options set.
+    #[unstable(feature = "net_socket_builder", issue = "none")]
+    #[must_use]
+    pub fn new() -> UdpSocketBuilder {
+        UdpSocketBuilder::default()
+    }
+
+    socket_options!();
+
+    /// Creates a UDP socket bound to the specified address with the configured
+    /// options.
+    ///
+    /// Like [`UdpSocket::bind`], every address yielded by `addr` is tried in
+    /// turn, and the error of the last attempt is returned if none succeeds.
+    #[unstable(feature = "net_socket_builder", issue = "none")]
+    pub fn bind<A: ToSocketAddrs>(&self, addr: A) -> io::Result<UdpSocket> {
+        super::each_addr(addr, |addr| net_imp::UdpSocket::bind_with(addr, &self.opts))
+            .map(UdpSocket::from_inner)
+    }
+}
+
+impl AsInnerMut<net_imp::SocketOptions> for TcpListenerBuilder {
+    #[inline]
+    fn as_inner_mut(&mut self) -> &mut net_imp::SocketOptions {
+        &mut self.opts
+    }
+}
+
+impl AsInnerMut<net_imp::SocketOptions> for TcpStreamBuilder {
+    #[inline]
+    fn as_inner_mut(&mut self) -> &mut net_imp::SocketOptions {
+        &mut self.opts
+    }
+}
+
+impl AsInnerMut<net_imp::SocketOptions> for UdpSocketBuilder {
+    #[inline]
+    fn as_inner_mut(&mut self) -> &mut net_imp::SocketOptions {
+        &mut self.opts
+    }
+}
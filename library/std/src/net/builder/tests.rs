@@ -0,0 +1,133 @@
+use crate::io::ErrorKind;
+use crate::io::prelude::*;
+use crate::net::test::{next_test_ip4, next_test_ip6};
+use crate::net::*;
+use crate::thread;
+use crate::time::{Duration, Instant};
+
+macro_rules! t {
+    ($e:expr) => {
+        match $e {
+            Ok(t) => t,
+            Err(e) => panic!("received error for `{}`: {}", stringify!($e), e),
+        }
+    };
+}
+
+#[test]
+fn listener_builder_accepts() {
+    let addr = next_test_ip4();
+    let listener = t!(TcpListenerBuilder::new().backlog(1).keepalive(true).bind(&addr));
+    assert_eq!(t!(listener.local_addr()), addr);
+
+    let t = thread::spawn(move || {
+        let mut stream = t!(TcpStream::connect(&addr));
+        t!(stream.write(&[99]));
+    });
+
+    let mut stream = t!(listener.accept()).0;
+    let mut buf = [0];
+    t!(stream.read(&mut buf));
+    assert_eq!(buf[0], 99);
+    t.join().unwrap();
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+fn listener_builder_reuse_port() {
+    let addr = next_test_ip4();
+    let mut builder = TcpListenerBuilder::new();
+    builder.reuse_port(true);
+    let _first = t!(builder.bind(&addr));
+    let _second = t!(builder.bind(&addr));
+
+    // Without the option, the address is still taken.
+    assert!(TcpListener::bind(&addr).is_err());
+}
+
+#[test]
+fn listener_builder_only_v6() {
+    let addr = next_test_ip6();
+    let listener = match TcpListenerBuilder::new().only_v6(true).bind(&addr) {
+        Ok(listener) => listener,
+        // IPv6 may not be available in the test environment.
+        Err(_) => return,
+    };
+    assert_eq!(t!(listener.only_v6()), true);
+}
+
+#[test]
+fn stream_builder_options() {
+    let addr = next_test_ip4();
+    let _listener = t!(TcpListener::bind(&addr));
+
+    let stream = t!(TcpStreamBuilder::new()
+        .nodelay(true)
+        .send_buffer_size(64 * 1024)
+        .recv_buffer_size(64 * 1024)
+        .connect(&addr));
+    assert_eq!(t!(stream.nodelay()), true);
+    assert_eq!(t!(stream.peer_addr()), addr);
+
+    let stream = t!(TcpStreamBuilder::new().connect_timeout(&addr, Duration::from_secs(5)));
+    assert_eq!(t!(stream.peer_addr()), addr);
+}
+
+#[test]
+fn udp_builder_send_recv() {
+    let addr1 = next_test_ip4();
+    let addr2 = next_test_ip4();
+    let mut builder = UdpSocketBuilder::new();
+    builder.reuse_address(true).recv_buffer_size(64 * 1024);
+    let server = t!(builder.bind(&addr1));
+    let client = t!(builder.bind(&addr2));
+
+    t!(client.send_to(&[99], &addr1));
+    let mut buf = [0];
+    let (n, src) = t!(server.recv_from(&mut buf));
+    assert_eq!(n, 1);
+    assert_eq!(buf[0], 99);
+    assert_eq!(src, addr2);
+}
+
+#[test]
+fn interleave_families() {
+    let v4 = |port| SocketAddr::from(([127, 0, 0, 1], port));
+    let v6 = |port| SocketAddr::from(([0, 0, 0, 0, 0, 0, 0, 1], port));
+
+    let addrs = vec![v6(1), v6(2), v6(3), v4(4), v4(5)];
+    assert_eq!(super::interleave_families(addrs.into_iter()), [v6(1), v4(4), v6(2), v4(5), v6(3)],);
+
+    let addrs = vec![v4(1), v6(2), v6(3)];
+    assert_eq!(super::interleave_families(addrs.into_iter()), [v4(1), v6(2), v6(3)]);
+}
+
+#[test]
+fn happy_eyeballs_skips_blackholed_address() {
+    let addr = next_test_ip4();
+    let _listener = t!(TcpListener::bind(&addr));
+
+    // An address in TEST-NET-1 (RFC 5737) never answers, so a sequential
+    // connect would hang until the operating system gives up on it.
+    let blackhole = SocketAddr::from(([192, 0, 2, 1], addr.port()));
+    let start = Instant::now();
+    let stream = t!(TcpStreamBuilder::new()
+        .happy_eyeballs(Duration::from_millis(50))
+        .connect(&[blackhole, addr][..]));
+    assert_eq!(t!(stream.peer_addr()), addr);
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn happy_eyeballs_all_failed() {
+    // Nothing listens on these ports, so every attempt is refused.
+    let addrs = [next_test_ip4(), next_test_ip4()];
+    let result =
+        TcpStreamBuilder::new().happy_eyeballs(Duration::from_millis(10)).connect(&addrs[..]);
+    assert!(result.is_err());
+
+    let result = TcpStreamBuilder::new()
+        .happy_eyeballs(Duration::from_millis(10))
+        .connect(&[] as &[SocketAddr]);
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+}
@@ -0,0 +1,78 @@
+//! Linux and Android-specific extensions to the socket builders in the
+//! [`std::net`] module.
+//!
+//! [`std::net`]: crate::net
+
+use crate::net;
+use crate::sealed::Sealed;
+use crate::sys_common::AsInnerMut;
+
+/// Os-specific extensions for [`TcpListenerBuilder`], [`TcpStreamBuilder`] and
+/// [`UdpSocketBuilder`].
+///
+/// [`TcpListenerBuilder`]: net::TcpListenerBuilder
+/// [`TcpStreamBuilder`]: net::TcpStreamBuilder
+/// [`UdpSocketBuilder`]: net::UdpSocketBuilder
+#[unstable(feature = "net_socket_builder", issue = "none")]
+pub trait SocketBuilderExt: Sealed {
+    /// Binds the socket to a network interface with `SO_BINDTODEVICE`.
+    ///
+    /// Only packets received on the interface named `interface`, e.g.
+    /// `b"eth0"`, are processed by the socket, and outgoing packets are routed
+    /// through it. Setting this option usually requires the `CAP_NET_RAW`
+    /// capability.
+    ///
+    /// See [`man 7 socket`](https://man7.org/linux/man-pages/man7/socket.7.html)
+    /// for more information.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(net_socket_builder)]
+    /// use std::net::TcpListenerBuilder;
+    /// #[cfg(target_os = "linux")]
+    /// use std::os::linux::net::SocketBuilderExt;
+    /// #[cfg(target_os = "android")]
+    /// use std::os::android::net::SocketBuilderExt;
+    ///
+    /// let listener = TcpListenerBuilder::new()
+    ///     .bind_device(b"eth0")
+    ///     .bind("0.0.0.0:8080")
+    ///     .expect("couldn't bind to eth0");
+    /// ```
+    #[unstable(feature = "net_socket_builder", issue = "none")]
+    fn bind_device(&mut self, interface: &[u8]) -> &mut Self;
+}
+
+#[unstable(feature = "net_socket_builder", issue = "none")]
+impl Sealed for net::TcpListenerBuilder {}
+
+#[unstable(feature = "net_socket_builder", issue = "none")]
+impl SocketBuilderExt for net::TcpListenerBuilder {
+    fn bind_device(&mut self, interface: &[u8]) -> &mut Self {
+        self.as_inner_mut().bind_device = Some(interface.to_vec());
+        self
+    }
+}
+
+#[unstable(feature = "net_socket_builder", issue = "none")]
+impl Sealed for net::TcpStreamBuilder {}
+
+#[unstable(feature = "net_socket_builder", issue = "none")]
+impl SocketBuilderExt for net::TcpStreamBuilder {
+    fn bind_device(&mut self, interface: &[u8]) -> &mut Self {
+        self.as_inner_mut().bind_device = Some(interface.to_vec());
+        self
+    }
+}
+
+#[unstable(feature = "net_socket_builder", issue = "none")]
+impl Sealed for net::UdpSocketBuilder {}
+
+#[unstable(feature = "net_socket_builder", issue = "none")]
+impl SocketBuilderExt for net::UdpSocketBuilder {
+    fn bind_device(&mut self, interface: &[u8]) -> &mut Self {
+        self.as_inner_mut().bind_device = Some(interface.to_vec());
+        self
+    }
+}
@@ -276,11 +276,12 @@ impl RefUnwindSafe for Condvar {}
 
 // https://github.com/rust-lang/rust/issues/62301
 #[stable(feature = "hashbrown", since = "1.36.0")]
-impl<K, V, S> UnwindSafe for collections::HashMap<K, V, S>
+impl<K, V, S, A> UnwindSafe for collections::HashMap<K, V, S, A>
 where
     K: UnwindSafe,
     V: UnwindSafe,
     S: UnwindSafe,
+    A: crate::alloc::Allocator + UnwindSafe,
 {
 }
 
@@ -1,9 +1,9 @@
 #![allow(dead_code)] // not used on all platforms
 
-use crate::fs;
 use crate::io::{self, Error, ErrorKind};
-use crate::path::Path;
+use crate::path::{Path, PathBuf};
 use crate::sys_common::ignore_notfound;
+use crate::{fmt, fs};
 
 pub(crate) const NOT_FILE_ERROR: Error = io::const_error!(
     ErrorKind::InvalidInput,
@@ -58,3 +58,134 @@ pub fn exists(path: &Path) -> io::Result<bool> {
         Err(error) => Err(error),
     }
 }
+
+/// A directory stream used by `fs::walk_dir` on platforms without `openat`.
+///
+/// Subdirectories are opened by their full path, so unlike the descriptor
+/// relative implementation a concurrent rename of an ancestor can redirect
+/// the walk.
+pub struct DirStream {
+    iter: super::ReadDir,
+    path: PathBuf,
+    canonical: Option<PathBuf>,
+}
+
+impl DirStream {
+    pub fn open(path: &Path) -> io::Result<DirStream> {
+        let iter = super::read_dir(path)?;
+        Ok(DirStream { iter, path: path.to_path_buf(), canonical: super::canonicalize(path).ok() })
+    }
+
+    pub fn open_child(
+        &self,
+        entry: &super::DirEntry,
+        follow_symlinks: bool,
+    ) -> io::Result<Option<DirStream>> {
+        let path = entry.path();
+        let attr =
+            if follow_symlinks { super::metadata(&path) } else { super::symlink_metadata(&path) };
+        match attr {
+            Ok(attr) if attr.file_type().is_dir() => DirStream::open(&path).map(Some),
+            Ok(_) => Ok(None),
+            // dangling symlink
+            Err(err) if follow_symlinks && err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn is_same_dir(&self, other: &DirStream) -> bool {
+        matches!((&self.canonical, &other.canonical), (Some(a), Some(b)) if a == b)
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn is_same_device(&self, other: &DirStream) -> io::Result<bool> {
+        Ok(super::imp::volume_serial_number(&self.path)?
+            == super::imp::volume_serial_number(&other.path)?)
+    }
+
+    #[cfg(unix)]
+    pub fn is_same_device(&self, other: &DirStream) -> io::Result<bool> {
+        use crate::os::unix::fs::MetadataExt;
+
+        Ok(crate::fs::metadata(&self.path)?.dev() == crate::fs::metadata(&other.path)?.dev())
+    }
+
+    #[cfg(not(any(unix, target_os = "windows")))]
+    pub fn is_same_device(&self, _other: &DirStream) -> io::Result<bool> {
+        Err(io::const_error!(
+            ErrorKind::Unsupported,
+            "staying on one file system is not supported on this platform",
+        ))
+    }
+}
+
+impl Iterator for DirStream {
+    type Item = io::Result<super::DirEntry>;
+
+    fn next(&mut self) -> Option<io::Result<super::DirEntry>> {
+        self.iter.next()
+    }
+}
+
+/// A directory handle used by `fs::Dir` on platforms without `openat`.
+///
+/// Every operation is performed on the path the directory was opened with,
+/// so this does not protect against the directory being moved or replaced.
+pub struct Dir {
+    root: PathBuf,
+}
+
+impl Dir {
+    pub fn open(path: &Path) -> io::Result<Dir> {
+        if !super::metadata(path)?.file_type().is_dir() {
+            return Err(io::const_error!(ErrorKind::NotADirectory, "not a directory"));
+        }
+        Ok(Dir { root: path.to_path_buf() })
+    }
+
+    pub fn open_dir(&self, path: &Path) -> io::Result<Dir> {
+        Dir::open(&self.root.join(path))
+    }
+
+    pub fn open_file(&self, path: &Path, opts: &super::OpenOptions) -> io::Result<super::File> {
+        super::File::open(&self.root.join(path), opts)
+    }
+
+    pub fn create_dir(&self, path: &Path) -> io::Result<()> {
+        super::DirBuilder::new().mkdir(&self.root.join(path))
+    }
+
+    pub fn remove_file(&self, path: &Path) -> io::Result<()> {
+        super::remove_file(&self.root.join(path))
+    }
+
+    pub fn remove_dir(&self, path: &Path) -> io::Result<()> {
+        super::remove_dir(&self.root.join(path))
+    }
+
+    pub fn rename(&self, from: &Path, to_dir: &Dir, to: &Path) -> io::Result<()> {
+        super::rename(&self.root.join(from), &to_dir.root.join(to))
+    }
+
+    pub fn metadata(&self, path: &Path) -> io::Result<super::FileAttr> {
+        super::metadata(&self.root.join(path))
+    }
+
+    pub fn symlink_metadata(&self, path: &Path) -> io::Result<super::FileAttr> {
+        super::symlink_metadata(&self.root.join(path))
+    }
+
+    pub fn read_dir(&self) -> io::Result<super::ReadDir> {
+        super::read_dir(&self.root)
+    }
+
+    pub fn symlink(&self, original: &Path, link: &Path) -> io::Result<()> {
+        super::symlink(original, &self.root.join(link))
+    }
+}
+
+impl fmt::Debug for Dir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dir").field("path", &self.root).finish()
+    }
+}
@@ -4,6 +4,7 @@
 use crate::path::{Path, PathBuf};
 
 pub mod common;
+mod watch;
 
 cfg_if::cfg_if! {
     if #[cfg(target_family = "unix")] {
@@ -46,10 +47,19 @@ pub fn with_native_path<T>(path: &Path, f: &dyn Fn(&Path) -> io::Result<T>) -> i
     f(path)
 }
 
+#[cfg(not(any(target_family = "unix", target_os = "windows")))]
+pub use common::Dir;
+#[cfg(not(target_family = "unix"))]
+pub use common::DirStream;
+#[cfg(any(target_family = "unix", target_os = "windows"))]
+pub use imp::Dir;
+#[cfg(target_family = "unix")]
+pub use imp::DirStream;
 pub use imp::{
     DirBuilder, DirEntry, File, FileAttr, FilePermissions, FileTimes, FileType, OpenOptions,
     ReadDir,
 };
+pub use watch::{WatchEvent, Watcher};
 
 pub fn read_dir(path: &Path) -> io::Result<ReadDir> {
     // FIXME: use with_native_path on all platforms
@@ -127,3 +137,14 @@ pub fn exists(path: &Path) -> io::Result<bool> {
     #[cfg(windows)]
     with_native_path(path, &imp::exists)
 }
+
+pub fn sync_dir(path: &Path) -> io::Result<()> {
+    // Only Unix can open and sync a directory to make changes to its entries durable.
+    #[cfg(unix)]
+    return with_native_path(path, &imp::sync_dir);
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        Ok(())
+    }
+}
@@ -249,7 +249,7 @@ pub struct FileAttr {
 
 // all DirEntry's will have a reference to this struct
 struct InnerReadDir {
-    dirp: Dir,
+    dirp: DirPtr,
     root: PathBuf,
 }
 
@@ -264,10 +264,10 @@ fn new(inner: InnerReadDir) -> Self {
     }
 }
 
-struct Dir(*mut libc::DIR);
+struct DirPtr(*mut libc::DIR);
 
-unsafe impl Send for Dir {}
-unsafe impl Sync for Dir {}
+unsafe impl Send for DirPtr {}
+unsafe impl Sync for DirPtr {}
 
 #[cfg(any(
     target_os = "android",
@@ -852,7 +852,7 @@ pub(crate) fn debug_assert_fd_is_open(fd: RawFd) {
     }
 }
 
-impl Drop for Dir {
+impl Drop for DirPtr {
     fn drop(&mut self) {
         // dirfd isn't supported everywhere
         #[cfg(not(any(
@@ -1832,7 +1832,7 @@ pub fn readdir(path: &Path) -> io::Result<ReadDir> {
         Err(Error::last_os_error())
     } else {
         let root = path.to_path_buf();
-        let inner = InnerReadDir { dirp: Dir(ptr), root };
+        let inner = InnerReadDir { dirp: DirPtr(ptr), root };
         Ok(ReadDir::new(inner))
     }
 }
@@ -1845,6 +1845,14 @@ pub fn rename(old: &CStr, new: &CStr) -> io::Result<()> {
     cvt(unsafe { libc::rename(old.as_ptr(), new.as_ptr()) }).map(|_| ())
 }
 
+pub fn sync_dir(p: &CStr) -> io::Result<()> {
+    let fd = cvt_r(|| unsafe {
+        open64(p.as_ptr(), libc::O_CLOEXEC | libc::O_RDONLY | libc::O_DIRECTORY)
+    })?;
+    let dir = File(unsafe { FileDesc::from_raw_fd(fd) });
+    dir.fsync()
+}
+
 pub fn set_perm(p: &CStr, perm: FilePermissions) -> io::Result<()> {
     cvt_r(|| unsafe { libc::chmod(p.as_ptr(), perm.mode) }).map(|_| ())
 }
@@ -2161,178 +2169,21 @@ pub fn mkfifo(path: &Path, mode: u32) -> io::Result<()> {
     })
 }
 
-pub use remove_dir_impl::remove_dir_all;
-
-// Fallback for REDOX, ESP-ID, Horizon, Vita, Vxworks and Miri
-#[cfg(any(
-    target_os = "redox",
-    target_os = "espidf",
-    target_os = "horizon",
-    target_os = "vita",
-    target_os = "nto",
-    target_os = "vxworks",
-    miri
-))]
-mod remove_dir_impl {
-    pub use crate::sys::fs::common::remove_dir_all;
-}
-
-// Modern implementation using openat(), unlinkat() and fdopendir()
-#[cfg(not(any(
-    target_os = "redox",
-    target_os = "espidf",
-    target_os = "horizon",
-    target_os = "vita",
-    target_os = "nto",
-    target_os = "vxworks",
-    miri
-)))]
-mod remove_dir_impl {
-    #[cfg(not(all(target_os = "linux", target_env = "gnu")))]
-    use libc::{fdopendir, openat, unlinkat};
-    #[cfg(all(target_os = "linux", target_env = "gnu"))]
-    use libc::{fdopendir, openat64 as openat, unlinkat};
-
-    use super::{Dir, DirEntry, InnerReadDir, ReadDir, lstat};
-    use crate::ffi::CStr;
-    use crate::io;
-    use crate::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd};
-    use crate::os::unix::prelude::{OwnedFd, RawFd};
-    use crate::path::{Path, PathBuf};
-    use crate::sys::common::small_c_string::run_path_with_cstr;
-    use crate::sys::{cvt, cvt_r};
-    use crate::sys_common::ignore_notfound;
-
-    pub fn openat_nofollow_dironly(parent_fd: Option<RawFd>, p: &CStr) -> io::Result<OwnedFd> {
-        let fd = cvt_r(|| unsafe {
-            openat(
-                parent_fd.unwrap_or(libc::AT_FDCWD),
-                p.as_ptr(),
-                libc::O_CLOEXEC | libc::O_RDONLY | libc::O_NOFOLLOW | libc::O_DIRECTORY,
-            )
-        })?;
-        Ok(unsafe { OwnedFd::from_raw_fd(fd) })
-    }
-
-    fn fdreaddir(dir_fd: OwnedFd) -> io::Result<(ReadDir, RawFd)> {
-        let ptr = unsafe { fdopendir(dir_fd.as_raw_fd()) };
-        if ptr.is_null() {
-            return Err(io::Error::last_os_error());
-        }
-        let dirp = Dir(ptr);
-        // file descriptor is automatically closed by libc::closedir() now, so give up ownership
-        let new_parent_fd = dir_fd.into_raw_fd();
-        // a valid root is not needed because we do not call any functions involving the full path
-        // of the `DirEntry`s.
-        let dummy_root = PathBuf::new();
-        let inner = InnerReadDir { dirp, root: dummy_root };
-        Ok((ReadDir::new(inner), new_parent_fd))
-    }
-
-    #[cfg(any(
-        target_os = "solaris",
-        target_os = "illumos",
-        target_os = "haiku",
-        target_os = "vxworks",
-        target_os = "aix",
-    ))]
-    fn is_dir(_ent: &DirEntry) -> Option<bool> {
-        None
-    }
-
-    #[cfg(not(any(
-        target_os = "solaris",
-        target_os = "illumos",
-        target_os = "haiku",
+cfg_if::cfg_if! {
+    // Fallback for REDOX, ESP-ID, Horizon, Vita, Vxworks and Miri
+    if #[cfg(any(
+        target_os = "redox",
+        target_os = "espidf",
+        target_os = "horizon",
+        target_os = "vita",
+        target_os = "nto",
         target_os = "vxworks",
-        target_os = "aix",
-    )))]
-    fn is_dir(ent: &DirEntry) -> Option<bool> {
-        match ent.entry.d_type {
-            libc::DT_UNKNOWN => None,
-            libc::DT_DIR => Some(true),
-            _ => Some(false),
-        }
-    }
-
-    fn is_enoent(result: &io::Result<()>) -> bool {
-        if let Err(err) = result
-            && matches!(err.raw_os_error(), Some(libc::ENOENT))
-        {
-            true
-        } else {
-            false
-        }
-    }
-
-    fn remove_dir_all_recursive(parent_fd: Option<RawFd>, path: &CStr) -> io::Result<()> {
-        // try opening as directory
-        let fd = match openat_nofollow_dironly(parent_fd, &path) {
-            Err(err) if matches!(err.raw_os_error(), Some(libc::ENOTDIR | libc::ELOOP)) => {
-                // not a directory - don't traverse further
-                // (for symlinks, older Linux kernels may return ELOOP instead of ENOTDIR)
-                return match parent_fd {
-                    // unlink...
-                    Some(parent_fd) => {
-                        cvt(unsafe { unlinkat(parent_fd, path.as_ptr(), 0) }).map(drop)
-                    }
-                    // ...unless this was supposed to be the deletion root directory
-                    None => Err(err),
-                };
-            }
-            result => result?,
-        };
-
-        // open the directory passing ownership of the fd
-        let (dir, fd) = fdreaddir(fd)?;
-        for child in dir {
-            let child = child?;
-            let child_name = child.name_cstr();
-            // we need an inner try block, because if one of these
-            // directories has already been deleted, then we need to
-            // continue the loop, not return ok.
-            let result: io::Result<()> = try {
-                match is_dir(&child) {
-                    Some(true) => {
-                        remove_dir_all_recursive(Some(fd), child_name)?;
-                    }
-                    Some(false) => {
-                        cvt(unsafe { unlinkat(fd, child_name.as_ptr(), 0) })?;
-                    }
-                    None => {
-                        // POSIX specifies that calling unlink()/unlinkat(..., 0) on a directory can succeed
-                        // if the process has the appropriate privileges. This however can causing orphaned
-                        // directories requiring an fsck e.g. on Solaris and Illumos. So we try recursing
-                        // into it first instead of trying to unlink() it.
-                        remove_dir_all_recursive(Some(fd), child_name)?;
-                    }
-                }
-            };
-            if result.is_err() && !is_enoent(&result) {
-                return result;
-            }
-        }
-
-        // unlink the directory after removing its contents
-        ignore_notfound(cvt(unsafe {
-            unlinkat(parent_fd.unwrap_or(libc::AT_FDCWD), path.as_ptr(), libc::AT_REMOVEDIR)
-        }))?;
-        Ok(())
-    }
-
-    fn remove_dir_all_modern(p: &CStr) -> io::Result<()> {
-        // We cannot just call remove_dir_all_recursive() here because that would not delete a passed
-        // symlink. No need to worry about races, because remove_dir_all_recursive() does not recurse
-        // into symlinks.
-        let attr = lstat(p)?;
-        if attr.file_type().is_symlink() {
-            super::unlink(p)
-        } else {
-            remove_dir_all_recursive(None, &p)
-        }
-    }
-
-    pub fn remove_dir_all(p: &Path) -> io::Result<()> {
-        run_path_with_cstr(p, &remove_dir_all_modern)
+        miri
+    ))] {
+        pub use crate::sys::fs::common::{Dir, DirStream, remove_dir_all};
+    } else {
+        // Modern implementation using openat(), fdopendir() and the other `*at` functions
+        mod dir;
+        pub use dir::{Dir, DirStream, remove_dir_all};
     }
 }
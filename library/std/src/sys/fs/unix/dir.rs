@@ -0,0 +1,339 @@
+//! Directory operations relative to an open directory descriptor.
+//!
+//! `remove_dir_all`, `fs::walk_dir` and `fs::Dir` look up every child with
+//! `openat()` and the other `*at` functions instead of by its full path, so a
+//! concurrent rename of an ancestor or a directory being swapped for a symlink
+//! cannot redirect them elsewhere.
+
+#[cfg(not(any(
+    all(target_os = "linux", not(target_env = "musl")),
+    target_os = "hurd",
+    target_os = "l4re",
+)))]
+use libc::fstatat as fstatat64;
+#[cfg(any(
+    all(target_os = "linux", not(target_env = "musl")),
+    target_os = "hurd",
+    target_os = "l4re",
+))]
+use libc::fstatat64;
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+use libc::openat;
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+use libc::openat64 as openat;
+use libc::{c_int, fdopendir, mkdirat, renameat, symlinkat, unlinkat};
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+use super::try_statx;
+use super::{
+    DirEntry, DirPtr, File, FileAttr, InnerReadDir, OpenOptions, ReadDir, fstat64, lstat, stat64,
+};
+use crate::ffi::CStr;
+use crate::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd};
+use crate::os::unix::prelude::{OwnedFd, RawFd};
+use crate::path::{Path, PathBuf};
+use crate::sys::common::small_c_string::run_path_with_cstr;
+use crate::sys::fd::FileDesc;
+use crate::sys::{cvt, cvt_r};
+use crate::sys_common::ignore_notfound;
+use crate::{fmt, io, mem};
+
+/// Opens the directory `p` relative to `parent_fd` for reading, with `flags`
+/// added to the open flags.
+fn openat_dir(parent_fd: RawFd, p: &CStr, flags: c_int) -> io::Result<OwnedFd> {
+    let fd = cvt_r(|| unsafe {
+        openat(parent_fd, p.as_ptr(), libc::O_CLOEXEC | libc::O_RDONLY | libc::O_DIRECTORY | flags)
+    })?;
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+/// Turns a directory descriptor into a `ReadDir`, returning the descriptor the
+/// stream reads from. It stays valid until the `ReadDir` is dropped.
+fn fdreaddir(dir_fd: OwnedFd, root: PathBuf) -> io::Result<(ReadDir, RawFd)> {
+    let ptr = unsafe { fdopendir(dir_fd.as_raw_fd()) };
+    if ptr.is_null() {
+        return Err(io::Error::last_os_error());
+    }
+    let dirp = DirPtr(ptr);
+    // file descriptor is automatically closed by libc::closedir() now, so give up ownership
+    let fd = dir_fd.into_raw_fd();
+    Ok((ReadDir::new(InnerReadDir { dirp, root }), fd))
+}
+
+#[cfg(any(
+    target_os = "solaris",
+    target_os = "illumos",
+    target_os = "haiku",
+    target_os = "vxworks",
+    target_os = "aix",
+))]
+fn is_dir(_ent: &DirEntry) -> Option<bool> {
+    None
+}
+
+#[cfg(not(any(
+    target_os = "solaris",
+    target_os = "illumos",
+    target_os = "haiku",
+    target_os = "vxworks",
+    target_os = "aix",
+)))]
+fn is_dir(ent: &DirEntry) -> Option<bool> {
+    match ent.entry.d_type {
+        libc::DT_UNKNOWN => None,
+        libc::DT_DIR => Some(true),
+        _ => Some(false),
+    }
+}
+
+fn is_enoent(result: &io::Result<()>) -> bool {
+    if let Err(err) = result
+        && matches!(err.raw_os_error(), Some(libc::ENOENT))
+    {
+        true
+    } else {
+        false
+    }
+}
+
+fn remove_dir_all_recursive(parent_fd: Option<RawFd>, path: &CStr) -> io::Result<()> {
+    // try opening as directory
+    let fd = match openat_dir(parent_fd.unwrap_or(libc::AT_FDCWD), &path, libc::O_NOFOLLOW) {
+        Err(err) if matches!(err.raw_os_error(), Some(libc::ENOTDIR | libc::ELOOP)) => {
+            // not a directory - don't traverse further
+            // (for symlinks, older Linux kernels may return ELOOP instead of ENOTDIR)
+            return match parent_fd {
+                // unlink...
+                Some(parent_fd) => cvt(unsafe { unlinkat(parent_fd, path.as_ptr(), 0) }).map(drop),
+                // ...unless this was supposed to be the deletion root directory
+                None => Err(err),
+            };
+        }
+        result => result?,
+    };
+
+    // open the directory passing ownership of the fd
+    // a valid root is not needed because we do not call any functions involving the full path
+    // of the `DirEntry`s.
+    let (dir, fd) = fdreaddir(fd, PathBuf::new())?;
+    for child in dir {
+        let child = child?;
+        let child_name = child.name_cstr();
+        // we need an inner try block, because if one of these
+        // directories has already been deleted, then we need to
+        // continue the loop, not return ok.
+        let result: io::Result<()> = try {
+            match is_dir(&child) {
+                Some(true) => {
+                    remove_dir_all_recursive(Some(fd), child_name)?;
+                }
+                Some(false) => {
+                    cvt(unsafe { unlinkat(fd, child_name.as_ptr(), 0) })?;
+                }
+                None => {
+                    // POSIX specifies that calling unlink()/unlinkat(..., 0) on a directory can succeed
+                    // if the process has the appropriate privileges. This however can causing orphaned
+                    // directories requiring an fsck e.g. on Solaris and Illumos. So we try recursing
+                    // into it first instead of trying to unlink() it.
+                    remove_dir_all_recursive(Some(fd), child_name)?;
+                }
+            }
+        };
+        if result.is_err() && !is_enoent(&result) {
+            return result;
+        }
+    }
+
+    // unlink the directory after removing its contents
+    ignore_notfound(cvt(unsafe {
+        unlinkat(parent_fd.unwrap_or(libc::AT_FDCWD), path.as_ptr(), libc::AT_REMOVEDIR)
+    }))?;
+    Ok(())
+}
+
+fn remove_dir_all_modern(p: &CStr) -> io::Result<()> {
+    // We cannot just call remove_dir_all_recursive() here because that would not delete a passed
+    // symlink. No need to worry about races, because remove_dir_all_recursive() does not recurse
+    // into symlinks.
+    let attr = lstat(p)?;
+    if attr.file_type().is_symlink() {
+        super::unlink(p)
+    } else {
+        remove_dir_all_recursive(None, &p)
+    }
+}
+
+pub fn remove_dir_all(p: &Path) -> io::Result<()> {
+    run_path_with_cstr(p, &remove_dir_all_modern)
+}
+
+/// A directory stream that remembers the descriptor it was opened from, so
+/// that subdirectories can be opened relative to it.
+pub struct DirStream {
+    iter: ReadDir,
+    fd: RawFd,
+    dev: u64,
+    ino: u64,
+}
+
+impl DirStream {
+    pub fn open(path: &Path) -> io::Result<DirStream> {
+        let fd = run_path_with_cstr(path, &|p| openat_dir(libc::AT_FDCWD, p, 0))?;
+        DirStream::from_fd(fd, path.to_path_buf())
+    }
+
+    /// Opens the directory `entry` (which must have been yielded by this
+    /// stream) relative to this stream's descriptor. Returns `Ok(None)` if
+    /// the entry turned out not to be a directory, e.g. because it was
+    /// replaced after it was read.
+    pub fn open_child(
+        &self,
+        entry: &DirEntry,
+        follow_symlinks: bool,
+    ) -> io::Result<Option<DirStream>> {
+        let flags = if follow_symlinks { 0 } else { libc::O_NOFOLLOW };
+        match openat_dir(self.fd, entry.name_cstr(), flags) {
+            Ok(fd) => DirStream::from_fd(fd, entry.path()).map(Some),
+            Err(err) if matches!(err.raw_os_error(), Some(libc::ENOTDIR)) => Ok(None),
+            // a symlink we were asked not to follow
+            // (older Linux kernels may return ELOOP instead of ENOTDIR)
+            Err(err) if !follow_symlinks && matches!(err.raw_os_error(), Some(libc::ELOOP)) => {
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn from_fd(dir_fd: OwnedFd, root: PathBuf) -> io::Result<DirStream> {
+        let mut stat: stat64 = unsafe { mem::zeroed() };
+        cvt(unsafe { fstat64(dir_fd.as_raw_fd(), &mut stat) })?;
+        let (iter, fd) = fdreaddir(dir_fd, root)?;
+        Ok(DirStream { iter, fd, dev: stat.st_dev as u64, ino: stat.st_ino as u64 })
+    }
+
+    pub fn is_same_dir(&self, other: &DirStream) -> bool {
+        self.dev == other.dev && self.ino == other.ino
+    }
+
+    pub fn is_same_device(&self, other: &DirStream) -> io::Result<bool> {
+        Ok(self.dev == other.dev)
+    }
+}
+
+impl Iterator for DirStream {
+    type Item = io::Result<DirEntry>;
+
+    fn next(&mut self) -> Option<io::Result<DirEntry>> {
+        self.iter.next()
+    }
+}
+
+/// An open directory that files are looked up relative to with the `*at`
+/// family of functions.
+pub struct Dir {
+    fd: OwnedFd,
+    // Only used for `Debug` and to construct the paths of entries returned by `read_dir`.
+    root: PathBuf,
+}
+
+impl Dir {
+    pub fn open(path: &Path) -> io::Result<Dir> {
+        let fd = run_path_with_cstr(path, &|p| openat_dir(libc::AT_FDCWD, p, 0))?;
+        Ok(Dir { fd, root: path.to_path_buf() })
+    }
+
+    pub fn open_dir(&self, path: &Path) -> io::Result<Dir> {
+        let fd = run_path_with_cstr(path, &|p| openat_dir(self.raw_fd(), p, 0))?;
+        Ok(Dir { fd, root: self.root.join(path) })
+    }
+
+    pub fn open_file(&self, path: &Path, opts: &OpenOptions) -> io::Result<File> {
+        let flags = libc::O_CLOEXEC
+            | opts.get_access_mode()?
+            | opts.get_creation_mode()?
+            | (opts.custom_flags as c_int & !libc::O_ACCMODE);
+        // See `File::open_c` for why `mode` is passed as a `c_int`.
+        let fd = run_path_with_cstr(path, &|p| {
+            cvt_r(|| unsafe { openat(self.raw_fd(), p.as_ptr(), flags, opts.mode as c_int) })
+        })?;
+        Ok(File(unsafe { FileDesc::from_raw_fd(fd) }))
+    }
+
+    pub fn create_dir(&self, path: &Path) -> io::Result<()> {
+        run_path_with_cstr(path, &|p| {
+            cvt(unsafe { mkdirat(self.raw_fd(), p.as_ptr(), 0o777) }).map(|_| ())
+        })
+    }
+
+    pub fn remove_file(&self, path: &Path) -> io::Result<()> {
+        run_path_with_cstr(path, &|p| {
+            cvt(unsafe { unlinkat(self.raw_fd(), p.as_ptr(), 0) }).map(|_| ())
+        })
+    }
+
+    pub fn remove_dir(&self, path: &Path) -> io::Result<()> {
+        run_path_with_cstr(path, &|p| {
+            cvt(unsafe { unlinkat(self.raw_fd(), p.as_ptr(), libc::AT_REMOVEDIR) }).map(|_| ())
+        })
+    }
+
+    pub fn rename(&self, from: &Path, to_dir: &Dir, to: &Path) -> io::Result<()> {
+        run_path_with_cstr(from, &|from| {
+            run_path_with_cstr(to, &|to| {
+                cvt(unsafe { renameat(self.raw_fd(), from.as_ptr(), to_dir.raw_fd(), to.as_ptr()) })
+                    .map(|_| ())
+            })
+        })
+    }
+
+    pub fn metadata(&self, path: &Path) -> io::Result<FileAttr> {
+        run_path_with_cstr(path, &|p| self.stat_at(p, 0))
+    }
+
+    pub fn symlink_metadata(&self, path: &Path) -> io::Result<FileAttr> {
+        run_path_with_cstr(path, &|p| self.stat_at(p, libc::AT_SYMLINK_NOFOLLOW))
+    }
+
+    fn stat_at(&self, p: &CStr, flags: c_int) -> io::Result<FileAttr> {
+        cfg_has_statx! {
+            if let Some(ret) = unsafe { try_statx(
+                self.raw_fd(),
+                p.as_ptr(),
+                flags | libc::AT_STATX_SYNC_AS_STAT,
+                libc::STATX_BASIC_STATS | libc::STATX_BTIME,
+            ) } {
+                return ret;
+            }
+        }
+
+        let mut stat: stat64 = unsafe { mem::zeroed() };
+        cvt(unsafe { fstatat64(self.raw_fd(), p.as_ptr(), &mut stat, flags) })?;
+        Ok(FileAttr::from_stat64(stat))
+    }
+
+    pub fn read_dir(&self) -> io::Result<ReadDir> {
+        // Open a new description of the directory instead of duplicating the
+        // descriptor, so that the read position is not shared between streams.
+        let fd = openat_dir(self.raw_fd(), c".", 0)?;
+        fdreaddir(fd, self.root.clone()).map(|(dir, _)| dir)
+    }
+
+    pub fn symlink(&self, original: &Path, link: &Path) -> io::Result<()> {
+        run_path_with_cstr(original, &|original| {
+            run_path_with_cstr(link, &|link| {
+                cvt(unsafe { symlinkat(original.as_ptr(), self.raw_fd(), link.as_ptr()) })
+                    .map(|_| ())
+            })
+        })
+    }
+
+    fn raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+impl fmt::Debug for Dir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dir").field("fd", &self.raw_fd()).field("path", &self.root).finish()
+    }
+}
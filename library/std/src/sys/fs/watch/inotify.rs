@@ -0,0 +1,289 @@
+//! The inotify implementation of `fs::Watcher`.
+//!
+//! inotify only watches single directories, so recursive watches add a watch
+//! for every subdirectory, including ones that are created or moved into the
+//! tree later. Both halves of a rename are matched up by their cookie when
+//! they are read in the same batch; a rename out of (or into) the watched
+//! tree is reported as a removal (or creation), and the watches below a
+//! directory that was moved out are removed.
+
+use libc::{c_int, inotify_event};
+
+use super::WatchEvent;
+use crate::collections::{BTreeMap, VecDeque};
+use crate::ffi::OsStr;
+use crate::fs::WatchEventKind;
+use crate::os::unix::ffi::OsStrExt;
+use crate::os::unix::io::{AsRawFd, FromRawFd, OwnedFd};
+use crate::path::{Path, PathBuf};
+use crate::sys::common::small_c_string::run_path_with_cstr;
+use crate::sys::{cvt, cvt_r};
+use crate::time::{Duration, Instant};
+use crate::{fmt, fs, io, mem, ptr};
+
+const WATCH_MASK: u32 = libc::IN_CREATE
+    | libc::IN_DELETE
+    | libc::IN_MODIFY
+    | libc::IN_ATTRIB
+    | libc::IN_MOVED_FROM
+    | libc::IN_MOVED_TO
+    | libc::IN_DELETE_SELF
+    | libc::IN_MOVE_SELF;
+
+// Large enough for many events with maximum length names at once.
+const BUFFER_SIZE: usize = 16 * 1024;
+
+struct Watch {
+    path: PathBuf,
+    recursive: bool,
+    // Whether this path was passed to `watch`, as opposed to being a
+    // subdirectory that is watched because of a recursive watch.
+    explicit: bool,
+}
+
+pub struct Watcher {
+    fd: OwnedFd,
+    watches: BTreeMap<c_int, Watch>,
+    pending: VecDeque<WatchEvent>,
+}
+
+impl Watcher {
+    pub fn new() -> io::Result<Watcher> {
+        let fd = cvt(unsafe { libc::inotify_init1(libc::IN_CLOEXEC | libc::IN_NONBLOCK) })?;
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        Ok(Watcher { fd, watches: BTreeMap::new(), pending: VecDeque::new() })
+    }
+
+    pub fn watch(&mut self, path: &Path, recursive: bool) -> io::Result<()> {
+        let is_dir = fs::metadata(path)?.is_dir();
+        self.add_watch(path, recursive, true)?;
+        if recursive && is_dir {
+            self.watch_subdirs(path, false)?;
+        }
+        Ok(())
+    }
+
+    pub fn unwatch(&mut self, path: &Path) -> io::Result<()> {
+        let Some((&wd, watch)) =
+            self.watches.iter().find(|(_, watch)| watch.explicit && watch.path == path)
+        else {
+            return Err(io::const_error!(io::ErrorKind::NotFound, "path is not being watched"));
+        };
+
+        let recursive = watch.recursive;
+        self.remove_watch(wd);
+        if recursive {
+            self.remove_subdir_watches(path);
+        }
+        Ok(())
+    }
+
+    pub fn next_event(&mut self, timeout: Option<Duration>) -> io::Result<Option<WatchEvent>> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(Some(event));
+            }
+
+            let timeout_ms = match deadline {
+                None => -1,
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    // Round up so that we don't spin for sub-millisecond timeouts.
+                    let millis = remaining.as_micros().div_ceil(1000);
+                    millis.min(c_int::MAX as u128) as c_int
+                }
+            };
+            let mut pollfd =
+                libc::pollfd { fd: self.fd.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+            let ready = cvt_r(|| unsafe { libc::poll(&mut pollfd, 1, timeout_ms) })?;
+            if ready == 0 {
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    return Ok(None);
+                }
+                continue;
+            }
+            self.read_events()?;
+        }
+    }
+
+    fn add_watch(&mut self, path: &Path, recursive: bool, explicit: bool) -> io::Result<c_int> {
+        let wd = run_path_with_cstr(path, &|p| {
+            cvt(unsafe { libc::inotify_add_watch(self.fd.as_raw_fd(), p.as_ptr(), WATCH_MASK) })
+        })?;
+        // inotify returns the existing descriptor if the inode is already watched.
+        let explicit = explicit || self.watches.get(&wd).is_some_and(|watch| watch.explicit);
+        let recursive = recursive || self.watches.get(&wd).is_some_and(|watch| watch.recursive);
+        self.watches.insert(wd, Watch { path: path.to_path_buf(), recursive, explicit });
+        Ok(wd)
+    }
+
+    /// Adds watches for all directories below `dir`. If `report` is set, the
+    /// contents are reported as created, because they may have been created
+    /// before the watch on `dir` was in place.
+    fn watch_subdirs(&mut self, dir: &Path, report: bool) -> io::Result<()> {
+        for entry in fs::walk_dir(dir) {
+            let entry = match entry {
+                Ok(entry) => entry,
+                // Entries may disappear while we are walking.
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            };
+            let path = entry.path();
+            if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                match self.add_watch(&path, true, false) {
+                    Ok(_) => {}
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                    Err(err) => return Err(err),
+                }
+            }
+            if report {
+                self.pending.push_back(WatchEvent::new(WatchEventKind::Create, path));
+            }
+        }
+        Ok(())
+    }
+
+    fn read_events(&mut self) -> io::Result<()> {
+        let mut buf = vec![0u8; BUFFER_SIZE];
+        let len = match cvt(unsafe {
+            libc::read(self.fd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len())
+        }) {
+            Ok(len) => len as usize,
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+            Err(err) => return Err(err),
+        };
+
+        let mut moved_from: Option<(u32, PathBuf, bool)> = None;
+        let mut offset = 0;
+        while offset + mem::size_of::<inotify_event>() <= len {
+            // The buffer is only byte aligned, and names are padded to arbitrary lengths.
+            let event: inotify_event =
+                unsafe { ptr::read_unaligned(buf.as_ptr().add(offset).cast()) };
+            let name_start = offset + mem::size_of::<inotify_event>();
+            let name = &buf[name_start..name_start + event.len as usize];
+            // The name is padded with nul bytes.
+            let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(name.len())];
+            offset = name_start + event.len as usize;
+
+            if event.mask & libc::IN_Q_OVERFLOW != 0 {
+                self.pending.push_back(WatchEvent::new(WatchEventKind::Overflow, PathBuf::new()));
+                continue;
+            }
+            if event.mask & libc::IN_IGNORED != 0 {
+                self.watches.remove(&event.wd);
+                continue;
+            }
+            let Some(watch) = self.watches.get(&event.wd) else { continue };
+            let path = if name.is_empty() {
+                watch.path.clone()
+            } else {
+                watch.path.join(OsStr::from_bytes(name))
+            };
+            let recursive = watch.recursive;
+            let explicit = watch.explicit;
+            let is_dir = event.mask & libc::IN_ISDIR != 0;
+
+            // A `IN_MOVED_FROM` is immediately followed by its `IN_MOVED_TO` if
+            // both ends of the rename are watched.
+            if let Some((cookie, from, from_is_dir)) = moved_from.take() {
+                if event.mask & libc::IN_MOVED_TO != 0 && event.cookie == cookie {
+                    if from_is_dir {
+                        self.rename_watches(&from, &path);
+                    }
+                    self.pending.push_back(WatchEvent {
+                        kind: WatchEventKind::Rename,
+                        path: from,
+                        new_path: Some(path),
+                    });
+                    continue;
+                }
+                self.moved_out(from, from_is_dir);
+            }
+
+            if event.mask & libc::IN_MOVED_FROM != 0 {
+                moved_from = Some((event.cookie, path, is_dir));
+            } else if event.mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0 {
+                self.pending.push_back(WatchEvent::new(WatchEventKind::Create, path.clone()));
+                if is_dir && recursive {
+                    // The directory may be gone again already; its removal is
+                    // reported by a later event, so keep going with the batch.
+                    match self.add_watch(&path, true, false) {
+                        Ok(_) => self.watch_subdirs(&path, true)?,
+                        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                        Err(err) => return Err(err),
+                    }
+                }
+            } else if event.mask & libc::IN_DELETE != 0 {
+                self.pending.push_back(WatchEvent::new(WatchEventKind::Remove, path));
+            } else if event.mask & libc::IN_DELETE_SELF != 0 {
+                // Removals of subdirectories are already reported by their parent.
+                if explicit {
+                    self.pending.push_back(WatchEvent::new(WatchEventKind::Remove, path));
+                }
+            } else if event.mask & libc::IN_MOVE_SELF != 0 {
+                // The watch follows the moved inode, so its path is stale now.
+                // Moves of subdirectories are already handled with their parent.
+                if explicit {
+                    self.remove_watch(event.wd);
+                    if recursive {
+                        self.remove_subdir_watches(&path);
+                    }
+                    self.pending.push_back(WatchEvent::new(WatchEventKind::Remove, path));
+                }
+            } else if event.mask & (libc::IN_MODIFY | libc::IN_ATTRIB) != 0 {
+                self.pending.push_back(WatchEvent::new(WatchEventKind::Modify, path));
+            }
+        }
+        if let Some((_, from, from_is_dir)) = moved_from {
+            self.moved_out(from, from_is_dir);
+        }
+        Ok(())
+    }
+
+    /// Handles a `IN_MOVED_FROM` without a matching `IN_MOVED_TO`, i.e. a
+    /// rename out of the watched tree.
+    fn moved_out(&mut self, from: PathBuf, is_dir: bool) {
+        if is_dir {
+            self.remove_subdir_watches(&from);
+        }
+        self.pending.push_back(WatchEvent::new(WatchEventKind::Remove, from));
+    }
+
+    fn remove_watch(&mut self, wd: c_int) {
+        self.watches.remove(&wd);
+        // The watch may already be gone if the directory was removed.
+        // Android's libc declares the descriptor as `u32`.
+        let _ = unsafe { libc::inotify_rm_watch(self.fd.as_raw_fd(), wd as _) };
+    }
+
+    /// Removes the watches for `dir` and the directories below it that were
+    /// added because of a recursive watch.
+    fn remove_subdir_watches(&mut self, dir: &Path) {
+        let removed: Vec<c_int> = self
+            .watches
+            .iter()
+            .filter(|(_, watch)| !watch.explicit && watch.path.starts_with(dir))
+            .map(|(&wd, _)| wd)
+            .collect();
+        for wd in removed {
+            self.remove_watch(wd);
+        }
+    }
+
+    /// Updates the paths of watches below a directory that was renamed.
+    fn rename_watches(&mut self, from: &Path, to: &Path) {
+        for watch in self.watches.values_mut() {
+            if let Ok(rest) = watch.path.strip_prefix(from) {
+                watch.path = to.join(rest);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Watcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let paths: Vec<_> = self.watches.values().map(|watch| &watch.path).collect();
+        f.debug_struct("Watcher").field("fd", &self.fd).field("paths", &paths).finish()
+    }
+}
@@ -0,0 +1,30 @@
+//! Filesystem change notifications for `fs::Watcher`.
+//!
+//! Linux and Android use inotify. All other platforms fall back to
+//! periodically scanning the watched paths and comparing their metadata.
+
+use crate::fs::WatchEventKind;
+use crate::path::PathBuf;
+
+cfg_if::cfg_if! {
+    if #[cfg(all(any(target_os = "linux", target_os = "android"), not(miri)))] {
+        mod inotify;
+        pub use inotify::Watcher;
+    } else {
+        mod polling;
+        pub use polling::Watcher;
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WatchEvent {
+    pub kind: WatchEventKind,
+    pub path: PathBuf,
+    pub new_path: Option<PathBuf>,
+}
+
+impl WatchEvent {
+    fn new(kind: WatchEventKind, path: PathBuf) -> WatchEvent {
+        WatchEvent { kind, path, new_path: None }
+    }
+}
@@ -0,0 +1,198 @@
+//! A portable implementation of `fs::Watcher` that periodically scans the
+//! watched paths and compares the metadata of every entry with the previous
+//! scan.
+//!
+//! Changes are only noticed at the granularity of the scan interval and the
+//! file system's modification timestamps, and renames are reported as a
+//! removal followed by a creation.
+
+use super::WatchEvent;
+use crate::collections::{BTreeMap, VecDeque};
+use crate::fs::{self, Metadata, WatchEventKind};
+use crate::path::{Path, PathBuf};
+use crate::time::{Duration, Instant, SystemTime};
+use crate::{fmt, io, thread};
+
+const SCAN_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Stamp {
+    is_dir: bool,
+    len: u64,
+    modified: Option<SystemTime>,
+    readonly: bool,
+}
+
+impl Stamp {
+    fn new(metadata: &Metadata) -> Stamp {
+        Stamp {
+            is_dir: metadata.is_dir(),
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+            readonly: metadata.permissions().readonly(),
+        }
+    }
+}
+
+struct Watch {
+    path: PathBuf,
+    recursive: bool,
+}
+
+impl Watch {
+    /// Returns whether `path` is recorded by scanning this watch.
+    fn covers(&self, path: &Path) -> bool {
+        if self.recursive {
+            path.starts_with(&self.path)
+        } else {
+            path == self.path || path.parent() == Some(&self.path)
+        }
+    }
+}
+
+pub struct Watcher {
+    watches: Vec<Watch>,
+    snapshot: BTreeMap<PathBuf, Stamp>,
+    pending: VecDeque<WatchEvent>,
+    last_scan: Instant,
+}
+
+impl Watcher {
+    pub fn new() -> io::Result<Watcher> {
+        Ok(Watcher {
+            watches: Vec::new(),
+            snapshot: BTreeMap::new(),
+            pending: VecDeque::new(),
+            last_scan: Instant::now(),
+        })
+    }
+
+    pub fn watch(&mut self, path: &Path, recursive: bool) -> io::Result<()> {
+        fs::metadata(path)?;
+        let index = match self.watches.iter().position(|watch| watch.path == path) {
+            Some(index) => {
+                self.watches[index].recursive |= recursive;
+                index
+            }
+            None => {
+                self.watches.push(Watch { path: path.to_path_buf(), recursive });
+                self.watches.len() - 1
+            }
+        };
+        // Record the current state of the newly watched entries without
+        // reporting anything for them. Entries that were already watched keep
+        // their previous state, so that changes to them are still reported.
+        let mut snapshot = BTreeMap::new();
+        scan_watch(&self.watches[index], &mut snapshot)?;
+        for (path, stamp) in snapshot {
+            self.snapshot.entry(path).or_insert(stamp);
+        }
+        Ok(())
+    }
+
+    pub fn unwatch(&mut self, path: &Path) -> io::Result<()> {
+        let Some(index) = self.watches.iter().position(|watch| watch.path == path) else {
+            return Err(io::const_error!(io::ErrorKind::NotFound, "path is not being watched"));
+        };
+        self.watches.remove(index);
+        let watches = &self.watches;
+        self.snapshot.retain(|path, _| watches.iter().any(|watch| watch.covers(path)));
+        Ok(())
+    }
+
+    pub fn next_event(&mut self, timeout: Option<Duration>) -> io::Result<Option<WatchEvent>> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(Some(event));
+            }
+
+            let now = Instant::now();
+            if deadline.is_some_and(|deadline| now >= deadline) {
+                return Ok(None);
+            }
+            let mut wake = self.last_scan + SCAN_INTERVAL;
+            if let Some(deadline) = deadline {
+                wake = wake.min(deadline);
+            }
+            thread::sleep(wake.saturating_duration_since(now));
+
+            if Instant::now() >= self.last_scan + SCAN_INTERVAL {
+                let snapshot = self.scan()?;
+                self.diff(&snapshot);
+                self.snapshot = snapshot;
+            }
+        }
+    }
+
+    fn scan(&mut self) -> io::Result<BTreeMap<PathBuf, Stamp>> {
+        self.last_scan = Instant::now();
+        let mut snapshot = BTreeMap::new();
+        for watch in &self.watches {
+            scan_watch(watch, &mut snapshot)?;
+        }
+        Ok(snapshot)
+    }
+
+    fn diff(&mut self, snapshot: &BTreeMap<PathBuf, Stamp>) {
+        for (path, stamp) in &self.snapshot {
+            if !snapshot.contains_key(path) {
+                self.pending.push_back(WatchEvent::new(WatchEventKind::Remove, path.clone()));
+            } else if snapshot[path] != *stamp {
+                self.pending.push_back(WatchEvent::new(WatchEventKind::Modify, path.clone()));
+            }
+        }
+        for path in snapshot.keys() {
+            if !self.snapshot.contains_key(path) {
+                self.pending.push_back(WatchEvent::new(WatchEventKind::Create, path.clone()));
+            }
+        }
+    }
+}
+
+/// Records the state of the entries covered by `watch` in `snapshot`.
+fn scan_watch(watch: &Watch, snapshot: &mut BTreeMap<PathBuf, Stamp>) -> io::Result<()> {
+    // The watched path itself may have been removed.
+    let Ok(metadata) = fs::metadata(&watch.path) else { return Ok(()) };
+    snapshot.insert(watch.path.clone(), Stamp::new(&metadata));
+    if !metadata.is_dir() {
+        return Ok(());
+    }
+
+    if watch.recursive {
+        for entry in fs::walk_dir(&watch.path) {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            };
+            if let Ok(metadata) = entry.metadata() {
+                snapshot.insert(entry.path(), Stamp::new(&metadata));
+            }
+        }
+    } else {
+        let entries = match fs::read_dir(&watch.path) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            };
+            if let Ok(metadata) = entry.metadata() {
+                snapshot.insert(entry.path(), Stamp::new(&metadata));
+            }
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Debug for Watcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let paths: Vec<_> = self.watches.iter().map(|watch| &watch.path).collect();
+        f.debug_struct("Watcher").field("paths", &paths).finish_non_exhaustive()
+    }
+}
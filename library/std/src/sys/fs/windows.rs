@@ -19,7 +19,9 @@
 use crate::sys_common::{AsInner, FromInner, IntoInner};
 use crate::{fmt, ptr, slice};
 
+mod dir;
 mod remove_dir_all;
+pub use dir::Dir;
 use remove_dir_all::remove_dir_all_iterative;
 
 pub struct File {
@@ -1390,6 +1392,20 @@ pub fn link(_original: &WCStr, _link: &WCStr) -> io::Result<()> {
     return Err(io::const_error!(io::ErrorKind::Unsupported, "hard link are not supported on UWP"));
 }
 
+/// Returns the serial number of the volume containing the directory at `path`,
+/// as reported by `GetFileInformationByHandle`.
+pub fn volume_serial_number(path: &Path) -> io::Result<u32> {
+    let mut opts = OpenOptions::new();
+    // No read or write permissions are necessary
+    opts.access_mode(0);
+    opts.custom_flags(c::FILE_FLAG_BACKUP_SEMANTICS);
+    let file = File::open(path, &opts)?;
+    file.file_attr()?.volume_serial_number().ok_or(io::const_error!(
+        io::ErrorKind::Unsupported,
+        "the volume serial number is not available on this platform",
+    ))
+}
+
 pub fn stat(path: &WCStr) -> io::Result<FileAttr> {
     match metadata(path, ReparsePoint::Follow) {
         Err(err) if err.raw_os_error() == Some(c::ERROR_CANT_ACCESS_FILE as i32) => {
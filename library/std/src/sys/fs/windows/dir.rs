@@ -0,0 +1,308 @@
+//! The Windows implementation of std::fs::Dir.
+//!
+//! Opening a file relative to a directory handle is not supported by win32
+//! functions, so this uses the lower level `NtCreateFile` function with the
+//! `RootDirectory` of its `OBJECT_ATTRIBUTES` set to the directory, like
+//! `remove_dir_all` does. Renames use `SetFileInformationByHandle` with a
+//! `FILE_RENAME_INFO` whose `RootDirectory` is the target directory, falling
+//! back from `FileRenameInfoEx` to `FileRenameInfo` where POSIX semantics are
+//! not supported.
+//!
+//! Creating symlinks and listing the directory have no handle-relative
+//! equivalent, so those operate on the current path of the directory handle.
+
+use core::ptr;
+
+use super::{File, FileAttr, OpenOptions, ReadDir, get_path, readdir, symlink};
+use crate::alloc::{Layout, alloc, dealloc};
+use crate::ffi::c_void;
+use crate::mem::offset_of;
+use crate::os::windows::prelude::*;
+use crate::path::{Path, PathBuf};
+use crate::sys::c;
+use crate::sys::pal::api::{self, UnicodeStrRef, WinError};
+use crate::sys::pal::{IoResult, to_u16s};
+use crate::{fmt, io};
+
+pub struct Dir {
+    handle: File,
+    // Only used for `Debug`.
+    root: PathBuf,
+}
+
+/// Converts a relative path to a nul terminated UTF-16 string suitable for
+/// the native API, which does not accept `/` as a separator.
+fn to_native_relative(path: &Path) -> io::Result<Vec<u16>> {
+    let mut name = to_u16s(path)?;
+    for c in &mut name {
+        if *c == b'/' as u16 {
+            *c = b'\\' as u16;
+        }
+    }
+    Ok(name)
+}
+
+impl Dir {
+    pub fn open(path: &Path) -> io::Result<Dir> {
+        let mut opts = OpenOptions::new();
+        opts.access_mode(c::FILE_LIST_DIRECTORY | c::FILE_TRAVERSE | c::SYNCHRONIZE);
+        // `FILE_FLAG_BACKUP_SEMANTICS` is required to open a directory.
+        opts.custom_flags(c::FILE_FLAG_BACKUP_SEMANTICS);
+        let handle = File::open(path, &opts)?;
+        if !handle.file_attr()?.file_type().is_dir() {
+            return Err(WinError::DIRECTORY).io_result();
+        }
+        Ok(Dir { handle, root: path.to_path_buf() })
+    }
+
+    /// Opens `path` relative to this directory with `NtCreateFile`.
+    ///
+    /// Unless the handle is opened for overlapped I/O, `access` should include
+    /// `SYNCHRONIZE` and `options` should include `FILE_SYNCHRONOUS_IO_NONALERT`.
+    fn create_file(
+        &self,
+        path: &Path,
+        access: u32,
+        attributes: u32,
+        share: u32,
+        disposition: u32,
+        options: u32,
+        qos: Option<&c::SECURITY_QUALITY_OF_SERVICE>,
+    ) -> io::Result<File> {
+        let name = to_native_relative(path)?;
+        let name = UnicodeStrRef::from_slice_with_nul(&name);
+        unsafe {
+            let object = c::OBJECT_ATTRIBUTES {
+                ObjectName: name.as_ptr(),
+                RootDirectory: self.handle.as_raw_handle(),
+                SecurityQualityOfService: qos.map_or(ptr::null(), ptr::from_ref),
+                ..c::OBJECT_ATTRIBUTES::with_length()
+            };
+            let mut handle = ptr::null_mut();
+            let mut io_status = c::IO_STATUS_BLOCK::PENDING;
+            let status = c::NtCreateFile(
+                &mut handle,
+                access,
+                &object,
+                &mut io_status,
+                ptr::null(),
+                attributes,
+                share,
+                disposition,
+                options,
+                ptr::null(),
+                0,
+            );
+            if c::nt_success(status) {
+                Ok(File::from_raw_handle(handle))
+            } else {
+                Err(WinError::new(c::RtlNtStatusToDosError(status))).io_result()
+            }
+        }
+    }
+
+    fn open_existing(&self, path: &Path, access: u32, options: u32) -> io::Result<File> {
+        let share = c::FILE_SHARE_DELETE | c::FILE_SHARE_READ | c::FILE_SHARE_WRITE;
+        self.create_file(
+            path,
+            access | c::SYNCHRONIZE,
+            0,
+            share,
+            c::FILE_OPEN,
+            // "_IO_NONALERT" means that a synchronous call won't be interrupted.
+            options | c::FILE_SYNCHRONOUS_IO_NONALERT,
+            None,
+        )
+    }
+
+    pub fn open_dir(&self, path: &Path) -> io::Result<Dir> {
+        let handle = self.open_existing(
+            path,
+            c::FILE_LIST_DIRECTORY | c::FILE_TRAVERSE,
+            c::FILE_DIRECTORY_FILE,
+        )?;
+        Ok(Dir { handle, root: self.root.join(path) })
+    }
+
+    pub fn open_file(&self, path: &Path, opts: &OpenOptions) -> io::Result<File> {
+        let disposition = match opts.get_creation_mode()? {
+            c::OPEN_EXISTING => c::FILE_OPEN,
+            // `File::open` emulates `CREATE_ALWAYS` with `OPEN_ALWAYS` and a manual
+            // truncation step, but `FILE_OVERWRITE_IF` does not have its issues.
+            c::OPEN_ALWAYS if opts.truncate => c::FILE_OVERWRITE_IF,
+            c::OPEN_ALWAYS => c::FILE_OPEN_IF,
+            c::TRUNCATE_EXISTING => c::FILE_OVERWRITE,
+            c::CREATE_NEW => c::FILE_CREATE,
+            _ => unreachable!(),
+        };
+        let flags = opts.custom_flags;
+        let mut access = opts.get_access_mode()?;
+        let mut options = c::FILE_NON_DIRECTORY_FILE
+            | if opts.create_new { c::FILE_OPEN_REPARSE_POINT } else { 0 };
+        for (flag, option) in [
+            (c::FILE_FLAG_WRITE_THROUGH, c::FILE_WRITE_THROUGH),
+            (c::FILE_FLAG_NO_BUFFERING, c::FILE_NO_INTERMEDIATE_BUFFERING),
+            (c::FILE_FLAG_RANDOM_ACCESS, c::FILE_RANDOM_ACCESS),
+            (c::FILE_FLAG_SEQUENTIAL_SCAN, c::FILE_SEQUENTIAL_ONLY),
+            (c::FILE_FLAG_DELETE_ON_CLOSE, c::FILE_DELETE_ON_CLOSE),
+            (c::FILE_FLAG_BACKUP_SEMANTICS, c::FILE_OPEN_FOR_BACKUP_INTENT),
+            (c::FILE_FLAG_OPEN_REPARSE_POINT, c::FILE_OPEN_REPARSE_POINT),
+            (c::FILE_FLAG_OPEN_NO_RECALL, c::FILE_OPEN_NO_RECALL),
+        ] {
+            if flags & flag != 0 {
+                options |= option;
+            }
+        }
+        // `CreateFileW` implicitly requests the access these options need.
+        if flags & c::FILE_FLAG_DELETE_ON_CLOSE != 0 {
+            access |= c::DELETE;
+        }
+        if flags & c::FILE_FLAG_OVERLAPPED == 0 {
+            access |= c::SYNCHRONIZE;
+            // "_IO_NONALERT" means that a synchronous call won't be interrupted.
+            options |= c::FILE_SYNCHRONOUS_IO_NONALERT;
+        }
+
+        // `OpenOptions::security_qos_flags` always sets `SECURITY_SQOS_PRESENT`.
+        let qos_flags = opts.security_qos_flags;
+        let qos = (qos_flags & c::SECURITY_SQOS_PRESENT != 0).then(|| {
+            c::SECURITY_QUALITY_OF_SERVICE {
+                Length: size_of::<c::SECURITY_QUALITY_OF_SERVICE>() as u32,
+                // The impersonation level flags are the `SECURITY_IMPERSONATION_LEVEL`
+                // values shifted by 16 bits.
+                ImpersonationLevel: ((qos_flags & c::SECURITY_DELEGATION) >> 16) as i32,
+                // `SECURITY_DYNAMIC_TRACKING` or `SECURITY_STATIC_TRACKING`.
+                ContextTrackingMode: (qos_flags & c::SECURITY_CONTEXT_TRACKING != 0) as u8,
+                EffectiveOnly: qos_flags & c::SECURITY_EFFECTIVE_ONLY != 0,
+            }
+        });
+
+        self.create_file(
+            path,
+            access,
+            // The low 16 bits of the custom flags are file attributes.
+            opts.attributes | (flags & 0xffff),
+            opts.share_mode,
+            disposition,
+            options,
+            qos.as_ref(),
+        )
+    }
+
+    pub fn create_dir(&self, path: &Path) -> io::Result<()> {
+        let share = c::FILE_SHARE_DELETE | c::FILE_SHARE_READ | c::FILE_SHARE_WRITE;
+        self.create_file(
+            path,
+            c::FILE_LIST_DIRECTORY | c::SYNCHRONIZE,
+            0,
+            share,
+            c::FILE_CREATE,
+            c::FILE_DIRECTORY_FILE | c::FILE_SYNCHRONOUS_IO_NONALERT,
+            None,
+        )
+        .map(drop)
+    }
+
+    pub fn remove_file(&self, path: &Path) -> io::Result<()> {
+        let options = c::FILE_NON_DIRECTORY_FILE | c::FILE_OPEN_REPARSE_POINT;
+        self.open_existing(path, c::DELETE, options)?.delete().io_result()
+    }
+
+    pub fn remove_dir(&self, path: &Path) -> io::Result<()> {
+        let options = c::FILE_DIRECTORY_FILE | c::FILE_OPEN_REPARSE_POINT;
+        self.open_existing(path, c::DELETE, options)?.delete().io_result()
+    }
+
+    pub fn rename(&self, from: &Path, to_dir: &Dir, to: &Path) -> io::Result<()> {
+        let f = self.open_existing(from, c::DELETE, c::FILE_OPEN_REPARSE_POINT)?;
+        let to = to_native_relative(to)?;
+
+        // Calculate the layout of the `FILE_RENAME_INFO` we pass to `SetFileInformation`
+        // This is a dynamically sized struct so we need to get the position of the last field to calculate the actual size.
+        let name_len_in_bytes: u32 = ((to.len() - 1) * 2)
+            .try_into()
+            .map_err(|_| io::const_error!(io::ErrorKind::InvalidFilename, "path too long"))?;
+        let offset: u32 = offset_of!(c::FILE_RENAME_INFO, FileName).try_into().unwrap();
+        let struct_size = offset + name_len_in_bytes + 2;
+        let layout =
+            Layout::from_size_align(struct_size as usize, align_of::<c::FILE_RENAME_INFO>())
+                .unwrap();
+
+        // SAFETY: We allocate enough memory for a full FILE_RENAME_INFO struct and a filename.
+        let file_rename_info;
+        unsafe {
+            file_rename_info = alloc(layout).cast::<c::FILE_RENAME_INFO>();
+            if file_rename_info.is_null() {
+                return Err(io::ErrorKind::OutOfMemory.into());
+            }
+
+            (&raw mut (*file_rename_info).Anonymous).write(c::FILE_RENAME_INFO_0 {
+                Flags: c::FILE_RENAME_FLAG_REPLACE_IF_EXISTS | c::FILE_RENAME_FLAG_POSIX_SEMANTICS,
+            });
+
+            (&raw mut (*file_rename_info).RootDirectory).write(to_dir.handle.as_raw_handle());
+            // Don't include the NULL in the size
+            (&raw mut (*file_rename_info).FileNameLength).write(name_len_in_bytes);
+
+            to.as_ptr().copy_to_nonoverlapping(
+                (&raw mut (*file_rename_info).FileName).cast::<u16>(),
+                to.len(),
+            );
+        }
+
+        let handle = f.as_raw_handle();
+        let set_rename_info = move |class| {
+            let result = unsafe {
+                c::SetFileInformationByHandle(
+                    handle,
+                    class,
+                    file_rename_info.cast::<c_void>(),
+                    struct_size,
+                )
+            };
+            if result == 0 { Err(api::get_last_error()) } else { Ok(()) }
+        };
+        // If `FileRenameInfoEx` is not supported for this filesystem or OS version
+        // then fall back to `FileRenameInfo`, which only replaces the target with
+        // win32 semantics.
+        let result = match set_rename_info(c::FileRenameInfoEx) {
+            Err(WinError::INVALID_PARAMETER)
+            | Err(WinError::NOT_SUPPORTED)
+            | Err(WinError::INVALID_FUNCTION) => {
+                unsafe {
+                    (&raw mut (*file_rename_info).Anonymous)
+                        .write(c::FILE_RENAME_INFO_0 { ReplaceIfExists: true });
+                }
+                set_rename_info(c::FileRenameInfo)
+            }
+            result => result,
+        };
+        unsafe { dealloc(file_rename_info.cast::<u8>(), layout) };
+        result.io_result()
+    }
+
+    pub fn metadata(&self, path: &Path) -> io::Result<FileAttr> {
+        self.open_existing(path, c::FILE_READ_ATTRIBUTES, 0)?.file_attr()
+    }
+
+    pub fn symlink_metadata(&self, path: &Path) -> io::Result<FileAttr> {
+        self.open_existing(path, c::FILE_READ_ATTRIBUTES, c::FILE_OPEN_REPARSE_POINT)?.file_attr()
+    }
+
+    pub fn read_dir(&self) -> io::Result<ReadDir> {
+        readdir(&get_path(&self.handle)?)
+    }
+
+    pub fn symlink(&self, original: &Path, link: &Path) -> io::Result<()> {
+        symlink(original, &get_path(&self.handle)?.join(link))
+    }
+}
+
+impl fmt::Debug for Dir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dir")
+            .field("handle", &self.handle.as_raw_handle())
+            .field("path", &self.root)
+            .finish()
+    }
+}
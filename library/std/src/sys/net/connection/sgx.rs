@@ -3,6 +3,7 @@
 use crate::sync::Arc;
 use crate::sys::abi::usercalls;
 use crate::sys::fd::FileDesc;
+use crate::sys::net::SocketOptions;
 use crate::sys::{AsInner, FromInner, IntoInner, TryIntoInner, sgx_ineffective, unsupported};
 use crate::time::Duration;
 use crate::{error, fmt};
@@ -99,6 +100,39 @@ pub fn connect_timeout(addr: &SocketAddr, dur: Duration) -> io::Result<TcpStream
         Self::connect(Ok(addr)) // FIXME: ignoring timeout
     }
 
+    pub fn connect_with(
+        addr: io::Result<&SocketAddr>,
+        opts: &SocketOptions,
+        timeout: Option<Duration>,
+    ) -> io::Result<TcpStream> {
+        if !opts.is_default() {
+            return unsupported();
+        }
+        match timeout {
+            Some(timeout) => TcpStream::connect_timeout(addr?, timeout),
+            None => TcpStream::connect(addr),
+        }
+    }
+
+    pub fn connect_happy_eyeballs(
+        addrs: &[SocketAddr],
+        opts: &SocketOptions,
+        _attempt_delay: Duration,
+    ) -> io::Result<TcpStream> {
+        // Without nonblocking connects, the addresses are tried one after
+        // another.
+        let mut last_err = None;
+        for addr in addrs {
+            match TcpStream::connect_with(Ok(addr), opts, None) {
+                Ok(stream) => return Ok(stream),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        Err(last_err.unwrap_or_else(|| {
+            io::const_error!(io::ErrorKind::InvalidInput, "could not resolve to any addresses")
+        }))
+    }
+
     pub fn set_read_timeout(&self, dur: Option<Duration>) -> io::Result<()> {
         match dur {
             Some(dur) if dur == Duration::default() => {
@@ -253,6 +287,16 @@ pub fn bind(addr: io::Result<&SocketAddr>) -> io::Result<TcpListener> {
         Ok(TcpListener { inner: Socket::new(fd, local_addr) })
     }
 
+    pub fn bind_with(
+        addr: io::Result<&SocketAddr>,
+        opts: &SocketOptions,
+    ) -> io::Result<TcpListener> {
+        if !opts.is_default() {
+            return unsupported();
+        }
+        TcpListener::bind(addr)
+    }
+
     pub fn socket_addr(&self) -> io::Result<SocketAddr> {
         addr_to_sockaddr(self.inner.local_addr.as_deref())
     }
@@ -320,6 +364,10 @@ pub fn bind(_: io::Result<&SocketAddr>) -> io::Result<UdpSocket> {
         unsupported()
     }
 
+    pub fn bind_with(_: io::Result<&SocketAddr>, _: &SocketOptions) -> io::Result<UdpSocket> {
+        unsupported()
+    }
+
     pub fn peer_addr(&self) -> io::Result<SocketAddr> {
         self.0
     }
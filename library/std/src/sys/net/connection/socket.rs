@@ -5,6 +5,7 @@
 use crate::io::{self, BorrowedCursor, ErrorKind, IoSlice, IoSliceMut};
 use crate::net::{Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, SocketAddrV4, SocketAddrV6};
 use crate::sys::common::small_c_string::run_with_cstr;
+use crate::sys::net::SocketOptions;
 use crate::sys_common::{AsInner, FromInner};
 use crate::time::Duration;
 use crate::{cmp, fmt, mem, ptr};
@@ -222,6 +223,43 @@ pub fn getsockopt<T: Copy>(sock: &Socket, level: c_int, option_name: c_int) -> i
     }
 }
 
+/// Applies the options that have to be set before `bind` or `connect`.
+fn apply_options(sock: &Socket, addr: &SocketAddr, opts: &SocketOptions) -> io::Result<()> {
+    if let Some(reuse_address) = opts.reuse_address {
+        setsockopt(sock, c::SOL_SOCKET, c::SO_REUSEADDR, reuse_address as c_int)?;
+    }
+    if let Some(reuse_port) = opts.reuse_port {
+        sock.set_reuse_port(reuse_port)?;
+    }
+    // `IPV6_V6ONLY` only exists for IPv6 sockets, and the builders may try
+    // addresses of both families.
+    if let (Some(only_v6), SocketAddr::V6(..)) = (opts.only_v6, addr) {
+        setsockopt(sock, c::IPPROTO_IPV6, c::IPV6_V6ONLY, only_v6 as c_int)?;
+    }
+    if let Some(size) = opts.send_buffer_size {
+        sock.set_send_buffer_size(size)?;
+    }
+    if let Some(size) = opts.recv_buffer_size {
+        sock.set_recv_buffer_size(size)?;
+    }
+    if let Some(keepalive) = opts.keepalive {
+        sock.set_keepalive(keepalive)?;
+    }
+    if let Some(idle) = opts.keepalive_idle {
+        sock.set_keepalive_idle(idle)?;
+    }
+    if let Some(interval) = opts.keepalive_interval {
+        sock.set_keepalive_interval(interval)?;
+    }
+    if let Some(nodelay) = opts.nodelay {
+        sock.set_nodelay(nodelay)?;
+    }
+    if let Some(interface) = &opts.bind_device {
+        sock.set_bind_device(interface)?;
+    }
+    Ok(())
+}
+
 fn sockname<F>(f: F) -> io::Result<SocketAddr>
 where
     F: FnOnce(*mut c::sockaddr, *mut c::socklen_t) -> c_int,
@@ -350,6 +388,115 @@ pub fn connect_timeout(addr: &SocketAddr, timeout: Duration) -> io::Result<TcpSt
         Ok(TcpStream { inner: sock })
     }
 
+    pub fn connect_with(
+        addr: io::Result<&SocketAddr>,
+        opts: &SocketOptions,
+        timeout: Option<Duration>,
+    ) -> io::Result<TcpStream> {
+        let addr = addr?;
+
+        init();
+
+        let sock = Socket::new(addr, c::SOCK_STREAM)?;
+        apply_options(&sock, addr, opts)?;
+        match timeout {
+            Some(timeout) => sock.connect_timeout(addr, timeout)?,
+            None => sock.connect(addr)?,
+        }
+        Ok(TcpStream { inner: sock })
+    }
+
+    /// Races connection attempts to `addrs`, starting a new attempt every
+    /// `attempt_delay` or as soon as the previous one failed, as described in
+    /// RFC 8305, section 5. The first attempt to succeed wins and all others
+    /// are abandoned.
+    #[cfg(any(unix, windows))]
+    pub fn connect_happy_eyeballs(
+        addrs: &[SocketAddr],
+        opts: &SocketOptions,
+        attempt_delay: Duration,
+    ) -> io::Result<TcpStream> {
+        // Bounds the number of sockets open at the same time, and stays below
+        // the size of `FD_SET` on Windows.
+        const MAX_PENDING: usize = 32;
+
+        init();
+
+        let mut addrs = addrs.iter();
+        let mut pending: Vec<Socket> = Vec::new();
+        let mut last_err = None;
+
+        loop {
+            if pending.len() < MAX_PENDING
+                && let Some(addr) = addrs.next()
+            {
+                let attempt = Socket::new(addr, c::SOCK_STREAM).and_then(|sock| {
+                    apply_options(&sock, addr, opts)?;
+                    let connected = sock.connect_nonblocking(addr)?;
+                    Ok((sock, connected))
+                });
+                match attempt {
+                    Ok((sock, true)) => {
+                        sock.set_nonblocking(false)?;
+                        return Ok(TcpStream { inner: sock });
+                    }
+                    Ok((sock, false)) => pending.push(sock),
+                    Err(e) => {
+                        // Move on to the next address right away.
+                        last_err = Some(e);
+                        continue;
+                    }
+                }
+            }
+
+            if pending.is_empty() {
+                return Err(last_err.unwrap_or_else(|| {
+                    io::const_error!(ErrorKind::InvalidInput, "could not resolve to any addresses")
+                }));
+            }
+
+            // Once no further attempt can be started, wait for the pending
+            // ones without a delay.
+            let timeout = if !addrs.as_slice().is_empty() && pending.len() < MAX_PENDING {
+                Some(attempt_delay)
+            } else {
+                None
+            };
+            match wait_connected(&pending, timeout)? {
+                None => {}
+                Some((idx, Ok(()))) => {
+                    let sock = pending.swap_remove(idx);
+                    sock.set_nonblocking(false)?;
+                    return Ok(TcpStream { inner: sock });
+                }
+                Some((idx, Err(e))) => {
+                    pending.swap_remove(idx);
+                    last_err = Some(e);
+                }
+            }
+        }
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    pub fn connect_happy_eyeballs(
+        addrs: &[SocketAddr],
+        opts: &SocketOptions,
+        _attempt_delay: Duration,
+    ) -> io::Result<TcpStream> {
+        // Without a way to wait on several sockets, the addresses are tried
+        // one after another.
+        let mut last_err = None;
+        for addr in addrs {
+            match TcpStream::connect_with(Ok(addr), opts, None) {
+                Ok(stream) => return Ok(stream),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        Err(last_err.unwrap_or_else(|| {
+            io::const_error!(ErrorKind::InvalidInput, "could not resolve to any addresses")
+        }))
+    }
+
     #[inline]
     pub fn socket(&self) -> &Socket {
         &self.inner
@@ -503,6 +650,13 @@ pub struct TcpListener {
 
 impl TcpListener {
     pub fn bind(addr: io::Result<&SocketAddr>) -> io::Result<TcpListener> {
+        TcpListener::bind_with(addr, &SocketOptions::default())
+    }
+
+    pub fn bind_with(
+        addr: io::Result<&SocketAddr>,
+        opts: &SocketOptions,
+    ) -> io::Result<TcpListener> {
         let addr = addr?;
 
         init();
@@ -514,10 +668,14 @@ pub fn bind(addr: io::Result<&SocketAddr>) -> io::Result<TcpListener> {
         // previous one.
         //
         // On Windows, this allows rebinding sockets which are actively in use,
-        // which allows “socket hijacking”, so we explicitly don't set it here.
+        // which allows “socket hijacking”, so we explicitly don't set it here
+        // unless asked to.
         // https://docs.microsoft.com/en-us/windows/win32/winsock/using-so-reuseaddr-and-so-exclusiveaddruse
         #[cfg(not(windows))]
-        setsockopt(&sock, c::SOL_SOCKET, c::SO_REUSEADDR, 1 as c_int)?;
+        if opts.reuse_address.is_none() {
+            setsockopt(&sock, c::SOL_SOCKET, c::SO_REUSEADDR, 1 as c_int)?;
+        }
+        apply_options(&sock, addr, opts)?;
 
         // Bind our new socket
         let (addr, len) = socket_addr_to_c(addr);
@@ -539,6 +697,11 @@ pub fn bind(addr: io::Result<&SocketAddr>) -> io::Result<TcpListener> {
             }
         }
 
+        let backlog = match opts.backlog {
+            Some(backlog) => cmp::min(backlog, c_int::MAX as u32) as c_int,
+            None => backlog,
+        };
+
         // Start listening
         cvt(unsafe { c::listen(sock.as_raw(), backlog) })?;
         Ok(TcpListener { inner: sock })
@@ -628,11 +791,16 @@ pub struct UdpSocket {
 
 impl UdpSocket {
     pub fn bind(addr: io::Result<&SocketAddr>) -> io::Result<UdpSocket> {
+        UdpSocket::bind_with(addr, &SocketOptions::default())
+    }
+
+    pub fn bind_with(addr: io::Result<&SocketAddr>, opts: &SocketOptions) -> io::Result<UdpSocket> {
         let addr = addr?;
 
         init();
 
         let sock = Socket::new(addr, c::SOCK_DGRAM)?;
+        apply_options(&sock, addr, opts)?;
         let (addr, len) = socket_addr_to_c(addr);
         cvt(unsafe { c::bind(sock.as_raw(), addr.as_ptr(), len as _) })?;
         Ok(UdpSocket { inner: sock })
@@ -351,6 +351,34 @@ pub fn nodelay(&self) -> io::Result<bool> {
         Ok(raw != 0)
     }
 
+    pub fn set_reuse_port(&self, _: bool) -> io::Result<()> {
+        crate::sys::unsupported()
+    }
+
+    pub fn set_send_buffer_size(&self, _: usize) -> io::Result<()> {
+        crate::sys::unsupported()
+    }
+
+    pub fn set_recv_buffer_size(&self, _: usize) -> io::Result<()> {
+        crate::sys::unsupported()
+    }
+
+    pub fn set_keepalive(&self, _: bool) -> io::Result<()> {
+        crate::sys::unsupported()
+    }
+
+    pub fn set_keepalive_idle(&self, _: Duration) -> io::Result<()> {
+        crate::sys::unsupported()
+    }
+
+    pub fn set_keepalive_interval(&self, _: Duration) -> io::Result<()> {
+        crate::sys::unsupported()
+    }
+
+    pub fn set_bind_device(&self, _: &[u8]) -> io::Result<()> {
+        crate::sys::unsupported()
+    }
+
     pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
         let mut nonblocking = nonblocking as c_int;
         cvt(unsafe {
@@ -245,6 +245,24 @@ pub fn connect_timeout(&self, addr: &SocketAddr, timeout: Duration) -> io::Resul
         }
     }
 
+    /// Starts connecting to `addr` and leaves the socket in nonblocking mode.
+    ///
+    /// Returns `true` if the connection was established immediately and
+    /// `false` if it is still in progress, see [`wait_connected`].
+    pub fn connect_nonblocking(&self, addr: &SocketAddr) -> io::Result<bool> {
+        self.set_nonblocking(true)?;
+        let r = unsafe {
+            let (addr, len) = socket_addr_to_c(addr);
+            cvt(libc::connect(self.as_raw_fd(), addr.as_ptr(), len))
+        };
+
+        match r {
+            Ok(_) => Ok(true),
+            Err(ref e) if e.raw_os_error() == Some(libc::EINPROGRESS) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
     pub fn accept(&self, storage: *mut sockaddr, len: *mut socklen_t) -> io::Result<Socket> {
         // Unfortunately the only known way right now to accept a socket and
         // atomically set the CLOEXEC flag is to use the `accept4` syscall on
@@ -468,6 +486,113 @@ pub fn nodelay(&self) -> io::Result<bool> {
         Ok(raw != 0)
     }
 
+    pub fn set_reuse_port(&self, reuse_port: bool) -> io::Result<()> {
+        cfg_if::cfg_if! {
+            if #[cfg(any(
+                target_os = "android",
+                target_os = "linux",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd",
+                target_os = "dragonfly",
+                target_os = "fuchsia",
+                target_vendor = "apple",
+            ))] {
+                setsockopt(self, libc::SOL_SOCKET, libc::SO_REUSEPORT, reuse_port as c_int)
+            } else {
+                let _ = reuse_port;
+                Err(io::const_error!(
+                    io::ErrorKind::Unsupported,
+                    "SO_REUSEPORT is not supported on this platform",
+                ))
+            }
+        }
+    }
+
+    pub fn set_send_buffer_size(&self, size: usize) -> io::Result<()> {
+        let size = cmp::min(size, c_int::MAX as usize) as c_int;
+        setsockopt(self, libc::SOL_SOCKET, libc::SO_SNDBUF, size)
+    }
+
+    pub fn set_recv_buffer_size(&self, size: usize) -> io::Result<()> {
+        let size = cmp::min(size, c_int::MAX as usize) as c_int;
+        setsockopt(self, libc::SOL_SOCKET, libc::SO_RCVBUF, size)
+    }
+
+    pub fn set_keepalive(&self, keepalive: bool) -> io::Result<()> {
+        setsockopt(self, libc::SOL_SOCKET, libc::SO_KEEPALIVE, keepalive as c_int)
+    }
+
+    pub fn set_keepalive_idle(&self, idle: Duration) -> io::Result<()> {
+        cfg_if::cfg_if! {
+            if #[cfg(any(
+                target_os = "android",
+                target_os = "linux",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "dragonfly",
+                target_os = "fuchsia",
+            ))] {
+                setsockopt(self, libc::IPPROTO_TCP, libc::TCP_KEEPIDLE, keepalive_secs(idle))
+            } else if #[cfg(target_vendor = "apple")] {
+                setsockopt(self, libc::IPPROTO_TCP, libc::TCP_KEEPALIVE, keepalive_secs(idle))
+            } else {
+                let _ = idle;
+                Err(io::const_error!(
+                    io::ErrorKind::Unsupported,
+                    "TCP keepalive times are not supported on this platform",
+                ))
+            }
+        }
+    }
+
+    pub fn set_keepalive_interval(&self, interval: Duration) -> io::Result<()> {
+        cfg_if::cfg_if! {
+            if #[cfg(any(
+                target_os = "android",
+                target_os = "linux",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "dragonfly",
+                target_os = "fuchsia",
+                target_vendor = "apple",
+            ))] {
+                setsockopt(self, libc::IPPROTO_TCP, libc::TCP_KEEPINTVL, keepalive_secs(interval))
+            } else {
+                let _ = interval;
+                Err(io::const_error!(
+                    io::ErrorKind::Unsupported,
+                    "TCP keepalive times are not supported on this platform",
+                ))
+            }
+        }
+    }
+
+    pub fn set_bind_device(&self, interface: &[u8]) -> io::Result<()> {
+        cfg_if::cfg_if! {
+            if #[cfg(any(target_os = "android", target_os = "linux"))] {
+                // The interface name is passed as a plain byte buffer, an
+                // empty one removes a previous binding.
+                cvt(unsafe {
+                    libc::setsockopt(
+                        self.as_raw_fd(),
+                        libc::SOL_SOCKET,
+                        libc::SO_BINDTODEVICE,
+                        interface.as_ptr().cast(),
+                        interface.len() as socklen_t,
+                    )
+                })?;
+                Ok(())
+            } else {
+                let _ = interface;
+                Err(io::const_error!(
+                    io::ErrorKind::Unsupported,
+                    "SO_BINDTODEVICE is not supported on this platform",
+                ))
+            }
+        }
+    }
+
     #[cfg(any(target_os = "android", target_os = "linux",))]
     pub fn set_quickack(&self, quickack: bool) -> io::Result<()> {
         setsockopt(self, libc::IPPROTO_TCP, libc::TCP_QUICKACK, quickack as c_int)
@@ -617,6 +742,88 @@ pub fn as_raw(&self) -> RawFd {
     }
 }
 
+/// Waits until one of `sockets` finishes the connection attempt started by
+/// [`Socket::connect_nonblocking`].
+///
+/// Returns the index of that socket together with the outcome of its attempt,
+/// or `None` if `timeout` elapsed first. A timeout of `None` waits forever.
+pub fn wait_connected(
+    sockets: &[Socket],
+    timeout: Option<Duration>,
+) -> io::Result<Option<(usize, io::Result<()>)>> {
+    let mut pollfds: Vec<libc::pollfd> = sockets
+        .iter()
+        .map(|sock| libc::pollfd { fd: sock.as_raw_fd(), events: libc::POLLOUT, revents: 0 })
+        .collect();
+
+    let start = Instant::now();
+
+    loop {
+        let timeout = match timeout {
+            None => -1,
+            Some(timeout) => {
+                let elapsed = start.elapsed();
+                if elapsed >= timeout {
+                    return Ok(None);
+                }
+
+                let timeout = timeout - elapsed;
+                let timeout = timeout
+                    .as_secs()
+                    .saturating_mul(1_000)
+                    .saturating_add(timeout.subsec_nanos().div_ceil(1_000_000) as u64);
+                cmp::min(cmp::max(timeout, 1), c_int::MAX as u64) as c_int
+            }
+        };
+
+        match unsafe { libc::poll(pollfds.as_mut_ptr(), pollfds.len() as libc::nfds_t, timeout) } {
+            -1 => {
+                let err = io::Error::last_os_error();
+                if !err.is_interrupted() {
+                    return Err(err);
+                }
+            }
+            0 => {}
+            _ => {
+                let Some(idx) = pollfds.iter().position(|pollfd| pollfd.revents != 0) else {
+                    continue;
+                };
+                let sock = &sockets[idx];
+                // Like in `connect_timeout`, look for POLLHUP or POLLERR
+                // rather than write readiness, and double-check `SO_ERROR`
+                // for platforms that don't report either.
+                let result = match sock.take_error() {
+                    Ok(Some(e)) => Err(e),
+                    Ok(None) if pollfds[idx].revents & (libc::POLLHUP | libc::POLLERR) != 0 => {
+                        Err(io::const_error!(
+                            io::ErrorKind::Uncategorized,
+                            "no error set after POLLHUP",
+                        ))
+                    }
+                    Ok(None) => Ok(()),
+                    Err(e) => Err(e),
+                };
+                return Ok(Some((idx, result)));
+            }
+        }
+    }
+}
+
+/// Converts a keepalive time to whole seconds, as expected by the
+/// `TCP_KEEP*` options. The kernel rejects a value of zero.
+#[cfg(any(
+    target_os = "android",
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "dragonfly",
+    target_os = "fuchsia",
+    target_vendor = "apple",
+))]
+fn keepalive_secs(dur: Duration) -> c_int {
+    cmp::max(1, cmp::min(dur.as_secs(), c_int::MAX as u64)) as c_int
+}
+
 impl AsInner<FileDesc> for Socket {
     #[inline]
     fn as_inner(&self) -> &FileDesc {
@@ -32,7 +32,7 @@ pub(super) mod netc {
         ADDRESS_FAMILY as sa_family_t, ADDRINFOA as addrinfo, IP_ADD_MEMBERSHIP,
         IP_DROP_MEMBERSHIP, IP_MULTICAST_LOOP, IP_MULTICAST_TTL, IP_TTL, IPPROTO_IP, IPPROTO_IPV6,
         IPV6_ADD_MEMBERSHIP, IPV6_DROP_MEMBERSHIP, IPV6_MULTICAST_LOOP, IPV6_V6ONLY, SO_BROADCAST,
-        SO_RCVTIMEO, SO_SNDTIMEO, SOCK_DGRAM, SOCK_STREAM, SOCKADDR as sockaddr,
+        SO_RCVTIMEO, SO_REUSEADDR, SO_SNDTIMEO, SOCK_DGRAM, SOCK_STREAM, SOCKADDR as sockaddr,
         SOCKADDR_STORAGE as sockaddr_storage, SOL_SOCKET, bind, connect, freeaddrinfo, getpeername,
         getsockname, getsockopt, listen, setsockopt,
     };
@@ -291,6 +291,19 @@ pub fn connect_timeout(&self, addr: &SocketAddr, timeout: Duration) -> io::Resul
         }
     }
 
+    /// Starts connecting to `addr` and leaves the socket in nonblocking mode.
+    ///
+    /// Returns `true` if the connection was established immediately and
+    /// `false` if it is still in progress, see [`wait_connected`].
+    pub fn connect_nonblocking(&self, addr: &SocketAddr) -> io::Result<bool> {
+        self.set_nonblocking(true)?;
+        match self.connect(addr) {
+            Ok(()) => Ok(true),
+            Err(ref error) if error.kind() == io::ErrorKind::WouldBlock => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+
     pub fn accept(&self, storage: *mut c::SOCKADDR, len: *mut c_int) -> io::Result<Socket> {
         let socket = unsafe { c::accept(self.as_raw(), storage, len) };
 
@@ -513,6 +526,45 @@ pub fn nodelay(&self) -> io::Result<bool> {
         Ok(raw != 0)
     }
 
+    pub fn set_reuse_port(&self, _reuse_port: bool) -> io::Result<()> {
+        Err(io::const_error!(
+            io::ErrorKind::Unsupported,
+            "SO_REUSEPORT is not supported on this platform",
+        ))
+    }
+
+    pub fn set_send_buffer_size(&self, size: usize) -> io::Result<()> {
+        let size = cmp::min(size, c_int::MAX as usize) as c_int;
+        setsockopt(self, c::SOL_SOCKET, c::SO_SNDBUF, size)
+    }
+
+    pub fn set_recv_buffer_size(&self, size: usize) -> io::Result<()> {
+        let size = cmp::min(size, c_int::MAX as usize) as c_int;
+        setsockopt(self, c::SOL_SOCKET, c::SO_RCVBUF, size)
+    }
+
+    pub fn set_keepalive(&self, keepalive: bool) -> io::Result<()> {
+        setsockopt(self, c::SOL_SOCKET, c::SO_KEEPALIVE, keepalive as c::BOOL)
+    }
+
+    // `TCP_KEEPIDLE` and `TCP_KEEPINTVL` are available since Windows 10, version 1709.
+    pub fn set_keepalive_idle(&self, idle: Duration) -> io::Result<()> {
+        let secs = cmp::max(1, cmp::min(idle.as_secs(), c_int::MAX as u64)) as c_int;
+        setsockopt(self, c::IPPROTO_TCP, c::TCP_KEEPIDLE, secs)
+    }
+
+    pub fn set_keepalive_interval(&self, interval: Duration) -> io::Result<()> {
+        let secs = cmp::max(1, cmp::min(interval.as_secs(), c_int::MAX as u64)) as c_int;
+        setsockopt(self, c::IPPROTO_TCP, c::TCP_KEEPINTVL, secs)
+    }
+
+    pub fn set_bind_device(&self, _interface: &[u8]) -> io::Result<()> {
+        Err(io::const_error!(
+            io::ErrorKind::Unsupported,
+            "SO_BINDTODEVICE is not supported on this platform",
+        ))
+    }
+
     pub fn take_error(&self) -> io::Result<Option<io::Error>> {
         let raw: c_int = getsockopt(self, c::SOL_SOCKET, c::SO_ERROR)?;
         if raw == 0 { Ok(None) } else { Ok(Some(io::Error::from_raw_os_error(raw as i32))) }
@@ -531,6 +583,74 @@ pub unsafe fn from_raw(raw: c::SOCKET) -> Self {
     }
 }
 
+/// Waits until one of `sockets` finishes the connection attempt started by
+/// [`Socket::connect_nonblocking`].
+///
+/// Returns the index of that socket together with the outcome of its attempt,
+/// or `None` if `timeout` elapsed first. A timeout of `None` waits forever.
+/// At most 64 sockets, the size of `FD_SET`, can be waited on.
+pub fn wait_connected(
+    sockets: &[Socket],
+    timeout: Option<Duration>,
+) -> io::Result<Option<(usize, io::Result<()>)>> {
+    let mut fds = unsafe { mem::zeroed::<c::FD_SET>() };
+    if sockets.len() > fds.fd_array.len() {
+        return Err(io::const_error!(io::ErrorKind::InvalidInput, "too many sockets to wait on"));
+    }
+    for (slot, sock) in fds.fd_array.iter_mut().zip(sockets) {
+        *slot = sock.as_raw();
+    }
+    fds.fd_count = sockets.len() as u32;
+
+    let timeout = timeout.map(|timeout| {
+        let mut timeout = c::TIMEVAL {
+            tv_sec: cmp::min(timeout.as_secs(), c_long::MAX as u64) as c_long,
+            tv_usec: timeout.subsec_micros() as c_long,
+        };
+        if timeout.tv_sec == 0 && timeout.tv_usec == 0 {
+            timeout.tv_usec = 1;
+        }
+        timeout
+    });
+
+    let mut writefds = fds;
+    let mut errorfds = fds;
+
+    let count = {
+        let result = unsafe {
+            c::select(
+                1,
+                ptr::null_mut(),
+                &mut writefds,
+                &mut errorfds,
+                timeout.as_ref().map_or(ptr::null(), |timeout| timeout as *const _),
+            )
+        };
+        cvt(result)?
+    };
+
+    if count == 0 {
+        return Ok(None);
+    }
+
+    // Sockets whose connection attempt failed are reported in `errorfds`,
+    // successfully connected ones in `writefds`.
+    let ready = errorfds.fd_array[..errorfds.fd_count as usize]
+        .iter()
+        .chain(&writefds.fd_array[..writefds.fd_count as usize]);
+    for raw in ready {
+        if let Some(idx) = sockets.iter().position(|sock| sock.as_raw() == *raw) {
+            let result = match sockets[idx].take_error() {
+                Ok(Some(e)) => Err(e),
+                Ok(None) => Ok(()),
+                Err(e) => Err(e),
+            };
+            return Ok(Some((idx, result)));
+        }
+    }
+    Ok(None)
+}
+
 #[unstable(reason = "not public", issue = "none", feature = "fd_read")]
 impl<'a> Read for &'a Socket {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
@@ -2,6 +2,7 @@
 use crate::io::{self, BorrowedCursor, IoSlice, IoSliceMut};
 use crate::net::{Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr};
 use crate::sync::{Arc, Mutex};
+use crate::sys::net::SocketOptions;
 use crate::sys::unsupported;
 use crate::time::Duration;
 
@@ -33,6 +34,39 @@ pub fn connect_timeout(addr: &SocketAddr, timeout: Duration) -> io::Result<TcpSt
         })
     }
 
+    pub fn connect_with(
+        addr: io::Result<&SocketAddr>,
+        opts: &SocketOptions,
+        timeout: Option<Duration>,
+    ) -> io::Result<TcpStream> {
+        if !opts.is_default() {
+            return unsupported();
+        }
+        match timeout {
+            Some(timeout) => TcpStream::connect_timeout(addr?, timeout),
+            None => TcpStream::connect(addr),
+        }
+    }
+
+    pub fn connect_happy_eyeballs(
+        addrs: &[SocketAddr],
+        opts: &SocketOptions,
+        _attempt_delay: Duration,
+    ) -> io::Result<TcpStream> {
+        // Without nonblocking connects, the addresses are tried one after
+        // another.
+        let mut last_err = None;
+        for addr in addrs {
+            match TcpStream::connect_with(Ok(addr), opts, None) {
+                Ok(stream) => return Ok(stream),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        Err(last_err.unwrap_or_else(|| {
+            io::const_error!(io::ErrorKind::InvalidInput, "could not resolve to any addresses")
+        }))
+    }
+
     pub fn set_read_timeout(&self, t: Option<Duration>) -> io::Result<()> {
         self.read_timeout.set(t).unwrap();
         Ok(())
@@ -149,6 +183,10 @@ pub fn bind(_: io::Result<&SocketAddr>) -> io::Result<TcpListener> {
         unsupported()
     }
 
+    pub fn bind_with(_: io::Result<&SocketAddr>, _: &SocketOptions) -> io::Result<TcpListener> {
+        unsupported()
+    }
+
     pub fn socket_addr(&self) -> io::Result<SocketAddr> {
         unsupported()
     }
@@ -199,6 +237,10 @@ pub fn bind(_: io::Result<&SocketAddr>) -> io::Result<UdpSocket> {
         unsupported()
     }
 
+    pub fn bind_with(_: io::Result<&SocketAddr>, _: &SocketOptions) -> io::Result<UdpSocket> {
+        unsupported()
+    }
+
     pub fn peer_addr(&self) -> io::Result<SocketAddr> {
         self.0
     }
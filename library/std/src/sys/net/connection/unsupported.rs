@@ -1,6 +1,7 @@
 use crate::fmt;
 use crate::io::{self, BorrowedCursor, IoSlice, IoSliceMut};
 use crate::net::{Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr};
+use crate::sys::net::SocketOptions;
 use crate::sys::unsupported;
 use crate::time::Duration;
 
@@ -15,6 +16,22 @@ pub fn connect_timeout(_: &SocketAddr, _: Duration) -> io::Result<TcpStream> {
         unsupported()
     }
 
+    pub fn connect_with(
+        _: io::Result<&SocketAddr>,
+        _: &SocketOptions,
+        _: Option<Duration>,
+    ) -> io::Result<TcpStream> {
+        unsupported()
+    }
+
+    pub fn connect_happy_eyeballs(
+        _: &[SocketAddr],
+        _: &SocketOptions,
+        _: Duration,
+    ) -> io::Result<TcpStream> {
+        unsupported()
+    }
+
     pub fn set_read_timeout(&self, _: Option<Duration>) -> io::Result<()> {
         self.0
     }
@@ -125,6 +142,10 @@ pub fn bind(_: io::Result<&SocketAddr>) -> io::Result<TcpListener> {
         unsupported()
     }
 
+    pub fn bind_with(_: io::Result<&SocketAddr>, _: &SocketOptions) -> io::Result<TcpListener> {
+        unsupported()
+    }
+
     pub fn socket_addr(&self) -> io::Result<SocketAddr> {
         self.0
     }
@@ -175,6 +196,10 @@ pub fn bind(_: io::Result<&SocketAddr>) -> io::Result<UdpSocket> {
         unsupported()
     }
 
+    pub fn bind_with(_: io::Result<&SocketAddr>, _: &SocketOptions) -> io::Result<UdpSocket> {
+        unsupported()
+    }
+
     pub fn peer_addr(&self) -> io::Result<SocketAddr> {
         self.0
     }
@@ -5,6 +5,7 @@
 use crate::net::{Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr};
 use crate::os::wasi::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, RawFd};
 use crate::sys::fd::WasiFd;
+use crate::sys::net::SocketOptions;
 use crate::sys::{err2io, unsupported};
 use crate::sys_common::{AsInner, FromInner, IntoInner};
 use crate::time::Duration;
@@ -68,6 +69,22 @@ pub fn connect_timeout(_: &SocketAddr, _: Duration) -> io::Result<TcpStream> {
         unsupported()
     }
 
+    pub fn connect_with(
+        _: io::Result<&SocketAddr>,
+        _: &SocketOptions,
+        _: Option<Duration>,
+    ) -> io::Result<TcpStream> {
+        unsupported()
+    }
+
+    pub fn connect_happy_eyeballs(
+        _: &[SocketAddr],
+        _: &SocketOptions,
+        _: Duration,
+    ) -> io::Result<TcpStream> {
+        unsupported()
+    }
+
     pub fn set_read_timeout(&self, _: Option<Duration>) -> io::Result<()> {
         unsupported()
     }
@@ -216,6 +233,10 @@ pub fn bind(_: io::Result<&SocketAddr>) -> io::Result<TcpListener> {
         unsupported()
     }
 
+    pub fn bind_with(_: io::Result<&SocketAddr>, _: &SocketOptions) -> io::Result<TcpListener> {
+        unsupported()
+    }
+
     pub fn socket_addr(&self) -> io::Result<SocketAddr> {
         unsupported()
     }
@@ -320,6 +341,10 @@ pub fn bind(_: io::Result<&SocketAddr>) -> io::Result<UdpSocket> {
         unsupported()
     }
 
+    pub fn bind_with(_: io::Result<&SocketAddr>, _: &SocketOptions) -> io::Result<UdpSocket> {
+        unsupported()
+    }
+
     pub fn peer_addr(&self) -> io::Result<SocketAddr> {
         unsupported()
     }
@@ -5,6 +5,7 @@
 use crate::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use crate::os::xous::services;
 use crate::sync::Arc;
+use crate::sys::net::SocketOptions;
 use crate::{fmt, io};
 
 macro_rules! unimpl {
@@ -37,6 +38,16 @@ pub fn bind(socketaddr: io::Result<&SocketAddr>) -> io::Result<TcpListener> {
         });
     }
 
+    pub fn bind_with(
+        addr: io::Result<&SocketAddr>,
+        opts: &SocketOptions,
+    ) -> io::Result<TcpListener> {
+        if !opts.is_default() {
+            return crate::sys::unsupported();
+        }
+        TcpListener::bind(addr)
+    }
+
     /// This returns the raw fd of a Listener, so that it can also be used by the
     /// accept routine to replenish the Listener object after its handle has been converted into
     /// a TcpStream object.
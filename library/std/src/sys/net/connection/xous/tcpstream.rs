@@ -6,6 +6,7 @@
 use crate::net::{IpAddr, Ipv4Addr, Shutdown, SocketAddr, SocketAddrV4, SocketAddrV6};
 use crate::os::xous::services;
 use crate::sync::Arc;
+use crate::sys::net::SocketOptions;
 use crate::time::Duration;
 
 macro_rules! unimpl {
@@ -135,6 +136,39 @@ pub fn connect_timeout(addr: &SocketAddr, duration: Duration) -> io::Result<TcpS
         })
     }
 
+    pub fn connect_with(
+        addr: io::Result<&SocketAddr>,
+        opts: &SocketOptions,
+        timeout: Option<Duration>,
+    ) -> io::Result<TcpStream> {
+        if !opts.is_default() {
+            return crate::sys::unsupported();
+        }
+        match timeout {
+            Some(timeout) => TcpStream::connect_timeout(addr?, timeout),
+            None => TcpStream::connect(addr),
+        }
+    }
+
+    pub fn connect_happy_eyeballs(
+        addrs: &[SocketAddr],
+        opts: &SocketOptions,
+        _attempt_delay: Duration,
+    ) -> io::Result<TcpStream> {
+        // Without nonblocking connects, the addresses are tried one after
+        // another.
+        let mut last_err = None;
+        for addr in addrs {
+            match TcpStream::connect_with(Ok(addr), opts, None) {
+                Ok(stream) => return Ok(stream),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        Err(last_err.unwrap_or_else(|| {
+            io::const_error!(io::ErrorKind::InvalidInput, "could not resolve to any addresses")
+        }))
+    }
+
     pub fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
         if let Some(to) = timeout {
             if to.is_zero() {
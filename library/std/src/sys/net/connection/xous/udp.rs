@@ -6,6 +6,7 @@
 use crate::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use crate::os::xous::services;
 use crate::sync::Arc;
+use crate::sys::net::SocketOptions;
 use crate::time::Duration;
 use crate::{fmt, io};
 
@@ -101,6 +102,13 @@ pub fn bind(socketaddr: io::Result<&SocketAddr>) -> io::Result<UdpSocket> {
         Err(io::const_error!(io::ErrorKind::InvalidInput, "invalid response"))
     }
 
+    pub fn bind_with(addr: io::Result<&SocketAddr>, opts: &SocketOptions) -> io::Result<UdpSocket> {
+        if !opts.is_default() {
+            return crate::sys::unsupported();
+        }
+        UdpSocket::bind(addr)
+    }
+
     pub fn peer_addr(&self) -> io::Result<SocketAddr> {
         match self.remote.get() {
             Some(dest) => Ok(dest),
@@ -38,4 +38,7 @@ mod connection {
     }
 }
 
+mod options;
+
 pub use connection::*;
+pub use options::SocketOptions;
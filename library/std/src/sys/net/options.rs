@@ -0,0 +1,38 @@
+use crate::time::Duration;
+
+/// Socket options that have to be applied before a socket is bound or
+/// connected, as configured by the builders in `std::net`.
+///
+/// Every option is `None` by default, in which case the platform default (or
+/// the default `std` applies in `bind`/`connect`) is used.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SocketOptions {
+    pub reuse_address: Option<bool>,
+    pub reuse_port: Option<bool>,
+    pub only_v6: Option<bool>,
+    pub send_buffer_size: Option<usize>,
+    pub recv_buffer_size: Option<usize>,
+    pub keepalive: Option<bool>,
+    pub keepalive_idle: Option<Duration>,
+    pub keepalive_interval: Option<Duration>,
+    pub nodelay: Option<bool>,
+    pub bind_device: Option<Vec<u8>>,
+    pub backlog: Option<u32>,
+}
+
+impl SocketOptions {
+    /// Returns `true` if no option has been set, so that a plain `bind` or
+    /// `connect` behaves identically.
+    // Only used by the platforms that support none of the options.
+    #[cfg_attr(
+        not(any(
+            all(target_vendor = "fortanix", target_env = "sgx"),
+            target_os = "xous",
+            target_os = "uefi",
+        )),
+        expect(dead_code)
+    )]
+    pub fn is_default(&self) -> bool {
+        *self == SocketOptions::default()
+    }
+}
@@ -118,3 +118,15 @@ pub fn into_id(self) -> Tid {
 pub fn available_parallelism() -> io::Result<NonZero<usize>> {
     unsafe { Ok(NonZero::new_unchecked(hermit_abi::available_parallelism())) }
 }
+
+pub fn current_affinity() -> io::Result<Vec<usize>> {
+    crate::sys::unsupported()
+}
+
+pub fn set_affinity(_cpus: &[usize]) -> io::Result<()> {
+    crate::sys::unsupported()
+}
+
+pub fn set_priority(_priority: crate::thread::Priority) -> io::Result<()> {
+    crate::sys::unsupported()
+}
@@ -364,3 +364,15 @@ unsafe fn terminate_and_delete_current_task() -> ! {
 pub fn available_parallelism() -> io::Result<NonZero<usize>> {
     super::unsupported()
 }
+
+pub fn current_affinity() -> io::Result<Vec<usize>> {
+    crate::sys::unsupported()
+}
+
+pub fn set_affinity(_cpus: &[usize]) -> io::Result<()> {
+    crate::sys::unsupported()
+}
+
+pub fn set_priority(_priority: crate::thread::Priority) -> io::Result<()> {
+    crate::sys::unsupported()
+}
@@ -150,6 +150,18 @@ pub fn available_parallelism() -> io::Result<NonZero<usize>> {
     Err(io::Error::UNKNOWN_THREAD_COUNT)
 }
 
+pub fn current_affinity() -> io::Result<Vec<usize>> {
+    crate::sys::unsupported()
+}
+
+pub fn set_affinity(_cpus: &[usize]) -> io::Result<()> {
+    crate::sys::unsupported()
+}
+
+pub fn set_priority(_priority: crate::thread::Priority) -> io::Result<()> {
+    crate::sys::unsupported()
+}
+
 fn min_stack_size(_: *const libc::pthread_attr_t) -> usize {
     libc::PTHREAD_STACK_MIN.try_into().expect("Infallible")
 }
@@ -60,3 +60,15 @@ pub fn available_parallelism() -> io::Result<NonZero<usize>> {
     // UEFI is single threaded
     Ok(NonZero::new(1).unwrap())
 }
+
+pub fn current_affinity() -> io::Result<Vec<usize>> {
+    crate::sys::unsupported()
+}
+
+pub fn set_affinity(_cpus: &[usize]) -> io::Result<()> {
+    crate::sys::unsupported()
+}
+
+pub fn set_priority(_priority: crate::thread::Priority) -> io::Result<()> {
+    crate::sys::unsupported()
+}
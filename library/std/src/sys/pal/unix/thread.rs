@@ -589,6 +589,90 @@ pub fn available_parallelism() -> io::Result<NonZero<usize>> {
     }
 }
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn current_affinity() -> io::Result<Vec<usize>> {
+    let mut set: libc::cpu_set_t = unsafe { mem::zeroed() };
+    super::cvt(unsafe { libc::sched_getaffinity(0, size_of::<libc::cpu_set_t>(), &mut set) })?;
+    let max_cpus = 8 * size_of::<libc::cpu_set_t>();
+    Ok((0..max_cpus).filter(|&cpu| unsafe { libc::CPU_ISSET(cpu, &set) }).collect())
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn set_affinity(cpus: &[usize]) -> io::Result<()> {
+    let mut set: libc::cpu_set_t = unsafe { mem::zeroed() };
+    let max_cpus = 8 * size_of::<libc::cpu_set_t>();
+    for &cpu in cpus {
+        if cpu >= max_cpus {
+            return Err(io::const_error!(io::ErrorKind::InvalidInput, "CPU index out of range"));
+        }
+        unsafe { libc::CPU_SET(cpu, &mut set) };
+    }
+    // A pid of 0 refers to the calling thread, not the whole process.
+    super::cvt(unsafe { libc::sched_setaffinity(0, size_of::<libc::cpu_set_t>(), &set) })?;
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+pub fn current_affinity() -> io::Result<Vec<usize>> {
+    Err(io::const_error!(
+        io::ErrorKind::Unsupported,
+        "thread affinity is not supported on the target platform",
+    ))
+}
+
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+pub fn set_affinity(_cpus: &[usize]) -> io::Result<()> {
+    current_affinity().map(drop)
+}
+
+pub fn set_priority(priority: crate::thread::Priority) -> io::Result<()> {
+    cfg_if::cfg_if! {
+        if #[cfg(any(target_os = "android", target_os = "linux"))] {
+            use crate::thread::Priority;
+
+            let nice = match priority {
+                Priority::Low => 10,
+                Priority::Normal => 0,
+                Priority::High => -10,
+            };
+            // Contrary to POSIX, the nice value is a per-thread attribute on
+            // Linux, and a `who` of 0 refers to the calling thread.
+            super::cvt(unsafe { libc::setpriority(libc::PRIO_PROCESS as _, 0, nice) })?;
+            Ok(())
+        } else if #[cfg(any(
+            target_vendor = "apple",
+            target_os = "freebsd",
+            target_os = "dragonfly",
+            target_os = "netbsd",
+            target_os = "openbsd",
+        ))] {
+            use super::{cvt, cvt_nz};
+            use crate::thread::Priority;
+
+            unsafe {
+                let thread = libc::pthread_self();
+                let mut policy = 0;
+                let mut param: libc::sched_param = mem::zeroed();
+                cvt_nz(libc::pthread_getschedparam(thread, &mut policy, &mut param))?;
+                let min = cvt(libc::sched_get_priority_min(policy))?;
+                let max = cvt(libc::sched_get_priority_max(policy))?;
+                param.sched_priority = match priority {
+                    Priority::Low => min,
+                    Priority::Normal => min + (max - min) / 2,
+                    Priority::High => max,
+                };
+                cvt_nz(libc::pthread_setschedparam(thread, policy, &param))
+            }
+        } else {
+            let _ = priority;
+            Err(io::const_error!(
+                io::ErrorKind::Unsupported,
+                "thread priorities are not supported on the target platform",
+            ))
+        }
+    }
+}
+
 #[cfg(any(target_os = "android", target_os = "linux"))]
 mod cgroups {
     //! Currently not covered
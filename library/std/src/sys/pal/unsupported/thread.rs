@@ -42,3 +42,15 @@ pub fn join(self) {
 pub fn available_parallelism() -> io::Result<NonZero<usize>> {
     unsupported()
 }
+
+pub fn current_affinity() -> io::Result<Vec<usize>> {
+    crate::sys::unsupported()
+}
+
+pub fn set_affinity(_cpus: &[usize]) -> io::Result<()> {
+    crate::sys::unsupported()
+}
+
+pub fn set_priority(_priority: crate::thread::Priority) -> io::Result<()> {
+    crate::sys::unsupported()
+}
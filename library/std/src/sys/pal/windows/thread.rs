@@ -6,7 +6,7 @@
 use crate::num::NonZero;
 use crate::os::windows::io::{AsRawHandle, HandleOrNull};
 use crate::sys::handle::Handle;
-use crate::sys::{c, stack_overflow};
+use crate::sys::{c, cvt, stack_overflow};
 use crate::sys_common::FromInner;
 use crate::time::{Duration, Instant};
 use crate::{io, ptr};
@@ -138,3 +138,36 @@ pub fn available_parallelism() -> io::Result<NonZero<usize>> {
         cpus => Ok(unsafe { NonZero::new_unchecked(cpus) }),
     }
 }
+
+pub fn current_affinity() -> io::Result<Vec<usize>> {
+    let mut affinity = c::GROUP_AFFINITY::default();
+    cvt(unsafe { c::GetThreadGroupAffinity(c::GetCurrentThread(), &mut affinity) })?;
+    let mask = affinity.Mask;
+    Ok((0..usize::BITS as usize).filter(|&cpu| mask & (1 << cpu) != 0).collect())
+}
+
+pub fn set_affinity(cpus: &[usize]) -> io::Result<()> {
+    let mut mask = 0usize;
+    for &cpu in cpus {
+        if cpu >= usize::BITS as usize {
+            return Err(io::const_error!(io::ErrorKind::InvalidInput, "CPU index out of range"));
+        }
+        mask |= 1 << cpu;
+    }
+    if unsafe { c::SetThreadAffinityMask(c::GetCurrentThread(), mask) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+pub fn set_priority(priority: crate::thread::Priority) -> io::Result<()> {
+    use crate::thread::Priority;
+
+    let priority = match priority {
+        Priority::Low => c::THREAD_PRIORITY_BELOW_NORMAL,
+        Priority::Normal => c::THREAD_PRIORITY_NORMAL,
+        Priority::High => c::THREAD_PRIORITY_ABOVE_NORMAL,
+    };
+    cvt(unsafe { c::SetThreadPriority(c::GetCurrentThread(), priority) })?;
+    Ok(())
+}
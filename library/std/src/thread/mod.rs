@@ -165,8 +165,8 @@
 use crate::mem::{self, ManuallyDrop, forget};
 use crate::num::NonZero;
 use crate::pin::Pin;
-use crate::sync::Arc;
 use crate::sync::atomic::{Atomic, AtomicUsize, Ordering};
+use crate::sync::{Arc, mpsc};
 use crate::sys::sync::Parker;
 use crate::sys::thread as imp;
 use crate::sys_common::{AsInner, IntoInner};
@@ -264,6 +264,10 @@ pub struct Builder {
     stack_size: Option<usize>,
     // Skip running and inheriting the thread spawn hooks
     no_hooks: bool,
+    // The CPUs the thread-to-be is allowed to run on
+    affinity: Option<Vec<usize>>,
+    // The scheduling priority of the thread-to-be
+    priority: Option<Priority>,
 }
 
 impl Builder {
@@ -287,7 +291,7 @@ impl Builder {
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn new() -> Builder {
-        Builder { name: None, stack_size: None, no_hooks: false }
+        Builder { name: None, stack_size: None, no_hooks: false, affinity: None, priority: None }
     }
 
     /// Names the thread-to-be. Currently the name is used for identification
@@ -353,6 +357,48 @@ pub fn no_hooks(mut self) -> Builder {
         self
     }
 
+    /// Restricts the new thread to run only on the given CPUs.
+    ///
+    /// CPUs are identified by their zero-based index, as used by
+    /// [`current_affinity`]. The affinity is applied by the new thread before
+    /// it runs any spawn hooks or the thread closure. If it can't be applied,
+    /// the thread closure is never run and [`spawn`](Builder::spawn) returns
+    /// the error. An empty set of CPUs is rejected with
+    /// [`io::ErrorKind::InvalidInput`] before the thread is created.
+    ///
+    /// See [`set_affinity`] for platform-specific behavior.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(thread_affinity)]
+    /// use std::thread;
+    ///
+    /// let handler = thread::Builder::new().affinity(&[0]).spawn(|| {
+    ///     assert_eq!(thread::current_affinity().unwrap(), [0]);
+    /// }).unwrap();
+    ///
+    /// handler.join().unwrap();
+    /// ```
+    #[unstable(feature = "thread_affinity", issue = "none")]
+    pub fn affinity(mut self, cpus: &[usize]) -> Builder {
+        self.affinity = Some(cpus.to_vec());
+        self
+    }
+
+    /// Sets the scheduling priority of the new thread.
+    ///
+    /// Like [`affinity`](Builder::affinity), the priority is applied by the new
+    /// thread before it runs the thread closure, and failing to apply it makes
+    /// [`spawn`](Builder::spawn) return an error.
+    ///
+    /// See [`set_priority`] for platform-specific behavior.
+    #[unstable(feature = "thread_affinity", issue = "none")]
+    pub fn priority(mut self, priority: Priority) -> Builder {
+        self.priority = Some(priority);
+        self
+    }
+
     /// Spawns a new thread by taking ownership of the `Builder`, and returns an
     /// [`io::Result`] to its [`JoinHandle`].
     ///
@@ -478,7 +524,11 @@ unsafe fn spawn_unchecked_<'scope, F, T>(
         F: Send,
         T: Send,
     {
-        let Builder { name, stack_size, no_hooks } = self;
+        let Builder { name, stack_size, no_hooks, affinity, priority } = self;
+
+        if affinity.as_ref().is_some_and(|cpus| cpus.is_empty()) {
+            return Err(io::const_error!(io::ErrorKind::InvalidInput, "empty CPU set"));
+        }
 
         let stack_size = stack_size.unwrap_or_else(|| {
             static MIN: Atomic<usize> = AtomicUsize::new(0);
@@ -509,6 +559,15 @@ unsafe fn spawn_unchecked_<'scope, F, T>(
 
         let their_thread = my_thread.clone();
 
+        // If the scheduling parameters can't be applied by the new thread, it
+        // sends back the error and exits instead of running `f`.
+        let (sched_tx, sched_rx) = if affinity.is_some() || priority.is_some() {
+            let (tx, rx) = mpsc::sync_channel(1);
+            (Some(tx), Some(rx))
+        } else {
+            (None, None)
+        };
+
         let my_packet: Arc<Packet<'scope, T>> = Arc::new(Packet {
             scope: scope_data,
             result: UnsafeCell::new(None),
@@ -553,6 +612,15 @@ fn drop(&mut self) {
                 imp::Thread::set_name(name);
             }
 
+            if let Some(sched_tx) = sched_tx {
+                let result = apply_sched(affinity.as_deref(), priority);
+                let failed = result.is_err();
+                let _ = sched_tx.send(result);
+                if failed {
+                    return;
+                }
+            }
+
             let f = f.into_inner();
             let try_result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
                 crate::sys::backtrace::__rust_begin_short_backtrace(|| hooks.run());
@@ -581,24 +649,30 @@ fn drop(&mut self) {
         let main =
             unsafe { Box::from_raw(Box::into_raw(main) as *mut (dyn FnOnce() + Send + 'static)) };
 
-        Ok(JoinInner {
-            // SAFETY:
-            //
-            // `imp::Thread::new` takes a closure with a `'static` lifetime, since it's passed
-            // through FFI or otherwise used with low-level threading primitives that have no
-            // notion of or way to enforce lifetimes.
-            //
-            // As mentioned in the `Safety` section of this function's documentation, the caller of
-            // this function needs to guarantee that the passed-in lifetime is sufficiently long
-            // for the lifetime of the thread.
-            //
-            // Similarly, the `sys` implementation must guarantee that no references to the closure
-            // exist after the thread has terminated, which is signaled by `Thread::join`
-            // returning.
-            native: unsafe { imp::Thread::new(stack_size, my_thread.name(), main)? },
-            thread: my_thread,
-            packet: my_packet,
-        })
+        // SAFETY:
+        //
+        // `imp::Thread::new` takes a closure with a `'static` lifetime, since it's passed
+        // through FFI or otherwise used with low-level threading primitives that have no
+        // notion of or way to enforce lifetimes.
+        //
+        // As mentioned in the `Safety` section of this function's documentation, the caller of
+        // this function needs to guarantee that the passed-in lifetime is sufficiently long
+        // for the lifetime of the thread.
+        //
+        // Similarly, the `sys` implementation must guarantee that no references to the closure
+        // exist after the thread has terminated, which is signaled by `Thread::join`
+        // returning.
+        let native = unsafe { imp::Thread::new(stack_size, my_thread.name(), main)? };
+
+        if let Some(sched_rx) = sched_rx {
+            // The thread always sends a result before it exits.
+            if let Ok(Err(err)) = sched_rx.recv() {
+                native.join();
+                return Err(err);
+            }
+        }
+
+        Ok(JoinInner { native, thread: my_thread, packet: my_packet })
     }
 }
 
@@ -2051,3 +2125,105 @@ fn _assert_both<T: Send + Sync>() {}
 pub fn available_parallelism() -> io::Result<NonZero<usize>> {
     imp::available_parallelism()
 }
+
+/// A portable hint for the scheduling priority of a thread.
+///
+/// Each priority maps to a fixed level of the platform's scheduler, as
+/// described in [`set_priority`]. [`Priority::Normal`] is the default level,
+/// which threads start out with unless the priority of the whole process was
+/// changed, for example with `nice` on Unix. Raising the priority above the
+/// default level may require additional privileges.
+#[unstable(feature = "thread_affinity", issue = "none")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Priority {
+    /// Prefer other threads when CPU time is scarce, e.g. for background work.
+    Low,
+    /// The default priority.
+    Normal,
+    /// Prefer this thread over others, e.g. for latency-sensitive work.
+    High,
+}
+
+/// Returns the CPUs the current thread is allowed to run on.
+///
+/// CPUs are identified by their zero-based index and returned in increasing
+/// order.
+///
+/// # Platform-specific behavior
+///
+/// This function currently corresponds to `sched_getaffinity` on Linux and
+/// Android and to `GetThreadGroupAffinity` on Windows, where only the CPUs of
+/// the thread's processor group are returned. Other platforms return an error.
+/// Note that, this [may change in the future][changes].
+///
+/// [changes]: io#platform-specific-behavior
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(thread_affinity)]
+/// use std::thread;
+///
+/// let cpus = thread::current_affinity().unwrap();
+/// println!("running on CPUs {cpus:?}");
+/// ```
+#[unstable(feature = "thread_affinity", issue = "none")]
+pub fn current_affinity() -> io::Result<Vec<usize>> {
+    imp::current_affinity()
+}
+
+/// Restricts the current thread to run only on the given CPUs.
+///
+/// # Errors
+///
+/// Returns an error of kind [`io::ErrorKind::InvalidInput`] if `cpus` is empty
+/// or contains an index that can't be represented on this platform, and an
+/// error from the operating system if none of the CPUs are available to the
+/// process.
+///
+/// # Platform-specific behavior
+///
+/// This function currently corresponds to `sched_setaffinity` on Linux and
+/// Android and to `SetThreadAffinityMask` on Windows, where only the first 64
+/// CPUs of the current processor group can be used. Other platforms return an
+/// error.
+/// Note that, this [may change in the future][changes].
+///
+/// [changes]: io#platform-specific-behavior
+#[unstable(feature = "thread_affinity", issue = "none")]
+pub fn set_affinity(cpus: &[usize]) -> io::Result<()> {
+    if cpus.is_empty() {
+        return Err(io::const_error!(io::ErrorKind::InvalidInput, "empty CPU set"));
+    }
+    imp::set_affinity(cpus)
+}
+
+/// Sets the scheduling priority of the current thread.
+///
+/// # Platform-specific behavior
+///
+/// On Linux and Android this sets the nice value of the thread to 10, 0 or
+/// -10, regardless of the nice value of the process, and lowering it below
+/// the current value requires `CAP_SYS_NICE` or a suitable `RLIMIT_NICE`. Apple platforms and the BSDs map the priorities onto the
+/// range supported by the thread's scheduling policy, and Windows uses
+/// `THREAD_PRIORITY_BELOW_NORMAL`, `THREAD_PRIORITY_NORMAL` and
+/// `THREAD_PRIORITY_ABOVE_NORMAL`, which are relative to the priority class of
+/// the process. Other platforms return an error.
+/// Note that, this [may change in the future][changes].
+///
+/// [changes]: io#platform-specific-behavior
+#[unstable(feature = "thread_affinity", issue = "none")]
+pub fn set_priority(priority: Priority) -> io::Result<()> {
+    imp::set_priority(priority)
+}
+
+fn apply_sched(affinity: Option<&[usize]>, priority: Option<Priority>) -> io::Result<()> {
+    if let Some(cpus) = affinity {
+        set_affinity(cpus)?;
+    }
+    if let Some(priority) = priority {
+        set_priority(priority)?;
+    }
+    Ok(())
+}
@@ -415,3 +415,29 @@ fn test_minimal_thread_stack() {
     assert_eq!(before, 0);
     assert_eq!(COUNT.load(Ordering::Relaxed), 1);
 }
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_builder_affinity() {
+    let cpus = thread::current_affinity().unwrap();
+    let first = cpus[0];
+    Builder::new()
+        .affinity(&[first])
+        .spawn(move || {
+            assert_eq!(thread::current_affinity().unwrap(), [first]);
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    // Only the spawned thread is pinned.
+    assert_eq!(thread::current_affinity().unwrap(), cpus);
+}
+
+#[test]
+fn test_builder_invalid_affinity() {
+    let ran = Arc::new(AtomicBool::new(false));
+    let ran2 = ran.clone();
+    let result = Builder::new().affinity(&[]).spawn(move || ran2.store(true, Ordering::SeqCst));
+    assert_eq!(result.unwrap_err().kind(), crate::io::ErrorKind::InvalidInput);
+    assert!(!ran.load(Ordering::SeqCst));
+}
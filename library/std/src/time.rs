@@ -600,6 +600,98 @@ pub fn checked_add(&self, duration: Duration) -> Option<SystemTime> {
     pub fn checked_sub(&self, duration: Duration) -> Option<SystemTime> {
         self.0.checked_sub_duration(&duration).map(SystemTime)
     }
+
+    /// Returns an object that formats this time as an [RFC 3339] timestamp in
+    /// UTC, like `2024-05-17T08:30:00.250Z`.
+    ///
+    /// The fractional seconds are truncated to the given `precision`, and
+    /// omitted entirely for [`Rfc3339Precision::Seconds`].
+    ///
+    /// RFC 3339 only covers the years 0000 to 9999. Times outside of that
+    /// range are written in the expanded ISO 8601 representation, with an
+    /// explicit sign and at least five digits for the year, like
+    /// `+10000-01-01T00:00:00Z`.
+    ///
+    /// `SystemTime` follows Unix time, which doesn't count leap seconds, so
+    /// the seconds field is never `60`.
+    ///
+    /// [RFC 3339]: https://www.rfc-editor.org/rfc/rfc3339
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(time_rfc3339)]
+    /// use std::time::{Duration, Rfc3339Precision, SystemTime};
+    ///
+    /// let time = SystemTime::UNIX_EPOCH + Duration::new(1_715_934_600, 250_000_000);
+    ///
+    /// assert_eq!(
+    ///     time.format_rfc3339(Rfc3339Precision::Seconds).to_string(),
+    ///     "2024-05-17T08:30:00Z",
+    /// );
+    /// assert_eq!(
+    ///     time.format_rfc3339(Rfc3339Precision::Millis).to_string(),
+    ///     "2024-05-17T08:30:00.250Z",
+    /// );
+    /// ```
+    #[unstable(feature = "time_rfc3339", issue = "none")]
+    #[must_use = "this does not format anything, it only returns an object that implements `Display`"]
+    pub fn format_rfc3339(&self, precision: Rfc3339Precision) -> FormatRfc3339 {
+        FormatRfc3339 { time: *self, precision }
+    }
+
+    /// Parses an [RFC 3339] timestamp, like `2024-05-17T08:30:00.250Z` or
+    /// `2024-05-17T10:30:00+02:00`.
+    ///
+    /// The date and time must be separated by `T`, `t` or a space. The time
+    /// must be followed by either `Z`, `z` or a numeric UTC offset, which is
+    /// applied to get the returned time. Fractional seconds of any length are
+    /// accepted, digits beyond nanosecond precision are ignored.
+    ///
+    /// A leap second (`60` in the seconds field) is accepted, but since
+    /// `SystemTime` follows Unix time, which doesn't count leap seconds, it is
+    /// folded into the following second: `2016-12-31T23:59:60Z` parses to the
+    /// same time as `2017-01-01T00:00:00Z`.
+    ///
+    /// Years outside of 0000 to 9999 are accepted in the expanded ISO 8601
+    /// representation written by [`format_rfc3339`], like
+    /// `+10000-01-01T00:00:00Z`, so every formatted time can be parsed back.
+    ///
+    /// [RFC 3339]: https://www.rfc-editor.org/rfc/rfc3339
+    /// [`format_rfc3339`]: SystemTime::format_rfc3339
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `s` is not a valid RFC 3339 timestamp, or if the
+    /// time it denotes can't be represented by `SystemTime` on this platform.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(time_rfc3339)]
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// let time = SystemTime::parse_rfc3339("2024-05-17T10:30:00.25+02:00").unwrap();
+    /// assert_eq!(time, SystemTime::UNIX_EPOCH + Duration::new(1_715_934_600, 250_000_000));
+    ///
+    /// assert!(SystemTime::parse_rfc3339("2024-02-30T00:00:00Z").is_err());
+    /// ```
+    #[unstable(feature = "time_rfc3339", issue = "none")]
+    pub fn parse_rfc3339(s: &str) -> Result<SystemTime, ParseRfc3339Error> {
+        let (secs, nanos) = parse_rfc3339(s.as_bytes())
+            .ok_or(ParseRfc3339Error { kind: ParseRfc3339ErrorKind::Invalid })?;
+        let time = if secs >= 0 {
+            u64::try_from(secs)
+                .ok()
+                .and_then(|secs| UNIX_EPOCH.checked_add(Duration::new(secs, nanos)))
+        } else {
+            u64::try_from(secs.unsigned_abs())
+                .ok()
+                .and_then(|secs| UNIX_EPOCH.checked_sub(Duration::from_secs(secs)))
+                .and_then(|time| time.checked_add(Duration::from_nanos(nanos.into())))
+        };
+        time.ok_or(ParseRfc3339Error { kind: ParseRfc3339ErrorKind::OutOfRange })
+    }
 }
 
 #[stable(feature = "time2", since = "1.8.0")]
@@ -719,6 +811,228 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
+/// The precision of the fractional seconds written by
+/// [`SystemTime::format_rfc3339`].
+#[unstable(feature = "time_rfc3339", issue = "none")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Rfc3339Precision {
+    /// Whole seconds, without a fractional part.
+    Seconds,
+    /// Three fractional digits.
+    Millis,
+    /// Six fractional digits.
+    Micros,
+    /// Nine fractional digits.
+    Nanos,
+}
+
+impl Rfc3339Precision {
+    fn digits(self) -> u32 {
+        match self {
+            Rfc3339Precision::Seconds => 0,
+            Rfc3339Precision::Millis => 3,
+            Rfc3339Precision::Micros => 6,
+            Rfc3339Precision::Nanos => 9,
+        }
+    }
+}
+
+/// Helper struct for formatting a [`SystemTime`] as an RFC 3339 timestamp.
+///
+/// This `struct` is created by the [`SystemTime::format_rfc3339`] method.
+/// See its documentation for more.
+#[unstable(feature = "time_rfc3339", issue = "none")]
+#[derive(Debug, Clone, Copy)]
+pub struct FormatRfc3339 {
+    time: SystemTime,
+    precision: Rfc3339Precision,
+}
+
+#[unstable(feature = "time_rfc3339", issue = "none")]
+impl fmt::Display for FormatRfc3339 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.width().is_none() && f.precision().is_none() {
+            return self.write(f);
+        }
+
+        /// Enough for a twelve-digit year with a sign and nine fractional
+        /// digits, which covers every `u64` number of seconds from the epoch.
+        struct Buffer {
+            buf: [u8; 48],
+            len: usize,
+        }
+
+        impl fmt::Write for Buffer {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                let dst = self.buf.get_mut(self.len..self.len + s.len()).ok_or(fmt::Error)?;
+                dst.copy_from_slice(s.as_bytes());
+                self.len += s.len();
+                Ok(())
+            }
+        }
+
+        let mut buf = Buffer { buf: [0; 48], len: 0 };
+        self.write(&mut buf)?;
+        // SAFETY: only `str`s were written to the buffer.
+        f.pad(unsafe { crate::str::from_utf8_unchecked(&buf.buf[..buf.len]) })
+    }
+}
+
+impl FormatRfc3339 {
+    fn write(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        let (secs, nanos) = match self.time.duration_since(UNIX_EPOCH) {
+            Ok(d) => (i128::from(d.as_secs()), d.subsec_nanos()),
+            Err(e) => {
+                let d = e.duration();
+                match d.subsec_nanos() {
+                    0 => (-i128::from(d.as_secs()), 0),
+                    nanos => (-i128::from(d.as_secs()) - 1, 1_000_000_000 - nanos),
+                }
+            }
+        };
+        // `secs` comes from a `u64`, so the number of days always fits in an `i64`.
+        let (year, month, day) = core::time::days_to_civil(secs.div_euclid(86400) as i64);
+        let secs_of_day = secs.rem_euclid(86400) as u32;
+
+        if (0..=9999).contains(&year) {
+            write!(f, "{year:04}")?;
+        } else {
+            write!(f, "{year:+06}")?;
+        }
+        write!(
+            f,
+            "-{month:02}-{day:02}T{:02}:{:02}:{:02}",
+            secs_of_day / 3600,
+            secs_of_day / 60 % 60,
+            secs_of_day % 60,
+        )?;
+        let digits = self.precision.digits();
+        if digits > 0 {
+            let frac = nanos / 10u32.pow(9 - digits);
+            write!(f, ".{frac:0width$}", width = digits as usize)?;
+        }
+        f.write_str("Z")
+    }
+}
+
+/// An error returned by [`SystemTime::parse_rfc3339`].
+#[unstable(feature = "time_rfc3339", issue = "none")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRfc3339Error {
+    kind: ParseRfc3339ErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ParseRfc3339ErrorKind {
+    Invalid,
+    OutOfRange,
+}
+
+#[unstable(feature = "time_rfc3339", issue = "none")]
+impl Error for ParseRfc3339Error {}
+
+#[unstable(feature = "time_rfc3339", issue = "none")]
+impl fmt::Display for ParseRfc3339Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self.kind {
+            ParseRfc3339ErrorKind::Invalid => "invalid RFC 3339 timestamp",
+            ParseRfc3339ErrorKind::OutOfRange => "RFC 3339 timestamp out of range for `SystemTime`",
+        })
+    }
+}
+
+/// Parses an RFC 3339 timestamp into seconds and nanoseconds since the Unix
+/// epoch, with the nanoseconds always being positive.
+fn parse_rfc3339(s: &[u8]) -> Option<(i128, u32)> {
+    fn digits(s: &mut &[u8], n: usize) -> Option<u32> {
+        let (head, tail) = s.split_at_checked(n)?;
+        let mut value = 0;
+        for &b in head {
+            if !b.is_ascii_digit() {
+                return None;
+            }
+            value = value * 10 + u32::from(b - b'0');
+        }
+        *s = tail;
+        Some(value)
+    }
+
+    fn expect(s: &mut &[u8], allowed: &[u8]) -> Option<u8> {
+        let (&b, tail) = s.split_first()?;
+        if !allowed.contains(&b) {
+            return None;
+        }
+        *s = tail;
+        Some(b)
+    }
+
+    let mut s = s;
+    // Years outside of 0000 to 9999 use the expanded ISO 8601 representation
+    // written by `FormatRfc3339`: a sign and at least five digits.
+    let year = match expect(&mut s, b"+-") {
+        None => i64::from(digits(&mut s, 4)?),
+        Some(sign) => {
+            let len = s.iter().take_while(|b| b.is_ascii_digit()).count();
+            // More digits would be out of range for `civil_to_days`.
+            if !(5..=15).contains(&len) {
+                return None;
+            }
+            let mut year = 0;
+            for &b in &s[..len] {
+                year = year * 10 + i64::from(b - b'0');
+            }
+            s = &s[len..];
+            if sign == b'-' { -year } else { year }
+        }
+    };
+    expect(&mut s, b"-")?;
+    let month = digits(&mut s, 2)?;
+    expect(&mut s, b"-")?;
+    let day = digits(&mut s, 2)?;
+    expect(&mut s, b"Tt ")?;
+    let hour = digits(&mut s, 2)?;
+    expect(&mut s, b":")?;
+    let minute = digits(&mut s, 2)?;
+    expect(&mut s, b":")?;
+    let second = digits(&mut s, 2)?;
+
+    let mut nanos = 0;
+    if expect(&mut s, b".").is_some() {
+        let len = s.iter().take_while(|b| b.is_ascii_digit()).count();
+        if len == 0 {
+            return None;
+        }
+        for (i, &b) in s[..len].iter().take(9).enumerate() {
+            nanos += u32::from(b - b'0') * 10u32.pow(8 - i as u32);
+        }
+        s = &s[len..];
+    }
+
+    let offset = match expect(&mut s, b"Zz+-")? {
+        b'Z' | b'z' => 0,
+        sign => {
+            let offset_hour = digits(&mut s, 2)?;
+            expect(&mut s, b":")?;
+            let offset_minute = digits(&mut s, 2)?;
+            if offset_hour > 23 || offset_minute > 59 {
+                return None;
+            }
+            let offset = i64::from(offset_hour * 3600 + offset_minute * 60);
+            if sign == b'-' { -offset } else { offset }
+        }
+    };
+    if !s.is_empty() || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let days = core::time::civil_to_days(year, month as u8, day as u8)?;
+    // A leap second is folded into the following second.
+    let secs = i128::from(days) * 86400 + i128::from(hour * 3600 + minute * 60 + second)
+        - i128::from(offset);
+    Some((secs, nanos))
+}
+
 impl FromInner<time::SystemTime> for SystemTime {
     fn from_inner(time: time::SystemTime) -> SystemTime {
         SystemTime(time)
@@ -1,7 +1,8 @@
 #![feature(duration_constants)]
+#![feature(time_rfc3339)]
 
 use std::fmt::Debug;
-use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, Rfc3339Precision, SystemTime, UNIX_EPOCH};
 
 macro_rules! assert_almost_eq {
     ($a:expr, $b:expr) => {{
@@ -227,3 +228,108 @@ fn check<T: Eq + Copy + Debug>(start: Option<T>, op: impl Fn(&T, Duration) -> Op
     check(instant.checked_add(Duration::from_secs(100)), Instant::checked_sub);
     check(instant.checked_add(Duration::from_secs(i64::MAX as _)), Instant::checked_sub);
 }
+
+#[test]
+fn system_time_format_rfc3339() {
+    let time = UNIX_EPOCH + Duration::new(951_782_400, 123_456_789);
+    assert_eq!(time.format_rfc3339(Rfc3339Precision::Seconds).to_string(), "2000-02-29T00:00:00Z");
+    assert_eq!(
+        time.format_rfc3339(Rfc3339Precision::Micros).to_string(),
+        "2000-02-29T00:00:00.123456Z"
+    );
+    assert_eq!(
+        time.format_rfc3339(Rfc3339Precision::Nanos).to_string(),
+        "2000-02-29T00:00:00.123456789Z"
+    );
+    assert_eq!(
+        UNIX_EPOCH.format_rfc3339(Rfc3339Precision::Millis).to_string(),
+        "1970-01-01T00:00:00.000Z"
+    );
+
+    if let Some(time) = UNIX_EPOCH.checked_sub(Duration::new(0, 1)) {
+        assert_eq!(
+            time.format_rfc3339(Rfc3339Precision::Nanos).to_string(),
+            "1969-12-31T23:59:59.999999999Z"
+        );
+    }
+    if let Some(time) = UNIX_EPOCH.checked_add(Duration::from_secs(253_402_300_800)) {
+        assert_eq!(
+            time.format_rfc3339(Rfc3339Precision::Seconds).to_string(),
+            "+10000-01-01T00:00:00Z"
+        );
+    }
+    if let Some(time) = UNIX_EPOCH.checked_sub(Duration::from_secs(62_198_755_200)) {
+        assert_eq!(
+            time.format_rfc3339(Rfc3339Precision::Seconds).to_string(),
+            "-00001-01-01T00:00:00Z"
+        );
+    }
+
+    // Width, fill and alignment apply to the whole timestamp.
+    let formatted = time.format_rfc3339(Rfc3339Precision::Seconds);
+    assert_eq!(format!("{formatted:>22}"), "  2000-02-29T00:00:00Z");
+    assert_eq!(format!("{formatted:-<22}"), "2000-02-29T00:00:00Z--");
+}
+
+#[test]
+fn system_time_parse_rfc3339() {
+    let time = UNIX_EPOCH + Duration::new(1_715_934_600, 250_000_000);
+    assert_eq!(SystemTime::parse_rfc3339("2024-05-17T08:30:00.25Z"), Ok(time));
+    assert_eq!(SystemTime::parse_rfc3339("2024-05-17t08:30:00.250000000000z"), Ok(time));
+    assert_eq!(SystemTime::parse_rfc3339("2024-05-17 10:30:00.25+02:00"), Ok(time));
+    assert_eq!(SystemTime::parse_rfc3339("2024-05-17T02:00:00.25-06:30"), Ok(time));
+
+    // Leap seconds are folded into the following second.
+    assert_eq!(
+        SystemTime::parse_rfc3339("2016-12-31T23:59:60Z"),
+        SystemTime::parse_rfc3339("2017-01-01T00:00:00Z"),
+    );
+
+    for invalid in [
+        "",
+        "2024-05-17",
+        "2024-05-17T08:30:00",
+        "2024-05-17T08:30Z",
+        "2024-05-17T08:30:00.Z",
+        "2024-5-17T08:30:00Z",
+        "2024-05-17T08:30:00+0200",
+        "2024-05-17T08:30:00Z ",
+        "2024-13-01T00:00:00Z",
+        "2023-02-29T00:00:00Z",
+        "2024-05-17T24:00:00Z",
+        "2024-05-17T08:60:00Z",
+        "2024-05-17T08:30:61Z",
+        "2024-05-17T08:30:00+24:00",
+        "+2024-05-17T08:30:00Z",
+        "10000-01-01T00:00:00Z",
+        "+1000000000000000-01-01T00:00:00Z",
+    ] {
+        assert!(SystemTime::parse_rfc3339(invalid).is_err(), "{invalid:?} parsed");
+    }
+}
+
+#[test]
+fn system_time_rfc3339_round_trip() {
+    let time = SystemTime::now();
+    let formatted = time.format_rfc3339(Rfc3339Precision::Nanos).to_string();
+    assert_eq!(SystemTime::parse_rfc3339(&formatted), Ok(time));
+}
+
+#[test]
+fn system_time_rfc3339_round_trip_expanded_years() {
+    // The last second of year 9999, the first second of year 10000 and the
+    // first second of year -1, if the platform can represent them.
+    let times = [
+        UNIX_EPOCH.checked_add(Duration::new(253_402_300_799, 999_999_999)),
+        UNIX_EPOCH.checked_add(Duration::from_secs(253_402_300_800)),
+        UNIX_EPOCH.checked_sub(Duration::from_secs(62_198_755_200)),
+    ];
+    for time in times.into_iter().flatten() {
+        let formatted = time.format_rfc3339(Rfc3339Precision::Nanos).to_string();
+        assert_eq!(SystemTime::parse_rfc3339(&formatted), Ok(time), "{formatted}");
+    }
+    assert_eq!(
+        SystemTime::parse_rfc3339("+09999-12-31T23:59:59Z"),
+        SystemTime::parse_rfc3339("9999-12-31T23:59:59Z"),
+    );
+}
@@ -3,7 +3,7 @@
     DisallowedPath, DisallowedPathWithoutReplacement, MacroMatcher, MatchLintBehaviour, PubUnderscoreFieldsBehaviour,
     Rename, SourceItemOrdering, SourceItemOrderingCategory, SourceItemOrderingModuleItemGroupings,
     SourceItemOrderingModuleItemKind, SourceItemOrderingTraitAssocItemKind, SourceItemOrderingTraitAssocItemKinds,
-    SourceItemOrderingWithinModuleItemGroupings,
+    SourceItemOrderingWithinModuleItemGroupings, UserLint,
 };
 use clippy_utils::msrvs::Msrv;
 use itertools::Itertools;
@@ -865,6 +865,28 @@ fn span_from_toml_range(file: &SourceFile, span: Range<usize>) -> Span {
     /// Enables verbose mode. Triggers if there is more than one uppercase char next to each other
     #[lints(upper_case_acronyms)]
     upper_case_acronyms_aggressive: bool = false,
+    /// Rules for the `user_lints` lint, written as tables with a `name`, an optional `reason`, and
+    /// one of the following kinds of rules:
+    ///
+    /// - `call`: the fully qualified path of a function or method that should not be called
+    /// - `method`: the name of a method that should not be called, optionally together with a
+    ///   `receiver-type`, the fully qualified path of a type that the type of the receiver must
+    ///   mention, e.g. `Result<u32, my_crate::Error>` mentions `my_crate::Error`
+    /// - `fn-returns`: the fully qualified path of a type, together with a `required-attribute`
+    ///   that all functions whose return type mentions the type must have, e.g. `must_use`
+    ///
+    /// Every rule can list modules in `allowed-in`, as fully qualified paths, in which the rule does not
+    /// apply. For example:
+    ///
+    /// ```toml
+    /// [[user-lints]]
+    /// name = "handle-constructor"
+    /// call = "my_crate::Handle::new"
+    /// allowed-in = ["my_crate::registry"]
+    /// reason = "create handles with `Registry::handle`"
+    /// ```
+    #[lints(user_lints)]
+    user_lints: Vec<UserLint> = Vec::new(),
     /// The size of the boxed type in bytes, where boxing in a `Vec` is allowed
     #[lints(vec_box)]
     vec_box_size_threshold: u64 = 4096,
@@ -195,6 +195,91 @@ pub fn create_disallowed_map<const REPLACEMENT_ALLOWED: bool>(
     (def_ids, prim_tys)
 }
 
+/// A rule of the `user-lints` configuration, evaluated by the `user_lints` lint.
+#[derive(Debug, Deserialize)]
+#[serde(try_from = "RawUserLint")]
+pub struct UserLint {
+    /// The name of the rule, shown in its diagnostics.
+    pub name: String,
+    pub reason: Option<String>,
+    pub kind: UserLintKind,
+    /// Modules, written as fully qualified paths, in which the rule does not apply.
+    pub allowed_in: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum UserLintKind {
+    /// Calls of the function or method at `path`.
+    Call { path: String },
+    /// Calls of methods named `method`, optionally only if the type of the receiver mentions the
+    /// type at `receiver_type`.
+    MethodCall {
+        method: String,
+        receiver_type: Option<String>,
+    },
+    /// Functions whose return type mentions the type at `ty`, and that do not have the attribute
+    /// `required_attribute`.
+    FnReturning { ty: String, required_attribute: String },
+}
+
+// `RawUserLint` is how a `UserLint` is written in the configuration. It is converted to a
+// `UserLint` once it has been checked that exactly one kind of rule was given.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct RawUserLint {
+    name: String,
+    reason: Option<String>,
+    call: Option<String>,
+    method: Option<String>,
+    receiver_type: Option<String>,
+    fn_returns: Option<String>,
+    required_attribute: Option<String>,
+    #[serde(default)]
+    allowed_in: Vec<String>,
+}
+
+impl TryFrom<RawUserLint> for UserLint {
+    type Error = String;
+
+    fn try_from(raw: RawUserLint) -> Result<Self, Self::Error> {
+        let name = raw.name;
+        let kind = match (raw.call, raw.method, raw.fn_returns) {
+            (Some(path), None, None) if raw.receiver_type.is_none() && raw.required_attribute.is_none() => {
+                UserLintKind::Call { path }
+            },
+            (None, Some(method), None) if raw.required_attribute.is_none() => UserLintKind::MethodCall {
+                method,
+                receiver_type: raw.receiver_type,
+            },
+            (None, None, Some(ty)) if raw.receiver_type.is_none() => {
+                let Some(required_attribute) = raw.required_attribute else {
+                    return Err(format!(
+                        "user lint `{name}`: `fn-returns` requires `required-attribute`"
+                    ));
+                };
+                UserLintKind::FnReturning { ty, required_attribute }
+            },
+            (None, None, None) => {
+                return Err(format!(
+                    "user lint `{name}`: expected one of `call`, `method` or `fn-returns`"
+                ));
+            },
+            _ => {
+                return Err(format!(
+                    "user lint `{name}`: `call`, `method` and `fn-returns` cannot be combined, and \
+                    `receiver-type` and `required-attribute` are only allowed with `method` and `fn-returns`"
+                ));
+            },
+        };
+        Ok(Self {
+            name,
+            reason: raw.reason,
+            kind,
+            allowed_in: raw.allowed_in,
+        })
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum MatchLintBehaviour {
     AllTypes,
@@ -691,6 +776,7 @@ fn serialize<S>(&self, _serializer: S) -> Result<S::Ok, S::Error>
 unimplemented_serialize! {
     Rename,
     MacroMatcher,
+    UserLint,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
@@ -396,10 +396,12 @@
 mod use_self;
 mod useless_concat;
 mod useless_conversion;
+mod user_lints;
 mod vec;
 mod vec_init_then_push;
 mod visibility;
 mod wildcard_imports;
+mod workspace;
 mod write;
 mod zero_div_zero;
 mod zero_repeat_side_effects;
@@ -413,6 +415,8 @@
 use rustc_lint::Lint;
 use utils::attr_collector::{AttrCollector, AttrStorage};
 
+pub use workspace::{register_workspace_lint_pass, write_workspace_summary};
+
 pub fn explain(name: &str) -> i32 {
     let target = format!("clippy::{}", name.to_ascii_uppercase());
 
@@ -829,5 +833,6 @@ pub fn register_lint_passes(store: &mut rustc_lint::LintStore, conf: &'static Co
     store.register_late_pass(|_| Box::new(cloned_ref_to_slice_refs::ClonedRefToSliceRefs::new(conf)));
     store.register_late_pass(|_| Box::new(infallible_try_from::InfallibleTryFrom));
     store.register_late_pass(|_| Box::new(coerce_container_to_any::CoerceContainerToAny));
+    store.register_late_pass(move |tcx| Box::new(user_lints::UserLints::new(tcx, conf)));
     // add lints here, do not remove this comment, it's used in `new_lint`
 }
@@ -0,0 +1,287 @@
+use clippy_config::Conf;
+use clippy_config::types::{UserLint, UserLintKind};
+use clippy_utils::diagnostics::span_lint_and_then;
+use clippy_utils::paths::{PathNS, find_crates, lookup_path};
+use clippy_utils::return_ty;
+use rustc_hir::attrs::AttributeKind;
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::DefId;
+use rustc_hir::{
+    Attribute, Expr, ExprKind, HirId, ImplItem, ImplItemKind, Item, ItemKind, OwnerId, TraitItem, TraitItemKind,
+    find_attr,
+};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_session::impl_lint_pass;
+use rustc_span::{Ident, Span, Symbol, sym};
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for code matching the rules configured in `user-lints` in clippy.toml. A rule
+    /// matches one of:
+    /// - calls of a function or method, given by its path
+    /// - calls of methods with a given name, optionally only if the type of the receiver mentions
+    ///   a given type
+    /// - functions whose return type mentions a given type, but that lack a given attribute
+    ///
+    /// Every rule can list modules in which it does not apply.
+    ///
+    /// Note: Even though this lint is warn-by-default, it will only trigger if
+    /// rules are defined in the clippy.toml file.
+    ///
+    /// ### Why is this bad?
+    /// Projects often have rules about how their own APIs are to be used, which are too specific
+    /// for a general purpose lint.
+    ///
+    /// ### Known problems
+    /// The return types of `async` functions are not checked.
+    ///
+    /// ### Example
+    /// An example clippy.toml configuration:
+    /// ```toml
+    /// # clippy.toml
+    /// [[user-lints]]
+    /// name = "handle-constructor"
+    /// call = "my_crate::Handle::new"
+    /// allowed-in = ["my_crate::registry"]
+    /// reason = "create handles with `Registry::handle`"
+    ///
+    /// [[user-lints]]
+    /// name = "unwrap-io-error"
+    /// method = "unwrap"
+    /// receiver-type = "std::io::Error"
+    ///
+    /// [[user-lints]]
+    /// name = "must-use-handle"
+    /// fn-returns = "my_crate::Handle"
+    /// required-attribute = "must_use"
+    /// ```
+    ///
+    /// ```rust,ignore
+    /// fn open() -> Handle {
+    ///     // `Handle::new` is only allowed in `my_crate::registry`.
+    ///     Handle::new(File::open("data").unwrap())
+    /// }
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// #[must_use]
+    /// fn open(registry: &Registry) -> io::Result<Handle> {
+    ///     Ok(registry.handle(File::open("data")?))
+    /// }
+    /// ```
+    #[clippy::version = "1.90.0"]
+    pub USER_LINTS,
+    style,
+    "code matching a rule configured in `user-lints`"
+}
+
+/// A `UserLint` with its paths resolved.
+struct Rule {
+    lint: &'static UserLint,
+    kind: RuleKind,
+    allowed_in: Vec<DefId>,
+}
+
+enum RuleKind {
+    Call {
+        path: &'static str,
+        def_ids: Vec<DefId>,
+    },
+    MethodCall {
+        method: Symbol,
+        receiver_types: Option<Vec<DefId>>,
+    },
+    FnReturning {
+        tys: Vec<DefId>,
+        attribute: Vec<Symbol>,
+    },
+}
+
+pub struct UserLints {
+    rules: Vec<Rule>,
+}
+
+impl UserLints {
+    pub fn new(tcx: TyCtxt<'_>, conf: &'static Conf) -> Self {
+        let rules = conf
+            .user_lints
+            .iter()
+            .map(|lint| {
+                let lookup = |path: &str, ns: PathNS, def_kind_predicate: fn(DefKind) -> bool, description: &str| {
+                    resolve(tcx, lint, path, ns, def_kind_predicate, description)
+                };
+                let kind = match &lint.kind {
+                    UserLintKind::Call { path } => RuleKind::Call {
+                        path,
+                        def_ids: lookup(
+                            path,
+                            PathNS::Value,
+                            |def_kind| matches!(def_kind, DefKind::Fn | DefKind::AssocFn),
+                            "function",
+                        ),
+                    },
+                    UserLintKind::MethodCall { method, receiver_type } => RuleKind::MethodCall {
+                        method: Symbol::intern(method),
+                        receiver_types: receiver_type
+                            .as_deref()
+                            .map(|path| lookup(path, PathNS::Type, is_adt, "type")),
+                    },
+                    UserLintKind::FnReturning { ty, required_attribute } => RuleKind::FnReturning {
+                        tys: lookup(ty, PathNS::Type, is_adt, "type"),
+                        attribute: required_attribute.split("::").map(Symbol::intern).collect(),
+                    },
+                };
+                let allowed_in = lint
+                    .allowed_in
+                    .iter()
+                    .flat_map(|path| lookup(path, PathNS::Type, |def_kind| def_kind == DefKind::Mod, "module"))
+                    .collect();
+                Rule { lint, kind, allowed_in }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    fn check_fn_item(&self, cx: &LateContext<'_>, owner_id: OwnerId, span: Span, ident: Ident) {
+        if span.from_expansion() {
+            return;
+        }
+        let attrs = cx.tcx.hir_attrs(owner_id.into());
+        let ret_ty = return_ty(cx, owner_id);
+        for rule in &self.rules {
+            if let RuleKind::FnReturning { tys, attribute } = &rule.kind
+                && mentions(ret_ty, tys)
+                && !has_attribute(attrs, attribute)
+            {
+                emit(
+                    cx,
+                    rule,
+                    owner_id.into(),
+                    ident.span,
+                    format!(
+                        "function returning `{ret_ty}` is missing `#[{}]`",
+                        attribute.iter().map(Symbol::as_str).collect::<Vec<_>>().join("::")
+                    ),
+                );
+            }
+        }
+    }
+}
+
+impl_lint_pass!(UserLints => [USER_LINTS]);
+
+impl<'tcx> LateLintPass<'tcx> for UserLints {
+    fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'_>) {
+        let (callee, span, method) = match &expr.kind {
+            ExprKind::Path(path) if let Res::Def(_, id) = cx.qpath_res(path, expr.hir_id) => (id, expr.span, None),
+            ExprKind::MethodCall(name, receiver, ..)
+                if let Some(id) = cx.typeck_results().type_dependent_def_id(expr.hir_id) =>
+            {
+                let receiver_ty = cx.typeck_results().expr_ty(receiver);
+                (id, name.ident.span, Some((name.ident.name, receiver_ty)))
+            },
+            _ => return,
+        };
+        for rule in &self.rules {
+            let msg = match &rule.kind {
+                RuleKind::Call { path, def_ids } if def_ids.contains(&callee) => format!("use of `{path}`"),
+                RuleKind::MethodCall {
+                    method: rule_method,
+                    receiver_types,
+                } if let Some((name, receiver_ty)) = method
+                    && name == *rule_method
+                    && receiver_types.as_ref().is_none_or(|tys| mentions(receiver_ty, tys)) =>
+                {
+                    format!("call of `{name}` on `{receiver_ty}`")
+                },
+                _ => continue,
+            };
+            emit(cx, rule, expr.hir_id, span, msg);
+        }
+    }
+
+    fn check_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx Item<'_>) {
+        if let ItemKind::Fn { ident, .. } = item.kind {
+            self.check_fn_item(cx, item.owner_id, item.span, ident);
+        }
+    }
+
+    fn check_impl_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx ImplItem<'_>) {
+        // The attributes of trait methods are checked on the trait.
+        if let ImplItemKind::Fn(..) = item.kind
+            && item.trait_item_def_id.is_none()
+        {
+            self.check_fn_item(cx, item.owner_id, item.span, item.ident);
+        }
+    }
+
+    fn check_trait_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx TraitItem<'_>) {
+        if let TraitItemKind::Fn(..) = item.kind {
+            self.check_fn_item(cx, item.owner_id, item.span, item.ident);
+        }
+    }
+}
+
+fn is_adt(def_kind: DefKind) -> bool {
+    matches!(def_kind, DefKind::Struct | DefKind::Enum | DefKind::Union)
+}
+
+/// Resolves a path of a user lint, warning if it does not refer to anything.
+fn resolve(
+    tcx: TyCtxt<'_>,
+    lint: &UserLint,
+    path: &str,
+    ns: PathNS,
+    def_kind_predicate: fn(DefKind) -> bool,
+    description: &str,
+) -> Vec<DefId> {
+    let sym_path: Vec<Symbol> = path.split("::").map(Symbol::intern).collect();
+    let mut resolutions = lookup_path(tcx, ns, &sym_path);
+    resolutions.retain(|&def_id| def_kind_predicate(tcx.def_kind(def_id)));
+    // Don't warn about unloaded crates, as for `disallowed-methods`.
+    if resolutions.is_empty() && (sym_path.len() < 2 || !find_crates(tcx, sym_path[0]).is_empty()) {
+        tcx.sess.dcx().warn(format!(
+            "user lint `{}`: `{path}` does not refer to a reachable {description}",
+            lint.name
+        ));
+    }
+    resolutions
+}
+
+/// Checks if `ty` is, or contains, one of the types in `tys`.
+fn mentions(ty: Ty<'_>, tys: &[DefId]) -> bool {
+    ty.walk().any(|arg| {
+        arg.as_type()
+            .is_some_and(|ty| matches!(ty.kind(), ty::Adt(adt, _) if tys.contains(&adt.did())))
+    })
+}
+
+fn has_attribute(attrs: &[Attribute], path: &[Symbol]) -> bool {
+    // Builtin attributes are parsed by the compiler, and cannot be matched by their path.
+    match path {
+        [sym::must_use] => find_attr!(attrs, AttributeKind::MustUse { .. }),
+        [sym::inline] => find_attr!(attrs, AttributeKind::Inline(..)),
+        [sym::track_caller] => find_attr!(attrs, AttributeKind::TrackCaller(..)),
+        [sym::cold] => find_attr!(attrs, AttributeKind::Cold(..)),
+        _ => attrs.iter().any(|attr| attr.path_matches(path)),
+    }
+}
+
+fn emit(cx: &LateContext<'_>, rule: &Rule, hir_id: HirId, span: Span, msg: String) {
+    let module = cx.tcx.parent_module(hir_id).to_def_id();
+    if rule
+        .allowed_in
+        .iter()
+        .any(|&allowed| cx.tcx.is_descendant_of(module, allowed))
+    {
+        return;
+    }
+    span_lint_and_then(cx, USER_LINTS, span, msg, |diag| {
+        diag.note(format!("this is forbidden by the user lint `{}`", rule.lint.name));
+        if let Some(reason) = &rule.lint.reason {
+            diag.note(reason.clone());
+        }
+    });
+}
@@ -0,0 +1,299 @@
+mod summary;
+
+use clippy_utils::diagnostics::{span_lint, span_lint_and_help, span_lint_and_sugg};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_errors::Applicability;
+use rustc_hir::def_id::LocalDefId;
+use rustc_hir::{ImplItem, ImplItemKind, Item, ItemKind};
+use rustc_lint::{LateContext, LateLintPass, LintStore};
+use rustc_middle::ty::TyCtxt;
+use rustc_session::impl_lint_pass;
+use rustc_span::{DUMMY_SP, Span};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use summary::{CrateSummary, item_path};
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for public functions of a library that are not used by any crate of the workspace,
+    /// not even by the library itself.
+    ///
+    /// This lint only runs with `cargo clippy --workspace-lints`, which checks all crates of the
+    /// workspace twice: first to collect which items each crate uses, then to run the lints.
+    ///
+    /// ### Why is this bad?
+    /// Functions that are not used anywhere are dead code. Since they are public, rustc's
+    /// `dead_code` lint cannot detect them.
+    ///
+    /// ### Known problems
+    /// Uses by crates outside of the workspace, and by workspace crates that were not checked,
+    /// are not known. Only use this lint for libraries that are not published, and check all
+    /// targets of the workspace with `--workspace --all-targets`.
+    ///
+    /// Doctests are not checked, so functions that are only used in doctests are reported.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// // In a library that is only used by the binaries of the workspace:
+    /// pub fn parse_args() {}
+    /// pub fn parse_args_legacy() {} // not used anywhere
+    /// ```
+    /// Use instead:
+    /// ```no_run
+    /// pub fn parse_args() {}
+    /// ```
+    #[clippy::version = "1.90.0"]
+    pub UNUSED_PUB_FN,
+    pedantic,
+    "public functions that are not used by any crate of the workspace"
+}
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for public functions, constants and statics of a library that are only used by
+    /// the library itself, and its unit tests.
+    ///
+    /// This lint only runs with `cargo clippy --workspace-lints`, which checks all crates of the
+    /// workspace twice: first to collect which items each crate uses, then to run the lints.
+    ///
+    /// ### Why is this bad?
+    /// Items that are public without being used by other crates enlarge the API of the library
+    /// for no reason, and hide unused items from rustc's `dead_code` lint.
+    ///
+    /// ### Known problems
+    /// Uses by crates outside of the workspace, and by workspace crates that were not checked,
+    /// are not known. Only use this lint for libraries that are not published, and check all
+    /// targets of the workspace with `--workspace --all-targets`.
+    ///
+    /// Types and traits are not checked, since they may be needed in public signatures.
+    ///
+    /// Doctests are not checked, so items that are used in doctests are reported as well, even
+    /// though doctests can only use public items.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// // In a library that is only used by the binaries of the workspace:
+    /// pub fn parse_args() -> Vec<String> {
+    ///     split_args("")
+    /// }
+    ///
+    /// pub fn split_args(args: &str) -> Vec<String> {
+    ///     args.split(' ').map(String::from).collect()
+    /// }
+    /// ```
+    /// Use instead:
+    /// ```no_run
+    /// pub fn parse_args() -> Vec<String> {
+    ///     split_args("")
+    /// }
+    ///
+    /// pub(crate) fn split_args(args: &str) -> Vec<String> {
+    ///     args.split(' ').map(String::from).collect()
+    /// }
+    /// ```
+    #[clippy::version = "1.90.0"]
+    pub UNNECESSARY_PUB,
+    pedantic,
+    "public items that are only used by their own crate"
+}
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for features of workspace packages that are not enabled in any crate of the
+    /// workspace.
+    ///
+    /// This lint only runs with `cargo clippy --workspace-lints`, which checks all crates of the
+    /// workspace twice: first to collect which items each crate uses, then to run the lints.
+    ///
+    /// ### Why is this bad?
+    /// Code behind a feature that is never enabled is never checked, and easily breaks
+    /// unnoticed.
+    ///
+    /// ### Known problems
+    /// Only the features enabled in the current run are known. Features that are only enabled
+    /// on the command line, or by crates outside of the workspace, are reported unless the run
+    /// enables them as well.
+    ///
+    /// ### Example
+    /// ```toml
+    /// # In a library that no crate of the workspace enables `legacy-api` for:
+    /// [features]
+    /// legacy-api = []
+    /// ```
+    #[clippy::version = "1.90.0"]
+    pub UNUSED_CARGO_FEATURE,
+    cargo,
+    "features of workspace packages that are never enabled"
+}
+
+/// Registers the pass running the workspace lints over the crate summaries in `summary_dir`.
+pub fn register_workspace_lint_pass(store: &mut LintStore, summary_dir: PathBuf) {
+    store.register_late_pass(move |_| Box::new(WorkspaceLints::new(summary_dir.clone())));
+}
+
+/// Writes the summary of the local crate used by the workspace lints to `summary_dir`.
+///
+/// # Errors
+///
+/// Returns an error if the summary file could not be created or written.
+pub fn write_workspace_summary(tcx: TyCtxt<'_>, summary_dir: &Path) -> io::Result<()> {
+    let mut summary = CrateSummary::new(tcx);
+    summary.collect_uses(tcx);
+    summary.write(tcx, summary_dir)
+}
+
+pub struct WorkspaceLints {
+    summary_dir: PathBuf,
+    summaries: Vec<CrateSummary>,
+    /// For every used item, the indices of the summaries of the crates using it.
+    users: FxHashMap<String, Vec<usize>>,
+    /// The summary of the local crate, if its items should be checked. Only libraries are
+    /// checked, and only if the summaries could be read.
+    local: Option<CrateSummary>,
+}
+
+impl WorkspaceLints {
+    pub fn new(summary_dir: PathBuf) -> Self {
+        Self {
+            summary_dir,
+            summaries: Vec::new(),
+            users: FxHashMap::default(),
+            local: None,
+        }
+    }
+
+    fn check_features(&self, cx: &LateContext<'_>, local: &CrateSummary) {
+        if local.package.is_none() {
+            return;
+        }
+        let package: Vec<_> = self
+            .summaries
+            .iter()
+            .filter(|summary| summary.package == local.package)
+            .collect();
+        // Report the features of the package only once, preferably for its library.
+        let primary = package
+            .iter()
+            .min_by_key(|summary| (!summary.is_lib, summary.is_test, &summary.name));
+        if primary.is_none_or(|primary| {
+            (primary.is_lib, primary.is_test, &primary.name) != (local.is_lib, local.is_test, &local.name)
+        }) {
+            return;
+        }
+
+        for feature in &local.declared_features {
+            if feature != "default" && !package.iter().any(|summary| summary.enabled_features.contains(feature)) {
+                span_lint_and_help(
+                    cx,
+                    UNUSED_CARGO_FEATURE,
+                    DUMMY_SP,
+                    format!("feature `{feature}` is never enabled in the workspace"),
+                    None,
+                    "remove the feature, or enable it for a crate of the workspace",
+                );
+            }
+        }
+    }
+
+    fn check_pub_item(&self, cx: &LateContext<'_>, def_id: LocalDefId, span: Span, vis_span: Span, is_fn: bool) {
+        let tcx = cx.tcx;
+        let Some(local) = &self.local else {
+            return;
+        };
+        if span.from_expansion()
+            || !cx.effective_visibilities.is_exported(def_id)
+            || (is_fn && tcx.codegen_fn_attrs(def_id).contains_extern_indicator())
+        {
+            return;
+        }
+
+        let users = self
+            .users
+            .get(&item_path(tcx, def_id.to_def_id()))
+            .map_or(&[][..], Vec::as_slice);
+        if users.is_empty() {
+            if is_fn {
+                span_lint_and_help(
+                    cx,
+                    UNUSED_PUB_FN,
+                    span,
+                    "public function is never used in the workspace",
+                    None,
+                    "remove the function",
+                );
+            }
+        } else if users.iter().all(|&index| {
+            // Only the library and its own test harness count, not the binaries of the package,
+            // nor their test harnesses, which may have the same crate name.
+            let user = &self.summaries[index];
+            user.name == local.name
+                && user.package == local.package
+                && (user.is_lib || (user.is_test && user.root == local.root))
+        }) {
+            span_lint_and_sugg(
+                cx,
+                UNNECESSARY_PUB,
+                vis_span,
+                "item is public, but only used by its own crate",
+                "restrict its visibility to the crate",
+                "pub(crate)".to_owned(),
+                Applicability::MaybeIncorrect,
+            );
+        }
+    }
+}
+
+impl_lint_pass!(WorkspaceLints => [UNUSED_PUB_FN, UNNECESSARY_PUB, UNUSED_CARGO_FEATURE]);
+
+impl<'tcx> LateLintPass<'tcx> for WorkspaceLints {
+    fn check_crate(&mut self, cx: &LateContext<'tcx>) {
+        match CrateSummary::read_all(&self.summary_dir) {
+            Ok(summaries) => self.summaries = summaries,
+            Err(e) => {
+                for lint in [UNUSED_PUB_FN, UNNECESSARY_PUB, UNUSED_CARGO_FEATURE] {
+                    span_lint(
+                        cx,
+                        lint,
+                        DUMMY_SP,
+                        format!("could not read the workspace summaries: {e}"),
+                    );
+                }
+                return;
+            },
+        }
+        for (index, summary) in self.summaries.iter().enumerate() {
+            for path in &summary.uses {
+                self.users.entry(path.clone()).or_default().push(index);
+            }
+        }
+
+        let local = CrateSummary::new(cx.tcx);
+        self.check_features(cx, &local);
+        if local.is_lib && !local.is_test {
+            self.local = Some(local);
+        }
+    }
+
+    fn check_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx Item<'tcx>) {
+        let is_fn = match item.kind {
+            ItemKind::Fn { .. } => true,
+            ItemKind::Const(..) | ItemKind::Static(..) => false,
+            _ => return,
+        };
+        self.check_pub_item(cx, item.owner_id.def_id, item.span, item.vis_span, is_fn);
+    }
+
+    fn check_impl_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx ImplItem<'tcx>) {
+        // Items of trait implementations are as public as the trait.
+        if item.trait_item_def_id.is_some() {
+            return;
+        }
+        let is_fn = match item.kind {
+            ImplItemKind::Fn(..) => true,
+            ImplItemKind::Const(..) => false,
+            ImplItemKind::Type(..) => return,
+        };
+        self.check_pub_item(cx, item.owner_id.def_id, item.span, item.vis_span, is_fn);
+    }
+}
@@ -0,0 +1,174 @@
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::def_id::{DefId, LOCAL_CRATE};
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_hir::{BodyId, Expr, HirId, Path, QPath};
+use rustc_middle::hir::nested_filter;
+use rustc_middle::ty::{TyCtxt, TypeckResults};
+use rustc_session::config::{CrateType, ExpectedValues};
+use rustc_span::{Span, sym};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path as FsPath;
+
+/// What the workspace lints need to know about a single crate. One summary is written per crate in
+/// the first phase of `cargo clippy --workspace-lints`, and all of them are read in the second.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CrateSummary {
+    /// The name of the crate.
+    pub name: String,
+    /// Whether other crates can use the items of this crate.
+    pub is_lib: bool,
+    /// Whether the crate is a test harness, which is built from the same sources as a library or
+    /// binary crate of the same name.
+    pub is_test: bool,
+    /// The manifest directory of the package the crate belongs to.
+    pub package: Option<String>,
+    /// The path of the crate root, which tells the test harness of a library apart from the test
+    /// harnesses of the binaries of the same package.
+    pub root: Option<String>,
+    /// The features declared by the package.
+    pub declared_features: Vec<String>,
+    /// The features of the package enabled for this crate.
+    pub enabled_features: Vec<String>,
+    /// The paths of all items used by the crate, including its own items, see [`item_path`].
+    pub uses: Vec<String>,
+}
+
+impl CrateSummary {
+    /// Describes the local crate, without its uses.
+    pub fn new(tcx: TyCtxt<'_>) -> Self {
+        let psess = &tcx.sess.psess;
+        // sorted below
+        #[expect(rustc::potential_query_instability)]
+        let mut declared_features: Vec<_> = match psess.check_config.expecteds.get(&sym::feature) {
+            Some(ExpectedValues::Some(values)) => values.iter().flatten().map(ToString::to_string).collect(),
+            _ => Vec::new(),
+        };
+        declared_features.sort();
+        let mut enabled_features: Vec<_> = psess
+            .config
+            .iter()
+            .filter(|(name, _)| *name == sym::feature)
+            .filter_map(|(_, value)| value.map(|value| value.to_string()))
+            .collect();
+        enabled_features.sort();
+
+        Self {
+            name: tcx.crate_name(LOCAL_CRATE).to_string(),
+            is_lib: tcx
+                .crate_types()
+                .iter()
+                .any(|crate_type| matches!(crate_type, CrateType::Rlib | CrateType::Dylib)),
+            is_test: tcx.sess.is_test_crate(),
+            package: std::env::var("CARGO_MANIFEST_DIR").ok(),
+            root: tcx
+                .sess
+                .local_crate_source_file()
+                .map(|file| file.local_path_if_available().display().to_string()),
+            declared_features,
+            enabled_features,
+            uses: Vec::new(),
+        }
+    }
+
+    /// Collects the items referred to by paths and method calls anywhere in the crate.
+    pub fn collect_uses(&mut self, tcx: TyCtxt<'_>) {
+        let mut collector = UseCollector {
+            tcx,
+            maybe_typeck_results: None,
+            uses: FxHashSet::default(),
+        };
+        tcx.hir_walk_toplevel_module(&mut collector);
+        // sorted below
+        #[expect(rustc::potential_query_instability)]
+        let uses = collector.uses.into_iter().collect();
+        self.uses = uses;
+        self.uses.sort();
+    }
+
+    /// Writes the summary to a file in `dir` named after the crate.
+    pub fn write(&self, tcx: TyCtxt<'_>, dir: &FsPath) -> io::Result<()> {
+        let file_name = format!("{}-{:016x}.json", self.name, tcx.stable_crate_id(LOCAL_CRATE).as_u64());
+        let mut writer = BufWriter::new(File::create(dir.join(file_name))?);
+        serde_json::to_writer(&mut writer, self)?;
+        writer.flush()
+    }
+
+    /// Reads all summaries in `dir`.
+    pub fn read_all(dir: &FsPath) -> io::Result<Vec<Self>> {
+        let mut summaries = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|extension| extension == "json") {
+                let reader = BufReader::new(File::open(&path)?);
+                summaries.push(serde_json::from_reader(reader)?);
+            }
+        }
+        Ok(summaries)
+    }
+}
+
+/// The path of an item as recorded in a [`CrateSummary`], e.g. `my_crate::module::function`. Unlike
+/// `DefId`s, this is the same in all crates referring to the item.
+pub fn item_path(tcx: TyCtxt<'_>, def_id: DefId) -> String {
+    format!(
+        "{}{}",
+        tcx.crate_name(def_id.krate),
+        tcx.def_path(def_id).to_string_no_crate_verbose()
+    )
+}
+
+struct UseCollector<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    maybe_typeck_results: Option<&'tcx TypeckResults<'tcx>>,
+    uses: FxHashSet<String>,
+}
+
+impl UseCollector<'_> {
+    fn record(&mut self, def_id: DefId) {
+        self.uses.insert(item_path(self.tcx, def_id));
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for UseCollector<'tcx> {
+    type NestedFilter = nested_filter::All;
+
+    fn maybe_tcx(&mut self) -> Self::MaybeTyCtxt {
+        self.tcx
+    }
+
+    fn visit_nested_body(&mut self, body_id: BodyId) {
+        let old_typeck_results = self.maybe_typeck_results.replace(self.tcx.typeck_body(body_id));
+        self.visit_body(self.tcx.hir_body(body_id));
+        self.maybe_typeck_results = old_typeck_results;
+    }
+
+    fn visit_path(&mut self, path: &Path<'tcx>, _: HirId) {
+        if let Some(def_id) = path.res.opt_def_id() {
+            self.record(def_id);
+        }
+        intravisit::walk_path(self, path);
+    }
+
+    fn visit_qpath(&mut self, qpath: &'tcx QPath<'tcx>, hir_id: HirId, _: Span) {
+        // Associated items referred to through their type, like `Type::function`.
+        if let QPath::TypeRelative(..) = qpath
+            && let Some(typeck_results) = self.maybe_typeck_results
+            && let Some(def_id) = typeck_results.qpath_res(qpath, hir_id).opt_def_id()
+        {
+            self.record(def_id);
+        }
+        intravisit::walk_qpath(self, qpath, hir_id);
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx Expr<'tcx>) {
+        // Method calls.
+        if let Some(typeck_results) = self.maybe_typeck_results
+            && let Some(def_id) = typeck_results.type_dependent_def_id(expr.hir_id)
+        {
+            self.record(def_id);
+        }
+        intravisit::walk_expr(self, expr);
+    }
+}
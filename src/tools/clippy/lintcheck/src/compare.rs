@@ -0,0 +1,412 @@
+//! Comparison of two clippy-drivers on the same set of crates.
+//!
+//! Both drivers check every crate, then their warnings are diffed per lint, and their ICEs and
+//! timings per crate. The result is rendered as a self-contained HTML report.
+
+use std::fmt::Write as _;
+use std::path::Path;
+use std::sync::atomic::AtomicUsize;
+use std::time::{Duration, Instant};
+
+use rayon::prelude::*;
+
+use crate::Crate;
+use crate::config::LintcheckConfig;
+use crate::json::{self, LintJson, LintWarnings};
+use crate::output::{ClippyCheckOutput, ClippyWarning, RustcIce};
+
+/// Slowdowns below this are never reported as performance regressions, since they are mostly noise.
+const MIN_PERF_REGRESSION: Duration = Duration::from_secs(1);
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+th, td { border: 1px solid #ccc; padding: 0.2em 0.6em; }
+td.num { text-align: right; }
+tr.ice { background: #fdd; }
+tr.regression { background: #ffd; }
+pre { background: #f6f6f6; padding: 0.5em; overflow-x: auto; }
+.added { color: #060; }
+.removed { color: #a00; }
+";
+
+/// The result of checking a crate with one of the drivers
+struct CrateRun {
+    warning_count: usize,
+    ice: Option<RustcIce>,
+    duration: Duration,
+}
+
+struct CrateComparison {
+    name: String,
+    version: String,
+    old: CrateRun,
+    new: CrateRun,
+}
+
+impl CrateComparison {
+    fn is_new_ice(&self) -> bool {
+        self.new.ice.is_some() && self.old.ice.is_none()
+    }
+
+    fn is_perf_regression(&self, threshold_percent: u32) -> bool {
+        let (old, new) = (self.old.duration, self.new.duration);
+        new >= old + MIN_PERF_REGRESSION
+            && new.as_secs_f64() >= old.as_secs_f64() * (1.0 + f64::from(threshold_percent) / 100.0)
+    }
+}
+
+/// Checks `crates` with both drivers, given as their path and version, and returns the HTML
+/// report comparing them.
+pub(crate) fn compare(
+    crates: &[Crate],
+    old_driver: (&Path, &str),
+    new_driver: (&Path, &str),
+    config: &LintcheckConfig,
+    lint_level_args: &[String],
+) -> String {
+    let counter = AtomicUsize::new(1);
+    let run = |krate: &Crate, driver: &Path, label: &str| {
+        let start = Instant::now();
+        let outputs = krate.run_clippy_lints(driver, label, &counter, crates.len() * 2, config, lint_level_args, None);
+        let duration = start.elapsed();
+
+        let mut warnings = Vec::new();
+        let mut ice = None;
+        for output in outputs {
+            match output {
+                ClippyCheckOutput::ClippyWarning(warning) => warnings.push(warning),
+                ClippyCheckOutput::RustcIce(rustc_ice) => ice = Some(rustc_ice),
+            }
+        }
+        let run = CrateRun {
+            warning_count: warnings.len(),
+            ice,
+            duration,
+        };
+        (run, warnings)
+    };
+
+    // Both drivers check a crate one after the other, so that they run under similar load
+    let results: Vec<(CrateComparison, Vec<ClippyWarning>, Vec<ClippyWarning>)> = crates
+        .par_iter()
+        .map(|krate| {
+            let (old, old_warnings) = run(krate, old_driver.0, "old");
+            let (new, new_warnings) = run(krate, new_driver.0, "new");
+            let comparison = CrateComparison {
+                name: krate.name.clone(),
+                version: krate.version.clone(),
+                old,
+                new,
+            };
+            (comparison, old_warnings, new_warnings)
+        })
+        .collect();
+
+    let mut crates = Vec::new();
+    let mut old_warnings = Vec::new();
+    let mut new_warnings = Vec::new();
+    for (krate, old, new) in results {
+        // The warnings of a crate that ICEd are incomplete, and would only show up as noise
+        if krate.old.ice.is_none() && krate.new.ice.is_none() {
+            old_warnings.extend(old);
+            new_warnings.extend(new);
+        }
+        crates.push(krate);
+    }
+    crates.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+    let lints = json::diff_warnings(json::to_lint_json(old_warnings), json::to_lint_json(new_warnings));
+
+    let new_ices = crates.iter().filter(|krate| krate.is_new_ice()).count();
+    let regressions = crates
+        .iter()
+        .filter(|krate| krate.is_perf_regression(config.perf_threshold))
+        .count();
+    println!(
+        "{} lints with changed warnings, {new_ices} new ICEs, {regressions} performance regressions",
+        lints.len()
+    );
+
+    render(&crates, &lints, (old_driver.1, new_driver.1), config.perf_threshold)
+}
+
+fn render(crates: &[CrateComparison], lints: &[LintWarnings], versions: (&str, &str), threshold: u32) -> String {
+    let mut html = String::new();
+    writeln!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Lintcheck comparison</title>\n\
+        <style>{STYLE}</style>\n</head>\n<body>\n<h1>Lintcheck comparison</h1>"
+    )
+    .unwrap();
+    writeln!(
+        html,
+        "<p>Old: <code>{}</code><br>New: <code>{}</code></p>",
+        escape(versions.0.trim()),
+        escape(versions.1.trim())
+    )
+    .unwrap();
+
+    render_crates(&mut html, crates, threshold);
+    render_lints(&mut html, lints);
+    render_ices(&mut html, crates);
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn render_crates(html: &mut String, crates: &[CrateComparison], threshold: u32) {
+    writeln!(html, "<h2>Crates</h2>").unwrap();
+    writeln!(
+        html,
+        "<p>Crates that ICE with the new driver only, and crates that take at least {threshold}% and {}s \
+        longer to check, are highlighted.</p>",
+        MIN_PERF_REGRESSION.as_secs()
+    )
+    .unwrap();
+    html.push_str(
+        "<table>\n<tr><th>Crate</th><th>Old warnings</th><th>New warnings</th>\
+        <th>Old time</th><th>New time</th><th>ICE</th></tr>\n",
+    );
+    for krate in crates {
+        let class = if krate.is_new_ice() {
+            " class=\"ice\""
+        } else if krate.is_perf_regression(threshold) {
+            " class=\"regression\""
+        } else {
+            ""
+        };
+        let ice = match (&krate.old.ice, &krate.new.ice) {
+            (None, None) => "",
+            (Some(_), None) => "fixed",
+            (None, Some(_)) => "<a href=\"#ices\">new</a>",
+            (Some(_), Some(_)) => "both",
+        };
+        writeln!(
+            html,
+            "<tr{class}><td>{} {}</td><td class=\"num\">{}</td><td class=\"num\">{}</td>\
+            <td class=\"num\">{:.2}s</td><td class=\"num\">{:.2}s</td><td>{ice}</td></tr>",
+            escape(&krate.name),
+            escape(&krate.version),
+            krate.old.warning_count,
+            krate.new.warning_count,
+            krate.old.duration.as_secs_f64(),
+            krate.new.duration.as_secs_f64(),
+        )
+        .unwrap();
+    }
+    html.push_str("</table>\n");
+}
+
+fn render_lints(html: &mut String, lints: &[LintWarnings]) {
+    writeln!(html, "<h2>Lints</h2>").unwrap();
+    if lints.is_empty() {
+        writeln!(html, "<p>No warnings were added, removed or changed.</p>").unwrap();
+        return;
+    }
+
+    html.push_str("<table>\n<tr><th>Lint</th><th>Added</th><th>Removed</th><th>Changed</th></tr>\n");
+    for lint in lints {
+        writeln!(
+            html,
+            "<tr><td><a href=\"#{}\"><code>{}</code></a></td><td class=\"num\">{}</td>\
+            <td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+            html_id(&lint.name),
+            escape(&lint.name),
+            lint.added.len(),
+            lint.removed.len(),
+            lint.changed.len(),
+        )
+        .unwrap();
+    }
+    html.push_str("</table>\n");
+
+    for lint in lints {
+        writeln!(
+            html,
+            "<h3 id=\"{}\"><code>{}</code></h3>",
+            html_id(&lint.name),
+            escape(&lint.name)
+        )
+        .unwrap();
+        render_warnings(html, "Added", &lint.added);
+        render_warnings(html, "Removed", &lint.removed);
+
+        if !lint.changed.is_empty() {
+            writeln!(
+                html,
+                "<details open>\n<summary>Changed ({})</summary>",
+                lint.changed.len()
+            )
+            .unwrap();
+            for (old, new) in &lint.changed {
+                render_location(html, new);
+                html.push_str("<pre>");
+                for change in diff::lines(&old.rendered, &new.rendered) {
+                    use diff::Result::{Both, Left, Right};
+
+                    match change {
+                        Both(unchanged, _) => writeln!(html, " {}", escape(unchanged)),
+                        Left(removed) => {
+                            writeln!(html, "<span class=\"removed\">-{}</span>", escape(removed))
+                        },
+                        Right(added) => {
+                            writeln!(html, "<span class=\"added\">+{}</span>", escape(added))
+                        },
+                    }
+                    .unwrap();
+                }
+                html.push_str("</pre>\n");
+            }
+            html.push_str("</details>\n");
+        }
+    }
+}
+
+fn render_warnings(html: &mut String, title: &str, warnings: &[LintJson]) {
+    if warnings.is_empty() {
+        return;
+    }
+
+    writeln!(html, "<details open>\n<summary>{title} ({})</summary>", warnings.len()).unwrap();
+    for warning in warnings {
+        render_location(html, warning);
+        writeln!(html, "<pre>{}</pre>", escape(&warning.rendered)).unwrap();
+    }
+    html.push_str("</details>\n");
+}
+
+fn render_location(html: &mut String, warning: &LintJson) {
+    writeln!(
+        html,
+        "<p><a href=\"{}\"><code>{}</code></a></p>",
+        escape(&warning.file_url),
+        escape(&warning.file_line)
+    )
+    .unwrap();
+}
+
+fn render_ices(html: &mut String, crates: &[CrateComparison]) {
+    writeln!(html, "<h2 id=\"ices\">New ICEs</h2>").unwrap();
+    let mut new_ices = crates.iter().filter(|krate| krate.is_new_ice()).peekable();
+    if new_ices.peek().is_none() {
+        writeln!(html, "<p>No crate ICEs with the new driver only.</p>").unwrap();
+    }
+    for krate in new_ices {
+        let ice = krate.new.ice.as_ref().unwrap();
+        writeln!(
+            html,
+            "<details>\n<summary>{} {}</summary>\n<pre>{}</pre>\n</details>",
+            escape(&krate.name),
+            escape(&krate.version),
+            escape(&ice.ice_content)
+        )
+        .unwrap();
+    }
+}
+
+fn html_id(lint_name: &str) -> String {
+    lint_name.replace("::", "-").replace('_', "-")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(secs: f64, ice: bool) -> CrateRun {
+        CrateRun {
+            warning_count: 0,
+            ice: ice.then(|| RustcIce {
+                crate_name: "krate".into(),
+                ice_content: "thread 'rustc' panicked at <somewhere>".into(),
+            }),
+            duration: Duration::from_secs_f64(secs),
+        }
+    }
+
+    fn comparison(name: &str, old: CrateRun, new: CrateRun) -> CrateComparison {
+        CrateComparison {
+            name: name.into(),
+            version: "1.0.0".into(),
+            old,
+            new,
+        }
+    }
+
+    fn lint_json(name: &str, rendered: &str) -> LintJson {
+        LintJson {
+            name: name.into(),
+            file_line: "krate-1.0.0/src/lib.rs:1".into(),
+            file_url: "https://docs.rs/crate/krate/1.0.0/source/src/lib.rs#1".into(),
+            rendered: rendered.into(),
+        }
+    }
+
+    #[test]
+    fn perf_regression() {
+        let check =
+            |old, new, threshold| comparison("krate", run(old, false), run(new, false)).is_perf_regression(threshold);
+
+        // Both the relative and the absolute slowdown have to be reached
+        assert!(check(10.0, 12.0, 20));
+        assert!(!check(10.0, 11.9, 20));
+        assert!(!check(1.0, 1.9, 20));
+        assert!(check(1.0, 2.0, 20));
+        assert!(check(10.0, 11.0, 0));
+        assert!(!check(10.0, 10.5, 0));
+        // Speedups are never regressions
+        assert!(!check(12.0, 10.0, 0));
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(
+            escape("<a href=\"x\">&amp;</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;amp;&lt;/a&gt;"
+        );
+        assert_eq!(html_id("clippy::needless_return"), "clippy-needless-return");
+    }
+
+    #[test]
+    fn report() {
+        let crates = [
+            comparison("iced", run(1.0, false), run(1.0, true)),
+            comparison("slow", run(10.0, false), run(20.0, false)),
+            comparison("fixed", run(1.0, true), run(1.0, false)),
+        ];
+        let lints = [LintWarnings {
+            name: "clippy::needless_return".into(),
+            added: vec![lint_json("clippy::needless_return", "warning: <added>")],
+            removed: Vec::new(),
+            changed: vec![(
+                lint_json("clippy::needless_return", "warning: old\nsame"),
+                lint_json("clippy::needless_return", "warning: new\nsame"),
+            )],
+        }];
+        let html = render(&crates, &lints, ("clippy 0.1.0 (old)", "clippy 0.1.0 (new)\n"), 20);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.ends_with("</html>\n"));
+        assert!(html.contains("<code>clippy 0.1.0 (new)</code>"));
+        assert!(html.contains("<tr class=\"ice\"><td>iced 1.0.0</td>"));
+        assert!(html.contains("<tr class=\"regression\"><td>slow 1.0.0</td>"));
+        assert!(html.contains("<tr><td>fixed 1.0.0</td>"));
+        assert!(html.contains("<td>fixed</td></tr>"));
+        assert!(html.contains("<a href=\"#clippy-needless-return\"><code>clippy::needless_return</code></a>"));
+        assert!(html.contains("<h3 id=\"clippy-needless-return\">"));
+        assert!(html.contains("<summary>Added (1)</summary>"));
+        assert!(!html.contains("<summary>Removed"));
+        assert!(html.contains("<pre>warning: &lt;added&gt;</pre>"));
+        assert!(html.contains("<span class=\"removed\">-warning: old</span>\n"));
+        assert!(html.contains("<span class=\"added\">+warning: new</span>\n"));
+        assert!(html.contains(" same\n"));
+        assert!(html.contains("<summary>iced 1.0.0</summary>"));
+        assert!(!html.contains("<summary>fixed 1.0.0</summary>"));
+    }
+}
@@ -55,6 +55,21 @@ pub(crate) struct LintcheckConfig {
     /// `target/lintcheck/sources/<package>-<version>/perf.data`
     #[clap(long)]
     pub perf: bool,
+    /// Also check the crates with the given clippy-driver, and write an HTML report comparing
+    /// its warnings, ICEs and timings to the ones of the checked clippy-driver
+    #[clap(
+        long,
+        value_name = "OLD-CLIPPY-DRIVER",
+        conflicts_with_all(["fix", "recursive", "perf"])
+    )]
+    pub compare: Option<PathBuf>,
+    /// Check the crates with the given clippy-driver instead of building Clippy
+    #[clap(long, value_name = "CLIPPY-DRIVER")]
+    pub driver: Option<PathBuf>,
+    /// With --compare, report crates that take at least this many percent longer to check as
+    /// performance regressions
+    #[clap(long, value_name = "PERCENT", default_value_t = 20, requires("compare"))]
+    pub perf_threshold: u32,
     #[command(subcommand)]
     pub subcommand: Option<Commands>,
 }
@@ -106,11 +121,15 @@ pub fn new() -> Self {
         // for the path where we save the lint results, get the filename without extension (so for
         // wasd.toml, use "wasd"...)
         let filename: PathBuf = config.sources_toml_path.file_stem().unwrap().into();
-        config.lintcheck_results_path = PathBuf::from(format!(
-            "lintcheck-logs/{}_logs.{}",
-            filename.display(),
-            config.format.file_extension(),
-        ));
+        config.lintcheck_results_path = if config.compare.is_some() {
+            PathBuf::from(format!("lintcheck-logs/{}_compare.html", filename.display()))
+        } else {
+            PathBuf::from(format!(
+                "lintcheck-logs/{}_logs.{}",
+                filename.display(),
+                config.format.file_extension(),
+            ))
+        };
 
         // look at the --threads arg, if 0 is passed, use the threads count
         if config.max_jobs == 0 {
@@ -18,13 +18,13 @@
 const TRUNCATION_TOTAL_TARGET: usize = 1000;
 
 #[derive(Debug, Deserialize, Serialize)]
-struct LintJson {
+pub(crate) struct LintJson {
     /// The lint name e.g. `clippy::bytes_nth`
-    name: String,
+    pub(crate) name: String,
     /// The filename and line number e.g. `anyhow-1.0.86/src/error.rs:42`
-    file_line: String,
-    file_url: String,
-    rendered: String,
+    pub(crate) file_line: String,
+    pub(crate) file_url: String,
+    pub(crate) rendered: String,
 }
 
 impl LintJson {
@@ -91,6 +91,11 @@ fn new(lints: &[LintWarnings]) -> Self {
 
 /// Creates the log file output for [`crate::config::OutputFormat::Json`]
 pub(crate) fn output(clippy_warnings: Vec<ClippyWarning>) -> String {
+    serde_json::to_string(&to_lint_json(clippy_warnings)).unwrap()
+}
+
+/// Converts the warnings to the format used by the JSON output, sorted by lint and location.
+pub(crate) fn to_lint_json(clippy_warnings: Vec<ClippyWarning>) -> Vec<LintJson> {
     let mut lints: Vec<LintJson> = clippy_warnings
         .into_iter()
         .map(|warning| {
@@ -109,7 +114,7 @@ pub(crate) fn output(clippy_warnings: Vec<ClippyWarning>) -> String {
         })
         .collect();
     lints.sort_by(|a, b| a.key().cmp(&b.key()));
-    serde_json::to_string(&lints).unwrap()
+    lints
 }
 
 /// Loads lint warnings from a JSON file at the given path.
@@ -124,9 +129,37 @@ fn load_warnings(path: &Path) -> Vec<LintJson> {
 /// Compares warnings from `old_path` and `new_path`, then displays a summary table
 /// and detailed information about added, removed, and changed warnings.
 pub(crate) fn diff(old_path: &Path, new_path: &Path, truncate: bool, write_summary: Option<PathBuf>) {
-    let old_warnings = load_warnings(old_path);
-    let new_warnings = load_warnings(new_path);
+    let lint_warnings = diff_warnings(load_warnings(old_path), load_warnings(new_path));
+    if lint_warnings.is_empty() {
+        return;
+    }
+
+    let summary = Summary::new(&lint_warnings);
+    if let Some(path) = write_summary {
+        let json = serde_json::to_string(&summary).unwrap();
+        fs::write(path, json).unwrap();
+    }
+
+    let truncate_after = if truncate {
+        // Max 15 ensures that we at least have five messages per lint
+        DEFAULT_LIMIT_PER_LINT
+            .min(TRUNCATION_TOTAL_TARGET / lint_warnings.len())
+            .max(15)
+    } else {
+        // No lint should ever each this number of lint emissions, so this is equivialent to
+        // No truncation
+        usize::MAX
+    };
 
+    println!("{summary}");
+    for lint in lint_warnings {
+        print_lint_warnings(&lint, truncate_after);
+    }
+}
+
+/// Groups the differences between two sorted lists of warnings by lint. Lints without any
+/// differences are left out.
+pub(crate) fn diff_warnings(old_warnings: Vec<LintJson>, new_warnings: Vec<LintJson>) -> Vec<LintWarnings> {
     let mut lint_warnings = vec![];
 
     for (name, changes) in &itertools::merge_join_by(old_warnings, new_warnings, |old, new| old.key().cmp(&new.key()))
@@ -157,40 +190,16 @@ pub(crate) fn diff(old_path: &Path, new_path: &Path, truncate: bool, write_summa
         }
     }
 
-    if lint_warnings.is_empty() {
-        return;
-    }
-
-    let summary = Summary::new(&lint_warnings);
-    if let Some(path) = write_summary {
-        let json = serde_json::to_string(&summary).unwrap();
-        fs::write(path, json).unwrap();
-    }
-
-    let truncate_after = if truncate {
-        // Max 15 ensures that we at least have five messages per lint
-        DEFAULT_LIMIT_PER_LINT
-            .min(TRUNCATION_TOTAL_TARGET / lint_warnings.len())
-            .max(15)
-    } else {
-        // No lint should ever each this number of lint emissions, so this is equivialent to
-        // No truncation
-        usize::MAX
-    };
-
-    println!("{summary}");
-    for lint in lint_warnings {
-        print_lint_warnings(&lint, truncate_after);
-    }
+    lint_warnings
 }
 
 /// Container for grouped lint warnings organized by status (added/removed/changed).
 #[derive(Debug)]
-struct LintWarnings {
-    name: String,
-    added: Vec<LintJson>,
-    removed: Vec<LintJson>,
-    changed: Vec<(LintJson, LintJson)>,
+pub(crate) struct LintWarnings {
+    pub(crate) name: String,
+    pub(crate) added: Vec<LintJson>,
+    pub(crate) removed: Vec<LintJson>,
+    pub(crate) changed: Vec<(LintJson, LintJson)>,
 }
 
 fn print_lint_warnings(lint: &LintWarnings, truncate_after: usize) {
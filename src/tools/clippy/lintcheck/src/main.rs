@@ -20,6 +20,7 @@
     clippy::literal_string_with_formatting_args
 )]
 
+mod compare;
 mod config;
 mod driver;
 mod input;
@@ -70,6 +71,7 @@ impl Crate {
     fn run_clippy_lints(
         &self,
         clippy_driver_path: &Path,
+        target_dir_label: &str,
         target_dir_index: &AtomicUsize,
         total_crates_to_lint: usize,
         config: &LintcheckConfig,
@@ -177,7 +179,23 @@ fn run_clippy_lints(
             cmd.arg("--message-format=json");
         }
 
-        let shared_target_dir = shared_target_dir(&format!("_{thread_index:?}"));
+        let shared_target_dir = shared_target_dir(&format!("{target_dir_label}_{thread_index:?}"));
+        if config.compare.is_some() {
+            // Make sure that the crate is checked again rather than its cached warnings being replayed, as the
+            // timings of the drivers are compared
+            let status = Command::new("cargo")
+                .args(["clean", "--quiet", "--package", &self.name])
+                .current_dir(&self.path)
+                .env("CARGO_TARGET_DIR", &shared_target_dir)
+                .status()
+                .expect("failed to run cargo");
+            if !status.success() {
+                eprintln!(
+                    "\nWARNING: failed to clean {} {} before checking it, its timing may be unreliable\n",
+                    self.name, self.version
+                );
+            }
+        }
         let all_output = cmd
             // use the looping index to create individual target dirs
             .env("CARGO_TARGET_DIR", shared_target_dir.as_os_str())
@@ -314,24 +332,46 @@ fn main() {
     }
 }
 
-#[allow(clippy::too_many_lines)]
-fn lintcheck(config: LintcheckConfig) {
-    let clippy_ver = build_clippy(config.perf);
-    let clippy_driver_path = fs::canonicalize(format!(
-        "{}/{}/clippy-driver{EXE_SUFFIX}",
-        target_dir(),
-        if config.perf { "release" } else { "debug" }
-    ))
-    .unwrap();
-
-    // assert that clippy is found
+/// Returns the version of the given clippy-driver.
+fn driver_version(clippy_driver_path: &Path) -> String {
+    let output = Command::new(clippy_driver_path)
+        .arg("--version")
+        .stderr(Stdio::inherit())
+        .output()
+        .unwrap_or_else(|e| panic!("failed to run {}: {e}", clippy_driver_path.display()));
     assert!(
-        clippy_driver_path.is_file(),
-        "{}/{}/clippy-driver binary not found! {}",
-        target_dir(),
-        if config.perf { "release" } else { "debug" },
+        output.status.success(),
+        "failed to get the version of {}",
         clippy_driver_path.display()
     );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[allow(clippy::too_many_lines)]
+fn lintcheck(config: LintcheckConfig) {
+    let (clippy_ver, clippy_driver_path) = if let Some(driver) = &config.driver {
+        let path = fs::canonicalize(driver)
+            .unwrap_or_else(|e| panic!("clippy-driver binary not found! {}: {e}", driver.display()));
+        (driver_version(&path), path)
+    } else {
+        let clippy_ver = build_clippy(config.perf);
+        let clippy_driver_path = fs::canonicalize(format!(
+            "{}/{}/clippy-driver{EXE_SUFFIX}",
+            target_dir(),
+            if config.perf { "release" } else { "debug" }
+        ))
+        .unwrap();
+
+        // assert that clippy is found
+        assert!(
+            clippy_driver_path.is_file(),
+            "{}/{}/clippy-driver binary not found! {}",
+            target_dir(),
+            if config.perf { "release" } else { "debug" },
+            clippy_driver_path.display()
+        );
+        (clippy_ver, clippy_driver_path)
+    };
 
     // download and extract the crates, then run clippy on them and collect clippy's warnings
     // flatten into one big list of warnings
@@ -398,6 +438,23 @@ fn lintcheck(config: LintcheckConfig) {
         .build_global()
         .unwrap();
 
+    if let Some(old_driver) = &config.compare {
+        let old_driver_path = fs::canonicalize(old_driver)
+            .unwrap_or_else(|e| panic!("clippy-driver binary not found! {}: {e}", old_driver.display()));
+        let report = compare::compare(
+            &crates,
+            (&old_driver_path, &driver_version(&old_driver_path)),
+            (&clippy_driver_path, &clippy_ver),
+            &config,
+            &lint_level_args,
+        );
+
+        println!("Writing report to {}", config.lintcheck_results_path.display());
+        fs::create_dir_all(config.lintcheck_results_path.parent().unwrap()).unwrap();
+        fs::write(&config.lintcheck_results_path, report).unwrap();
+        return;
+    }
+
     let server = config.recursive.then(|| {
         let _: io::Result<()> = fs::remove_dir_all(format!("{}/lintcheck/shared_target_dir/recursive", target_dir()));
 
@@ -409,6 +466,7 @@ fn lintcheck(config: LintcheckConfig) {
         .flat_map(|krate| {
             krate.run_clippy_lints(
                 &clippy_driver_path,
+                "",
                 &counter,
                 crates.len(),
                 &config,
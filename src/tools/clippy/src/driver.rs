@@ -10,6 +10,7 @@
 // (Currently there is no way to opt into sysroot crates without `extern crate`.)
 extern crate rustc_driver;
 extern crate rustc_interface;
+extern crate rustc_middle;
 extern crate rustc_session;
 extern crate rustc_span;
 
@@ -20,15 +21,18 @@
 
 use clippy_utils::sym;
 use declare_clippy_lint::LintListBuilder;
+use rustc_driver::Compilation;
 use rustc_interface::interface;
+use rustc_middle::ty::TyCtxt;
 use rustc_session::EarlyDiagCtxt;
 use rustc_session::config::ErrorOutputType;
+use rustc_session::lint::Level;
 use rustc_session::parse::ParseSess;
 use rustc_span::symbol::Symbol;
 
 use std::env;
 use std::fs::read_to_string;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::exit;
 
 use anstream::println;
@@ -128,8 +132,18 @@ fn config(&mut self, config: &mut interface::Config) {
     }
 }
 
+/// The phases of `cargo clippy --workspace-lints`, with the directory containing the summaries of
+/// the workspace crates.
+enum WorkspaceLints {
+    /// Write a summary of the crate, without linting it.
+    Collect(PathBuf),
+    /// Lint the crate, including the workspace lints.
+    Check(PathBuf),
+}
+
 struct ClippyCallbacks {
     clippy_args_var: Option<String>,
+    workspace_lints: Option<WorkspaceLints>,
 }
 
 impl rustc_driver::Callbacks for ClippyCallbacks {
@@ -139,6 +153,11 @@ fn config(&mut self, config: &mut interface::Config) {
         let conf_path = clippy_config::lookup_conf_file();
         let previous = config.register_lints.take();
         let clippy_args_var = self.clippy_args_var.take();
+        let (collect_summary, summary_dir) = match &self.workspace_lints {
+            Some(WorkspaceLints::Collect(dir)) => (true, Some(dir.clone())),
+            Some(WorkspaceLints::Check(dir)) => (false, Some(dir.clone())),
+            None => (false, None),
+        };
         config.psess_created = Some(Box::new(move |psess| {
             track_clippy_args(psess, clippy_args_var.as_deref());
             track_files(psess);
@@ -161,8 +180,16 @@ fn config(&mut self, config: &mut interface::Config) {
             list_builder.insert(clippy_lints::declared_lints::LINTS);
             list_builder.register(lint_store);
 
+            // The crate is only linted once all summaries are written.
+            if collect_summary {
+                return;
+            }
+
             let conf = clippy_config::Conf::read(sess, &conf_path);
             clippy_lints::register_lint_passes(lint_store, conf);
+            if let Some(summary_dir) = &summary_dir {
+                clippy_lints::register_workspace_lint_pass(lint_store, summary_dir.clone());
+            }
 
             #[cfg(feature = "internal")]
             clippy_lints_internal::register_lints(lint_store);
@@ -179,6 +206,23 @@ fn config(&mut self, config: &mut interface::Config) {
 
         // Disable flattening and inlining of format_args!(), so the HIR matches with the AST.
         config.opts.unstable_opts.flatten_format_args = false;
+
+        // Warnings are emitted when the crate is linted in the second phase.
+        if collect_summary {
+            config.opts.lint_cap = Some(Level::Allow);
+        }
+    }
+
+    fn after_analysis<'tcx>(&mut self, _: &interface::Compiler, tcx: TyCtxt<'tcx>) -> Compilation {
+        if let Some(WorkspaceLints::Collect(summary_dir)) = &self.workspace_lints
+            && let Err(e) = clippy_lints::write_workspace_summary(tcx, summary_dir)
+        {
+            tcx.dcx().fatal(format!(
+                "could not write the workspace summary to `{}`: {e}",
+                summary_dir.display()
+            ));
+        }
+        Compilation::Continue
     }
 }
 
@@ -305,6 +349,7 @@ pub fn main() {
         pass_sysroot_env_if_given(&mut args, sys_root_env);
 
         let mut no_deps = false;
+        let mut workspace_lints = None;
         let clippy_args_var = env::var("CLIPPY_ARGS").ok();
         let clippy_args = clippy_args_var
             .as_deref()
@@ -316,7 +361,18 @@ pub fn main() {
                     no_deps = true;
                     None
                 },
-                _ => Some(s.to_string()),
+                _ => {
+                    // Set by `cargo clippy --workspace-lints`
+                    if let Some(dir) = s.strip_prefix("--collect-workspace-summary=") {
+                        workspace_lints = Some(WorkspaceLints::Collect(dir.into()));
+                        None
+                    } else if let Some(dir) = s.strip_prefix("--check-workspace-summaries=") {
+                        workspace_lints = Some(WorkspaceLints::Check(dir.into()));
+                        None
+                    } else {
+                        Some(s.to_string())
+                    }
+                },
             })
             .chain(vec!["--cfg".into(), "clippy".into()])
             .collect::<Vec<String>>();
@@ -335,7 +391,13 @@ pub fn main() {
         let clippy_enabled = !cap_lints_allow && relevant_package && !info_query;
         if clippy_enabled {
             args.extend(clippy_args);
-            rustc_driver::run_compiler(&args, &mut ClippyCallbacks { clippy_args_var });
+            rustc_driver::run_compiler(
+                &args,
+                &mut ClippyCallbacks {
+                    clippy_args_var,
+                    workspace_lints,
+                },
+            );
         } else {
             rustc_driver::run_compiler(&args, &mut RustcCallbacks { clippy_args_var });
         }
@@ -4,9 +4,9 @@
 // warn on lints, that are included in `rust-lang/rust`s bootstrap
 #![warn(rust_2018_idioms, unused_lifetimes)]
 
-use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{self, Command};
+use std::{env, fs};
 
 use anstream::println;
 
@@ -50,10 +50,12 @@ pub fn main() {
     }
 }
 
+#[derive(Clone)]
 struct ClippyCmd {
     cargo_subcommand: &'static str,
     args: Vec<String>,
     clippy_args: Vec<String>,
+    workspace_lints: bool,
 }
 
 impl ClippyCmd {
@@ -64,6 +66,7 @@ fn new<I>(mut old_args: I) -> Self
         let mut cargo_subcommand = "check";
         let mut args = vec![];
         let mut clippy_args: Vec<String> = vec![];
+        let mut workspace_lints = false;
 
         for arg in old_args.by_ref() {
             match arg.as_str() {
@@ -75,6 +78,10 @@ fn new<I>(mut old_args: I) -> Self
                     clippy_args.push("--no-deps".into());
                     continue;
                 },
+                "--workspace-lints" => {
+                    workspace_lints = true;
+                    continue;
+                },
                 "--" => break,
                 _ => {},
             }
@@ -91,6 +98,7 @@ fn new<I>(mut old_args: I) -> Self
             cargo_subcommand,
             args,
             clippy_args,
+            workspace_lints,
         }
     }
 
@@ -106,6 +114,12 @@ fn path() -> PathBuf {
         path
     }
 
+    /// Passes the directory containing the summaries of the workspace crates to `clippy-driver`.
+    fn with_summary_dir(mut self, flag: &str, summary_dir: &Path) -> Self {
+        self.clippy_args.push(format!("{flag}={}", summary_dir.display()));
+        self
+    }
+
     fn into_std_cmd(self) -> Command {
         let mut cmd = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".into()));
         let clippy_args: String = self
@@ -132,8 +146,38 @@ fn process<I>(old_args: I) -> Result<(), i32>
 {
     let cmd = ClippyCmd::new(old_args);
 
-    let mut cmd = cmd.into_std_cmd();
+    if cmd.workspace_lints {
+        return process_workspace_lints(cmd);
+    }
+
+    run(cmd.into_std_cmd())
+}
+
+/// Runs Clippy twice for `--workspace-lints`: first to write a summary of every crate of the
+/// workspace, then to lint the crates, including the lints reading the summaries of all crates.
+fn process_workspace_lints(cmd: ClippyCmd) -> Result<(), i32> {
+    let summary_dir = env::temp_dir().join(format!("clippy-workspace-lints-{}", process::id()));
+    if let Err(e) = fs::create_dir_all(&summary_dir) {
+        eprintln!("error: could not create `{}`: {e}", summary_dir.display());
+        return Err(1);
+    }
 
+    let result = run(cmd
+        .clone()
+        .with_summary_dir("--collect-workspace-summary", &summary_dir)
+        .into_std_cmd())
+    .and_then(|()| {
+        run(cmd
+            .with_summary_dir("--check-workspace-summaries", &summary_dir)
+            .into_std_cmd())
+    });
+
+    // The summaries are only valid for this run.
+    let _ = fs::remove_dir_all(&summary_dir);
+    result
+}
+
+fn run(mut cmd: Command) -> Result<(), i32> {
     let exit_status = cmd
         .spawn()
         .expect("could not run cargo")
@@ -158,6 +202,7 @@ pub fn help_message() -> &'static str {
 <green,bold>Common options:</>
     <cyan,bold>--no-deps</>                Run Clippy only on the given crate, without linting the dependencies
     <cyan,bold>--fix</>                    Automatically apply lint suggestions. This flag implies <cyan>--no-deps</> and <cyan>--all-targets</>
+    <cyan,bold>--workspace-lints</>        Also run the lints that need to know all crates of the workspace, by checking it twice
     <cyan,bold>-h</>, <cyan,bold>--help</>               Print this message
     <cyan,bold>-V</>, <cyan,bold>--version</>            Print version info and exit
     <cyan,bold>--explain [LINT]</>         Print the documentation for a given lint
@@ -212,6 +257,17 @@ fn no_deps_not_duplicated_with_fix() {
         assert_eq!(cmd.clippy_args.iter().filter(|arg| *arg == "--no-deps").count(), 1);
     }
 
+    #[test]
+    fn workspace_lints() {
+        let args = "cargo clippy --workspace-lints -- -W clippy::unused_pub_fn"
+            .split_whitespace()
+            .map(ToString::to_string);
+        let cmd = ClippyCmd::new(args);
+        assert!(cmd.workspace_lints);
+        assert!(!cmd.args.iter().any(|arg| arg == "--workspace-lints"));
+        assert_eq!(cmd.clippy_args, ["-W", "clippy::unused_pub_fn"]);
+    }
+
     #[test]
     fn check() {
         let args = "cargo clippy".split_whitespace().map(ToString::to_string);
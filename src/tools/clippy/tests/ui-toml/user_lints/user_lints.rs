@@ -0,0 +1,60 @@
+#![warn(clippy::user_lints)]
+#![allow(dead_code, clippy::double_must_use, clippy::unnecessary_literal_unwrap)]
+
+#[derive(Debug)]
+struct HandleError;
+
+struct Handle(u32);
+
+impl Handle {
+    fn new(id: u32) -> Self {
+        //~^ user_lints
+        Self(id)
+    }
+
+    fn try_new(id: u32) -> Result<Self, HandleError> {
+        //~^ user_lints
+        if id == 0 {
+            return Err(HandleError);
+        }
+        Ok(Self::new(id))
+        //~^ user_lints
+    }
+}
+
+mod registry {
+    use super::Handle;
+
+    pub struct Registry;
+
+    impl Registry {
+        pub fn handle(&self, id: u32) -> Handle {
+            Handle::new(id)
+        }
+    }
+}
+
+fn open(id: u32) -> Handle {
+    //~^ user_lints
+    Handle::new(id)
+    //~^ user_lints
+}
+
+#[must_use]
+fn open_registered(registry: &registry::Registry, id: u32) -> Option<Handle> {
+    Some(registry.handle(id))
+}
+
+#[must_use]
+fn open_checked(id: u32) -> Result<Handle, HandleError> {
+    Handle::try_new(id)
+}
+
+fn main() {
+    let _ = open(1);
+    let _ = open_registered(&registry::Registry, 1);
+    let _ = open_checked(1).unwrap();
+    //~^ user_lints
+    let _ = open_checked(1).map_err(|_| ()).unwrap();
+    let _ = Some(1).unwrap();
+}
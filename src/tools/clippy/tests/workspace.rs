@@ -143,3 +143,57 @@ fn test_no_deps_ignores_path_deps_in_workspaces() {
     // Make sure Cargo is aware of the new `--cfg` flag.
     lint_path_dep();
 }
+
+#[test]
+fn test_workspace_lints() {
+    if IS_RUSTC_TEST_SUITE {
+        return;
+    }
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let target_dir = root.join("target").join("workspace_test");
+    let cwd = root.join("tests/workspace_test");
+
+    let clippy = |workspace_lints: bool| {
+        let mut command = Command::new(&*CARGO_CLIPPY_PATH);
+        command
+            .current_dir(&cwd)
+            .env("CARGO_INCREMENTAL", "0")
+            .env("CARGO_TARGET_DIR", &target_dir)
+            .arg("clippy")
+            .arg("--all-targets")
+            .args(["-p", "workspace_lints_app"])
+            .args(["-p", "workspace_lints_library"]);
+        if workspace_lints {
+            command.arg("--workspace-lints");
+        }
+        let output = command
+            .arg("--")
+            .arg("-Cdebuginfo=0") // disable debuginfo to generate less data in the target dir
+            .output()
+            .unwrap();
+        println!("status: {}", output.status);
+        println!("stdout: {}", String::from_utf8_lossy(&output.stdout));
+        println!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+        assert!(output.status.success());
+        String::from_utf8(output.stderr).unwrap()
+    };
+
+    // The workspace lints only run with `--workspace-lints`.
+    let stderr = clippy(false);
+    assert!(!stderr.contains("in the workspace"));
+    assert!(!stderr.contains("only used by its own crate"));
+
+    let stderr = clippy(true);
+    assert!(stderr.contains("warning: public function is never used in the workspace"));
+    assert!(stderr.contains("pub fn unused() {}"));
+    assert!(stderr.contains("warning: item is public, but only used by its own crate"));
+    assert!(stderr.contains("pub fn used_by_library() {}"));
+    assert!(!stderr.contains("pub fn used_by_app()"));
+    // Unit tests of the library belong to its crate, unit tests of a binary with the same name don't.
+    assert!(stderr.contains("pub fn used_by_library_tests() {}"));
+    assert!(!stderr.contains("pub fn used_by_binary_tests()"));
+    assert!(stderr.contains("warning: feature `unused` is never enabled in the workspace"));
+    assert!(!stderr.contains("feature `used`"));
+    // The crates are not linted while collecting the summaries.
+    assert_eq!(stderr.matches("warning: feature `unused`").count(), 1);
+}
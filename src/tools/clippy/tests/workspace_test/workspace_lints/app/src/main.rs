@@ -0,0 +1,3 @@
+fn main() {
+    workspace_lints_library::used_by_app();
+}
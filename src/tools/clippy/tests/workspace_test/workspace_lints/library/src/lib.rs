@@ -0,0 +1,21 @@
+#![warn(clippy::unused_pub_fn, clippy::unnecessary_pub, clippy::unused_cargo_feature)]
+
+pub fn used_by_app() {
+    used_by_library();
+}
+
+pub fn used_by_library() {}
+
+pub fn unused() {}
+
+pub fn used_by_library_tests() {}
+
+pub fn used_by_binary_tests() {}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn uses() {
+        super::used_by_library_tests();
+    }
+}
@@ -0,0 +1,9 @@
+fn main() {}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn uses() {
+        workspace_lints_library::used_by_binary_tests();
+    }
+}
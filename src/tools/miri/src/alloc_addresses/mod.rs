@@ -275,6 +275,20 @@ fn alloc_id_from_addr(
         }
     }
 
+    /// Returns the live allocation containing `addr`, and the offset of `addr` within it. Unlike
+    /// `alloc_id_from_addr`, this ignores whether the allocation has been exposed; it is meant for
+    /// inspecting memory from outside the program. Zero-sized allocations, like functions, contain
+    /// their base address.
+    fn alloc_containing_addr(&self, addr: u64) -> Option<(AllocId, Size)> {
+        let this = self.eval_context_ref();
+        let global_state = this.machine.alloc_addresses.borrow();
+        let pos = global_state.int_to_ptr_map.partition_point(|&(base, _)| base <= addr);
+        let (base, alloc_id) = *global_state.int_to_ptr_map[..pos].last()?;
+        let offset = addr - base;
+        let size = this.get_alloc_info(alloc_id).size;
+        (offset < max(size.bytes(), 1)).then(|| (alloc_id, Size::from_bytes(offset)))
+    }
+
     /// Returns the base address of an allocation, or an error if no base address could be found
     ///
     /// # Panics
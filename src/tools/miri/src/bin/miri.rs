@@ -676,6 +676,22 @@ fn main() {
             } else {
                 fatal_error!("-Zmiri-native-lib `{}` does not exist", filename);
             }
+        } else if arg == "-Zmiri-vfs" {
+            miri_config.vfs = true;
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-vfs-seed=") {
+            let dir = PathBuf::from(param);
+            if !dir.is_dir() {
+                fatal_error!("-Zmiri-vfs-seed `{}` is not a directory", param);
+            }
+            miri_config.vfs = true;
+            miri_config.vfs_seed = Some(dir);
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-gdbserver=") {
+            miri_config.gdbserver = Some(match param.parse::<u16>() {
+                Ok(port) => miri::GdbServerAddress::Tcp(port),
+                Err(_) => miri::GdbServerAddress::Unix(PathBuf::from(param)),
+            });
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-coverage=") {
+            miri_config.coverage = Some(PathBuf::from(param));
         } else if arg == "-Zmiri-native-lib-enable-tracing" {
             miri_config.native_lib_enable_tracing = true;
         } else if let Some(param) = arg.strip_prefix("-Zmiri-num-cpus=") {
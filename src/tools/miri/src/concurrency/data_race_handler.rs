@@ -1,8 +1,10 @@
+use std::cell::RefCell;
 use std::rc::Rc;
 
+use super::vector_clock::VClock;
 use super::{data_race, weak_memory};
 use crate::concurrency::GenmcCtx;
-use crate::{VisitProvenance, VisitWith};
+use crate::*;
 
 pub enum GlobalDataRaceHandler {
     /// No data race detection will be done.
@@ -89,3 +91,61 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
         }
     }
 }
+
+/// The clock attached to an address by the `miri_release` family of annotations.
+/// Contains the joined clocks of all threads that released the address so far.
+#[derive(Default, Clone, Debug)]
+struct SyncAnnotation(Rc<RefCell<VClock>>);
+
+impl<'tcx> EvalContextExt<'tcx> for MiriInterpCx<'tcx> {}
+pub trait EvalContextExt<'tcx>: MiriInterpCxExt<'tcx> {
+    /// Implements `miri_acquire`: the current thread synchronizes with every `miri_release` of
+    /// `ptr` that happened before, like an acquire load reading from a release store would.
+    fn annotate_acquire(&mut self, ptr: Pointer, name: &str) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        let Some(annotation) = this.get_sync_annotation(ptr, name)? else {
+            return interp_ok(());
+        };
+        let data_race = this.machine.data_race.as_vclocks_ref().unwrap();
+        data_race.acquire_clock(&annotation.0.borrow(), &this.machine.threads);
+        interp_ok(())
+    }
+
+    /// Implements `miri_release`: every later `miri_acquire` of `ptr` synchronizes with the
+    /// current thread at this point. Releases by different threads accumulate.
+    fn annotate_release(&mut self, ptr: Pointer, name: &str) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        let Some(annotation) = this.get_sync_annotation(ptr, name)? else {
+            return interp_ok(());
+        };
+        let data_race = this.machine.data_race.as_vclocks_ref().unwrap();
+        data_race
+            .release_clock(&this.machine.threads, |clock| annotation.0.borrow_mut().join(clock));
+        interp_ok(())
+    }
+}
+
+impl<'tcx> EvalContextPrivExt<'tcx> for MiriInterpCx<'tcx> {}
+trait EvalContextPrivExt<'tcx>: MiriInterpCxExt<'tcx> {
+    /// Returns the annotation clock of the given address, or `None` if data race detection is
+    /// disabled and the annotations have no effect.
+    fn get_sync_annotation(
+        &mut self,
+        ptr: Pointer,
+        name: &str,
+    ) -> InterpResult<'tcx, Option<SyncAnnotation>> {
+        let this = self.eval_context_mut();
+        match &this.machine.data_race {
+            GlobalDataRaceHandler::None => return interp_ok(None),
+            GlobalDataRaceHandler::Genmc(_) =>
+                throw_unsup_format!("`{name}` is not supported in GenMC mode"),
+            GlobalDataRaceHandler::Vclocks(_) => {}
+        }
+        // Like futexes, annotations are tied to the allocation, so they disappear when the
+        // memory is freed and are not inherited by a new allocation at the same address.
+        let Some(annotation) = this.get_sync_or_init(ptr, |_| SyncAnnotation::default()) else {
+            throw_unsup_format!("`{name}` must be passed a pointer to mutable memory, got {ptr:?}");
+        };
+        interp_ok(Some(annotation.clone()))
+    }
+}
@@ -1,6 +1,6 @@
 pub mod cpu_affinity;
 pub mod data_race;
-mod data_race_handler;
+pub mod data_race_handler;
 pub mod init_once;
 pub mod sync;
 pub mod thread;
@@ -110,6 +110,8 @@ pub enum BlockReason {
     Eventfd,
     /// Blocked on unnamed_socket.
     UnnamedSocket,
+    /// Blocked on a socket.
+    Socket,
 }
 
 /// The state of a thread.
@@ -1224,6 +1226,9 @@ fn run_threads(&mut self) -> InterpResult<'tcx, !> {
             }
             match this.schedule()? {
                 SchedulingAction::ExecuteStep => {
+                    if this.machine.gdbserver.is_some() {
+                        this.gdbserver_before_step()?;
+                    }
                     if !this.step()? {
                         // See if this thread can do something else.
                         match this.run_on_stack_empty()? {
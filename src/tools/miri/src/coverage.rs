@@ -0,0 +1,154 @@
+//! Code coverage for `-Zmiri-coverage`: we count how often each basic block of each function in a
+//! local crate gets executed, and write the result as an lcov tracefile. That is also what
+//! `llvm-cov export -format=lcov` produces for `-C instrument-coverage` builds, so reports from
+//! Miri and from native runs can be merged with the usual lcov tooling.
+//!
+//! Basic blocks are mapped to source lines via the spans of their statements and terminators. A
+//! line counts as executed as often as the most frequently executed block on it. Cleanup blocks are
+//! ignored, like they are for `-C instrument-coverage`.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+use either::Either;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def_id::DefId;
+use rustc_index::IndexVec;
+use rustc_middle::mir::{self, BasicBlock};
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_span::{FileName, Span};
+
+use crate::*;
+
+pub struct Coverage {
+    /// Where to write the report. `%p` is replaced by the process ID.
+    path: PathBuf,
+    /// How often each basic block was executed, for every function that was called at all.
+    counts: FxHashMap<DefId, IndexVec<BasicBlock, u64>>,
+}
+
+/// The coverage of a single source file.
+#[derive(Default)]
+struct FileCoverage {
+    /// The first line, name, and call count of each function.
+    functions: Vec<(usize, String, u64)>,
+    /// The execution count of each line with code on it.
+    lines: BTreeMap<usize, u64>,
+}
+
+impl Coverage {
+    pub fn new(path: PathBuf) -> Self {
+        Coverage { path, counts: FxHashMap::default() }
+    }
+
+    /// Writes the report. It covers all functions that were executed, as well as all functions of
+    /// the crate being run, even if they were never executed.
+    pub fn write_report(&self, tcx: TyCtxt<'_>) -> io::Result<()> {
+        let mut functions = tcx
+            .mir_keys(())
+            .iter()
+            .map(|def_id| def_id.to_def_id())
+            .filter(|&def_id| tcx.def_kind(def_id).is_fn_like())
+            .collect::<Vec<_>>();
+        functions.extend(self.counts.keys().filter(|def_id| !def_id.is_local()));
+
+        let mut files = BTreeMap::<PathBuf, FileCoverage>::new();
+        for def_id in functions {
+            let counts = self.counts.get(&def_id);
+            let count = |block: BasicBlock| counts.map_or(0, |counts| counts[block]);
+            if let Some((file, line)) = source_line(tcx, tcx.def_span(def_id)) {
+                // Unlike `def_path_str`, this never contains commas, which lcov cannot handle.
+                let name = format!(
+                    "{}{}",
+                    tcx.crate_name(def_id.krate),
+                    tcx.def_path(def_id).to_string_no_crate_verbose()
+                );
+                let calls = count(mir::START_BLOCK);
+                files.entry(file).or_default().functions.push((line, name, calls));
+            }
+            let body = tcx.instance_mir(ty::InstanceKind::Item(def_id));
+            for (block, data) in body.basic_blocks.iter_enumerated() {
+                if data.is_cleanup {
+                    continue;
+                }
+                let spans =
+                    data.statements.iter().map(|stmt| stmt.source_info.span).chain(
+                        data.terminator.iter().map(|terminator| terminator.source_info.span),
+                    );
+                for span in spans {
+                    if let Some((file, line)) = source_line(tcx, span) {
+                        let lines = &mut files.entry(file).or_default().lines;
+                        let line_count = lines.entry(line).or_default();
+                        *line_count = (*line_count).max(count(block));
+                    }
+                }
+            }
+        }
+
+        let path = match self.path.to_str() {
+            Some(path) if path.contains("%p") =>
+                PathBuf::from(path.replace("%p", &std::process::id().to_string())),
+            _ => self.path.clone(),
+        };
+        let mut out = BufWriter::new(File::create(path)?);
+        for (file, coverage) in &files {
+            writeln!(out, "SF:{}", file.display())?;
+            for (line, name, _) in &coverage.functions {
+                writeln!(out, "FN:{line},{name}")?;
+            }
+            for (_, name, calls) in &coverage.functions {
+                writeln!(out, "FNDA:{calls},{name}")?;
+            }
+            let functions_hit = coverage.functions.iter().filter(|(_, _, calls)| *calls > 0);
+            writeln!(out, "FNF:{}", coverage.functions.len())?;
+            writeln!(out, "FNH:{}", functions_hit.count())?;
+            for (line, count) in &coverage.lines {
+                writeln!(out, "DA:{line},{count}")?;
+            }
+            writeln!(out, "LF:{}", coverage.lines.len())?;
+            writeln!(out, "LH:{}", coverage.lines.values().filter(|&&count| count > 0).count())?;
+            writeln!(out, "end_of_record")?;
+        }
+        out.flush()
+    }
+}
+
+/// Returns the file and line where `span` starts, looking through macro expansions. Paths are
+/// made absolute, like in the reports of `llvm-cov`.
+fn source_line(tcx: TyCtxt<'_>, span: Span) -> Option<(PathBuf, usize)> {
+    let span = span.source_callsite();
+    if span.is_dummy() {
+        return None;
+    }
+    let loc = tcx.sess.source_map().lookup_char_pos(span.lo());
+    let FileName::Real(name) = &loc.file.name else {
+        return None;
+    };
+    let working_dir = tcx.sess.opts.working_dir.local_path_if_available();
+    Some((working_dir.join(name.local_path_if_available()), loc.line))
+}
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+pub trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Records that the current basic block of the active thread is being executed.
+    fn record_coverage(&mut self) {
+        let this = self.eval_context_mut();
+        let frame = this.frame();
+        let ty::InstanceKind::Item(def_id) = frame.instance().def else {
+            // Shims do not correspond to any source code.
+            return;
+        };
+        if !(def_id.is_local() || this.machine.local_crates.contains(&def_id.krate)) {
+            return;
+        }
+        let Either::Left(loc) = frame.current_loc() else {
+            return;
+        };
+        let blocks = frame.body().basic_blocks.len();
+        let coverage = this.machine.coverage.as_mut().unwrap();
+        coverage.counts.entry(def_id).or_insert_with(|| IndexVec::from_elem_n(0, blocks))
+            [loc.block] += 1;
+    }
+}
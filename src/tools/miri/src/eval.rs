@@ -172,6 +172,14 @@ pub struct MiriConfig {
     pub float_nondet: bool,
     /// Whether floating-point operations can have a non-deterministic rounding error.
     pub float_rounding_error: bool,
+    /// Whether to use an in-memory file system instead of the host file system.
+    pub vfs: bool,
+    /// The host directory to copy into the in-memory file system on startup.
+    pub vfs_seed: Option<PathBuf>,
+    /// Where to wait for a debugger to connect, if at all.
+    pub gdbserver: Option<GdbServerAddress>,
+    /// Where to write the coverage report, if at all.
+    pub coverage: Option<PathBuf>,
 }
 
 impl Default for MiriConfig {
@@ -214,6 +222,10 @@ fn default() -> MiriConfig {
             force_intrinsic_fallback: false,
             float_nondet: true,
             float_rounding_error: true,
+            vfs: false,
+            vfs_seed: None,
+            gdbserver: None,
+            coverage: None,
         }
     }
 }
@@ -529,6 +541,15 @@ pub fn eval_entry<'tcx>(
     // (but that "error" might be just "regular program termination").
     let Err(err) = res.report_err();
 
+    // Give the debugger a chance to look at the program state before we report the error.
+    ecx.gdbserver_report_termination(err.kind());
+
+    if let Some(coverage) = &ecx.machine.coverage
+        && let Err(err) = coverage.write_report(tcx)
+    {
+        tcx.dcx().err(format!("failed to write the coverage report: {err}"));
+    }
+
     // Show diagnostic, if any.
     let (return_code, leak_check) = report_error(&ecx, err)?;
 
@@ -0,0 +1,870 @@
+//! A server for the GDB remote serial protocol, so that a debugger can attach to the interpreted
+//! program when `-Zmiri-gdbserver` is set.
+//!
+//! The debugger sees a process whose threads are the threads of the interpreted program. It can
+//! interrupt and continue execution, single-step one MIR statement or terminator at a time, and
+//! read memory at the addresses Miri assigned to allocations. It can also insert breakpoints at
+//! the addresses Miri assigned to functions (e.g. `break *0x1234` in GDB, using an address shown
+//! by `monitor alloc` or printed by the program), which stop whenever that function is called.
+//!
+//! There are no machine registers to show, and the debugger only knows how to describe and unwind
+//! the frames of real architectures, so register reads report a single unavailable register. The
+//! debugger also has no debug info for the interpreted program, so it cannot resolve functions or
+//! source lines to addresses by itself. Everything that is specific to the interpreter (the
+//! interpreter stack, locals via MIR debuginfo, and breakpoints on functions and source lines) is
+//! therefore exposed via `monitor` commands.
+//!
+//! When the program runs into an error, the debugger gets to inspect the state at the point of
+//! the error before Miri prints its diagnostic.
+
+mod protocol;
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Write as _};
+use std::io;
+use std::net::{Ipv4Addr, TcpListener};
+use std::path::{Path, PathBuf};
+
+use either::Either;
+use rustc_abi::Size;
+use rustc_hir::def_id::DefId;
+use rustc_middle::mir;
+use rustc_middle::ty::TyCtxt;
+
+use self::protocol::{Connection, Incoming, Stream, decode_hex, encode_hex, parse_hex};
+use crate::*;
+
+/// The signals we report to the debugger as the reason for a stop.
+const SIGINT: u8 = 2;
+const SIGTRAP: u8 = 5;
+const SIGABRT: u8 = 6;
+const SIGSEGV: u8 = 11;
+
+/// How many steps we take between checks whether the debugger wants to interrupt the program.
+const INTERRUPT_POLL_INTERVAL: u32 = 1024;
+
+/// The reply to register reads. We have no registers, so we report one unavailable register.
+const UNAVAILABLE_REGISTER: &str = "xxxxxxxxxxxxxxxx";
+
+const MONITOR_HELP: &str = "\
+Miri supports the following monitor commands:
+  backtrace              show the stack of the selected thread
+  frame <n>              select frame <n> of the selected thread
+  locals                 show the local variables of the selected frame
+  break <function>       stop whenever <function> is called
+  break <file>:<line>    stop whenever execution reaches <line> of <file>
+  breakpoints            list all breakpoints
+  delete <n>             delete breakpoint <n>
+  alloc <address>        show the allocation containing <address>
+";
+
+/// Where to wait for the debugger to connect.
+#[derive(Clone, Debug)]
+pub enum GdbServerAddress {
+    /// A TCP port on the loopback interface.
+    Tcp(u16),
+    /// A Unix domain socket.
+    Unix(PathBuf),
+}
+
+impl fmt::Display for GdbServerAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GdbServerAddress::Tcp(port) => write!(f, "port {port}"),
+            GdbServerAddress::Unix(path) => write!(f, "`{}`", path.display()),
+        }
+    }
+}
+
+enum Breakpoint {
+    Function(String),
+    Line {
+        file: PathBuf,
+        line: usize,
+    },
+    /// A breakpoint the debugger inserted at the address of a function.
+    Address {
+        addr: u64,
+        def_id: DefId,
+        name: String,
+    },
+}
+
+impl Breakpoint {
+    fn parse(spec: &str) -> Option<Breakpoint> {
+        if let Some((file, line)) = spec.rsplit_once(':')
+            && let Ok(line) = line.parse()
+        {
+            return Some(Breakpoint::Line { file: PathBuf::from(file), line });
+        }
+        (!spec.is_empty()).then(|| Breakpoint::Function(spec.to_owned()))
+    }
+
+    fn matches<'tcx>(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        frame: &Frame<'tcx, Provenance, FrameExtra<'tcx>>,
+    ) -> bool {
+        match self {
+            Breakpoint::Function(name) => {
+                // We only want to stop when the function is entered.
+                if frame.current_loc() != Either::Left(mir::Location::START) {
+                    return false;
+                }
+                let path = tcx.def_path_str(frame.instance().def_id());
+                path == *name || path.ends_with(&format!("::{name}"))
+            }
+            Breakpoint::Address { def_id, .. } =>
+                frame.current_loc() == Either::Left(mir::Location::START)
+                    && frame.instance().def_id() == *def_id,
+            Breakpoint::Line { file, line } => {
+                let span = frame.current_span();
+                if span.is_dummy() {
+                    return false;
+                }
+                let loc = tcx.sess.source_map().lookup_char_pos(span.lo());
+                let name = loc.file.name.prefer_remapped_unconditionally().to_string();
+                loc.line == *line && Path::new(&name).ends_with(file)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Breakpoint::Function(name) => write!(f, "function `{name}`"),
+            Breakpoint::Line { file, line } => write!(f, "{}:{line}", file.display()),
+            Breakpoint::Address { addr, name, .. } => write!(f, "{addr:#x} (function `{name}`)"),
+        }
+    }
+}
+
+/// What the debugger wants to happen once it is done inspecting a stopped program.
+enum Outcome {
+    Resume,
+    Kill,
+    Detach,
+}
+
+/// How to respond to a packet from the debugger.
+enum Reply {
+    Packet(String),
+    /// Output of a monitor command.
+    Monitor(String),
+    NoAck,
+    Resume {
+        step: bool,
+    },
+    Kill,
+    Detach,
+}
+
+/// What a packet from the debugger asks us to do.
+#[derive(Debug, PartialEq, Eq)]
+enum Request<'a> {
+    /// `?`
+    StopReason,
+    /// `c`, `s`, and their `vCont` equivalents. We do not support resuming at a different
+    /// address, or delivering signals.
+    Resume {
+        step: bool,
+    },
+    Detach,
+    Kill,
+    /// `g` and `p`.
+    ReadRegisters,
+    /// `G`, `P`, `M`, and `X`. The debugger may not modify the state of the program.
+    Write,
+    ReadMemory {
+        addr: u64,
+        len: u64,
+    },
+    /// `Hg`: which thread's state the debugger wants to inspect.
+    SelectThread(&'a str),
+    /// `Hc`: which thread should continue. Execution always continues on whatever thread the
+    /// scheduler picks.
+    ContinueThread(&'a str),
+    ThreadAlive(&'a str),
+    /// `Z0` and `Z1`: software and hardware breakpoints are the same to us.
+    InsertBreakpoint {
+        addr: u64,
+    },
+    RemoveBreakpoint {
+        addr: u64,
+    },
+    Supported,
+    StartNoAckMode,
+    Attached,
+    CurrentThread,
+    /// `qfThreadInfo`
+    FirstThreadInfo,
+    /// `qsThreadInfo`
+    NextThreadInfo,
+    ThreadExtraInfo(&'a str),
+    Symbol,
+    /// `qRcmd`
+    Monitor(String),
+    /// `vCont?`
+    ResumeActions,
+    /// A request we support, but with arguments we do not understand.
+    Malformed,
+    Unsupported,
+}
+
+impl<'a> Request<'a> {
+    fn parse(packet: &'a str) -> Request<'a> {
+        let Some(kind) = packet.chars().next() else {
+            return Request::Unsupported;
+        };
+        let args = &packet[kind.len_utf8()..];
+        let request = match kind {
+            '?' => Some(Request::StopReason),
+            'c' | 'C' => Some(Request::Resume { step: false }),
+            's' | 'S' => Some(Request::Resume { step: true }),
+            'D' => Some(Request::Detach),
+            'k' => Some(Request::Kill),
+            'g' | 'p' => Some(Request::ReadRegisters),
+            'G' | 'P' | 'M' | 'X' => Some(Request::Write),
+            'm' =>
+                args.split_once(',').and_then(|(addr, len)| {
+                    let addr = parse_hex(addr.as_bytes())?;
+                    let len = parse_hex(len.as_bytes())?;
+                    Some(Request::ReadMemory { addr, len })
+                }),
+            'H' =>
+                match args.split_at_checked(1) {
+                    Some(("g", thread)) => Some(Request::SelectThread(thread)),
+                    Some(("c", thread)) => Some(Request::ContinueThread(thread)),
+                    _ => None,
+                },
+            'T' => Some(Request::ThreadAlive(args)),
+            'Z' | 'z' => {
+                // `Z<type>,<addr>,<kind>`, possibly followed by conditions that we ignore.
+                let mut fields = args.split([',', ';']);
+                if !matches!(fields.next(), Some("0" | "1")) {
+                    // Watchpoints are not supported.
+                    return Request::Unsupported;
+                }
+                fields.next().and_then(|addr| parse_hex(addr.as_bytes())).map(|addr| {
+                    if kind == 'Z' {
+                        Request::InsertBreakpoint { addr }
+                    } else {
+                        Request::RemoveBreakpoint { addr }
+                    }
+                })
+            }
+            'q' | 'Q' | 'v' => return Request::parse_query(packet),
+            _ => return Request::Unsupported,
+        };
+        request.unwrap_or(Request::Malformed)
+    }
+
+    fn parse_query(packet: &'a str) -> Request<'a> {
+        let (name, args) = packet.split_once([':', ',', ';']).unwrap_or((packet, ""));
+        match name {
+            "qSupported" => Request::Supported,
+            "QStartNoAckMode" => Request::StartNoAckMode,
+            "qAttached" => Request::Attached,
+            "qC" => Request::CurrentThread,
+            "qfThreadInfo" => Request::FirstThreadInfo,
+            "qsThreadInfo" => Request::NextThreadInfo,
+            "qThreadExtraInfo" => Request::ThreadExtraInfo(args),
+            "qSymbol" => Request::Symbol,
+            "qRcmd" =>
+                match decode_hex(args) {
+                    Some(command) => Request::Monitor(String::from_utf8_lossy(&command).into()),
+                    None => Request::Malformed,
+                },
+            "vCont?" => Request::ResumeActions,
+            "vCont" =>
+                match args.chars().next() {
+                    Some('c' | 'C') => Request::Resume { step: false },
+                    Some('s' | 'S') => Request::Resume { step: true },
+                    _ => Request::Malformed,
+                },
+            _ => Request::Unsupported,
+        }
+    }
+}
+
+pub struct GdbServer {
+    conn: Connection,
+    /// Whether the debugger let the program run and is waiting to hear about the next stop.
+    running: bool,
+    /// Whether to stop before the next step of the interpreter.
+    stop_at_next_step: bool,
+    /// How many steps we took since we last checked for an interrupt request.
+    steps_since_poll: u32,
+    /// The stop reply describing why the program stopped most recently.
+    last_stop: String,
+    breakpoints: BTreeMap<u32, Breakpoint>,
+    next_breakpoint: u32,
+    /// The thread, stack depth, and breakpoint of the last step that was at a breakpoint. We only
+    /// stop when arriving at a breakpoint, not on every step taken there.
+    last_hit: Option<(ThreadId, usize, u32)>,
+    /// The thread whose state the debugger is inspecting.
+    thread: ThreadId,
+    /// The frame (counted from the innermost one) whose locals the debugger is inspecting.
+    frame: usize,
+}
+
+impl GdbServer {
+    /// Waits for a debugger to connect to `address`.
+    pub fn new(address: &GdbServerAddress) -> io::Result<GdbServer> {
+        let stream: Box<dyn Stream> = match address {
+            GdbServerAddress::Tcp(port) => {
+                let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, *port))?;
+                eprintln!("Waiting for a debugger to connect to {}...", listener.local_addr()?);
+                let (stream, _) = listener.accept()?;
+                stream.set_nodelay(true)?;
+                Box::new(stream)
+            }
+            #[cfg(unix)]
+            GdbServerAddress::Unix(path) => {
+                let listener = std::os::unix::net::UnixListener::bind(path)?;
+                eprintln!("Waiting for a debugger to connect to {}...", path.display());
+                let (stream, _) = listener.accept()?;
+                // Nobody else is going to connect.
+                std::fs::remove_file(path)?;
+                Box::new(stream)
+            }
+            #[cfg(not(unix))]
+            GdbServerAddress::Unix(_) =>
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "Unix domain sockets are only supported on Unix hosts",
+                )),
+        };
+        Ok(GdbServer {
+            conn: Connection::new(stream),
+            running: false,
+            // Give the debugger a chance to set things up before the program starts.
+            stop_at_next_step: true,
+            steps_since_poll: 0,
+            last_stop: format!("S{SIGTRAP:02x}"),
+            breakpoints: BTreeMap::new(),
+            next_breakpoint: 1,
+            last_hit: None,
+            thread: ThreadId::MAIN_THREAD,
+            frame: 0,
+        })
+    }
+}
+
+/// The debugger counts threads starting at 1.
+fn gdb_thread_id(thread: ThreadId) -> u64 {
+    u64::from(thread.to_u32()) + 1
+}
+
+impl<'tcx> EvalContextPrivExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+trait EvalContextPrivExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    fn gdbserver<'a>(&'a mut self) -> &'a mut GdbServer
+    where
+        'tcx: 'a,
+    {
+        self.eval_context_mut().machine.gdbserver.as_mut().unwrap()
+    }
+
+    fn gdbserver_disconnect(&mut self, err: io::Error) {
+        let this = self.eval_context_mut();
+        this.tcx.dcx().warn(format!("lost the connection to the debugger: {err}"));
+        this.machine.gdbserver = None;
+    }
+
+    /// Checks whether the active thread just arrived at a breakpoint.
+    fn gdbserver_breakpoint_hit(&mut self) -> bool {
+        let this = self.eval_context_mut();
+        let server = this.machine.gdbserver.as_ref().unwrap();
+        if server.breakpoints.is_empty() {
+            return false;
+        }
+        let frame = this.frame();
+        let hit = server
+            .breakpoints
+            .iter()
+            .find(|(_, breakpoint)| breakpoint.matches(*this.tcx, frame))
+            .map(|(&id, _)| (this.active_thread(), this.active_thread_stack().len(), id));
+        let server = this.gdbserver();
+        let arrived = hit.is_some() && hit != server.last_hit;
+        server.last_hit = hit;
+        arrived
+    }
+
+    /// Lets the debugger know that the program stopped, and answers its requests until it wants
+    /// the program to continue.
+    fn gdbserver_stop(&mut self, signal: u8) -> io::Result<Outcome> {
+        let this = self.eval_context_mut();
+        let thread = this.active_thread();
+        let server = this.gdbserver();
+        server.last_stop = format!("T{signal:02x}thread:{:x};", gdb_thread_id(thread));
+        server.thread = thread;
+        server.frame = 0;
+        if server.running {
+            server.running = false;
+            server.conn.send(&server.last_stop)?;
+        }
+        loop {
+            let Incoming::Packet(packet) = this.gdbserver().conn.receive()? else {
+                // The program is already stopped.
+                continue;
+            };
+            let reply = this.gdbserver_handle_packet(&String::from_utf8_lossy(&packet));
+            let server = this.gdbserver();
+            match reply {
+                Reply::Packet(reply) => server.conn.send(&reply)?,
+                Reply::Monitor(output) => {
+                    if !output.is_empty() {
+                        server.conn.send(&format!("O{}", encode_hex(output.as_bytes())))?;
+                    }
+                    server.conn.send("OK")?;
+                }
+                Reply::NoAck => {
+                    server.conn.send("OK")?;
+                    server.conn.start_no_ack_mode();
+                }
+                Reply::Resume { step } => {
+                    server.running = true;
+                    server.stop_at_next_step = step;
+                    server.steps_since_poll = 0;
+                    return Ok(Outcome::Resume);
+                }
+                Reply::Kill => return Ok(Outcome::Kill),
+                Reply::Detach => {
+                    server.conn.send("OK")?;
+                    return Ok(Outcome::Detach);
+                }
+            }
+        }
+    }
+
+    fn gdbserver_handle_packet(&mut self, packet: &str) -> Reply {
+        let this = self.eval_context_mut();
+        let reply = match Request::parse(packet) {
+            Request::StopReason => this.gdbserver().last_stop.clone(),
+            Request::Resume { step } => return Reply::Resume { step },
+            Request::Detach => return Reply::Detach,
+            Request::Kill => return Reply::Kill,
+            Request::ReadRegisters => UNAVAILABLE_REGISTER.to_owned(),
+            Request::Write | Request::Malformed => "E01".to_owned(),
+            Request::ReadMemory { addr, len } => this.gdbserver_read_memory(addr, len),
+            Request::SelectThread(thread) =>
+                match this.gdbserver_parse_thread(thread) {
+                    Some(thread) => {
+                        let server = this.gdbserver();
+                        server.thread = thread;
+                        server.frame = 0;
+                        "OK".to_owned()
+                    }
+                    None => "E01".to_owned(),
+                },
+            Request::ContinueThread(thread) | Request::ThreadAlive(thread) =>
+                String::from(if this.gdbserver_parse_thread(thread).is_some() {
+                    "OK"
+                } else {
+                    "E01"
+                }),
+            Request::InsertBreakpoint { addr } => this.gdbserver_insert_breakpoint(addr),
+            Request::RemoveBreakpoint { addr } => {
+                this.gdbserver().breakpoints.retain(|_, breakpoint| {
+                    !matches!(breakpoint, Breakpoint::Address { addr: other, .. } if *other == addr)
+                });
+                "OK".to_owned()
+            }
+            Request::Supported => "PacketSize=4000;QStartNoAckMode+;vContSupported+".to_owned(),
+            Request::StartNoAckMode => return Reply::NoAck,
+            Request::Attached => "1".to_owned(),
+            Request::CurrentThread => format!("QC{:x}", gdb_thread_id(this.active_thread())),
+            Request::FirstThreadInfo => {
+                let threads = this
+                    .machine
+                    .threads
+                    .all_stacks()
+                    .filter(|(_, stack)| !stack.is_empty())
+                    .map(|(thread, _)| format!("{:x}", gdb_thread_id(thread)))
+                    .collect::<Vec<_>>();
+                format!("m{}", threads.join(","))
+            }
+            Request::NextThreadInfo => "l".to_owned(),
+            Request::ThreadExtraInfo(thread) =>
+                match this.gdbserver_parse_thread(thread) {
+                    Some(thread) => {
+                        let name = this.machine.threads.get_thread_display_name(thread);
+                        encode_hex(name.as_bytes())
+                    }
+                    None => "E01".to_owned(),
+                },
+            Request::Symbol => "OK".to_owned(),
+            Request::Monitor(command) => return Reply::Monitor(this.gdbserver_monitor(&command)),
+            Request::ResumeActions => "vCont;c;C;s;S".to_owned(),
+            Request::Unsupported => String::new(),
+        };
+        Reply::Packet(reply)
+    }
+
+    fn gdbserver_add_breakpoint(&mut self, breakpoint: Breakpoint) -> u32 {
+        let server = self.gdbserver();
+        let id = server.next_breakpoint;
+        server.next_breakpoint += 1;
+        server.breakpoints.insert(id, breakpoint);
+        id
+    }
+
+    /// Handles a `Z0` or `Z1` packet. The address must be one that Miri assigned to a function;
+    /// the breakpoint stops whenever that function is called.
+    fn gdbserver_insert_breakpoint(&mut self, addr: u64) -> String {
+        let this = self.eval_context_mut();
+        let instance = match this.alloc_containing_addr(addr) {
+            Some((alloc_id, Size::ZERO)) =>
+                match this.tcx.try_get_global_alloc(alloc_id) {
+                    Some(GlobalAlloc::Function { instance }) => instance,
+                    _ => return "E01".to_owned(),
+                },
+            _ => return "E01".to_owned(),
+        };
+        // The debugger re-inserts its breakpoints every time the program resumes.
+        let exists = this.gdbserver().breakpoints.values().any(
+            |breakpoint| matches!(breakpoint, Breakpoint::Address { addr: other, .. } if *other == addr),
+        );
+        if !exists {
+            let def_id = instance.def_id();
+            let name = this.tcx.def_path_str(def_id);
+            this.gdbserver_add_breakpoint(Breakpoint::Address { addr, def_id, name });
+        }
+        "OK".to_owned()
+    }
+
+    /// Parses a thread ID sent by the debugger. `0` (any thread) and `-1` (all threads) are
+    /// taken to mean the active thread.
+    fn gdbserver_parse_thread(&self, id: &str) -> Option<ThreadId> {
+        let this = self.eval_context_ref();
+        if id == "0" || id == "-1" {
+            return Some(this.active_thread());
+        }
+        let id = parse_hex(id.as_bytes())?.checked_sub(1)?;
+        let thread = this.machine.threads.thread_id_try_from(id).ok()?;
+        (!this.gdbserver_stack(thread).is_empty()).then_some(thread)
+    }
+
+    fn gdbserver_stack(&self, thread: ThreadId) -> &[Frame<'tcx, Provenance, FrameExtra<'tcx>>] {
+        let this = self.eval_context_ref();
+        this.machine
+            .threads
+            .all_stacks()
+            .find(|&(id, _)| id == thread)
+            .map_or(&[], |(_, stack)| stack)
+    }
+
+    /// Handles an `m<addr>,<len>` packet.
+    fn gdbserver_read_memory(&self, addr: u64, len: u64) -> String {
+        let this = self.eval_context_ref();
+        let Some((alloc_id, offset)) = this.alloc_containing_addr(addr) else {
+            return "E14".to_owned();
+        };
+        let Some(alloc) = this.get_alloc_raw(alloc_id).discard_err() else {
+            return "E14".to_owned();
+        };
+        // We only return the part of the range that lies within this allocation. The debugger
+        // asks for the rest separately.
+        let start = offset.bytes_usize();
+        let end = start.saturating_add(usize::try_from(len).unwrap_or(usize::MAX)).min(alloc.len());
+        if start == end {
+            // This is a zero-sized allocation.
+            return "E14".to_owned();
+        }
+        // This does not go through the machine hooks, so that the debugger does not affect the
+        // behavior of the program. The debugger cannot see which bytes are uninitialized.
+        encode_hex(alloc.inspect_with_uninit_and_ptr_outside_interpreter(start..end))
+    }
+
+    fn gdbserver_monitor(&mut self, command: &str) -> String {
+        let this = self.eval_context_mut();
+        let command = command.trim();
+        let (command, arg) =
+            command.split_once(' ').map_or((command, ""), |(command, arg)| (command, arg.trim()));
+        let mut out = String::new();
+        match command {
+            "help" => out.push_str(MONITOR_HELP),
+            "backtrace" | "bt" => {
+                let server = this.machine.gdbserver.as_ref().unwrap();
+                let stack = this.gdbserver_stack(server.thread);
+                for (idx, frame) in stack.iter().rev().enumerate() {
+                    let marker = if idx == server.frame { '*' } else { ' ' };
+                    let desc = this.gdbserver_describe_frame(frame);
+                    writeln!(out, "{marker}#{idx} {desc}").unwrap();
+                }
+            }
+            "frame" => {
+                let stack = this.gdbserver_stack(this.machine.gdbserver.as_ref().unwrap().thread);
+                match arg
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|idx| Some((idx, stack.iter().rev().nth(idx)?)))
+                {
+                    Some((idx, frame)) => {
+                        writeln!(out, "#{idx} {}", this.gdbserver_describe_frame(frame)).unwrap();
+                        this.gdbserver().frame = idx;
+                    }
+                    None => out.push_str("no such frame\n"),
+                }
+            }
+            "locals" => this.gdbserver_locals(&mut out),
+            "break" =>
+                match Breakpoint::parse(arg) {
+                    Some(breakpoint) => {
+                        let desc = breakpoint.to_string();
+                        let id = this.gdbserver_add_breakpoint(breakpoint);
+                        writeln!(out, "breakpoint {id} at {desc}").unwrap();
+                    }
+                    None => out.push_str("usage: break <function> | break <file>:<line>\n"),
+                },
+            "breakpoints" =>
+                for (id, breakpoint) in &this.gdbserver().breakpoints {
+                    writeln!(out, "breakpoint {id} at {breakpoint}").unwrap();
+                },
+            "delete" => {
+                let server = this.gdbserver();
+                if arg.parse().ok().and_then(|id| server.breakpoints.remove(&id)).is_none() {
+                    out.push_str("no such breakpoint\n");
+                }
+            }
+            "alloc" => this.gdbserver_describe_alloc(arg, &mut out),
+            _ => writeln!(out, "unknown command `{command}`; try `monitor help`").unwrap(),
+        }
+        out
+    }
+
+    fn gdbserver_describe_frame(
+        &self,
+        frame: &Frame<'tcx, Provenance, FrameExtra<'tcx>>,
+    ) -> String {
+        let this = self.eval_context_ref();
+        let span = this.tcx.sess.source_map().span_to_embeddable_string(frame.current_span());
+        format!("{} at {span}", frame.instance())
+    }
+
+    fn gdbserver_locals(&self, out: &mut String) {
+        let this = self.eval_context_ref();
+        let server = this.machine.gdbserver.as_ref().unwrap();
+        let Some(frame) = this.gdbserver_stack(server.thread).iter().rev().nth(server.frame) else {
+            return;
+        };
+        for info in &frame.body().var_debug_info {
+            let value = match &info.value {
+                mir::VarDebugInfoContents::Place(place) =>
+                    this.gdbserver_describe_place(frame, *place),
+                mir::VarDebugInfoContents::Const(constant) => constant.to_string(),
+            };
+            writeln!(out, "{} = {value}", info.name).unwrap();
+        }
+    }
+
+    /// Describes the value of a place for the debugger. This does not go through the machine
+    /// hooks, so that the debugger does not affect the behavior of the program.
+    fn gdbserver_describe_place(
+        &self,
+        frame: &Frame<'tcx, Provenance, FrameExtra<'tcx>>,
+        place: mir::Place<'tcx>,
+    ) -> String {
+        let this = self.eval_context_ref();
+        let in_memory = |ptr: Pointer| format!("<in memory at {:#x}>", ptr.addr().bytes());
+        match (frame.locals[place.local].as_mplace_or_imm(), place.projection.as_slice()) {
+            (None, _) => "<dead>".to_owned(),
+            (Some(Either::Left((ptr, _))), []) => in_memory(ptr),
+            (Some(Either::Right(imm)), []) =>
+                match this.layout_of_local(frame, place.local, None).discard_err() {
+                    Some(layout) => ImmTy::from_immediate(imm, layout).to_string(),
+                    None => "<unavailable>".to_owned(),
+                },
+            (
+                Some(Either::Right(Immediate::Scalar(ptr) | Immediate::ScalarPair(ptr, _))),
+                [mir::ProjectionElem::Deref],
+            ) =>
+                match ptr.to_pointer(this).discard_err() {
+                    Some(ptr) => in_memory(ptr),
+                    None => "<unavailable>".to_owned(),
+                },
+            _ => "<unavailable>".to_owned(),
+        }
+    }
+
+    fn gdbserver_describe_alloc(&self, addr: &str, out: &mut String) {
+        let this = self.eval_context_ref();
+        let addr = match addr.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).ok(),
+            None => addr.parse().ok(),
+        };
+        let Some(addr) = addr else {
+            out.push_str("usage: alloc <address>\n");
+            return;
+        };
+        match this.alloc_containing_addr(addr) {
+            Some((alloc_id, _))
+                if let Some(GlobalAlloc::Function { instance }) =
+                    this.tcx.try_get_global_alloc(alloc_id) =>
+                writeln!(out, "{addr:#x} is the address of function `{instance}`").unwrap(),
+            Some((alloc_id, offset)) => {
+                let info = this.get_alloc_info(alloc_id);
+                writeln!(
+                    out,
+                    "{addr:#x} is at offset {} of {alloc_id:?} (size {}, align {}, starting at {:#x})",
+                    offset.bytes(),
+                    info.size.bytes(),
+                    info.align.bytes(),
+                    addr - offset.bytes(),
+                )
+                .unwrap();
+            }
+            None => writeln!(out, "{addr:#x} does not point into a live allocation").unwrap(),
+        }
+    }
+
+    fn gdbserver_report_termination_inner(
+        &mut self,
+        err: &InterpErrorKind<'tcx>,
+    ) -> io::Result<()> {
+        let this = self.eval_context_mut();
+        let info = match err {
+            InterpErrorKind::MachineStop(info) => info.downcast_ref::<TerminationInfo>(),
+            _ => None,
+        };
+        let signal = match info {
+            Some(&TerminationInfo::Exit { code, .. }) => {
+                let server = this.gdbserver();
+                if server.running {
+                    server.conn.send(&format!("W{:02x}", code & 0xff))?;
+                }
+                return Ok(());
+            }
+            Some(TerminationInfo::Interrupted) => {
+                let server = this.gdbserver();
+                if server.running {
+                    server.conn.send(&format!("X{SIGINT:02x}"))?;
+                }
+                return Ok(());
+            }
+            Some(TerminationInfo::Abort(_)) => SIGABRT,
+            _ => SIGSEGV,
+        };
+        let server = this.gdbserver();
+        if server.running {
+            let message = "Miri found an error; it will be reported once the program continues\n";
+            server.conn.send(&format!("O{}", encode_hex(message.as_bytes())))?;
+        }
+        if let Outcome::Resume = this.gdbserver_stop(signal)? {
+            this.gdbserver().conn.send(&format!("X{signal:02x}"))?;
+        }
+        Ok(())
+    }
+}
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+pub trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Called before each step of the active thread, to hand control to the debugger if the
+    /// program should stop here.
+    fn gdbserver_before_step(&mut self) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        if this.active_thread_stack().is_empty() {
+            return interp_ok(());
+        }
+        let hit = this.gdbserver_breakpoint_hit();
+        let server = this.gdbserver();
+        let signal = if server.stop_at_next_step || hit {
+            SIGTRAP
+        } else {
+            server.steps_since_poll += 1;
+            if server.steps_since_poll < INTERRUPT_POLL_INTERVAL {
+                return interp_ok(());
+            }
+            server.steps_since_poll = 0;
+            match server.conn.poll_interrupt() {
+                Ok(true) => SIGINT,
+                Ok(false) => return interp_ok(()),
+                Err(err) => {
+                    this.gdbserver_disconnect(err);
+                    return interp_ok(());
+                }
+            }
+        };
+        match this.gdbserver_stop(signal) {
+            Ok(Outcome::Resume) => {}
+            Ok(Outcome::Detach) => this.machine.gdbserver = None,
+            Ok(Outcome::Kill) => {
+                this.machine.gdbserver = None;
+                this.machine.handle_abnormal_termination();
+                throw_machine_stop!(TerminationInfo::Interrupted);
+            }
+            Err(err) => this.gdbserver_disconnect(err),
+        }
+        interp_ok(())
+    }
+
+    /// Tells the debugger that the program terminated. If it terminated due to an error, the
+    /// debugger gets to inspect the program state before the error is reported.
+    fn gdbserver_report_termination(&mut self, err: &InterpErrorKind<'tcx>) {
+        let this = self.eval_context_mut();
+        if this.machine.gdbserver.is_none() {
+            return;
+        }
+        match this.gdbserver_report_termination_inner(err) {
+            Ok(()) => this.machine.gdbserver = None,
+            Err(err) => this.gdbserver_disconnect(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_request() {
+        assert_eq!(Request::parse("?"), Request::StopReason);
+        assert_eq!(Request::parse("c"), Request::Resume { step: false });
+        assert_eq!(Request::parse("S05"), Request::Resume { step: true });
+        assert_eq!(Request::parse("vCont;s:1"), Request::Resume { step: true });
+        assert_eq!(Request::parse("vCont?"), Request::ResumeActions);
+        assert_eq!(Request::parse("vCont;t"), Request::Malformed);
+        assert_eq!(Request::parse("g"), Request::ReadRegisters);
+        assert_eq!(Request::parse("M1000,1:00"), Request::Write);
+        assert_eq!(Request::parse("m1a0,20"), Request::ReadMemory { addr: 0x1a0, len: 0x20 });
+        assert_eq!(Request::parse("m1a0"), Request::Malformed);
+        assert_eq!(Request::parse("Hg2"), Request::SelectThread("2"));
+        assert_eq!(Request::parse("Hc-1"), Request::ContinueThread("-1"));
+        assert_eq!(Request::parse("Hx1"), Request::Malformed);
+        assert_eq!(Request::parse("T3"), Request::ThreadAlive("3"));
+        assert_eq!(Request::parse("qThreadExtraInfo,2"), Request::ThreadExtraInfo("2"));
+        assert_eq!(Request::parse("qSupported:multiprocess+;swbreak+"), Request::Supported);
+        assert_eq!(Request::parse("qRcmd,6c6f63616c73"), Request::Monitor("locals".to_owned()));
+        assert_eq!(Request::parse("qRcmd,6c6"), Request::Malformed);
+        assert_eq!(Request::parse("vMustReplyEmpty"), Request::Unsupported);
+        assert_eq!(Request::parse(""), Request::Unsupported);
+    }
+
+    #[test]
+    fn parse_breakpoint_request() {
+        assert_eq!(Request::parse("Z0,1a2b,1"), Request::InsertBreakpoint { addr: 0x1a2b });
+        assert_eq!(Request::parse("Z1,1a2b,1;X2,ab"), Request::InsertBreakpoint { addr: 0x1a2b });
+        assert_eq!(Request::parse("z0,1a2b,1"), Request::RemoveBreakpoint { addr: 0x1a2b });
+        assert_eq!(Request::parse("Z0,zz,1"), Request::Malformed);
+        assert_eq!(Request::parse("Z0"), Request::Malformed);
+        // Watchpoints are not supported.
+        assert_eq!(Request::parse("Z2,1a2b,4"), Request::Unsupported);
+    }
+
+    #[test]
+    fn parse_breakpoint() {
+        assert!(
+            matches!(Breakpoint::parse("main"), Some(Breakpoint::Function(name)) if name == "main")
+        );
+        assert!(matches!(
+            Breakpoint::parse("std::thread::spawn"),
+            Some(Breakpoint::Function(name)) if name == "std::thread::spawn"
+        ));
+        assert!(matches!(
+            Breakpoint::parse("src/main.rs:12"),
+            Some(Breakpoint::Line { file, line: 12 }) if file == Path::new("src/main.rs")
+        ));
+        assert!(Breakpoint::parse("").is_none());
+    }
+}
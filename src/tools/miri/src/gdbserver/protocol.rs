@@ -0,0 +1,255 @@
+//! Framing of packets in the GDB remote serial protocol.
+//! See <https://sourceware.org/gdb/current/onlinedocs/gdb.html/Overview.html>.
+
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::TcpStream;
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+
+/// The byte the debugger sends outside of a packet to interrupt the running program.
+const INTERRUPT: u8 = 0x03;
+
+/// A byte stream connecting us to the debugger.
+pub trait Stream: Read + Write {
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()>;
+}
+
+impl Stream for TcpStream {
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        TcpStream::set_nonblocking(self, nonblocking)
+    }
+}
+
+#[cfg(unix)]
+impl Stream for UnixStream {
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        UnixStream::set_nonblocking(self, nonblocking)
+    }
+}
+
+/// Something the debugger sent us.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Incoming {
+    Packet(Vec<u8>),
+    /// The debugger wants the running program to stop.
+    Interrupt,
+}
+
+pub struct Connection {
+    stream: Box<dyn Stream>,
+    /// A byte that was read while polling for interrupts, but that belongs to the next packet.
+    peeked: Option<u8>,
+    /// Whether packets are no longer acknowledged (`QStartNoAckMode`).
+    no_ack: bool,
+}
+
+impl Connection {
+    pub fn new(stream: Box<dyn Stream>) -> Self {
+        Connection { stream, peeked: None, no_ack: false }
+    }
+
+    /// Stops acknowledging packets, and stops waiting for our packets to be acknowledged.
+    pub fn start_no_ack_mode(&mut self) {
+        self.no_ack = true;
+    }
+
+    fn read_byte(&mut self) -> io::Result<u8> {
+        if let Some(byte) = self.peeked.take() {
+            return Ok(byte);
+        }
+        let mut buf = [0];
+        self.stream.read_exact(&mut buf)?;
+        Ok(buf[0])
+    }
+
+    /// Blocks until the debugger sends a packet or an interrupt request.
+    pub fn receive(&mut self) -> io::Result<Incoming> {
+        loop {
+            match self.read_byte()? {
+                b'$' => {}
+                INTERRUPT => return Ok(Incoming::Interrupt),
+                // Acknowledgments of packets we already considered delivered.
+                _ => continue,
+            }
+            let mut data = Vec::new();
+            let mut sum = 0u8;
+            loop {
+                let byte = self.read_byte()?;
+                if byte == b'#' {
+                    break;
+                }
+                sum = sum.wrapping_add(byte);
+                data.push(byte);
+            }
+            let checksum = [self.read_byte()?, self.read_byte()?];
+            if self.no_ack {
+                return Ok(Incoming::Packet(unescape(&data)));
+            }
+            if parse_hex(&checksum) == Some(u64::from(sum)) {
+                self.stream.write_all(b"+")?;
+                return Ok(Incoming::Packet(unescape(&data)));
+            }
+            // Ask for the packet to be sent again.
+            self.stream.write_all(b"-")?;
+        }
+    }
+
+    /// Sends a packet, and waits until the debugger acknowledges it.
+    pub fn send(&mut self, data: &str) -> io::Result<()> {
+        let mut packet = vec![b'$'];
+        for &byte in data.as_bytes() {
+            if matches!(byte, b'#' | b'$' | b'}' | b'*') {
+                packet.extend([b'}', byte ^ 0x20]);
+            } else {
+                packet.push(byte);
+            }
+        }
+        let sum = packet[1..].iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte));
+        write!(packet, "#{sum:02x}")?;
+        loop {
+            self.stream.write_all(&packet)?;
+            self.stream.flush()?;
+            if self.no_ack {
+                return Ok(());
+            }
+            match self.read_byte()? {
+                b'-' => continue,
+                b'+' => return Ok(()),
+                // The debugger went ahead without acknowledging. Keep the byte for later.
+                byte => {
+                    self.peeked = Some(byte);
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Checks, without blocking, whether the debugger wants the running program to stop.
+    pub fn poll_interrupt(&mut self) -> io::Result<bool> {
+        if self.peeked.is_some() {
+            // There is a packet waiting, which will be handled at the next stop.
+            return Ok(false);
+        }
+        let mut buf = [0];
+        self.stream.set_nonblocking(true)?;
+        let res = self.stream.read(&mut buf);
+        self.stream.set_nonblocking(false)?;
+        match res {
+            Ok(0) => Err(ErrorKind::UnexpectedEof.into()),
+            Ok(_) if buf[0] == INTERRUPT => Ok(true),
+            Ok(_) => {
+                if buf[0] == b'$' {
+                    self.peeked = Some(buf[0]);
+                }
+                Ok(false)
+            }
+            Err(err) if err.kind() == ErrorKind::WouldBlock => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+fn unescape(data: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(data.len());
+    let mut bytes = data.iter();
+    while let Some(&byte) = bytes.next() {
+        if byte == b'}'
+            && let Some(&escaped) = bytes.next()
+        {
+            result.push(escaped ^ 0x20);
+        } else {
+            result.push(byte);
+        }
+    }
+    result
+}
+
+pub fn parse_hex(hex: &[u8]) -> Option<u64> {
+    u64::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()
+}
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+pub fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::io::Cursor;
+    use std::rc::Rc;
+
+    use super::*;
+
+    /// A stream that reads from a fixed buffer, and records what is written to it.
+    struct FakeStream {
+        input: Cursor<Vec<u8>>,
+        output: Rc<RefCell<Vec<u8>>>,
+    }
+
+    impl Read for FakeStream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for FakeStream {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.output.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Stream for FakeStream {
+        fn set_nonblocking(&self, _nonblocking: bool) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn connection(input: &[u8]) -> (Connection, Rc<RefCell<Vec<u8>>>) {
+        let output = Rc::new(RefCell::new(Vec::new()));
+        let stream = FakeStream { input: Cursor::new(input.to_vec()), output: output.clone() };
+        (Connection::new(Box::new(stream)), output)
+    }
+
+    #[test]
+    fn receive() {
+        let (mut conn, output) = connection(b"+$qC#b4$m10,4#bad\x03$m10,4#2e");
+        assert_eq!(conn.receive().unwrap(), Incoming::Packet(b"qC".to_vec()));
+        assert_eq!(conn.receive().unwrap(), Incoming::Interrupt);
+        assert_eq!(conn.receive().unwrap(), Incoming::Packet(b"m10,4".to_vec()));
+        // The packet with the wrong checksum was rejected.
+        assert_eq!(*output.borrow(), b"+-+");
+        assert!(conn.receive().is_err());
+    }
+
+    #[test]
+    fn send() {
+        let (mut conn, output) = connection(b"-+");
+        conn.send("a#b").unwrap();
+        // The packet was sent twice since the first attempt was rejected.
+        assert_eq!(*output.borrow(), b"$a}\x03b#43$a}\x03b#43");
+
+        let (mut conn, output) = connection(b"");
+        conn.start_no_ack_mode();
+        conn.send("OK").unwrap();
+        assert_eq!(*output.borrow(), b"$OK#9a");
+    }
+
+    #[test]
+    fn hex() {
+        assert_eq!(encode_hex(b"\x00\xffa"), "00ff61");
+        assert_eq!(decode_hex("00ff61"), Some(b"\x00\xffa".to_vec()));
+        assert_eq!(decode_hex("0"), None);
+        assert_eq!(decode_hex("zz"), None);
+    }
+}
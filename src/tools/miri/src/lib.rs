@@ -77,9 +77,11 @@
 mod borrow_tracker;
 mod clock;
 mod concurrency;
+mod coverage;
 mod data_structures;
 mod diagnostics;
 mod eval;
+mod gdbserver;
 mod helpers;
 mod intrinsics;
 mod machine;
@@ -126,6 +128,7 @@ pub mod native_lib {
 pub use crate::concurrency::data_race::{
     AtomicFenceOrd, AtomicReadOrd, AtomicRwOrd, AtomicWriteOrd, EvalContextExt as _,
 };
+pub use crate::concurrency::data_race_handler::EvalContextExt as _;
 pub use crate::concurrency::init_once::{EvalContextExt as _, InitOnceRef};
 pub use crate::concurrency::sync::{CondvarRef, EvalContextExt as _, MutexRef, RwLockRef};
 pub use crate::concurrency::thread::{
@@ -133,6 +136,7 @@ pub mod native_lib {
     ThreadManager, TimeoutAnchor, TimeoutClock, UnblockKind,
 };
 pub use crate::concurrency::{GenmcConfig, GenmcCtx};
+pub use crate::coverage::{Coverage, EvalContextExt as _};
 pub use crate::data_structures::dedup_range_map::DedupRangeMap;
 pub use crate::data_structures::mono_hash_map::MonoHashMap;
 pub use crate::diagnostics::{
@@ -142,6 +146,7 @@ pub mod native_lib {
     AlignmentCheck, BacktraceStyle, IsolatedOp, MiriConfig, MiriEntryFnType, RejectOpWith,
     ValidationMode, create_ecx, eval_entry,
 };
+pub use crate::gdbserver::{EvalContextExt as _, GdbServer, GdbServerAddress};
 pub use crate::helpers::{AccessKind, EvalContextExt as _, ToU64 as _, ToUsize as _};
 pub use crate::intrinsics::EvalContextExt as _;
 pub use crate::machine::{
@@ -469,10 +469,19 @@ pub struct MiriMachine<'tcx> {
     pub(crate) fds: shims::FdTable,
     /// The table of directory descriptors.
     pub(crate) dirs: shims::DirTable,
+    /// The in-memory file system that replaces the host file system, if enabled.
+    pub(crate) vfs: Option<shims::Vfs>,
+    /// The connection to the debugger, if `-Zmiri-gdbserver` is set.
+    pub(crate) gdbserver: Option<GdbServer>,
+    /// The execution counts of basic blocks, if `-Zmiri-coverage` is set.
+    pub(crate) coverage: Option<Coverage>,
 
     /// The list of all EpollEventInterest.
     pub(crate) epoll_interests: shims::EpollInterestTable,
 
+    /// The addresses in use on the emulated loopback network.
+    pub(crate) loopback_network: shims::LoopbackNetwork,
+
     /// This machine's monotone clock.
     pub(crate) monotonic_clock: MonotonicClock,
 
@@ -690,7 +699,19 @@ pub(crate) fn new(
             validation: config.validation,
             fds: shims::FdTable::init(config.mute_stdout_stderr),
             epoll_interests: shims::EpollInterestTable::new(),
+            loopback_network: shims::LoopbackNetwork::new(),
             dirs: Default::default(),
+            vfs: config.vfs.then(|| {
+                shims::Vfs::new(config.vfs_seed.as_deref()).unwrap_or_else(|err| {
+                    tcx.dcx().fatal(format!("failed to set up the in-memory file system: {err}"))
+                })
+            }),
+            gdbserver: config.gdbserver.as_ref().map(|address| {
+                GdbServer::new(address).unwrap_or_else(|err| {
+                    tcx.dcx().fatal(format!("failed to set up the GDB server on {address}: {err}"))
+                })
+            }),
+            coverage: config.coverage.clone().map(Coverage::new),
             layouts,
             threads,
             thread_cpu_affinity,
@@ -894,6 +915,10 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
             alloc_addresses,
             fds,
             epoll_interests:_,
+            loopback_network: _,
+            vfs: _,
+            gdbserver: _,
+            coverage: _,
             tcx: _,
             isolated_op: _,
             validation: _,
@@ -1584,6 +1609,9 @@ fn stack_mut<'a>(
     fn before_terminator(ecx: &mut InterpCx<'tcx, Self>) -> InterpResult<'tcx> {
         ecx.machine.basic_block_count += 1u64; // a u64 that is only incremented by 1 will "never" overflow
         ecx.machine.since_gc += 1;
+        if ecx.machine.coverage.is_some() {
+            ecx.record_coverage();
+        }
         // Possibly report our progress. This will point at the terminator we are about to execute.
         if let Some(report_progress) = ecx.machine.report_progress {
             if ecx.machine.basic_block_count.is_multiple_of(u64::from(report_progress)) {
@@ -1,10 +1,11 @@
 use std::any::Any;
 use std::collections::BTreeMap;
-use std::fs::{File, Metadata};
+use std::fs::File;
 use std::io::{ErrorKind, IsTerminal, Seek, SeekFrom, Write};
 use std::marker::CoercePointee;
 use std::ops::Deref;
 use std::rc::{Rc, Weak};
+use std::time::SystemTime;
 use std::{fs, io};
 
 use rustc_abi::Size;
@@ -129,6 +130,90 @@ pub fn downcast<T: FileDescription + 'static>(self) -> Option<FileDescriptionRef
     }
 }
 
+/// The type of a file.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FileKind {
+    File,
+    Dir,
+    Symlink,
+    BlockDevice,
+    CharDevice,
+    Fifo,
+    Socket,
+    Unknown,
+}
+
+impl From<fs::FileType> for FileKind {
+    fn from(file_type: fs::FileType) -> Self {
+        #[cfg(unix)]
+        use std::os::unix::fs::FileTypeExt;
+
+        match () {
+            _ if file_type.is_dir() => FileKind::Dir,
+            _ if file_type.is_file() => FileKind::File,
+            _ if file_type.is_symlink() => FileKind::Symlink,
+            // Certain file types are only supported when the host is a Unix system.
+            #[cfg(unix)]
+            _ if file_type.is_block_device() => FileKind::BlockDevice,
+            #[cfg(unix)]
+            _ if file_type.is_char_device() => FileKind::CharDevice,
+            #[cfg(unix)]
+            _ if file_type.is_fifo() => FileKind::Fifo,
+            #[cfg(unix)]
+            _ if file_type.is_socket() => FileKind::Socket,
+            _ => FileKind::Unknown,
+        }
+    }
+}
+
+/// The metadata of a file, either on the host or in the in-memory file system.
+#[derive(Debug, Clone)]
+pub struct Metadata {
+    pub kind: FileKind,
+    pub len: u64,
+    /// The permission bits of the mode, if we can provide them.
+    pub permissions: Option<u32>,
+    pub created: Option<SystemTime>,
+    pub accessed: Option<SystemTime>,
+    pub modified: Option<SystemTime>,
+    pub ino: u64,
+    pub dev: u64,
+    pub uid: u32,
+    pub gid: u32,
+}
+
+impl From<fs::Metadata> for Metadata {
+    fn from(metadata: fs::Metadata) -> Self {
+        // FIXME: Provide more fields using platform specific methods.
+        cfg_select! {
+            unix => {
+                use std::os::unix::fs::MetadataExt;
+                let dev = metadata.dev();
+                let uid = metadata.uid();
+                let gid = metadata.gid();
+            }
+            _ => {
+                let dev = 0;
+                let uid = 0;
+                let gid = 0;
+            }
+        }
+
+        Metadata {
+            kind: metadata.file_type().into(),
+            len: metadata.len(),
+            permissions: None,
+            created: metadata.created().ok(),
+            accessed: metadata.accessed().ok(),
+            modified: metadata.modified().ok(),
+            ino: 0,
+            dev,
+            uid,
+            gid,
+        }
+    }
+}
+
 /// Represents an open file description.
 pub trait FileDescription: std::fmt::Debug + FileDescriptionExt {
     fn name(&self) -> &'static str;
@@ -194,7 +279,7 @@ fn close<'tcx>(
         throw_unsup_format!("cannot close {}", self.name());
     }
 
-    fn metadata<'tcx>(&self) -> InterpResult<'tcx, io::Result<fs::Metadata>> {
+    fn metadata<'tcx>(&self) -> InterpResult<'tcx, io::Result<Metadata>> {
         throw_unsup_format!("obtaining metadata is only supported on file-backed file descriptors");
     }
 
@@ -388,7 +473,7 @@ fn close<'tcx>(
     }
 
     fn metadata<'tcx>(&self) -> InterpResult<'tcx, io::Result<Metadata>> {
-        interp_ok(self.file.metadata())
+        interp_ok(self.file.metadata().map(Metadata::from))
     }
 
     fn is_tty(&self, communicate_allowed: bool) -> bool {
@@ -395,6 +395,20 @@ fn emulate_foreign_item_inner(
                     _ => unreachable!(),
                 };
             }
+            // Annotates custom synchronization for the data race detector. See the README for details.
+            "miri_acquire" | "miri_release" | "miri_release_acquire" => {
+                let [ptr] = this.check_shim_sig_lenient(abi, CanonAbi::Rust, link_name, args)?;
+                let ptr = this.read_pointer(ptr)?;
+                let name = link_name.as_str();
+                // For `miri_release_acquire`, acquire first so that the release also passes on
+                // everything that was acquired, like an acquire-release RMW does.
+                if name != "miri_release" {
+                    this.annotate_acquire(ptr, name)?;
+                }
+                if name != "miri_acquire" {
+                    this.annotate_release(ptr, name)?;
+                }
+            }
             // Promises that a pointer has a given symbolic alignment.
             "miri_promise_symbolic_alignment" => {
                 use rustc_abi::AlignFromBytesError;
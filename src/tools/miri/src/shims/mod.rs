@@ -7,6 +7,7 @@
 #[cfg(all(unix, feature = "native-lib"))]
 mod native_lib;
 mod unix;
+mod vfs;
 mod wasi;
 mod windows;
 mod x86;
@@ -26,7 +27,8 @@
 pub use self::files::FdTable;
 #[cfg(all(unix, feature = "native-lib"))]
 pub use self::native_lib::trace::{init_sv, register_retcode_sv};
-pub use self::unix::{DirTable, EpollInterestTable};
+pub use self::unix::{DirTable, EpollInterestTable, LoopbackNetwork};
+pub use self::vfs::Vfs;
 
 /// What needs to be done after emulating an item (a shim or an intrinsic) is done.
 pub enum EmulateItemResult {
@@ -21,7 +21,7 @@ pub struct ShimSig<'tcx, const ARGS: usize> {
 /// ```
 #[macro_export]
 macro_rules! shim_sig {
-    (extern $abi:literal fn($($arg:ty),*) -> $ret:ty) => {
+    (extern $abi:literal fn($($arg:ty),* $(,)?) -> $ret:ty) => {
         |this| $crate::shims::sig::ShimSig {
             abi: std::str::FromStr::from_str($abi).expect("incorrect abi specified"),
             args: [$(shim_sig_arg!(this, $arg)),*],
@@ -214,6 +214,49 @@ fn fcntl(
         }
     }
 
+    fn ioctl(
+        &mut self,
+        fd_num: &OpTy<'tcx>,
+        cmd: &OpTy<'tcx>,
+        varargs: &[OpTy<'tcx>],
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd_num = this.read_scalar(fd_num)?.to_i32()?;
+        // The type of the request differs between targets (and libcs), so we go by its size.
+        let cmd_size = cmd.layout.size;
+        let cmd = this.read_scalar(cmd)?.to_uint(cmd_size)?;
+
+        let fioclex = this.eval_libc("FIOCLEX").to_uint(cmd_size)?;
+        let fionbio = this.eval_libc("FIONBIO").to_uint(cmd_size)?;
+
+        // Check if this is a valid open file descriptor.
+        let Some(fd) = this.machine.fds.get(fd_num) else {
+            return this.set_last_error_and_return_i32(LibcError("EBADF"));
+        };
+
+        if cmd == fioclex {
+            // Since we don't support `exec`, this is a NOP.
+            interp_ok(Scalar::from_i32(0))
+        } else if cmd == fionbio {
+            let [arg] = check_min_vararg_count("ioctl(fd, FIONBIO, ...)", varargs)?;
+            let arg = this.read_pointer(arg)?;
+            let nonblock = this.read_scalar(&this.ptr_to_mplace(arg, this.machine.layouts.i32))?;
+
+            // This is the same as toggling `O_NONBLOCK` with `fcntl`.
+            let o_nonblock = this.eval_libc_i32("O_NONBLOCK");
+            let mut flags = fd.get_flags(this)?.to_i32()?;
+            if nonblock.to_i32()? != 0 {
+                flags |= o_nonblock;
+            } else {
+                flags &= !o_nonblock;
+            }
+            fd.set_flags(flags, this)
+        } else {
+            throw_unsup_format!("ioctl: unsupported command {cmd:#x}");
+        }
+    }
+
     fn close(&mut self, fd_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
         let this = self.eval_context_mut();
 
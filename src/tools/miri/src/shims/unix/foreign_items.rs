@@ -277,6 +277,12 @@ fn emulate_foreign_item_inner(
                 let result = this.fcntl(fd_num, cmd, varargs)?;
                 this.write_scalar(result, dest)?;
             }
+            "ioctl" => {
+                let ([fd_num, cmd], varargs) =
+                    this.check_shim_sig_variadic_lenient(abi, CanonAbi::C, link_name, args)?;
+                let result = this.ioctl(fd_num, cmd, varargs)?;
+                this.write_scalar(result, dest)?;
+            }
             "dup" => {
                 let [old_fd] = this.check_shim_sig(
                     shim_sig!(extern "C" fn(i32) -> i32),
@@ -374,6 +380,26 @@ fn emulate_foreign_item_inner(
                 let result = this.rmdir(path)?;
                 this.write_scalar(result, dest)?;
             }
+            "chmod" => {
+                let [path, mode] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(*const _, libc::mode_t) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.chmod(path, mode)?;
+                this.write_scalar(result, dest)?;
+            }
+            "fchmod" => {
+                let [fd, mode] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, libc::mode_t) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.fchmod(fd, mode)?;
+                this.write_scalar(result, dest)?;
+            }
             "opendir" => {
                 let [name] = this.check_shim_sig(
                     shim_sig!(extern "C" fn(*const _) -> *mut _),
@@ -541,6 +567,165 @@ fn emulate_foreign_item_inner(
                 this.write_scalar(result, dest)?;
             }
 
+            // Sockets
+            "socket" => {
+                let [domain, type_, protocol] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, i32, i32) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.socket(domain, type_, protocol)?;
+                this.write_scalar(result, dest)?;
+            }
+            "bind" => {
+                let [socket, address, address_len] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *const _, libc::socklen_t) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.bind(socket, address, address_len)?;
+                this.write_scalar(result, dest)?;
+            }
+            "listen" => {
+                let [socket, backlog] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, i32) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.listen(socket, backlog)?;
+                this.write_scalar(result, dest)?;
+            }
+            "connect" => {
+                let [socket, address, address_len] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *const _, libc::socklen_t) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.connect(socket, address, address_len)?;
+                this.write_scalar(result, dest)?;
+            }
+            "accept" => {
+                let [socket, address, address_len] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *mut _, *mut _) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                this.accept4(socket, address, address_len, /*flags*/ None, dest)?;
+            }
+            "accept4" => {
+                // Currently this function does not exist on all Unixes, e.g. on macOS.
+                this.check_target_os(&["linux", "android", "freebsd", "illumos"], link_name)?;
+                let [socket, address, address_len, flags] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *mut _, *mut _, i32) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                this.accept4(socket, address, address_len, Some(flags), dest)?;
+            }
+            "send" => {
+                let [socket, buf, len, flags] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *const _, usize, i32) -> isize),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                this.send(socket, buf, len, flags, /*address*/ None, dest)?;
+            }
+            "sendto" => {
+                let [socket, buf, len, flags, address, address_len] = this.check_shim_sig(
+                    shim_sig!(
+                        extern "C" fn(
+                            i32,
+                            *const _,
+                            usize,
+                            i32,
+                            *const _,
+                            libc::socklen_t,
+                        ) -> isize
+                    ),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                this.send(socket, buf, len, flags, Some((address, address_len)), dest)?;
+            }
+            "recv" => {
+                let [socket, buf, len, flags] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *mut _, usize, i32) -> isize),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                this.recv(socket, buf, len, flags, /*address*/ None, dest)?;
+            }
+            "recvfrom" => {
+                let [socket, buf, len, flags, address, address_len] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *mut _, usize, i32, *mut _, *mut _) -> isize),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                this.recv(socket, buf, len, flags, Some((address, address_len)), dest)?;
+            }
+            "shutdown" => {
+                let [socket, how] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, i32) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.shutdown(socket, how)?;
+                this.write_scalar(result, dest)?;
+            }
+            "getsockname" => {
+                let [socket, address, address_len] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *mut _, *mut _) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.getsockname(socket, address, address_len)?;
+                this.write_scalar(result, dest)?;
+            }
+            "getpeername" => {
+                let [socket, address, address_len] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *mut _, *mut _) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.getpeername(socket, address, address_len)?;
+                this.write_scalar(result, dest)?;
+            }
+            "setsockopt" => {
+                let [socket, level, option_name, option_value, option_len] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, i32, i32, *const _, libc::socklen_t) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result =
+                    this.setsockopt(socket, level, option_name, option_value, option_len)?;
+                this.write_scalar(result, dest)?;
+            }
+            "getsockopt" => {
+                let [socket, level, option_name, option_value, option_len] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, i32, i32, *mut _, *mut _) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result =
+                    this.getsockopt(socket, level, option_name, option_value, option_len)?;
+                this.write_scalar(result, dest)?;
+            }
+
             // Time
             "gettimeofday" => {
                 let [tv, tz] = this.check_shim_sig(
@@ -2,7 +2,7 @@
 
 use std::borrow::Cow;
 use std::fs::{
-    DirBuilder, File, FileType, OpenOptions, ReadDir, TryLockError, read_dir, remove_dir,
+    DirBuilder, File, OpenOptions, Permissions, ReadDir, TryLockError, read_dir, remove_dir,
     remove_file, rename,
 };
 use std::io::{self, ErrorKind, Read, Seek, SeekFrom, Write};
@@ -13,10 +13,11 @@
 use rustc_data_structures::fx::FxHashMap;
 
 use self::shims::time::system_time_to_duration;
-use crate::shims::files::FileHandle;
+use crate::shims::files::{FileHandle, FileKind, Metadata};
 use crate::shims::os_str::bytes_to_os_str;
 use crate::shims::sig::check_min_vararg_count;
 use crate::shims::unix::fd::{FlockOp, UnixFileDescription};
+use crate::shims::vfs::{self, VfsFile};
 use crate::*;
 
 impl UnixFileDescription for FileHandle {
@@ -115,6 +116,41 @@ fn flock<'tcx>(
     }
 }
 
+impl UnixFileDescription for VfsFile {
+    fn pread<'tcx>(
+        &self,
+        _communicate_allowed: bool,
+        offset: u64,
+        ptr: Pointer,
+        len: usize,
+        ecx: &mut MiriInterpCx<'tcx>,
+        finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+    ) -> InterpResult<'tcx> {
+        let result = match self.read_at(offset, len) {
+            Ok(bytes) => {
+                ecx.write_bytes_ptr(ptr, bytes.iter().copied())?;
+                Ok(bytes.len())
+            }
+            Err(e) => Err(IoError::HostError(e)),
+        };
+        finish.call(ecx, result)
+    }
+
+    fn pwrite<'tcx>(
+        &self,
+        _communicate_allowed: bool,
+        ptr: Pointer,
+        len: usize,
+        offset: u64,
+        ecx: &mut MiriInterpCx<'tcx>,
+        finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+    ) -> InterpResult<'tcx> {
+        let bytes = ecx.read_bytes_ptr_strip_provenance(ptr, Size::from_bytes(len))?;
+        let result = self.write_at(offset, bytes, vfs::now(ecx)).map(|()| len);
+        finish.call(ecx, result.map_err(IoError::HostError))
+    }
+}
+
 impl<'tcx> EvalContextExtPrivate<'tcx> for crate::MiriInterpCx<'tcx> {}
 trait EvalContextExtPrivate<'tcx>: crate::MiriInterpCxExt<'tcx> {
     fn macos_fbsd_solarish_write_stat_buf(
@@ -135,7 +171,7 @@ fn macos_fbsd_solarish_write_stat_buf(
                 ("st_dev", metadata.dev.into()),
                 ("st_mode", mode.try_into().unwrap()),
                 ("st_nlink", 0),
-                ("st_ino", 0),
+                ("st_ino", metadata.ino.into()),
                 ("st_uid", metadata.uid.into()),
                 ("st_gid", metadata.gid.into()),
                 ("st_rdev", 0),
@@ -173,59 +209,64 @@ fn macos_fbsd_solarish_write_stat_buf(
         interp_ok(0)
     }
 
-    fn file_type_to_d_type(
-        &mut self,
-        file_type: std::io::Result<FileType>,
-    ) -> InterpResult<'tcx, i32> {
-        #[cfg(unix)]
-        use std::os::unix::fs::FileTypeExt;
-
+    fn file_type_to_d_type(&mut self, file_type: FileKind) -> InterpResult<'tcx, i32> {
         let this = self.eval_context_mut();
-        match file_type {
-            Ok(file_type) => {
-                match () {
-                    _ if file_type.is_dir() => interp_ok(this.eval_libc("DT_DIR").to_u8()?.into()),
-                    _ if file_type.is_file() => interp_ok(this.eval_libc("DT_REG").to_u8()?.into()),
-                    _ if file_type.is_symlink() =>
-                        interp_ok(this.eval_libc("DT_LNK").to_u8()?.into()),
-                    // Certain file types are only supported when the host is a Unix system.
-                    #[cfg(unix)]
-                    _ if file_type.is_block_device() =>
-                        interp_ok(this.eval_libc("DT_BLK").to_u8()?.into()),
-                    #[cfg(unix)]
-                    _ if file_type.is_char_device() =>
-                        interp_ok(this.eval_libc("DT_CHR").to_u8()?.into()),
-                    #[cfg(unix)]
-                    _ if file_type.is_fifo() =>
-                        interp_ok(this.eval_libc("DT_FIFO").to_u8()?.into()),
-                    #[cfg(unix)]
-                    _ if file_type.is_socket() =>
-                        interp_ok(this.eval_libc("DT_SOCK").to_u8()?.into()),
-                    // Fallback
-                    _ => interp_ok(this.eval_libc("DT_UNKNOWN").to_u8()?.into()),
-                }
-            }
-            Err(_) => {
-                // Fallback on error
-                interp_ok(this.eval_libc("DT_UNKNOWN").to_u8()?.into())
-            }
-        }
+        let d_type = match file_type {
+            FileKind::Dir => "DT_DIR",
+            FileKind::File => "DT_REG",
+            FileKind::Symlink => "DT_LNK",
+            FileKind::BlockDevice => "DT_BLK",
+            FileKind::CharDevice => "DT_CHR",
+            FileKind::Fifo => "DT_FIFO",
+            FileKind::Socket => "DT_SOCK",
+            FileKind::Unknown => "DT_UNKNOWN",
+        };
+        interp_ok(this.eval_libc(d_type).to_u8()?.into())
     }
 }
 
+/// The entries of an open directory that have not been returned yet.
+#[derive(Debug)]
+enum DirEntries {
+    /// The directory reader on the host.
+    Host(ReadDir),
+    /// A snapshot of a directory in the in-memory file system, taken by opendir().
+    Virtual(std::vec::IntoIter<vfs::DirEntry>),
+}
+
 /// An open directory, tracked by DirHandler.
 #[derive(Debug)]
 struct OpenDir {
-    /// The directory reader on the host.
-    read_dir: ReadDir,
+    entries: DirEntries,
     /// The most recent entry returned by readdir().
     /// Will be freed by the next call.
     entry: Option<Pointer>,
 }
 
 impl OpenDir {
-    fn new(read_dir: ReadDir) -> Self {
-        Self { read_dir, entry: None }
+    fn new(entries: DirEntries) -> Self {
+        Self { entries, entry: None }
+    }
+
+    fn next_entry(&mut self) -> Option<io::Result<vfs::DirEntry>> {
+        match &mut self.entries {
+            DirEntries::Host(read_dir) =>
+                Some(read_dir.next()?.map(|dir_entry| {
+                    // If the host is a Unix system, fill in the inode number with its real value.
+                    // If not, use 0 as a fallback value.
+                    #[cfg(unix)]
+                    let ino = std::os::unix::fs::DirEntryExt::ino(&dir_entry);
+                    #[cfg(not(unix))]
+                    let ino = 0u64;
+
+                    vfs::DirEntry {
+                        name: dir_entry.file_name(),
+                        ino,
+                        kind: dir_entry.file_type().map_or(FileKind::Unknown, FileKind::from),
+                    }
+                })),
+            DirEntries::Virtual(entries) => entries.next().map(Ok),
+        }
     }
 }
 
@@ -250,10 +291,10 @@ pub struct DirTable {
 
 impl DirTable {
     #[expect(clippy::arithmetic_side_effects)]
-    fn insert_new(&mut self, read_dir: ReadDir) -> u64 {
+    fn insert_new(&mut self, entries: DirEntries) -> u64 {
         let id = self.next_id;
         self.next_id += 1;
-        self.streams.try_insert(id, OpenDir::new(read_dir)).unwrap();
+        self.streams.try_insert(id, OpenDir::new(entries)).unwrap();
         id
     }
 }
@@ -278,6 +319,20 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
     }
 }
 
+/// Updates host file permissions to match the Unix `mode`.
+fn set_host_permissions(permissions: &mut Permissions, mode: u32) {
+    cfg_select! {
+        unix => {
+            use std::os::unix::fs::PermissionsExt;
+            permissions.set_mode(mode);
+        }
+        _ => {
+            // Non-Unix hosts only know whether a file is read-only.
+            permissions.set_readonly(mode & 0o222 == 0);
+        }
+    }
+}
+
 fn maybe_sync_file(
     file: &File,
     writable: bool,
@@ -305,10 +360,12 @@ fn open(
         let this = self.eval_context_mut();
 
         let path_raw = this.read_pointer(path_raw)?;
-        let path = this.read_path_from_c_str(path_raw)?;
+        let path = this.read_path_from_c_str(path_raw)?.into_owned();
         let flag = this.read_scalar(flag)?.to_i32()?;
 
         let mut options = OpenOptions::new();
+        // The same options, in case we open the file in the in-memory file system.
+        let mut vfs_options = vfs::OpenOptions::default();
 
         let o_rdonly = this.eval_libc_i32("O_RDONLY");
         let o_wronly = this.eval_libc_i32("O_WRONLY");
@@ -327,10 +384,14 @@ fn open(
         if access_mode == o_rdonly {
             writable = false;
             options.read(true);
+            vfs_options.read = true;
         } else if access_mode == o_wronly {
             options.write(true);
+            vfs_options.write = true;
         } else if access_mode == o_rdwr {
             options.read(true).write(true);
+            vfs_options.read = true;
+            vfs_options.write = true;
         } else {
             throw_unsup_format!("unsupported access mode {:#x}", access_mode);
         }
@@ -342,11 +403,13 @@ fn open(
         let o_append = this.eval_libc_i32("O_APPEND");
         if flag & o_append == o_append {
             options.append(true);
+            vfs_options.append = true;
             mirror |= o_append;
         }
         let o_trunc = this.eval_libc_i32("O_TRUNC");
         if flag & o_trunc == o_trunc {
             options.truncate(true);
+            vfs_options.truncate = true;
             mirror |= o_trunc;
         }
         let o_creat = this.eval_libc_i32("O_CREAT");
@@ -356,6 +419,7 @@ fn open(
             // (see https://github.com/rust-lang/rust/issues/71915).
             let [mode] = check_min_vararg_count("open(pathname, O_CREAT, ...)", varargs)?;
             let mode = this.read_scalar(mode)?.to_u32()?;
+            vfs_options.mode = mode;
 
             #[cfg(unix)]
             {
@@ -366,7 +430,7 @@ fn open(
             #[cfg(not(unix))]
             {
                 // Only support default mode for non-UNIX (i.e. Windows) host
-                if mode != 0o666 {
+                if mode != 0o666 && this.machine.vfs.is_none() {
                     throw_unsup_format!(
                         "non-default mode 0o{:o} is not supported on non-Unix hosts",
                         mode
@@ -380,8 +444,10 @@ fn open(
             if flag & o_excl == o_excl {
                 mirror |= o_excl;
                 options.create_new(true);
+                vfs_options.create_new = true;
             } else {
                 options.create(true);
+                vfs_options.create = true;
             }
         }
         let o_cloexec = this.eval_libc_i32("O_CLOEXEC");
@@ -412,10 +478,11 @@ fn open(
             {
                 // O_NOFOLLOW only fails when the trailing component is a symlink;
                 // the entire rest of the path can still contain symlinks.
-                if path.is_symlink() {
+                if this.machine.vfs.is_none() && path.is_symlink() {
                     return this.set_last_error_and_return_i32(LibcError("ELOOP"));
                 }
             }
+            vfs_options.nofollow = true;
             mirror |= o_nofollow;
         }
 
@@ -426,14 +493,22 @@ fn open(
         }
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`open`", reject_with)?;
             return this.set_last_error_and_return_i32(ErrorKind::PermissionDenied);
         }
 
-        let fd = options
-            .open(path)
-            .map(|file| this.machine.fds.insert_new(FileHandle { file, writable }));
+        let now = vfs::now(this);
+        let fd = match &mut this.machine.vfs {
+            Some(vfs) =>
+                vfs.open(&path, &vfs_options, now).map(|file| this.machine.fds.insert_new(file)),
+            None =>
+                options
+                    .open(path)
+                    .map(|file| this.machine.fds.insert_new(FileHandle { file, writable })),
+        };
 
         interp_ok(Scalar::from_i32(this.try_unwrap_io_result(fd)?))
     }
@@ -480,16 +555,22 @@ fn lseek64(
     fn unlink(&mut self, path_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
         let this = self.eval_context_mut();
 
-        let path = this.read_path_from_c_str(this.read_pointer(path_op)?)?;
+        let path = this.read_path_from_c_str(this.read_pointer(path_op)?)?.into_owned();
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`unlink`", reject_with)?;
             return this.set_last_error_and_return_i32(ErrorKind::PermissionDenied);
         }
 
-        let result = remove_file(path).map(|_| 0);
-        interp_ok(Scalar::from_i32(this.try_unwrap_io_result(result)?))
+        let now = vfs::now(this);
+        let result = match &mut this.machine.vfs {
+            Some(vfs) => vfs.remove_file(&path, now),
+            None => remove_file(path),
+        };
+        interp_ok(Scalar::from_i32(this.try_unwrap_io_result(result.map(|_| 0))?))
     }
 
     fn symlink(
@@ -509,17 +590,23 @@ fn create_link(src: &Path, dst: &Path) -> std::io::Result<()> {
         }
 
         let this = self.eval_context_mut();
-        let target = this.read_path_from_c_str(this.read_pointer(target_op)?)?;
-        let linkpath = this.read_path_from_c_str(this.read_pointer(linkpath_op)?)?;
+        let target = this.read_path_from_c_str(this.read_pointer(target_op)?)?.into_owned();
+        let linkpath = this.read_path_from_c_str(this.read_pointer(linkpath_op)?)?.into_owned();
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`symlink`", reject_with)?;
             return this.set_last_error_and_return_i32(ErrorKind::PermissionDenied);
         }
 
-        let result = create_link(&target, &linkpath).map(|_| 0);
-        interp_ok(Scalar::from_i32(this.try_unwrap_io_result(result)?))
+        let now = vfs::now(this);
+        let result = match &mut this.machine.vfs {
+            Some(vfs) => vfs.symlink(&target, &linkpath, now),
+            None => create_link(&target, &linkpath),
+        };
+        interp_ok(Scalar::from_i32(this.try_unwrap_io_result(result.map(|_| 0))?))
     }
 
     fn macos_fbsd_solarish_stat(
@@ -537,7 +624,9 @@ fn macos_fbsd_solarish_stat(
         let path = this.read_path_from_c_str(path_scalar)?.into_owned();
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`stat`", reject_with)?;
             return this.set_last_error_and_return_i32(LibcError("EACCES"));
         }
@@ -570,7 +659,9 @@ fn macos_fbsd_solarish_lstat(
         let path = this.read_path_from_c_str(path_scalar)?.into_owned();
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`lstat`", reject_with)?;
             return this.set_last_error_and_return_i32(LibcError("EACCES"));
         }
@@ -600,7 +691,9 @@ fn macos_fbsd_solarish_fstat(
         let fd = this.read_scalar(fd_op)?.to_i32()?;
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`fstat`", reject_with)?;
             // Set error code as "EBADF" (bad fd)
             return this.set_last_error_and_return_i32(LibcError("EBADF"));
@@ -661,7 +754,9 @@ fn linux_statx(
         }
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`statx`", reject_with)?;
             let ecode = if path.is_absolute() || dirfd == this.eval_libc_i32("AT_FDCWD") {
                 // since `path` is provided, either absolute or
@@ -702,7 +797,8 @@ fn linux_statx(
         // The `mode` field specifies the type of the file and the permissions over the file for
         // the owner, its group and other users. Given that we can only provide the file type
         // without using platform specific methods, we only set the bits corresponding to the file
-        // type. This should be an `__u16` but `libc` provides its values as `u32`.
+        // type, unless the file is in the in-memory file system. This should be an `__u16` but
+        // `libc` provides its values as `u32`.
         let mode: u16 = metadata
             .mode
             .to_u32()?
@@ -745,7 +841,7 @@ fn linux_statx(
                 ("stx_uid", 0),
                 ("stx_gid", 0),
                 ("stx_mode", mode.into()),
-                ("stx_ino", 0),
+                ("stx_ino", metadata.ino.into()),
                 ("stx_size", metadata.size.into()),
                 ("stx_blocks", 0),
                 ("stx_attributes_mask", 0),
@@ -806,66 +902,150 @@ fn rename(
             return this.set_last_error_and_return_i32(LibcError("EFAULT"));
         }
 
-        let oldpath = this.read_path_from_c_str(oldpath_ptr)?;
-        let newpath = this.read_path_from_c_str(newpath_ptr)?;
+        let oldpath = this.read_path_from_c_str(oldpath_ptr)?.into_owned();
+        let newpath = this.read_path_from_c_str(newpath_ptr)?.into_owned();
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`rename`", reject_with)?;
             return this.set_last_error_and_return_i32(ErrorKind::PermissionDenied);
         }
 
-        let result = rename(oldpath, newpath).map(|_| 0);
+        let now = vfs::now(this);
+        let result = match &mut this.machine.vfs {
+            Some(vfs) => vfs.rename(&oldpath, &newpath, now),
+            None => rename(oldpath, newpath),
+        };
 
-        interp_ok(Scalar::from_i32(this.try_unwrap_io_result(result)?))
+        interp_ok(Scalar::from_i32(this.try_unwrap_io_result(result.map(|_| 0))?))
     }
 
     fn mkdir(&mut self, path_op: &OpTy<'tcx>, mode_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
         let this = self.eval_context_mut();
 
-        #[cfg_attr(not(unix), allow(unused_variables))]
         let mode = if matches!(&*this.tcx.sess.target.os, "macos" | "freebsd") {
             u32::from(this.read_scalar(mode_op)?.to_u16()?)
         } else {
             this.read_scalar(mode_op)?.to_u32()?
         };
 
-        let path = this.read_path_from_c_str(this.read_pointer(path_op)?)?;
+        let path = this.read_path_from_c_str(this.read_pointer(path_op)?)?.into_owned();
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`mkdir`", reject_with)?;
             return this.set_last_error_and_return_i32(ErrorKind::PermissionDenied);
         }
 
-        #[cfg_attr(not(unix), allow(unused_mut))]
-        let mut builder = DirBuilder::new();
+        let now = vfs::now(this);
+        let result = match &mut this.machine.vfs {
+            Some(vfs) => vfs.create_dir(&path, mode, now),
+            None => {
+                #[cfg_attr(not(unix), allow(unused_mut))]
+                let mut builder = DirBuilder::new();
 
-        // If the host supports it, forward on the mode of the directory
-        // (i.e. permission bits and the sticky bit)
-        #[cfg(unix)]
+                // If the host supports it, forward on the mode of the directory
+                // (i.e. permission bits and the sticky bit)
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::DirBuilderExt;
+                    builder.mode(mode);
+                }
+
+                builder.create(path)
+            }
+        };
+        let result = result.map(|_| 0i32);
+
+        interp_ok(Scalar::from_i32(this.try_unwrap_io_result(result)?))
+    }
+
+    fn chmod(&mut self, path_op: &OpTy<'tcx>, mode_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let mode = if matches!(&*this.tcx.sess.target.os, "macos" | "freebsd") {
+            u32::from(this.read_scalar(mode_op)?.to_u16()?)
+        } else {
+            this.read_scalar(mode_op)?.to_u32()?
+        };
+
+        let path = this.read_path_from_c_str(this.read_pointer(path_op)?)?.into_owned();
+
+        // Reject if isolation is enabled.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
         {
-            use std::os::unix::fs::DirBuilderExt;
-            builder.mode(mode);
+            this.reject_in_isolation("`chmod`", reject_with)?;
+            return this.set_last_error_and_return_i32(ErrorKind::PermissionDenied);
         }
 
-        let result = builder.create(path).map(|_| 0i32);
+        let result = match &mut this.machine.vfs {
+            Some(vfs) => vfs.set_permissions(&path, mode),
+            None =>
+                std::fs::metadata(&path).and_then(|metadata| {
+                    let mut permissions = metadata.permissions();
+                    set_host_permissions(&mut permissions, mode);
+                    std::fs::set_permissions(&path, permissions)
+                }),
+        };
 
-        interp_ok(Scalar::from_i32(this.try_unwrap_io_result(result)?))
+        interp_ok(Scalar::from_i32(this.try_unwrap_io_result(result.map(|_| 0i32))?))
+    }
+
+    fn fchmod(&mut self, fd_op: &OpTy<'tcx>, mode_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd_num = this.read_scalar(fd_op)?.to_i32()?;
+        let mode = if matches!(&*this.tcx.sess.target.os, "macos" | "freebsd") {
+            u32::from(this.read_scalar(mode_op)?.to_u16()?)
+        } else {
+            this.read_scalar(mode_op)?.to_u32()?
+        };
+
+        // Isolation check is done when opening the file.
+
+        let Some(fd) = this.machine.fds.get(fd_num) else {
+            return this.set_last_error_and_return_i32(LibcError("EBADF"));
+        };
+        if let Some(file) = fd.clone().downcast::<VfsFile>() {
+            file.set_permissions(mode);
+            return interp_ok(Scalar::from_i32(0));
+        }
+        let file = fd.downcast::<FileHandle>().ok_or_else(|| {
+            err_unsup_format!("`fchmod` is only supported on file-backed file descriptors")
+        })?;
+        let result = file.file.metadata().and_then(|metadata| {
+            let mut permissions = metadata.permissions();
+            set_host_permissions(&mut permissions, mode);
+            file.file.set_permissions(permissions)
+        });
+
+        interp_ok(Scalar::from_i32(this.try_unwrap_io_result(result.map(|_| 0i32))?))
     }
 
     fn rmdir(&mut self, path_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
         let this = self.eval_context_mut();
 
-        let path = this.read_path_from_c_str(this.read_pointer(path_op)?)?;
+        let path = this.read_path_from_c_str(this.read_pointer(path_op)?)?.into_owned();
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`rmdir`", reject_with)?;
             return this.set_last_error_and_return_i32(ErrorKind::PermissionDenied);
         }
 
-        let result = remove_dir(path).map(|_| 0i32);
+        let now = vfs::now(this);
+        let result = match &mut this.machine.vfs {
+            Some(vfs) => vfs.remove_dir(&path, now),
+            None => remove_dir(path),
+        };
+        let result = result.map(|_| 0i32);
 
         interp_ok(Scalar::from_i32(this.try_unwrap_io_result(result)?))
     }
@@ -876,17 +1056,23 @@ fn opendir(&mut self, name_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
         let name = this.read_path_from_c_str(this.read_pointer(name_op)?)?;
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`opendir`", reject_with)?;
             this.set_last_error(LibcError("EACCES"))?;
             return interp_ok(Scalar::null_ptr(this));
         }
 
-        let result = read_dir(name);
+        let result = match &this.machine.vfs {
+            Some(vfs) =>
+                vfs.read_dir(&name).map(|entries| DirEntries::Virtual(entries.into_iter())),
+            None => read_dir(name).map(DirEntries::Host),
+        };
 
         match result {
-            Ok(dir_iter) => {
-                let id = this.machine.dirs.insert_new(dir_iter);
+            Ok(entries) => {
+                let id = this.machine.dirs.insert_new(entries);
 
                 // The libc API for opendir says that this method returns a pointer to an opaque
                 // structure, but we are returning an ID number. Thus, pass it as a scalar of
@@ -914,7 +1100,9 @@ fn linux_solarish_readdir64(
         let dirp = this.read_target_usize(dirp_op)?;
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`readdir`", reject_with)?;
             this.set_last_error(LibcError("EBADF"))?;
             return interp_ok(Scalar::null_ptr(this));
@@ -924,7 +1112,7 @@ fn linux_solarish_readdir64(
             err_unsup_format!("the DIR pointer passed to readdir64 did not come from opendir")
         })?;
 
-        let entry = match open_dir.read_dir.next() {
+        let entry = match open_dir.next_entry() {
             Some(Ok(dir_entry)) => {
                 // Write the directory entry into a newly allocated buffer.
                 // The name is written with write_bytes, while the rest of the
@@ -948,7 +1136,7 @@ fn linux_solarish_readdir64(
                 //     pub d_name: [c_char; 3],
                 // }
 
-                let mut name = dir_entry.file_name(); // not a Path as there are no separators!
+                let mut name = dir_entry.name; // not a Path as there are no separators!
                 name.push("\0"); // Add a NUL terminator
                 let name_bytes = name.as_encoded_bytes();
                 let name_len = u64::try_from(name_bytes.len()).unwrap();
@@ -967,16 +1155,9 @@ fn linux_solarish_readdir64(
                 )?;
                 let entry: Pointer = entry.into();
 
-                // If the host is a Unix system, fill in the inode number with its real value.
-                // If not, use 0 as a fallback value.
-                #[cfg(unix)]
-                let ino = std::os::unix::fs::DirEntryExt::ino(&dir_entry);
-                #[cfg(not(unix))]
-                let ino = 0u64;
-
-                let file_type = this.file_type_to_d_type(dir_entry.file_type())?;
+                let file_type = this.file_type_to_d_type(dir_entry.kind)?;
                 this.write_int_fields_named(
-                    &[("d_ino", ino.into()), ("d_off", 0), ("d_reclen", size.into())],
+                    &[("d_ino", dir_entry.ino.into()), ("d_off", 0), ("d_reclen", size.into())],
                     &this.ptr_to_mplace(entry, dirent_layout),
                 )?;
 
@@ -1026,7 +1207,9 @@ fn macos_fbsd_readdir_r(
         let result_place = this.deref_pointer_as(result_op, this.machine.layouts.mut_raw_ptr)?;
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`readdir_r`", reject_with)?;
             // Return error code, do *not* set `errno`.
             return interp_ok(this.eval_libc("EBADF"));
@@ -1035,7 +1218,7 @@ fn macos_fbsd_readdir_r(
         let open_dir = this.machine.dirs.streams.get_mut(&dirp).ok_or_else(|| {
             err_unsup_format!("the DIR pointer passed to readdir_r did not come from opendir")
         })?;
-        interp_ok(match open_dir.read_dir.next() {
+        interp_ok(match open_dir.next_entry() {
             Some(Ok(dir_entry)) => {
                 // Write into entry, write pointer to result, return 0 on success.
                 // The name is written with write_os_str_to_c_str, while the rest of the
@@ -1054,7 +1237,7 @@ fn macos_fbsd_readdir_r(
                 let entry_place = this.deref_pointer_as(entry_op, this.libc_ty_layout("dirent"))?;
                 let name_place = this.project_field_named(&entry_place, "d_name")?;
 
-                let file_name = dir_entry.file_name(); // not a Path as there are no separators!
+                let file_name = dir_entry.name; // not a Path as there are no separators!
                 let (name_fits, file_name_buf_len) = this.write_os_str_to_c_str(
                     &file_name,
                     name_place.ptr(),
@@ -1067,14 +1250,8 @@ fn macos_fbsd_readdir_r(
                     );
                 }
 
-                // If the host is a Unix system, fill in the inode number with its real value.
-                // If not, use 0 as a fallback value.
-                #[cfg(unix)]
-                let ino = std::os::unix::fs::DirEntryExt::ino(&dir_entry);
-                #[cfg(not(unix))]
-                let ino = 0u64;
-
-                let file_type = this.file_type_to_d_type(dir_entry.file_type())?;
+                let ino = dir_entry.ino;
+                let file_type = this.file_type_to_d_type(dir_entry.kind)?;
 
                 // Common fields.
                 this.write_int_fields_named(
@@ -1131,7 +1308,9 @@ fn closedir(&mut self, dirp_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
         let dirp = this.read_target_usize(dirp_op)?;
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`closedir`", reject_with)?;
             return this.set_last_error_and_return_i32(LibcError("EBADF"));
         }
@@ -1152,7 +1331,9 @@ fn ftruncate64(&mut self, fd_num: i32, length: i128) -> InterpResult<'tcx, Scala
         let this = self.eval_context_mut();
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`ftruncate64`", reject_with)?;
             // Set error code as "EBADF" (bad fd)
             return this.set_last_error_and_return_i32(LibcError("EBADF"));
@@ -1162,6 +1343,14 @@ fn ftruncate64(&mut self, fd_num: i32, length: i128) -> InterpResult<'tcx, Scala
             return this.set_last_error_and_return_i32(LibcError("EBADF"));
         };
 
+        if let Some(file) = fd.clone().downcast::<VfsFile>() {
+            let Ok(length) = length.try_into() else {
+                return this.set_last_error_and_return_i32(LibcError("EINVAL"));
+            };
+            let result = file.set_len(length, vfs::now(this)).map(|_| 0i32);
+            return interp_ok(Scalar::from_i32(this.try_unwrap_io_result(result)?));
+        }
+
         // FIXME: Support ftruncate64 for all FDs
         let file = fd.downcast::<FileHandle>().ok_or_else(|| {
             err_unsup_format!("`ftruncate64` is only supported on file-backed file descriptors")
@@ -1192,7 +1381,9 @@ fn fsync(&mut self, fd_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
         let fd = this.read_scalar(fd_op)?.to_i32()?;
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`fsync`", reject_with)?;
             // Set error code as "EBADF" (bad fd)
             return this.set_last_error_and_return_i32(LibcError("EBADF"));
@@ -1206,6 +1397,10 @@ fn ffullsync_fd(&mut self, fd_num: i32) -> InterpResult<'tcx, Scalar> {
         let Some(fd) = this.machine.fds.get(fd_num) else {
             return this.set_last_error_and_return_i32(LibcError("EBADF"));
         };
+        if fd.clone().downcast::<VfsFile>().is_some() {
+            // The in-memory file system has no storage to synchronize with.
+            return interp_ok(Scalar::from_i32(0));
+        }
         // Only regular files support synchronization.
         let file = fd.downcast::<FileHandle>().ok_or_else(|| {
             err_unsup_format!("`fsync` is only supported on file-backed file descriptors")
@@ -1220,7 +1415,9 @@ fn fdatasync(&mut self, fd_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
         let fd = this.read_scalar(fd_op)?.to_i32()?;
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`fdatasync`", reject_with)?;
             // Set error code as "EBADF" (bad fd)
             return this.set_last_error_and_return_i32(LibcError("EBADF"));
@@ -1229,6 +1426,10 @@ fn fdatasync(&mut self, fd_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
         let Some(fd) = this.machine.fds.get(fd) else {
             return this.set_last_error_and_return_i32(LibcError("EBADF"));
         };
+        if fd.clone().downcast::<VfsFile>().is_some() {
+            // The in-memory file system has no storage to synchronize with.
+            return interp_ok(Scalar::from_i32(0));
+        }
         // Only regular files support synchronization.
         let file = fd.downcast::<FileHandle>().ok_or_else(|| {
             err_unsup_format!("`fdatasync` is only supported on file-backed file descriptors")
@@ -1262,7 +1463,9 @@ fn sync_file_range(
         }
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`sync_file_range`", reject_with)?;
             // Set error code as "EBADF" (bad fd)
             return this.set_last_error_and_return_i32(LibcError("EBADF"));
@@ -1271,6 +1474,10 @@ fn sync_file_range(
         let Some(fd) = this.machine.fds.get(fd) else {
             return this.set_last_error_and_return_i32(LibcError("EBADF"));
         };
+        if fd.clone().downcast::<VfsFile>().is_some() {
+            // The in-memory file system has no storage to synchronize with.
+            return interp_ok(Scalar::from_i32(0));
+        }
         // Only regular files support synchronization.
         let file = fd.downcast::<FileHandle>().ok_or_else(|| {
             err_unsup_format!("`sync_data_range` is only supported on file-backed file descriptors")
@@ -1292,13 +1499,18 @@ fn readlink(
         let bufsize = this.read_target_usize(bufsize_op)?;
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`readlink`", reject_with)?;
             this.set_last_error(LibcError("EACCES"))?;
             return interp_ok(-1);
         }
 
-        let result = std::fs::read_link(pathname);
+        let result = match &this.machine.vfs {
+            Some(vfs) => vfs.read_link(&pathname),
+            None => std::fs::read_link(pathname),
+        };
         match result {
             Ok(resolved) => {
                 // 'readlink' truncates the resolved path if the provided buffer is not large
@@ -1354,13 +1566,18 @@ fn realpath(
         let processed_ptr = this.read_pointer(processed_path_op)?;
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`realpath`", reject_with)?;
             this.set_last_error(LibcError("EACCES"))?;
             return interp_ok(Scalar::from_target_usize(0, this));
         }
 
-        let result = std::fs::canonicalize(pathname);
+        let result = match &this.machine.vfs {
+            Some(vfs) => vfs.canonicalize(&pathname),
+            None => std::fs::canonicalize(pathname),
+        };
         match result {
             Ok(resolved) => {
                 let path_max = this
@@ -1427,7 +1644,9 @@ fn mkstemp(&mut self, template_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
         let template_bytes = template.as_mut_slice();
 
         // Reject if isolation is enabled.
-        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op
+            && this.machine.vfs.is_none()
+        {
             this.reject_in_isolation("`mkstemp`", reject_with)?;
             return this.set_last_error_and_return_i32(LibcError("EACCES"));
         }
@@ -1476,6 +1695,13 @@ fn mkstemp(&mut self, template_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
             // Do not allow others to read or modify this file.
             fopts.share_mode(0);
         }
+        let vfs_options = vfs::OpenOptions {
+            read: true,
+            write: true,
+            create_new: true,
+            mode: 0o600,
+            ..Default::default()
+        };
 
         // If the generated file already exists, we will try again `max_attempts` many times.
         for _ in 0..max_attempts {
@@ -1493,13 +1719,25 @@ fn mkstemp(&mut self, template_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
             // To actually open the file, turn this into a host OsString.
             let p = bytes_to_os_str(template_bytes)?.to_os_string();
 
-            let possibly_unique = std::env::temp_dir().join::<PathBuf>(p.into());
-
-            let file = fopts.open(possibly_unique);
+            let now = vfs::now(this);
+            let fd = match &mut this.machine.vfs {
+                Some(vfs) => {
+                    // The in-memory file system always has a `/tmp`, which is also what
+                    // `std::env::temp_dir` returns in isolation mode.
+                    let possibly_unique = Path::new("/tmp").join(p);
+                    vfs.open(&possibly_unique, &vfs_options, now)
+                        .map(|file| this.machine.fds.insert_new(file))
+                }
+                None => {
+                    let possibly_unique = std::env::temp_dir().join::<PathBuf>(p.into());
+                    fopts.open(possibly_unique).map(|f| {
+                        this.machine.fds.insert_new(FileHandle { file: f, writable: true })
+                    })
+                }
+            };
 
-            match file {
-                Ok(f) => {
-                    let fd = this.machine.fds.insert_new(FileHandle { file: f, writable: true });
+            match fd {
+                Ok(fd) => {
                     return interp_ok(Scalar::from_i32(fd));
                 }
                 Err(e) =>
@@ -1522,12 +1760,9 @@ fn mkstemp(&mut self, template_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
 }
 
 /// Extracts the number of seconds and nanoseconds elapsed between `time` and the unix epoch when
-/// `time` is Ok. Returns `None` if `time` is an error. Fails if `time` happens before the unix
-/// epoch.
-fn extract_sec_and_nsec<'tcx>(
-    time: std::io::Result<SystemTime>,
-) -> InterpResult<'tcx, Option<(u64, u32)>> {
-    match time.ok() {
+/// `time` is `Some`. Fails if `time` happens before the unix epoch.
+fn extract_sec_and_nsec<'tcx>(time: Option<SystemTime>) -> InterpResult<'tcx, Option<(u64, u32)>> {
+    match time {
         Some(time) => {
             let duration = system_time_to_duration(&time)?;
             interp_ok(Some((duration.as_secs(), duration.subsec_nanos())))
@@ -1544,6 +1779,7 @@ struct FileMetadata {
     created: Option<(u64, u32)>,
     accessed: Option<(u64, u32)>,
     modified: Option<(u64, u32)>,
+    ino: u64,
     dev: u64,
     uid: u32,
     gid: u32,
@@ -1555,8 +1791,11 @@ fn from_path<'tcx>(
         path: &Path,
         follow_symlink: bool,
     ) -> InterpResult<'tcx, Result<FileMetadata, IoError>> {
-        let metadata =
-            if follow_symlink { std::fs::metadata(path) } else { std::fs::symlink_metadata(path) };
+        let metadata = match &ecx.machine.vfs {
+            Some(vfs) => vfs.metadata(path, follow_symlink),
+            None if follow_symlink => std::fs::metadata(path).map(Metadata::from),
+            None => std::fs::symlink_metadata(path).map(Metadata::from),
+        };
 
         FileMetadata::from_meta(ecx, metadata)
     }
@@ -1576,7 +1815,7 @@ fn from_fd_num<'tcx>(
 
     fn from_meta<'tcx>(
         ecx: &mut MiriInterpCx<'tcx>,
-        metadata: Result<std::fs::Metadata, std::io::Error>,
+        metadata: Result<Metadata, std::io::Error>,
     ) -> InterpResult<'tcx, Result<FileMetadata, IoError>> {
         let metadata = match metadata {
             Ok(metadata) => metadata,
@@ -1585,40 +1824,34 @@ fn from_meta<'tcx>(
             }
         };
 
-        let file_type = metadata.file_type();
-
-        let mode_name = if file_type.is_file() {
-            "S_IFREG"
-        } else if file_type.is_dir() {
-            "S_IFDIR"
-        } else {
-            "S_IFLNK"
+        let mode_name = match metadata.kind {
+            FileKind::File => "S_IFREG",
+            FileKind::Dir => "S_IFDIR",
+            _ => "S_IFLNK",
         };
 
-        let mode = ecx.eval_libc(mode_name);
-
-        let size = metadata.len();
-
-        let created = extract_sec_and_nsec(metadata.created())?;
-        let accessed = extract_sec_and_nsec(metadata.accessed())?;
-        let modified = extract_sec_and_nsec(metadata.modified())?;
-
-        // FIXME: Provide more fields using platform specific methods.
-
-        cfg_select! {
-            unix => {
-                use std::os::unix::fs::MetadataExt;
-                let dev = metadata.dev();
-                let uid = metadata.uid();
-                let gid = metadata.gid();
-            }
-            _ => {
-                let dev = 0;
-                let uid = 0;
-                let gid = 0;
-            }
-        }
-
-        interp_ok(Ok(FileMetadata { mode, size, created, accessed, modified, dev, uid, gid }))
+        // Only the in-memory file system tells us the permission bits.
+        let mode_size = ecx.libc_ty_layout("mode_t").size;
+        let mode = ecx.eval_libc(mode_name).to_uint(mode_size)?
+            | u128::from(metadata.permissions.unwrap_or(0));
+        let mode = Scalar::from_uint(mode, mode_size);
+
+        let size = metadata.len;
+
+        let created = extract_sec_and_nsec(metadata.created)?;
+        let accessed = extract_sec_and_nsec(metadata.accessed)?;
+        let modified = extract_sec_and_nsec(metadata.modified)?;
+
+        interp_ok(Ok(FileMetadata {
+            mode,
+            size,
+            created,
+            accessed,
+            modified,
+            ino: metadata.ino,
+            dev: metadata.dev,
+            uid: metadata.uid,
+            gid: metadata.gid,
+        }))
     }
 }
@@ -4,6 +4,7 @@
 mod fd;
 mod fs;
 mod mem;
+mod socket;
 mod sync;
 mod thread;
 mod unnamed_socket;
@@ -21,9 +22,10 @@
 pub use self::fs::{DirTable, EvalContextExt as _};
 pub use self::linux_like::epoll::EpollInterestTable;
 pub use self::mem::EvalContextExt as _;
+pub use self::socket::{EvalContextExt as _, LoopbackNetwork};
 pub use self::sync::EvalContextExt as _;
 pub use self::thread::{EvalContextExt as _, ThreadNameResult};
 pub use self::unnamed_socket::EvalContextExt as _;
 
 // Make up some constants.
-const UID: u32 = 1000;
+pub(crate) const UID: u32 = 1000;
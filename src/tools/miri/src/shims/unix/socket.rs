@@ -0,0 +1,1418 @@
+//! This implements `AF_INET` and `AF_INET6` sockets on a loopback network that exists entirely
+//! inside Miri, so programs can talk to themselves over TCP and UDP without touching the host.
+//! Only loopback and unspecified addresses are supported. Connections are established
+//! immediately on `connect`, which keeps scheduling deterministic and works under isolation.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, VecDeque};
+use std::io;
+use std::io::ErrorKind;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::ops::RangeInclusive;
+
+use rand::Rng;
+use rustc_abi::Size;
+
+use crate::concurrency::VClock;
+use crate::shims::files::{
+    EvalContextExt as _, FileDescription, FileDescriptionRef, WeakFileDescriptionRef,
+};
+use crate::shims::unix::UnixFileDescription;
+use crate::shims::unix::linux_like::epoll::{EpollReadyEvents, EvalContextExt as _};
+use crate::*;
+
+/// The maximum number of bytes buffered for reading on a socket.
+/// This number is arbitrary as the value can always
+/// be configured in the real system.
+const MAX_SOCKET_BUFFER_CAPACITY: usize = 212992;
+
+/// The ports handed out when binding to port 0. This is the range suggested by IANA.
+const EPHEMERAL_PORTS: RangeInclusive<u16> = 49152..=65535;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SocketKind {
+    /// `SOCK_STREAM`, i.e. TCP.
+    Stream,
+    /// `SOCK_DGRAM`, i.e. UDP.
+    Datagram,
+}
+
+/// The addresses in use on the loopback network.
+#[derive(Debug)]
+pub struct LoopbackNetwork {
+    /// All sockets bound to a local address. Entries of closed sockets are removed lazily.
+    bound: BTreeMap<(SocketKind, SocketAddr), WeakFileDescriptionRef<Socket>>,
+    /// The next port to try when binding to port 0.
+    next_ephemeral_port: u16,
+}
+
+impl LoopbackNetwork {
+    pub fn new() -> Self {
+        LoopbackNetwork { bound: BTreeMap::new(), next_ephemeral_port: *EPHEMERAL_PORTS.start() }
+    }
+
+    /// Finds the socket that receives what is sent to `addr`: either the one bound to exactly that
+    /// address, or the one bound to the unspecified address of the same family and port.
+    fn lookup(&self, kind: SocketKind, addr: SocketAddr) -> Option<FileDescriptionRef<Socket>> {
+        let get = |addr| self.bound.get(&(kind, addr)).and_then(|socket| socket.upgrade());
+        get(addr).or_else(|| get(SocketAddr::new(unspecified_ip(addr.is_ipv6()), addr.port())))
+    }
+
+    /// Whether binding a socket to `addr` conflicts with an open socket.
+    fn is_in_use(&self, kind: SocketKind, addr: SocketAddr) -> bool {
+        self.bound.iter().any(|(&(bound_kind, bound_addr), socket)| {
+            bound_kind == kind
+                && bound_addr.is_ipv6() == addr.is_ipv6()
+                && bound_addr.port() == addr.port()
+                && (bound_addr.ip() == addr.ip()
+                    || bound_addr.ip().is_unspecified()
+                    || addr.ip().is_unspecified())
+                && socket.upgrade().is_some()
+        })
+    }
+
+    /// Binds `socket` to `addr`, picking a free ephemeral port if the port is 0.
+    /// Returns the address the socket ended up bound to.
+    fn bind(
+        &mut self,
+        socket: &FileDescriptionRef<Socket>,
+        mut addr: SocketAddr,
+    ) -> Result<SocketAddr, IoError> {
+        if addr.port() == 0 {
+            // Ports are handed out round-robin, so that a port is not reused right after its
+            // socket was closed.
+            let mut free_port = None;
+            for _ in EPHEMERAL_PORTS {
+                let port = self.next_ephemeral_port;
+                self.next_ephemeral_port = if port == *EPHEMERAL_PORTS.end() {
+                    *EPHEMERAL_PORTS.start()
+                } else {
+                    port.strict_add(1)
+                };
+                if !self.is_in_use(socket.kind, SocketAddr::new(addr.ip(), port)) {
+                    free_port = Some(port);
+                    break;
+                }
+            }
+            let Some(port) = free_port else {
+                return Err(LibcError("EADDRINUSE"));
+            };
+            addr.set_port(port);
+        } else if self.is_in_use(socket.kind, addr) {
+            return Err(LibcError("EADDRINUSE"));
+        }
+        self.bound.insert((socket.kind, addr), FileDescriptionRef::downgrade(socket));
+        Ok(addr)
+    }
+
+    /// Forgets the addresses of sockets that have been closed.
+    fn remove_closed(&mut self) {
+        self.bound.retain(|_, socket| socket.upgrade().is_some());
+    }
+}
+
+fn unspecified_ip(is_ipv6: bool) -> IpAddr {
+    if is_ipv6 { Ipv6Addr::UNSPECIFIED.into() } else { Ipv4Addr::UNSPECIFIED.into() }
+}
+
+fn loopback_ip(is_ipv6: bool) -> IpAddr {
+    if is_ipv6 { Ipv6Addr::LOCALHOST.into() } else { Ipv4Addr::LOCALHOST.into() }
+}
+
+/// Whether `ip` refers to this host, which is the only host on the loopback network.
+fn is_local_ip(ip: IpAddr) -> bool {
+    ip.is_loopback() || ip.is_unspecified()
+}
+
+#[derive(Debug)]
+enum SocketState {
+    /// Neither listening nor connected, but possibly bound.
+    Idle,
+    /// A listening stream socket, with the connections that have not been accepted yet.
+    Listening(VecDeque<PendingConnection>),
+    /// A connected stream socket. The peer is gone once it has been closed.
+    Connected { peer: WeakFileDescriptionRef<Socket>, peer_addr: SocketAddr },
+    /// A datagram socket with a default destination.
+    DatagramConnected(SocketAddr),
+}
+
+/// A connection to a listening socket that has not been accepted yet.
+#[derive(Debug)]
+struct PendingConnection {
+    /// The listener's end of the connection, which `accept` returns.
+    server: FileDescriptionRef<Socket>,
+    /// The clock of the `connect`, so that `accept` can synchronize with it.
+    clock: VClock,
+}
+
+#[derive(Debug, Default)]
+struct Buffer {
+    /// The received bytes of a stream socket.
+    bytes: VecDeque<u8>,
+    /// The received datagrams of a datagram socket, with their source address.
+    datagrams: VecDeque<(SocketAddr, Vec<u8>)>,
+    /// The joined clocks of all writes to this buffer, so that reads can synchronize with them.
+    clock: VClock,
+}
+
+impl Buffer {
+    fn len(&self) -> usize {
+        self.bytes
+            .len()
+            .strict_add(self.datagrams.iter().map(|(_, data)| data.len()).sum::<usize>())
+    }
+}
+
+/// An `AF_INET` or `AF_INET6` socket on the loopback network.
+#[derive(Debug)]
+struct Socket {
+    kind: SocketKind,
+    /// Whether this is an `AF_INET6` socket.
+    is_ipv6: bool,
+    /// The address this socket is bound to, if any.
+    local_addr: Cell<Option<SocketAddr>>,
+    state: RefCell<SocketState>,
+    /// The data we are reading from. Our peers write into this buffer.
+    readbuf: RefCell<Buffer>,
+    /// Whether reading has been shut down with `shutdown`.
+    read_closed: Cell<bool>,
+    /// Whether writing has been shut down with `shutdown`. The peer then reads EOF.
+    write_closed: Cell<bool>,
+    /// The integer options set with `setsockopt`, by level and name.
+    options: RefCell<BTreeMap<(i32, i32), i32>>,
+    /// A list of thread ids blocked because there was nothing to read or accept.
+    /// Once something arrives, these threads will be unblocked.
+    blocked_read_tid: RefCell<Vec<ThreadId>>,
+    /// A list of thread ids blocked because the peer's buffer was full.
+    /// Once the peer reads some bytes, these threads will be unblocked.
+    blocked_write_tid: RefCell<Vec<ThreadId>>,
+    /// Whether this fd is non-blocking or not.
+    is_nonblock: Cell<bool>,
+}
+
+impl Socket {
+    fn new(kind: SocketKind, is_ipv6: bool, is_nonblock: bool) -> Self {
+        Socket {
+            kind,
+            is_ipv6,
+            local_addr: Cell::new(None),
+            state: RefCell::new(SocketState::Idle),
+            readbuf: RefCell::new(Buffer::default()),
+            read_closed: Cell::new(false),
+            write_closed: Cell::new(false),
+            options: RefCell::new(BTreeMap::new()),
+            blocked_read_tid: RefCell::new(Vec::new()),
+            blocked_write_tid: RefCell::new(Vec::new()),
+            is_nonblock: Cell::new(is_nonblock),
+        }
+    }
+
+    /// Returns the peer of a connected stream socket, `Ok(None)` if the peer has been closed,
+    /// or `Err(())` if the socket is not a connected stream socket.
+    fn stream_peer(&self) -> Result<Option<FileDescriptionRef<Socket>>, ()> {
+        match &*self.state.borrow() {
+            SocketState::Connected { peer, .. } => Ok(peer.upgrade()),
+            _ => Err(()),
+        }
+    }
+}
+
+impl FileDescription for Socket {
+    fn name(&self) -> &'static str {
+        "socket"
+    }
+
+    fn close<'tcx>(
+        self,
+        _communicate_allowed: bool,
+        ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<()>> {
+        let peers: Vec<FileDescriptionRef<Socket>> = match self.state.into_inner() {
+            SocketState::Connected { peer, .. } => peer.upgrade().into_iter().collect(),
+            // Connections that were not accepted yet are dropped, so their clients see EOF.
+            SocketState::Listening(pending) =>
+                pending
+                    .iter()
+                    .filter_map(|pending| pending.server.stream_peer().ok().flatten())
+                    .collect(),
+            SocketState::Idle | SocketState::DatagramConnected(_) => Vec::new(),
+        };
+        for peer in peers {
+            // Reads of the peer now return EOF, and its writes fail.
+            wake_up(ecx, &peer.blocked_read_tid)?;
+            wake_up(ecx, &peer.blocked_write_tid)?;
+            ecx.check_and_update_readiness(peer)?;
+        }
+        ecx.machine.loopback_network.remove_closed();
+        interp_ok(Ok(()))
+    }
+
+    fn read<'tcx>(
+        self: FileDescriptionRef<Self>,
+        _communicate_allowed: bool,
+        ptr: Pointer,
+        len: usize,
+        ecx: &mut MiriInterpCx<'tcx>,
+        finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+    ) -> InterpResult<'tcx> {
+        match self.kind {
+            SocketKind::Stream => stream_read(self, ptr, len, ecx, finish),
+            SocketKind::Datagram => datagram_recv(self, ptr, len, None, ecx, finish),
+        }
+    }
+
+    fn write<'tcx>(
+        self: FileDescriptionRef<Self>,
+        _communicate_allowed: bool,
+        ptr: Pointer,
+        len: usize,
+        ecx: &mut MiriInterpCx<'tcx>,
+        finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+    ) -> InterpResult<'tcx> {
+        match self.kind {
+            SocketKind::Stream => stream_write(self, ptr, len, ecx, finish),
+            SocketKind::Datagram => {
+                let result = ecx.datagram_send(&self, ptr, len, None)?;
+                finish.call(ecx, result)
+            }
+        }
+    }
+
+    fn nondet_short_accesses(&self) -> bool {
+        // Shortening a datagram access would truncate the datagram.
+        self.kind == SocketKind::Stream
+    }
+
+    fn as_unix<'tcx>(&self, _ecx: &MiriInterpCx<'tcx>) -> &dyn UnixFileDescription {
+        self
+    }
+
+    fn get_flags<'tcx>(&self, ecx: &mut MiriInterpCx<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let mut flags = ecx.eval_libc_i32("O_RDWR");
+        if self.is_nonblock.get() {
+            flags |= ecx.eval_libc_i32("O_NONBLOCK");
+        }
+        interp_ok(Scalar::from_i32(flags))
+    }
+
+    fn set_flags<'tcx>(
+        &self,
+        mut flag: i32,
+        ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let o_nonblock = ecx.eval_libc_i32("O_NONBLOCK");
+        let o_rdwr = ecx.eval_libc_i32("O_RDWR");
+
+        // O_NONBLOCK flag can be set / unset by user.
+        self.is_nonblock.set(flag & o_nonblock == o_nonblock);
+        flag &= !o_nonblock;
+        // Ignore the file access mode.
+        flag &= !o_rdwr;
+
+        if flag != 0 {
+            throw_unsup_format!("fcntl: only O_NONBLOCK is supported for F_SETFL on sockets")
+        }
+        interp_ok(Scalar::from_i32(0))
+    }
+}
+
+impl UnixFileDescription for Socket {
+    fn get_epoll_ready_events<'tcx>(&self) -> InterpResult<'tcx, EpollReadyEvents> {
+        let mut ready = EpollReadyEvents::new();
+        match &*self.state.borrow() {
+            SocketState::Idle if self.kind == SocketKind::Stream => {
+                // Like Linux, report an unconnected stream socket as hung up, but writable.
+                ready.epollout = true;
+                ready.epollhup = true;
+            }
+            SocketState::Listening(pending) => {
+                ready.epollin = !pending.is_empty();
+            }
+            SocketState::Connected { peer, .. } => {
+                let peer = peer.upgrade();
+                let peer_write_closed = peer.as_ref().is_none_or(|peer| peer.write_closed.get());
+                ready.epollin = !self.readbuf.borrow().bytes.is_empty()
+                    || peer_write_closed
+                    || self.read_closed.get();
+                ready.epollrdhup = peer_write_closed;
+                ready.epollout = match &peer {
+                    Some(peer) if !self.write_closed.get() =>
+                        peer.readbuf.borrow().bytes.len() < MAX_SOCKET_BUFFER_CAPACITY,
+                    // Writes fail without blocking.
+                    _ => true,
+                };
+                ready.epollhup = peer.is_none() || (self.write_closed.get() && peer_write_closed);
+            }
+            SocketState::Idle | SocketState::DatagramConnected(_) => {
+                ready.epollin =
+                    !self.readbuf.borrow().datagrams.is_empty() || self.read_closed.get();
+                // Sending a datagram never blocks.
+                ready.epollout = true;
+            }
+        }
+        interp_ok(ready)
+    }
+}
+
+/// Unblocks all the given threads, so that they retry their operation.
+fn wake_up<'tcx>(
+    ecx: &mut MiriInterpCx<'tcx>,
+    threads: &RefCell<Vec<ThreadId>>,
+) -> InterpResult<'tcx> {
+    let waiting_threads = std::mem::take(&mut *threads.borrow_mut());
+    // FIXME: We can randomize the order of unblocking.
+    for thread_id in waiting_threads {
+        ecx.unblock_thread(thread_id, BlockReason::Socket)?;
+    }
+    interp_ok(())
+}
+
+/// Read from a connected stream socket and return the number of bytes read.
+fn stream_read<'tcx>(
+    self_ref: FileDescriptionRef<Socket>,
+    ptr: Pointer,
+    len: usize,
+    ecx: &mut MiriInterpCx<'tcx>,
+    finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+) -> InterpResult<'tcx> {
+    let Ok(peer) = self_ref.stream_peer() else {
+        return finish.call(ecx, Err(LibcError("ENOTCONN")));
+    };
+    // Always succeed on read size 0.
+    if len == 0 {
+        return finish.call(ecx, Ok(0));
+    }
+
+    if self_ref.readbuf.borrow().bytes.is_empty() {
+        if self_ref.read_closed.get() || peer.as_ref().is_none_or(|peer| peer.write_closed.get()) {
+            // Nothing more will arrive, 0 bytes successfully read indicates end-of-file.
+            return finish.call(ecx, Ok(0));
+        } else if self_ref.is_nonblock.get() {
+            return finish.call(ecx, Err(ErrorKind::WouldBlock.into()));
+        }
+        self_ref.blocked_read_tid.borrow_mut().push(ecx.active_thread());
+        // Block the current thread; only keep a weak ref for this.
+        let weak_self_ref = FileDescriptionRef::downgrade(&self_ref);
+        ecx.block_thread(
+            BlockReason::Socket,
+            None,
+            callback!(
+                @capture<'tcx> {
+                    weak_self_ref: WeakFileDescriptionRef<Socket>,
+                    ptr: Pointer,
+                    len: usize,
+                    finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+                }
+                |this, unblock: UnblockKind| {
+                    assert_eq!(unblock, UnblockKind::Ready);
+                    let Some(self_ref) = weak_self_ref.upgrade() else {
+                        return finish.call(this, Err(LibcError("EBADF")));
+                    };
+                    stream_read(self_ref, ptr, len, this, finish)
+                }
+            ),
+        );
+        return interp_ok(());
+    }
+
+    // There's data to be read!
+    let mut readbuf = self_ref.readbuf.borrow_mut();
+    // Synchronize with all previous writes to this buffer.
+    ecx.acquire_clock(&readbuf.clock);
+    let read_size = ecx.read_from_host(&mut readbuf.bytes, len, ptr)?.unwrap();
+    drop(readbuf);
+
+    if let Some(peer) = peer {
+        // The peer can write again.
+        wake_up(ecx, &peer.blocked_write_tid)?;
+        ecx.check_and_update_readiness(peer)?;
+    }
+    finish.call(ecx, Ok(read_size))
+}
+
+/// Write to a connected stream socket and return the number of bytes written.
+fn stream_write<'tcx>(
+    self_ref: FileDescriptionRef<Socket>,
+    ptr: Pointer,
+    len: usize,
+    ecx: &mut MiriInterpCx<'tcx>,
+    finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+) -> InterpResult<'tcx> {
+    let Ok(peer) = self_ref.stream_peer() else {
+        return finish.call(ecx, Err(LibcError("ENOTCONN")));
+    };
+    let Some(peer) = peer.filter(|_| !self_ref.write_closed.get()) else {
+        // The connection has been closed in this direction.
+        return finish.call(ecx, Err(ErrorKind::BrokenPipe.into()));
+    };
+    // Always succeed on write size 0.
+    if len == 0 {
+        return finish.call(ecx, Ok(0));
+    }
+    if peer.read_closed.get() {
+        // Nobody will ever read this, so we can just as well drop it.
+        return finish.call(ecx, Ok(len));
+    }
+
+    let available_space = MAX_SOCKET_BUFFER_CAPACITY.strict_sub(peer.readbuf.borrow().bytes.len());
+    if available_space == 0 {
+        if self_ref.is_nonblock.get() {
+            return finish.call(ecx, Err(ErrorKind::WouldBlock.into()));
+        }
+        self_ref.blocked_write_tid.borrow_mut().push(ecx.active_thread());
+        // Block the current thread; only keep a weak ref for this.
+        let weak_self_ref = FileDescriptionRef::downgrade(&self_ref);
+        ecx.block_thread(
+            BlockReason::Socket,
+            None,
+            callback!(
+                @capture<'tcx> {
+                    weak_self_ref: WeakFileDescriptionRef<Socket>,
+                    ptr: Pointer,
+                    len: usize,
+                    finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+                }
+                |this, unblock: UnblockKind| {
+                    assert_eq!(unblock, UnblockKind::Ready);
+                    let Some(self_ref) = weak_self_ref.upgrade() else {
+                        return finish.call(this, Err(LibcError("EBADF")));
+                    };
+                    stream_write(self_ref, ptr, len, this, finish)
+                }
+            ),
+        );
+        return interp_ok(());
+    }
+
+    // There is space to write!
+    let mut writebuf = peer.readbuf.borrow_mut();
+    // Remember this clock so `read` can synchronize with us.
+    ecx.release_clock(|clock| {
+        writebuf.clock.join(clock);
+    });
+    // Do full write / partial write based on the space available.
+    let write_size = len.min(available_space);
+    let actual_write_size = ecx.write_to_host(&mut writebuf.bytes, write_size, ptr)?.unwrap();
+    assert_eq!(actual_write_size, write_size);
+    drop(writebuf);
+
+    // The peer can read now.
+    wake_up(ecx, &peer.blocked_read_tid)?;
+    ecx.check_and_update_readiness(peer)?;
+    finish.call(ecx, Ok(write_size))
+}
+
+/// Receive a datagram and return its length, or `len` if it had to be truncated.
+/// If `from` is given, the source address is stored there like `recvfrom` does.
+fn datagram_recv<'tcx>(
+    self_ref: FileDescriptionRef<Socket>,
+    ptr: Pointer,
+    len: usize,
+    from: Option<(Pointer, Pointer)>,
+    ecx: &mut MiriInterpCx<'tcx>,
+    finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+) -> InterpResult<'tcx> {
+    let mut readbuf = self_ref.readbuf.borrow_mut();
+    let datagram = readbuf.datagrams.pop_front();
+    if datagram.is_some() {
+        // Synchronize with all previous sends to this socket.
+        ecx.acquire_clock(&readbuf.clock);
+    }
+    drop(readbuf);
+
+    let Some((source, data)) = datagram else {
+        if self_ref.read_closed.get() {
+            return finish.call(ecx, Ok(0));
+        } else if self_ref.is_nonblock.get() {
+            return finish.call(ecx, Err(ErrorKind::WouldBlock.into()));
+        }
+        self_ref.blocked_read_tid.borrow_mut().push(ecx.active_thread());
+        // Block the current thread; only keep a weak ref for this.
+        let weak_self_ref = FileDescriptionRef::downgrade(&self_ref);
+        ecx.block_thread(
+            BlockReason::Socket,
+            None,
+            callback!(
+                @capture<'tcx> {
+                    weak_self_ref: WeakFileDescriptionRef<Socket>,
+                    ptr: Pointer,
+                    len: usize,
+                    from: Option<(Pointer, Pointer)>,
+                    finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+                }
+                |this, unblock: UnblockKind| {
+                    assert_eq!(unblock, UnblockKind::Ready);
+                    let Some(self_ref) = weak_self_ref.upgrade() else {
+                        return finish.call(this, Err(LibcError("EBADF")));
+                    };
+                    datagram_recv(self_ref, ptr, len, from, this, finish)
+                }
+            ),
+        );
+        return interp_ok(());
+    };
+
+    // The part of the datagram that does not fit into the buffer is discarded.
+    let read_size = len.min(data.len());
+    ecx.write_bytes_ptr(ptr, data[..read_size].iter().copied())?;
+    if let Some((address, address_len)) = from {
+        ecx.write_socket_addr(source, address, address_len)?;
+    }
+    finish.call(ecx, Ok(read_size))
+}
+
+/// Accept a connection on a listening socket and store the new file descriptor in `dest`.
+fn socket_accept<'tcx>(
+    listener: FileDescriptionRef<Socket>,
+    address: Pointer,
+    address_len: Pointer,
+    is_nonblock: bool,
+    dest: MPlaceTy<'tcx>,
+    ecx: &mut MiriInterpCx<'tcx>,
+) -> InterpResult<'tcx> {
+    let connection = match &mut *listener.state.borrow_mut() {
+        SocketState::Listening(pending) => Some(pending.pop_front()),
+        _ => None,
+    };
+    let Some(connection) = connection else {
+        return ecx.set_last_error_and_return(LibcError("EINVAL"), &dest);
+    };
+
+    let Some(PendingConnection { server: connection, clock }) = connection else {
+        if listener.is_nonblock.get() {
+            return ecx.set_last_error_and_return(ErrorKind::WouldBlock, &dest);
+        }
+        listener.blocked_read_tid.borrow_mut().push(ecx.active_thread());
+        // Block the current thread; only keep a weak ref for this.
+        let weak_listener = FileDescriptionRef::downgrade(&listener);
+        ecx.block_thread(
+            BlockReason::Socket,
+            None,
+            callback!(
+                @capture<'tcx> {
+                    weak_listener: WeakFileDescriptionRef<Socket>,
+                    address: Pointer,
+                    address_len: Pointer,
+                    is_nonblock: bool,
+                    dest: MPlaceTy<'tcx>,
+                }
+                |this, unblock: UnblockKind| {
+                    assert_eq!(unblock, UnblockKind::Ready);
+                    let Some(listener) = weak_listener.upgrade() else {
+                        return this.set_last_error_and_return(LibcError("EBADF"), &dest);
+                    };
+                    socket_accept(listener, address, address_len, is_nonblock, dest, this)
+                }
+            ),
+        );
+        return interp_ok(());
+    };
+
+    // Synchronize with the `connect` of this connection.
+    ecx.acquire_clock(&clock);
+    connection.is_nonblock.set(is_nonblock);
+    let SocketState::Connected { peer_addr, .. } = *connection.state.borrow() else {
+        unreachable!("pending connections are always connected")
+    };
+    if !ecx.ptr_is_null(address)? {
+        ecx.write_socket_addr(peer_addr, address, address_len)?;
+    }
+    let fd = ecx.machine.fds.insert(connection);
+    ecx.write_int(fd, &dest)
+}
+
+impl<'tcx> EvalContextPrivExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+trait EvalContextPrivExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Returns the socket behind a file descriptor.
+    fn get_socket(&self, fd_num: i32) -> Result<FileDescriptionRef<Socket>, IoError> {
+        let this = self.eval_context_ref();
+        let Some(fd) = this.machine.fds.get(fd_num) else {
+            return Err(LibcError("EBADF"));
+        };
+        fd.downcast::<Socket>().ok_or(LibcError("ENOTSOCK"))
+    }
+
+    /// Binds an unbound socket to an ephemeral port on `ip`, like the kernel does when a socket
+    /// is used before being bound.
+    fn autobind(
+        &mut self,
+        socket: &FileDescriptionRef<Socket>,
+        ip: IpAddr,
+    ) -> Result<SocketAddr, IoError> {
+        let this = self.eval_context_mut();
+        if let Some(addr) = socket.local_addr.get() {
+            return Ok(addr);
+        }
+        let addr = this.machine.loopback_network.bind(socket, SocketAddr::new(ip, 0))?;
+        socket.local_addr.set(Some(addr));
+        Ok(addr)
+    }
+
+    /// Reads a `sockaddr_in` or `sockaddr_in6` of `address_len` bytes, which has to match the
+    /// address family of the socket.
+    fn read_socket_addr(
+        &self,
+        address: Pointer,
+        address_len: u32,
+        is_ipv6: bool,
+    ) -> InterpResult<'tcx, Result<SocketAddr, IoError>> {
+        let this = self.eval_context_ref();
+        let address_len = u64::from(address_len);
+
+        let sockaddr = this.ptr_to_mplace(address, this.libc_ty_layout("sockaddr"));
+        if address_len < sockaddr.layout.size.bytes() {
+            return interp_ok(Err(LibcError("EINVAL")));
+        }
+        let family = this.project_field_named(&sockaddr, "sa_family")?;
+        let family = this.read_scalar(&family)?.to_uint(family.layout.size)?;
+        let expected_family = this.eval_libc_i32(if is_ipv6 { "AF_INET6" } else { "AF_INET" });
+        if family != u128::try_from(expected_family).unwrap() {
+            return interp_ok(Err(LibcError("EAFNOSUPPORT")));
+        }
+
+        let read_bytes = move |place: &MPlaceTy<'tcx>| {
+            this.read_bytes_ptr_strip_provenance(place.ptr(), place.layout.size)
+        };
+        let addr = if is_ipv6 {
+            let sockaddr = this.ptr_to_mplace(address, this.libc_ty_layout("sockaddr_in6"));
+            if address_len < sockaddr.layout.size.bytes() {
+                return interp_ok(Err(LibcError("EINVAL")));
+            }
+            let port = read_bytes(&this.project_field_named(&sockaddr, "sin6_port")?)?;
+            let ip = this.project_field_named(&sockaddr, "sin6_addr")?;
+            let ip = read_bytes(&this.project_field_named(&ip, "s6_addr")?)?;
+            let flowinfo = this.project_field_named(&sockaddr, "sin6_flowinfo")?;
+            let scope_id = this.project_field_named(&sockaddr, "sin6_scope_id")?;
+            SocketAddr::V6(SocketAddrV6::new(
+                Ipv6Addr::from(<[u8; 16]>::try_from(ip).unwrap()),
+                u16::from_be_bytes(port.try_into().unwrap()),
+                this.read_scalar(&flowinfo)?.to_u32()?,
+                this.read_scalar(&scope_id)?.to_u32()?,
+            ))
+        } else {
+            let sockaddr = this.ptr_to_mplace(address, this.libc_ty_layout("sockaddr_in"));
+            if address_len < sockaddr.layout.size.bytes() {
+                return interp_ok(Err(LibcError("EINVAL")));
+            }
+            let port = read_bytes(&this.project_field_named(&sockaddr, "sin_port")?)?;
+            let ip = this.project_field_named(&sockaddr, "sin_addr")?;
+            let ip = read_bytes(&this.project_field_named(&ip, "s_addr")?)?;
+            SocketAddr::V4(SocketAddrV4::new(
+                Ipv4Addr::from(<[u8; 4]>::try_from(ip).unwrap()),
+                u16::from_be_bytes(port.try_into().unwrap()),
+            ))
+        };
+        interp_ok(Ok(addr))
+    }
+
+    /// Stores `addr` as a `sockaddr_in` or `sockaddr_in6` in the `address` buffer, whose size is
+    /// read from `address_len`. The address is truncated if it does not fit, and its full size is
+    /// stored in `address_len`.
+    fn write_socket_addr(
+        &mut self,
+        addr: SocketAddr,
+        address: Pointer,
+        address_len: Pointer,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let address_len = this.ptr_to_mplace(address_len, this.libc_ty_layout("socklen_t"));
+        let capacity = this.read_scalar(&address_len)?.to_u32()?;
+
+        // Assemble the address in a temporary allocation, so that it can be truncated.
+        let layout =
+            this.libc_ty_layout(if addr.is_ipv6() { "sockaddr_in6" } else { "sockaddr_in" });
+        let sockaddr = this.allocate(layout, MiriMemoryKind::Machine.into())?;
+        this.write_bytes_ptr(sockaddr.ptr(), std::iter::repeat_n(0, layout.size.bytes_usize()))?;
+        match addr {
+            SocketAddr::V4(addr) => {
+                // BSDs store the length of the address in the address.
+                if let Some(len) = this.try_project_field_named(&sockaddr, "sin_len")? {
+                    this.write_int(layout.size.bytes(), &len)?;
+                }
+                let family = this.project_field_named(&sockaddr, "sin_family")?;
+                this.write_int(this.eval_libc_i32("AF_INET"), &family)?;
+                let port = this.project_field_named(&sockaddr, "sin_port")?;
+                this.write_bytes_ptr(port.ptr(), addr.port().to_be_bytes())?;
+                let ip = this.project_field_named(&sockaddr, "sin_addr")?;
+                let ip = this.project_field_named(&ip, "s_addr")?;
+                this.write_bytes_ptr(ip.ptr(), addr.ip().octets())?;
+            }
+            SocketAddr::V6(addr) => {
+                // BSDs store the length of the address in the address.
+                if let Some(len) = this.try_project_field_named(&sockaddr, "sin6_len")? {
+                    this.write_int(layout.size.bytes(), &len)?;
+                }
+                let family = this.project_field_named(&sockaddr, "sin6_family")?;
+                this.write_int(this.eval_libc_i32("AF_INET6"), &family)?;
+                let port = this.project_field_named(&sockaddr, "sin6_port")?;
+                this.write_bytes_ptr(port.ptr(), addr.port().to_be_bytes())?;
+                let flowinfo = this.project_field_named(&sockaddr, "sin6_flowinfo")?;
+                this.write_int(addr.flowinfo(), &flowinfo)?;
+                let ip = this.project_field_named(&sockaddr, "sin6_addr")?;
+                let ip = this.project_field_named(&ip, "s6_addr")?;
+                this.write_bytes_ptr(ip.ptr(), addr.ip().octets())?;
+                let scope_id = this.project_field_named(&sockaddr, "sin6_scope_id")?;
+                this.write_int(addr.scope_id(), &scope_id)?;
+            }
+        }
+
+        let copy_size = Size::from_bytes(capacity).min(layout.size);
+        this.mem_copy(sockaddr.ptr(), address, copy_size, /*nonoverlapping*/ true)?;
+        this.deallocate_ptr(sockaddr.ptr(), None, MiriMemoryKind::Machine.into())?;
+        this.write_int(layout.size.bytes(), &address_len)
+    }
+
+    /// Sends a datagram to `to`, or to the address the socket is connected to.
+    fn datagram_send(
+        &mut self,
+        socket: &FileDescriptionRef<Socket>,
+        ptr: Pointer,
+        len: usize,
+        to: Option<SocketAddr>,
+    ) -> InterpResult<'tcx, Result<usize, IoError>> {
+        let this = self.eval_context_mut();
+
+        let connected_addr = match *socket.state.borrow() {
+            SocketState::DatagramConnected(addr) => Some(addr),
+            _ => None,
+        };
+        let Some(mut to) = to.or(connected_addr) else {
+            return interp_ok(Err(LibcError("EDESTADDRREQ")));
+        };
+        if socket.write_closed.get() {
+            return interp_ok(Err(ErrorKind::BrokenPipe.into()));
+        }
+        if !is_local_ip(to.ip()) {
+            return interp_ok(Err(LibcError("ENETUNREACH")));
+        }
+        if to.ip().is_unspecified() {
+            // Sending to the unspecified address sends to this host.
+            to.set_ip(loopback_ip(socket.is_ipv6));
+        }
+
+        // Sending from an unbound socket binds it, so that the receiver can reply.
+        let mut from = match this.autobind(socket, unspecified_ip(socket.is_ipv6)) {
+            Ok(addr) => addr,
+            Err(e) => return interp_ok(Err(e)),
+        };
+        if from.ip().is_unspecified() {
+            from.set_ip(loopback_ip(socket.is_ipv6));
+        }
+
+        let data = this.read_bytes_ptr_strip_provenance(ptr, Size::from_bytes(len))?.to_vec();
+        // Datagrams to a port nobody listens on, or to a socket whose buffer is full, are
+        // silently dropped, like on a real network.
+        if let Some(receiver) = this.machine.loopback_network.lookup(SocketKind::Datagram, to) {
+            let mut writebuf = receiver.readbuf.borrow_mut();
+            if writebuf.len().strict_add(len) > MAX_SOCKET_BUFFER_CAPACITY {
+                return interp_ok(Ok(len));
+            }
+            // Remember this clock so `recv` can synchronize with us.
+            this.release_clock(|clock| {
+                writebuf.clock.join(clock);
+            });
+            writebuf.datagrams.push_back((from, data));
+            drop(writebuf);
+
+            wake_up(this, &receiver.blocked_read_tid)?;
+            this.check_and_update_readiness(receiver)?;
+        }
+        interp_ok(Ok(len))
+    }
+
+    /// Returns the integer options we support for `setsockopt` and `getsockopt`, with their
+    /// default values.
+    fn socket_int_options(&self) -> Vec<((i32, i32), i32)> {
+        let this = self.eval_context_ref();
+        let sol_socket = this.eval_libc_i32("SOL_SOCKET");
+        let mut options = vec![
+            ((sol_socket, this.eval_libc_i32("SO_REUSEADDR")), 0),
+            ((sol_socket, this.eval_libc_i32("SO_KEEPALIVE")), 0),
+            ((sol_socket, this.eval_libc_i32("SO_BROADCAST")), 0),
+            ((this.eval_libc_i32("IPPROTO_TCP"), this.eval_libc_i32("TCP_NODELAY")), 0),
+            ((this.eval_libc_i32("IPPROTO_IP"), this.eval_libc_i32("IP_TTL")), 64),
+            ((this.eval_libc_i32("IPPROTO_IPV6"), this.eval_libc_i32("IPV6_V6ONLY")), 0),
+        ];
+        if matches!(&*this.tcx.sess.target.os, "macos" | "freebsd") {
+            // `std` sets this on every socket to avoid `SIGPIPE`, which we never raise anyway.
+            options.push(((sol_socket, this.eval_libc_i32("SO_NOSIGPIPE")), 0));
+        }
+        options
+    }
+}
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+pub trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// For more information on the arguments see the socket manpage:
+    /// <https://man7.org/linux/man-pages/man2/socket.2.html>
+    fn socket(
+        &mut self,
+        domain: &OpTy<'tcx>,
+        type_: &OpTy<'tcx>,
+        protocol: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let domain = this.read_scalar(domain)?.to_i32()?;
+        let mut flags = this.read_scalar(type_)?.to_i32()?;
+        let protocol = this.read_scalar(protocol)?.to_i32()?;
+
+        let is_ipv6 = if domain == this.eval_libc_i32("AF_INET") {
+            false
+        } else if domain == this.eval_libc_i32("AF_INET6") {
+            true
+        } else {
+            throw_unsup_format!(
+                "socket: domain {domain:#x} is unsupported, only AF_INET and AF_INET6 are allowed"
+            );
+        };
+
+        // Interpret the flag. Every flag we recognize is "subtracted" from `flags`, so
+        // if there is anything left at the end, that's an unsupported flag.
+        let mut is_nonblock = false;
+        if this.tcx.sess.target.os != "macos" {
+            // SOCK_NONBLOCK and SOCK_CLOEXEC don't exist on macOS.
+            let sock_nonblock = this.eval_libc_i32("SOCK_NONBLOCK");
+            let sock_cloexec = this.eval_libc_i32("SOCK_CLOEXEC");
+            if flags & sock_nonblock == sock_nonblock {
+                is_nonblock = true;
+                flags &= !sock_nonblock;
+            }
+            // As usual we ignore CLOEXEC.
+            if flags & sock_cloexec == sock_cloexec {
+                flags &= !sock_cloexec;
+            }
+        }
+
+        let kind = if flags == this.eval_libc_i32("SOCK_STREAM") {
+            SocketKind::Stream
+        } else if flags == this.eval_libc_i32("SOCK_DGRAM") {
+            SocketKind::Datagram
+        } else {
+            throw_unsup_format!(
+                "socket: type {flags:#x} is unsupported, only SOCK_STREAM, SOCK_DGRAM, \
+                 SOCK_CLOEXEC and SOCK_NONBLOCK are allowed"
+            );
+        };
+        let default_protocol = match kind {
+            SocketKind::Stream => this.eval_libc_i32("IPPROTO_TCP"),
+            SocketKind::Datagram => this.eval_libc_i32("IPPROTO_UDP"),
+        };
+        if protocol != 0 && protocol != default_protocol {
+            throw_unsup_format!("socket: socket protocol {protocol} is unsupported");
+        }
+
+        let fd = this.machine.fds.insert_new(Socket::new(kind, is_ipv6, is_nonblock));
+        interp_ok(Scalar::from_i32(fd))
+    }
+
+    fn bind(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        address: &OpTy<'tcx>,
+        address_len: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        let address = this.read_pointer(address)?;
+        let address_len = this.read_scalar(address_len)?.to_u32()?;
+
+        let socket = match this.get_socket(socket) {
+            Ok(socket) => socket,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+        let addr = match this.read_socket_addr(address, address_len, socket.is_ipv6)? {
+            Ok(addr) => addr,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+        if socket.local_addr.get().is_some() {
+            return this.set_last_error_and_return_i32(LibcError("EINVAL"));
+        }
+        if !is_local_ip(addr.ip()) {
+            // There is no other host on the loopback network.
+            return this.set_last_error_and_return_i32(LibcError("EADDRNOTAVAIL"));
+        }
+        match this.machine.loopback_network.bind(&socket, addr) {
+            Ok(addr) => socket.local_addr.set(Some(addr)),
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        }
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn listen(&mut self, socket: &OpTy<'tcx>, backlog: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        // Connections are never refused because of a full backlog, so we ignore it.
+        let _backlog = this.read_scalar(backlog)?.to_i32()?;
+
+        let socket = match this.get_socket(socket) {
+            Ok(socket) => socket,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+        if socket.kind != SocketKind::Stream {
+            return this.set_last_error_and_return_i32(LibcError("EOPNOTSUPP"));
+        }
+        match *socket.state.borrow() {
+            SocketState::Idle => {}
+            SocketState::Listening(_) => return interp_ok(Scalar::from_i32(0)),
+            _ => return this.set_last_error_and_return_i32(LibcError("EINVAL")),
+        }
+        // Listening on an unbound socket binds it to any address.
+        if let Err(e) = this.autobind(&socket, unspecified_ip(socket.is_ipv6)) {
+            return this.set_last_error_and_return_i32(e);
+        }
+        *socket.state.borrow_mut() = SocketState::Listening(VecDeque::new());
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn connect(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        address: &OpTy<'tcx>,
+        address_len: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        let address = this.read_pointer(address)?;
+        let address_len = this.read_scalar(address_len)?.to_u32()?;
+
+        let socket = match this.get_socket(socket) {
+            Ok(socket) => socket,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+        let mut addr = match this.read_socket_addr(address, address_len, socket.is_ipv6)? {
+            Ok(addr) => addr,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+        if !is_local_ip(addr.ip()) {
+            // There is no other host on the loopback network.
+            return this.set_last_error_and_return_i32(LibcError("ENETUNREACH"));
+        }
+        if addr.ip().is_unspecified() {
+            // Connecting to the unspecified address connects to this host.
+            addr.set_ip(loopback_ip(socket.is_ipv6));
+        }
+
+        if socket.kind == SocketKind::Datagram {
+            // This just sets the default destination of the socket.
+            if let Err(e) = this.autobind(&socket, unspecified_ip(socket.is_ipv6)) {
+                return this.set_last_error_and_return_i32(e);
+            }
+            *socket.state.borrow_mut() = SocketState::DatagramConnected(addr);
+            return interp_ok(Scalar::from_i32(0));
+        }
+
+        match *socket.state.borrow() {
+            SocketState::Idle => {}
+            SocketState::Connected { .. } =>
+                return this.set_last_error_and_return_i32(LibcError("EISCONN")),
+            _ => return this.set_last_error_and_return_i32(LibcError("EINVAL")),
+        }
+        let Some(listener) = this
+            .machine
+            .loopback_network
+            .lookup(SocketKind::Stream, addr)
+            .filter(|listener| matches!(*listener.state.borrow(), SocketState::Listening(_)))
+        else {
+            return this.set_last_error_and_return_i32(LibcError("ECONNREFUSED"));
+        };
+        let local_addr = match this.autobind(&socket, addr.ip()) {
+            Ok(local_addr) => local_addr,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+
+        // Create the listener's end of the connection, which `accept` will return.
+        let server =
+            this.machine.fds.new_ref(Socket::new(SocketKind::Stream, socket.is_ipv6, false));
+        server.local_addr.set(Some(addr));
+        *server.state.borrow_mut() = SocketState::Connected {
+            peer: FileDescriptionRef::downgrade(&socket),
+            peer_addr: local_addr,
+        };
+        *socket.state.borrow_mut() = SocketState::Connected {
+            peer: FileDescriptionRef::downgrade(&server),
+            peer_addr: addr,
+        };
+        // Remember this clock so `accept` can synchronize with us.
+        let mut clock = VClock::default();
+        this.release_clock(|release| clock.join(release));
+        match &mut *listener.state.borrow_mut() {
+            SocketState::Listening(pending) =>
+                pending.push_back(PendingConnection { server, clock }),
+            _ => unreachable!(),
+        }
+
+        // The connection can be accepted now.
+        wake_up(this, &listener.blocked_read_tid)?;
+        this.check_and_update_readiness(listener)?;
+        // The socket became writable.
+        this.check_and_update_readiness(socket)?;
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    /// For more information on the arguments see the accept manpage:
+    /// <https://man7.org/linux/man-pages/man2/accept.2.html>
+    fn accept4(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        address: &OpTy<'tcx>,
+        address_len: &OpTy<'tcx>,
+        flags: Option<&OpTy<'tcx>>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        let address = this.read_pointer(address)?;
+        let address_len = this.read_pointer(address_len)?;
+        let mut flags = match flags {
+            Some(flags) => this.read_scalar(flags)?.to_i32()?,
+            None => 0,
+        };
+
+        let mut is_nonblock = false;
+        if flags != 0 {
+            let sock_nonblock = this.eval_libc_i32("SOCK_NONBLOCK");
+            let sock_cloexec = this.eval_libc_i32("SOCK_CLOEXEC");
+            if flags & sock_nonblock == sock_nonblock {
+                is_nonblock = true;
+                flags &= !sock_nonblock;
+            }
+            // As usual we ignore CLOEXEC.
+            if flags & sock_cloexec == sock_cloexec {
+                flags &= !sock_cloexec;
+            }
+            if flags != 0 {
+                throw_unsup_format!("unsupported flags in `accept4`");
+            }
+        }
+
+        let socket = match this.get_socket(socket) {
+            Ok(socket) => socket,
+            Err(e) => return this.set_last_error_and_return(e, dest),
+        };
+        socket_accept(socket, address, address_len, is_nonblock, dest.clone(), this)
+    }
+
+    /// Implements `send` and, if `address` is given, `sendto`.
+    fn send(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        buf: &OpTy<'tcx>,
+        len: &OpTy<'tcx>,
+        flags: &OpTy<'tcx>,
+        address: Option<(&OpTy<'tcx>, &OpTy<'tcx>)>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        let buf = this.read_pointer(buf)?;
+        let len = this.read_target_usize(len)?;
+        let mut flags = this.read_scalar(flags)?.to_i32()?;
+
+        if this.tcx.sess.target.os != "macos" {
+            // We never raise `SIGPIPE`, so this flag changes nothing.
+            flags &= !this.eval_libc_i32("MSG_NOSIGNAL");
+        }
+        if flags != 0 {
+            throw_unsup_format!("send: flags {flags:#x} are unsupported");
+        }
+
+        let socket = match this.get_socket(socket) {
+            Ok(socket) => socket,
+            Err(e) => return this.set_last_error_and_return(e, dest),
+        };
+        // Check that the *entire* buffer is actually valid memory.
+        this.check_ptr_access(buf, Size::from_bytes(len), CheckInAllocMsg::MemoryAccess)?;
+        // The result has to fit into an `isize`.
+        let len = len.min(u64::try_from(this.target_isize_max()).unwrap());
+        let len = usize::try_from(len).unwrap();
+        // Non-deterministically decide to further reduce the length, simulating a partial access
+        // like `read` and `write` do.
+        let len =
+            if socket.nondet_short_accesses() && len >= 2 && this.machine.rng.get_mut().random() {
+                len / 2
+            } else {
+                len
+            };
+
+        let finish = io_finish(dest);
+        match socket.kind {
+            // The destination of a connected stream socket is fixed.
+            SocketKind::Stream => stream_write(socket, buf, len, this, finish),
+            SocketKind::Datagram => {
+                let mut to = None;
+                if let Some((address, address_len)) = address {
+                    let address = this.read_pointer(address)?;
+                    let address_len = this.read_scalar(address_len)?.to_u32()?;
+                    if !this.ptr_is_null(address)? {
+                        match this.read_socket_addr(address, address_len, socket.is_ipv6)? {
+                            Ok(addr) => to = Some(addr),
+                            Err(e) => return this.set_last_error_and_return(e, dest),
+                        }
+                    }
+                }
+                let result = this.datagram_send(&socket, buf, len, to)?;
+                finish.call(this, result)
+            }
+        }
+    }
+
+    /// Implements `recv` and, if `address` is given, `recvfrom`.
+    fn recv(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        buf: &OpTy<'tcx>,
+        len: &OpTy<'tcx>,
+        flags: &OpTy<'tcx>,
+        address: Option<(&OpTy<'tcx>, &OpTy<'tcx>)>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        let buf = this.read_pointer(buf)?;
+        let len = this.read_target_usize(len)?;
+        let flags = this.read_scalar(flags)?.to_i32()?;
+
+        if flags != 0 {
+            throw_unsup_format!("recv: flags {flags:#x} are unsupported");
+        }
+
+        let socket = match this.get_socket(socket) {
+            Ok(socket) => socket,
+            Err(e) => return this.set_last_error_and_return(e, dest),
+        };
+        // Check that the *entire* buffer is actually valid memory.
+        this.check_ptr_access(buf, Size::from_bytes(len), CheckInAllocMsg::MemoryAccess)?;
+        // The result has to fit into an `isize`.
+        let len = len.min(u64::try_from(this.target_isize_max()).unwrap());
+        let len = usize::try_from(len).unwrap();
+        // Non-deterministically decide to further reduce the length, simulating a partial access
+        // like `read` and `write` do.
+        let len =
+            if socket.nondet_short_accesses() && len >= 2 && this.machine.rng.get_mut().random() {
+                len / 2
+            } else {
+                len
+            };
+
+        let finish = io_finish(dest);
+        match socket.kind {
+            // A stream socket only has one source, so the address is not filled in.
+            SocketKind::Stream => stream_read(socket, buf, len, this, finish),
+            SocketKind::Datagram => {
+                let mut from = None;
+                if let Some((address, address_len)) = address {
+                    let address = this.read_pointer(address)?;
+                    let address_len = this.read_pointer(address_len)?;
+                    if !this.ptr_is_null(address)? {
+                        from = Some((address, address_len));
+                    }
+                }
+                datagram_recv(socket, buf, len, from, this, finish)
+            }
+        }
+    }
+
+    fn shutdown(&mut self, socket: &OpTy<'tcx>, how: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        let how = this.read_scalar(how)?.to_i32()?;
+
+        let socket = match this.get_socket(socket) {
+            Ok(socket) => socket,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+        let (shut_read, shut_write) = if how == this.eval_libc_i32("SHUT_RD") {
+            (true, false)
+        } else if how == this.eval_libc_i32("SHUT_WR") {
+            (false, true)
+        } else if how == this.eval_libc_i32("SHUT_RDWR") {
+            (true, true)
+        } else {
+            return this.set_last_error_and_return_i32(LibcError("EINVAL"));
+        };
+
+        let peer = match *socket.state.borrow() {
+            SocketState::Connected { ref peer, .. } => peer.upgrade(),
+            SocketState::DatagramConnected(_) => None,
+            _ => return this.set_last_error_and_return_i32(LibcError("ENOTCONN")),
+        };
+        if shut_read {
+            socket.read_closed.set(true);
+        }
+        if shut_write {
+            socket.write_closed.set(true);
+        }
+
+        // Blocked reads of this socket and the peer might return EOF now, and blocked writes fail.
+        wake_up(this, &socket.blocked_read_tid)?;
+        wake_up(this, &socket.blocked_write_tid)?;
+        this.check_and_update_readiness(socket)?;
+        if let Some(peer) = peer {
+            wake_up(this, &peer.blocked_read_tid)?;
+            this.check_and_update_readiness(peer)?;
+        }
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn getsockname(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        address: &OpTy<'tcx>,
+        address_len: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        let address = this.read_pointer(address)?;
+        let address_len = this.read_pointer(address_len)?;
+
+        let socket = match this.get_socket(socket) {
+            Ok(socket) => socket,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+        // An unbound socket reports the unspecified address.
+        let addr = socket
+            .local_addr
+            .get()
+            .unwrap_or_else(|| SocketAddr::new(unspecified_ip(socket.is_ipv6), 0));
+        this.write_socket_addr(addr, address, address_len)?;
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn getpeername(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        address: &OpTy<'tcx>,
+        address_len: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        let address = this.read_pointer(address)?;
+        let address_len = this.read_pointer(address_len)?;
+
+        let socket = match this.get_socket(socket) {
+            Ok(socket) => socket,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+        let addr = match *socket.state.borrow() {
+            SocketState::Connected { peer_addr, .. } => peer_addr,
+            SocketState::DatagramConnected(addr) => addr,
+            _ => return this.set_last_error_and_return_i32(LibcError("ENOTCONN")),
+        };
+        this.write_socket_addr(addr, address, address_len)?;
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn setsockopt(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        level: &OpTy<'tcx>,
+        option_name: &OpTy<'tcx>,
+        option_value: &OpTy<'tcx>,
+        option_len: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        let level = this.read_scalar(level)?.to_i32()?;
+        let option_name = this.read_scalar(option_name)?.to_i32()?;
+        let option_value = this.read_pointer(option_value)?;
+        let option_len = this.read_scalar(option_len)?.to_u32()?;
+
+        let socket = match this.get_socket(socket) {
+            Ok(socket) => socket,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+        let option = (level, option_name);
+        if !this.socket_int_options().iter().any(|&(supported, _)| supported == option) {
+            throw_unsup_format!("setsockopt: option {option_name} at level {level} is unsupported");
+        }
+        if u64::from(option_len) < this.machine.layouts.i32.size.bytes() {
+            return this.set_last_error_and_return_i32(LibcError("EINVAL"));
+        }
+        let value = this.ptr_to_mplace(option_value, this.machine.layouts.i32);
+        let value = this.read_scalar(&value)?.to_i32()?;
+        socket.options.borrow_mut().insert(option, value);
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn getsockopt(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        level: &OpTy<'tcx>,
+        option_name: &OpTy<'tcx>,
+        option_value: &OpTy<'tcx>,
+        option_len: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let socket = this.read_scalar(socket)?.to_i32()?;
+        let level = this.read_scalar(level)?.to_i32()?;
+        let option_name = this.read_scalar(option_name)?.to_i32()?;
+        let option_value = this.read_pointer(option_value)?;
+        let option_len = this.read_pointer(option_len)?;
+
+        let socket = match this.get_socket(socket) {
+            Ok(socket) => socket,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+        let option = (level, option_name);
+        let value = if level == this.eval_libc_i32("SOL_SOCKET")
+            && option_name == this.eval_libc_i32("SO_ERROR")
+        {
+            // Errors are always reported right away, so there is never a pending one.
+            0
+        } else if level == this.eval_libc_i32("SOL_SOCKET")
+            && option_name == this.eval_libc_i32("SO_TYPE")
+        {
+            match socket.kind {
+                SocketKind::Stream => this.eval_libc_i32("SOCK_STREAM"),
+                SocketKind::Datagram => this.eval_libc_i32("SOCK_DGRAM"),
+            }
+        } else if let Some(&(_, default)) =
+            this.socket_int_options().iter().find(|&&(supported, _)| supported == option)
+        {
+            socket.options.borrow().get(&option).copied().unwrap_or(default)
+        } else {
+            throw_unsup_format!("getsockopt: option {option_name} at level {level} is unsupported");
+        };
+
+        let option_len = this.ptr_to_mplace(option_len, this.libc_ty_layout("socklen_t"));
+        let capacity = this.read_scalar(&option_len)?.to_u32()?;
+        let size = this.machine.layouts.i32.size;
+        if u64::from(capacity) < size.bytes() {
+            return this.set_last_error_and_return_i32(LibcError("EINVAL"));
+        }
+        let value_place = this.ptr_to_mplace(option_value, this.machine.layouts.i32);
+        this.write_scalar(Scalar::from_i32(value), &value_place)?;
+        this.write_int(size.bytes(), &option_len)?;
+        interp_ok(Scalar::from_i32(0))
+    }
+}
+
+/// Returns a callback that stores the result of a socket operation in `dest`, like `read` does.
+fn io_finish<'tcx>(dest: &MPlaceTy<'tcx>) -> DynMachineCallback<'tcx, Result<usize, IoError>> {
+    let dest = dest.clone();
+    callback!(
+        @capture<'tcx> {
+            dest: MPlaceTy<'tcx>,
+        }
+        |this, result: Result<usize, IoError>| {
+            match result {
+                // This must fit since the length was capped to `isize::MAX`.
+                Ok(size) => this.write_int(u64::try_from(size).unwrap(), &dest),
+                Err(e) => this.set_last_error_and_return(e, &dest),
+            }
+        }
+    )
+}
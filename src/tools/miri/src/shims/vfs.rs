@@ -0,0 +1,681 @@
+//! An in-memory file system that replaces the host file system when `-Zmiri-vfs` is set.
+//!
+//! This makes file system access available in isolation mode, and makes programs that use the file
+//! system behave the same regardless of the state of the host. The only time the host file system
+//! is consulted is on startup, when `-Zmiri-vfs-seed` asks us to copy a host directory.
+//!
+//! Paths are given in host syntax, like for all other file system shims. Since there is no working
+//! directory in isolation mode, relative paths are resolved against the root, and path prefixes
+//! (such as Windows drive letters) are ignored.
+
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::io::{self, ErrorKind, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+use std::time::SystemTime;
+
+use rustc_abi::Size;
+
+use crate::shims::files::{FileDescription, FileDescriptionRef, FileKind, Metadata};
+use crate::shims::unix::UnixFileDescription;
+use crate::*;
+
+/// The permission bits that are removed from the mode of newly created files and directories,
+/// like the usual default `umask`.
+const UMASK: u32 = 0o022;
+
+/// How many symlinks we follow while resolving a single path before failing with `ELOOP`.
+const MAX_SYMLINKS: usize = 40;
+
+/// The largest size a file can grow to. Files are stored contiguously in memory, so without a
+/// limit a single `ftruncate` or `pwrite` at a large offset would abort the interpreter; beyond
+/// this, writes and truncations fail with `EFBIG`.
+const MAX_FILE_SIZE: u64 = 1 << 30;
+
+type NodeRef = Rc<RefCell<Node>>;
+
+#[derive(Debug)]
+enum NodeContent {
+    File(Vec<u8>),
+    /// The entries of a directory, not including `.` and `..`.
+    Dir(BTreeMap<OsString, NodeRef>),
+    Symlink(PathBuf),
+}
+
+/// A file, directory or symlink. Nodes are shared between their parent directory and all open
+/// file descriptions, so a file that is removed while it is open stays around until it is closed.
+#[derive(Debug)]
+struct Node {
+    content: NodeContent,
+    /// The inode number, unique among all nodes of the file system.
+    ino: u64,
+    /// The permission bits of the mode.
+    permissions: u32,
+    created: SystemTime,
+    accessed: SystemTime,
+    modified: SystemTime,
+}
+
+impl Node {
+    fn kind(&self) -> FileKind {
+        match self.content {
+            NodeContent::File(_) => FileKind::File,
+            NodeContent::Dir(_) => FileKind::Dir,
+            NodeContent::Symlink(_) => FileKind::Symlink,
+        }
+    }
+
+    fn metadata(&self) -> Metadata {
+        let len = match &self.content {
+            NodeContent::File(bytes) => bytes.len(),
+            NodeContent::Dir(_) => 0,
+            NodeContent::Symlink(target) => target.as_os_str().len(),
+        };
+        Metadata {
+            kind: self.kind(),
+            len: len.try_into().unwrap(),
+            permissions: Some(self.permissions),
+            created: Some(self.created),
+            accessed: Some(self.accessed),
+            modified: Some(self.modified),
+            ino: self.ino,
+            dev: 0,
+            uid: shims::unix::UID,
+            gid: shims::unix::UID,
+        }
+    }
+
+    fn entries(&self) -> io::Result<&BTreeMap<OsString, NodeRef>> {
+        match &self.content {
+            NodeContent::Dir(entries) => Ok(entries),
+            _ => Err(ErrorKind::NotADirectory.into()),
+        }
+    }
+
+    fn entries_mut(&mut self) -> io::Result<&mut BTreeMap<OsString, NodeRef>> {
+        match &mut self.content {
+            NodeContent::Dir(entries) => Ok(entries),
+            _ => Err(ErrorKind::NotADirectory.into()),
+        }
+    }
+
+    /// Ensures that this is a directory in which we may add and remove entries.
+    fn check_dir_writable(&self) -> io::Result<()> {
+        self.entries()?;
+        if self.permissions & 0o200 == 0 {
+            return Err(ErrorKind::PermissionDenied.into());
+        }
+        Ok(())
+    }
+
+    fn touch(&mut self, now: SystemTime) {
+        self.accessed = now;
+        self.modified = now;
+    }
+}
+
+/// Returns whether `node` is `descendant` or one of its ancestors.
+fn is_ancestor(node: &NodeRef, descendant: &NodeRef) -> bool {
+    if Rc::ptr_eq(node, descendant) {
+        return true;
+    }
+    match &node.borrow().content {
+        NodeContent::Dir(entries) => entries.values().any(|entry| is_ancestor(entry, descendant)),
+        _ => false,
+    }
+}
+
+/// A path component that still needs to be resolved.
+enum Step {
+    Root,
+    Parent,
+    Name(OsString),
+}
+
+fn steps(path: &Path) -> impl DoubleEndedIterator<Item = Step> {
+    path.components().filter_map(|component| {
+        match component {
+            Component::Prefix(_) | Component::CurDir => None,
+            Component::RootDir => Some(Step::Root),
+            Component::ParentDir => Some(Step::Parent),
+            Component::Normal(name) => Some(Step::Name(name.to_owned())),
+        }
+    })
+}
+
+/// Returns the current time, to be used for timestamps. This is based on the monotone clock
+/// rather than the system time, so that timestamps are deterministic in isolation mode.
+pub fn now(ecx: &MiriInterpCx<'_>) -> SystemTime {
+    let clock = &ecx.machine.monotonic_clock;
+    SystemTime::UNIX_EPOCH.checked_add(clock.now().duration_since(clock.epoch())).unwrap()
+}
+
+/// Options for opening a file, mirroring [`std::fs::OpenOptions`].
+#[derive(Debug, Default)]
+pub struct OpenOptions {
+    pub read: bool,
+    pub write: bool,
+    pub append: bool,
+    pub truncate: bool,
+    pub create: bool,
+    pub create_new: bool,
+    /// Fail instead of following a symlink in the last component of the path.
+    pub nofollow: bool,
+    /// The permission bits of a newly created file, before applying the umask.
+    pub mode: u32,
+}
+
+/// An entry returned when listing a directory.
+#[derive(Debug)]
+pub struct DirEntry {
+    pub name: OsString,
+    pub ino: u64,
+    pub kind: FileKind,
+}
+
+/// The in-memory file system.
+#[derive(Debug)]
+pub struct Vfs {
+    root: NodeRef,
+    /// The inode number to use for the next node.
+    next_ino: u64,
+}
+
+impl Vfs {
+    /// Creates a file system with the contents of the host directory `seed`, if given, and an
+    /// (initially empty) `/tmp` directory.
+    pub fn new(seed: Option<&Path>) -> io::Result<Vfs> {
+        let mut vfs = Vfs {
+            root: Rc::new(RefCell::new(Node {
+                content: NodeContent::Dir(BTreeMap::new()),
+                ino: 1,
+                permissions: 0o755,
+                created: SystemTime::UNIX_EPOCH,
+                accessed: SystemTime::UNIX_EPOCH,
+                modified: SystemTime::UNIX_EPOCH,
+            })),
+            next_ino: 2,
+        };
+        let root = vfs.root.clone();
+        if let Some(seed) = seed {
+            vfs.seed_dir(&root, seed)?;
+        }
+        if !root.borrow().entries()?.contains_key(OsStr::new("tmp")) {
+            // Everyone may create files in `/tmp`, but only remove their own ones.
+            let tmp =
+                vfs.new_node(NodeContent::Dir(BTreeMap::new()), 0o1777, SystemTime::UNIX_EPOCH);
+            root.borrow_mut().entries_mut()?.insert("tmp".into(), tmp);
+        }
+        Ok(vfs)
+    }
+
+    fn new_node(&mut self, content: NodeContent, permissions: u32, now: SystemTime) -> NodeRef {
+        let ino = self.next_ino;
+        self.next_ino = self.next_ino.strict_add(1);
+        Rc::new(RefCell::new(Node {
+            content,
+            ino,
+            permissions,
+            created: now,
+            accessed: now,
+            modified: now,
+        }))
+    }
+
+    /// Copies the contents of the host directory `host_dir` into `dir`. The timestamps of the host
+    /// files are not copied, so that they cannot make the program behave differently.
+    fn seed_dir(&mut self, dir: &NodeRef, host_dir: &Path) -> io::Result<()> {
+        // Sort the entries, so that inode numbers do not depend on the order the host lists them in.
+        let mut entries = fs::read_dir(host_dir)?.collect::<io::Result<Vec<_>>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+        for entry in entries {
+            let path = entry.path();
+            let meta = fs::symlink_metadata(&path)?;
+            let file_type = meta.file_type();
+            let content = if file_type.is_symlink() {
+                NodeContent::Symlink(fs::read_link(&path)?)
+            } else if file_type.is_dir() {
+                NodeContent::Dir(BTreeMap::new())
+            } else {
+                NodeContent::File(fs::read(&path)?)
+            };
+
+            cfg_select! {
+                unix => {
+                    use std::os::unix::fs::PermissionsExt;
+                    let permissions = meta.permissions().mode() & 0o7777;
+                }
+                _ => {
+                    let permissions = match (file_type.is_dir(), meta.permissions().readonly()) {
+                        (true, false) => 0o755,
+                        (true, true) => 0o555,
+                        (false, false) => 0o644,
+                        (false, true) => 0o444,
+                    };
+                }
+            }
+
+            let node = self.new_node(content, permissions, SystemTime::UNIX_EPOCH);
+            if file_type.is_dir() {
+                self.seed_dir(&node, &path)?;
+            }
+            dir.borrow_mut().entries_mut()?.insert(entry.file_name(), node);
+        }
+        Ok(())
+    }
+
+    /// Resolves `path`, and returns all nodes on the way from the root to the node it refers to,
+    /// together with their names. Symlinks are followed, except in the last component of the path
+    /// if `follow_last` is false.
+    fn walk(&self, path: &Path, follow_last: bool) -> io::Result<Vec<(OsString, NodeRef)>> {
+        if path.as_os_str().is_empty() {
+            return Err(ErrorKind::NotFound.into());
+        }
+        let mut stack = vec![(OsString::new(), self.root.clone())];
+        // The steps that still need to be taken, in reverse order.
+        let mut pending = steps(path).rev().collect::<Vec<_>>();
+        let mut symlinks: usize = 0;
+        while let Some(step) = pending.pop() {
+            let dir = stack.last().unwrap().1.clone();
+            match step {
+                Step::Root => stack.truncate(1),
+                Step::Parent => {
+                    dir.borrow().entries()?;
+                    // `..` of the root is the root itself.
+                    if stack.len() > 1 {
+                        stack.pop();
+                    }
+                }
+                Step::Name(name) => {
+                    let node =
+                        dir.borrow().entries()?.get(&name).cloned().ok_or(ErrorKind::NotFound)?;
+                    let target = match &node.borrow().content {
+                        NodeContent::Symlink(target) if follow_last || !pending.is_empty() =>
+                            Some(target.clone()),
+                        _ => None,
+                    };
+                    if let Some(target) = target {
+                        symlinks = symlinks.strict_add(1);
+                        if symlinks > MAX_SYMLINKS {
+                            return Err(ErrorKind::FilesystemLoop.into());
+                        }
+                        // A relative target is resolved starting at the directory containing the
+                        // symlink, which is where we are now.
+                        pending.extend(steps(&target).rev());
+                    } else {
+                        stack.push((name, node));
+                    }
+                }
+            }
+        }
+        Ok(stack)
+    }
+
+    fn lookup(&self, path: &Path, follow_last: bool) -> io::Result<NodeRef> {
+        Ok(self.walk(path, follow_last)?.pop().unwrap().1)
+    }
+
+    /// Resolves the directory containing the last component of `path`, and returns it together
+    /// with the name of that last component.
+    fn lookup_parent(&self, path: &Path) -> io::Result<(NodeRef, OsString)> {
+        let name = path.file_name().ok_or(ErrorKind::InvalidInput)?;
+        let parent = path.parent().unwrap();
+        let parent = if parent.as_os_str().is_empty() { Path::new(".") } else { parent };
+        let dir = self.lookup(parent, true)?;
+        dir.borrow().entries()?;
+        Ok((dir, name.to_owned()))
+    }
+
+    pub fn open(
+        &mut self,
+        path: &Path,
+        options: &OpenOptions,
+        now: SystemTime,
+    ) -> io::Result<VfsFile> {
+        let node = match self.lookup(path, !options.nofollow) {
+            Ok(_) if options.create_new => return Err(ErrorKind::AlreadyExists.into()),
+            Ok(node) => node,
+            Err(err)
+                if err.kind() == ErrorKind::NotFound && (options.create || options.create_new) =>
+            {
+                let (parent, name) = self.lookup_parent(path)?;
+                let mut parent = parent.borrow_mut();
+                if parent.entries()?.contains_key(&name) {
+                    // The last component is a dangling symlink.
+                    return Err(if options.create_new {
+                        ErrorKind::AlreadyExists.into()
+                    } else {
+                        err
+                    });
+                }
+                parent.check_dir_writable()?;
+                let node = self.new_node(NodeContent::File(Vec::new()), options.mode & !UMASK, now);
+                parent.entries_mut()?.insert(name, node.clone());
+                parent.touch(now);
+                return Ok(VfsFile::new(node, options));
+            }
+            Err(err) => return Err(err),
+        };
+
+        let mut file = node.borrow_mut();
+        match file.content {
+            NodeContent::Symlink(_) => return Err(ErrorKind::FilesystemLoop.into()),
+            NodeContent::Dir(_) if options.write || options.append =>
+                return Err(ErrorKind::IsADirectory.into()),
+            _ => {}
+        }
+        if (options.read && file.permissions & 0o400 == 0)
+            || ((options.write || options.append) && file.permissions & 0o200 == 0)
+        {
+            return Err(ErrorKind::PermissionDenied.into());
+        }
+        if options.truncate && options.write {
+            if let NodeContent::File(bytes) = &mut file.content {
+                bytes.clear();
+            }
+            file.touch(now);
+        }
+        drop(file);
+        Ok(VfsFile::new(node, options))
+    }
+
+    pub fn metadata(&self, path: &Path, follow_symlinks: bool) -> io::Result<Metadata> {
+        Ok(self.lookup(path, follow_symlinks)?.borrow().metadata())
+    }
+
+    pub fn read_dir(&self, path: &Path) -> io::Result<Vec<DirEntry>> {
+        let dir = self.lookup(path, true)?;
+        let dir = dir.borrow();
+        let entries = dir.entries()?;
+        if dir.permissions & 0o400 == 0 {
+            return Err(ErrorKind::PermissionDenied.into());
+        }
+        Ok(entries
+            .iter()
+            .map(|(name, node)| {
+                let node = node.borrow();
+                DirEntry { name: name.clone(), ino: node.ino, kind: node.kind() }
+            })
+            .collect())
+    }
+
+    pub fn create_dir(&mut self, path: &Path, mode: u32, now: SystemTime) -> io::Result<()> {
+        let (parent, name) = self.lookup_parent(path)?;
+        let mut parent = parent.borrow_mut();
+        if parent.entries()?.contains_key(&name) {
+            return Err(ErrorKind::AlreadyExists.into());
+        }
+        parent.check_dir_writable()?;
+        let node = self.new_node(NodeContent::Dir(BTreeMap::new()), mode & !UMASK, now);
+        parent.entries_mut()?.insert(name, node);
+        parent.touch(now);
+        Ok(())
+    }
+
+    pub fn remove_dir(&mut self, path: &Path, now: SystemTime) -> io::Result<()> {
+        let (parent, name) = self.lookup_parent(path)?;
+        let mut parent = parent.borrow_mut();
+        let node = parent.entries()?.get(&name).cloned().ok_or(ErrorKind::NotFound)?;
+        if !node.borrow().entries()?.is_empty() {
+            return Err(ErrorKind::DirectoryNotEmpty.into());
+        }
+        parent.check_dir_writable()?;
+        parent.entries_mut()?.remove(&name);
+        parent.touch(now);
+        Ok(())
+    }
+
+    pub fn remove_file(&mut self, path: &Path, now: SystemTime) -> io::Result<()> {
+        let (parent, name) = self.lookup_parent(path)?;
+        let mut parent = parent.borrow_mut();
+        let node = parent.entries()?.get(&name).cloned().ok_or(ErrorKind::NotFound)?;
+        if node.borrow().kind() == FileKind::Dir {
+            return Err(ErrorKind::IsADirectory.into());
+        }
+        parent.check_dir_writable()?;
+        parent.entries_mut()?.remove(&name);
+        parent.touch(now);
+        Ok(())
+    }
+
+    pub fn rename(&mut self, from: &Path, to: &Path, now: SystemTime) -> io::Result<()> {
+        let (from_parent, from_name) = self.lookup_parent(from)?;
+        let (to_parent, to_name) = self.lookup_parent(to)?;
+        let node =
+            from_parent.borrow().entries()?.get(&from_name).cloned().ok_or(ErrorKind::NotFound)?;
+        from_parent.borrow().check_dir_writable()?;
+        to_parent.borrow().check_dir_writable()?;
+
+        let is_dir = node.borrow().kind() == FileKind::Dir;
+        if let Some(existing) = to_parent.borrow().entries()?.get(&to_name) {
+            if Rc::ptr_eq(existing, &node) {
+                return Ok(());
+            }
+            // A directory can only replace an empty directory, and a file only a file.
+            match (&existing.borrow().content, is_dir) {
+                (NodeContent::Dir(_), false) => return Err(ErrorKind::IsADirectory.into()),
+                (NodeContent::Dir(entries), true) if !entries.is_empty() =>
+                    return Err(ErrorKind::DirectoryNotEmpty.into()),
+                (NodeContent::File(_) | NodeContent::Symlink(_), true) =>
+                    return Err(ErrorKind::NotADirectory.into()),
+                _ => {}
+            }
+        }
+        if is_dir && is_ancestor(&node, &to_parent) {
+            // A directory cannot become its own subdirectory.
+            return Err(ErrorKind::InvalidInput.into());
+        }
+
+        from_parent.borrow_mut().entries_mut()?.remove(&from_name);
+        from_parent.borrow_mut().touch(now);
+        to_parent.borrow_mut().entries_mut()?.insert(to_name, node);
+        to_parent.borrow_mut().touch(now);
+        Ok(())
+    }
+
+    pub fn symlink(&mut self, target: &Path, link: &Path, now: SystemTime) -> io::Result<()> {
+        let (parent, name) = self.lookup_parent(link)?;
+        let mut parent = parent.borrow_mut();
+        if parent.entries()?.contains_key(&name) {
+            return Err(ErrorKind::AlreadyExists.into());
+        }
+        parent.check_dir_writable()?;
+        let node = self.new_node(NodeContent::Symlink(target.to_owned()), 0o777, now);
+        parent.entries_mut()?.insert(name, node);
+        parent.touch(now);
+        Ok(())
+    }
+
+    pub fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        match &self.lookup(path, false)?.borrow().content {
+            NodeContent::Symlink(target) => Ok(target.clone()),
+            _ => Err(ErrorKind::InvalidInput.into()),
+        }
+    }
+
+    /// Returns the absolute path of the node `path` refers to, without any symlinks or `..`.
+    pub fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        let mut result = PathBuf::from(std::path::MAIN_SEPARATOR_STR);
+        result.extend(self.walk(path, true)?.into_iter().skip(1).map(|(name, _)| name));
+        Ok(result)
+    }
+
+    pub fn set_permissions(&mut self, path: &Path, mode: u32) -> io::Result<()> {
+        self.lookup(path, true)?.borrow_mut().permissions = mode & 0o7777;
+        Ok(())
+    }
+}
+
+/// A file or directory of the in-memory file system that was opened by the program.
+#[derive(Debug)]
+pub struct VfsFile {
+    node: NodeRef,
+    readable: bool,
+    writable: bool,
+    append: bool,
+    /// The position of the cursor for `read`, `write` and `seek`.
+    cursor: Cell<u64>,
+}
+
+impl VfsFile {
+    fn new(node: NodeRef, options: &OpenOptions) -> Self {
+        VfsFile {
+            node,
+            readable: options.read,
+            writable: options.write || options.append,
+            append: options.append,
+            cursor: Cell::new(0),
+        }
+    }
+
+    /// Reads up to `len` bytes starting at `offset`, without moving the cursor.
+    pub fn read_at(&self, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+        if !self.readable {
+            // Like `FileHandle`, we use `PermissionDenied` as a platform-independent stand-in for
+            // the `EBADF` that Unix would return.
+            return Err(ErrorKind::PermissionDenied.into());
+        }
+        match &self.node.borrow().content {
+            NodeContent::File(bytes) => {
+                let start = usize::try_from(offset).unwrap_or(usize::MAX).min(bytes.len());
+                let end = start.saturating_add(len).min(bytes.len());
+                Ok(bytes[start..end].to_vec())
+            }
+            NodeContent::Dir(_) => Err(ErrorKind::IsADirectory.into()),
+            NodeContent::Symlink(_) => unreachable!("symlinks cannot be opened"),
+        }
+    }
+
+    /// Writes `data` starting at `offset`, without moving the cursor. Writing past the end of the
+    /// file fills the gap with zeros. Fails if the file would grow beyond `MAX_FILE_SIZE`.
+    pub fn write_at(&self, offset: u64, data: &[u8], now: SystemTime) -> io::Result<()> {
+        if !self.writable {
+            return Err(ErrorKind::PermissionDenied.into());
+        }
+        let mut node = self.node.borrow_mut();
+        let NodeContent::File(bytes) = &mut node.content else {
+            return Err(ErrorKind::IsADirectory.into());
+        };
+        let end = offset
+            .checked_add(u64::try_from(data.len()).unwrap())
+            .filter(|&end| end <= MAX_FILE_SIZE)
+            .ok_or(ErrorKind::FileTooLarge)?;
+        // Both fit in `usize` since they are at most `MAX_FILE_SIZE`.
+        let (start, end) = (usize::try_from(offset).unwrap(), usize::try_from(end).unwrap());
+        if bytes.len() < end {
+            bytes.resize(end, 0);
+        }
+        bytes[start..end].copy_from_slice(data);
+        node.touch(now);
+        Ok(())
+    }
+
+    /// Truncates or extends the file to `len` bytes, which may be at most `MAX_FILE_SIZE`.
+    pub fn set_len(&self, len: u64, now: SystemTime) -> io::Result<()> {
+        if !self.writable {
+            return Err(ErrorKind::InvalidInput.into());
+        }
+        let mut node = self.node.borrow_mut();
+        let NodeContent::File(bytes) = &mut node.content else {
+            return Err(ErrorKind::InvalidInput.into());
+        };
+        if len > MAX_FILE_SIZE {
+            return Err(ErrorKind::FileTooLarge.into());
+        }
+        bytes.resize(usize::try_from(len).unwrap(), 0);
+        node.touch(now);
+        Ok(())
+    }
+
+    pub fn set_permissions(&self, mode: u32) {
+        self.node.borrow_mut().permissions = mode & 0o7777;
+    }
+
+    fn len(&self) -> u64 {
+        self.node.borrow().metadata().len
+    }
+}
+
+impl FileDescription for VfsFile {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn read<'tcx>(
+        self: FileDescriptionRef<Self>,
+        _communicate_allowed: bool,
+        ptr: Pointer,
+        len: usize,
+        ecx: &mut MiriInterpCx<'tcx>,
+        finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+    ) -> InterpResult<'tcx> {
+        let result = match self.read_at(self.cursor.get(), len) {
+            Ok(bytes) => {
+                ecx.write_bytes_ptr(ptr, bytes.iter().copied())?;
+                let read_size = u64::try_from(bytes.len()).unwrap();
+                self.cursor.set(self.cursor.get().strict_add(read_size));
+                Ok(bytes.len())
+            }
+            Err(e) => Err(IoError::HostError(e)),
+        };
+        finish.call(ecx, result)
+    }
+
+    fn write<'tcx>(
+        self: FileDescriptionRef<Self>,
+        _communicate_allowed: bool,
+        ptr: Pointer,
+        len: usize,
+        ecx: &mut MiriInterpCx<'tcx>,
+        finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+    ) -> InterpResult<'tcx> {
+        let bytes = ecx.read_bytes_ptr_strip_provenance(ptr, Size::from_bytes(len))?;
+        // In append mode, every write goes to the end of the file.
+        let offset = if self.append { self.len() } else { self.cursor.get() };
+        let result = self.write_at(offset, bytes, now(ecx)).map(|()| {
+            self.cursor.set(offset.strict_add(u64::try_from(len).unwrap()));
+            len
+        });
+        finish.call(ecx, result.map_err(IoError::HostError))
+    }
+
+    fn seek<'tcx>(
+        &self,
+        _communicate_allowed: bool,
+        offset: SeekFrom,
+    ) -> InterpResult<'tcx, io::Result<u64>> {
+        let (base, delta) = match offset {
+            SeekFrom::Start(offset) => (offset, 0),
+            SeekFrom::Current(delta) => (self.cursor.get(), delta),
+            SeekFrom::End(delta) => (self.len(), delta),
+        };
+        let Some(cursor) = base.checked_add_signed(delta) else {
+            return interp_ok(Err(ErrorKind::InvalidInput.into()));
+        };
+        self.cursor.set(cursor);
+        interp_ok(Ok(cursor))
+    }
+
+    fn close<'tcx>(
+        self,
+        _communicate_allowed: bool,
+        _ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<()>> {
+        interp_ok(Ok(()))
+    }
+
+    fn metadata<'tcx>(&self) -> InterpResult<'tcx, io::Result<Metadata>> {
+        interp_ok(Ok(self.node.borrow().metadata()))
+    }
+
+    fn as_unix<'tcx>(&self, ecx: &MiriInterpCx<'tcx>) -> &dyn UnixFileDescription {
+        assert!(
+            ecx.target_os_is_unix(),
+            "unix file operations are only available for unix targets"
+        );
+        self
+    }
+}
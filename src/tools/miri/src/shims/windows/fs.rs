@@ -1,4 +1,4 @@
-use std::fs::{Metadata, OpenOptions};
+use std::fs::OpenOptions;
 use std::io;
 use std::io::SeekFrom;
 use std::path::PathBuf;
@@ -6,7 +6,8 @@
 
 use bitflags::bitflags;
 
-use crate::shims::files::{FileDescription, FileHandle};
+use crate::shims::files::{FileDescription, FileHandle, FileKind, Metadata};
+use crate::shims::vfs;
 use crate::shims::windows::handle::{EvalContextExt as _, Handle};
 use crate::*;
 
@@ -21,7 +22,7 @@ fn name(&self) -> &'static str {
     }
 
     fn metadata<'tcx>(&self) -> InterpResult<'tcx, io::Result<Metadata>> {
-        interp_ok(self.path.metadata())
+        interp_ok(self.path.metadata().map(Metadata::from))
     }
 
     fn close<'tcx>(
@@ -165,7 +166,9 @@ fn CreateFileW(
 
         let this = self.eval_context_mut();
         this.assert_target_os("windows", "CreateFileW");
-        this.check_no_isolation("`CreateFileW`")?;
+        if this.machine.vfs.is_none() {
+            this.check_no_isolation("`CreateFileW`")?;
+        }
 
         // This function appears to always set the error to 0. This is important for some flag
         // combinations, which may set error code on success.
@@ -207,7 +210,11 @@ fn CreateFileW(
 
         // We need to know if the file is a directory to correctly open directory handles.
         // This is racy, but currently the stdlib doesn't appear to offer a better solution.
-        let is_dir = file_name.is_dir();
+        let is_dir = match &this.machine.vfs {
+            Some(vfs) =>
+                vfs.metadata(&file_name, true).is_ok_and(|meta| meta.kind == FileKind::Dir),
+            None => file_name.is_dir(),
+        };
 
         // BACKUP_SEMANTICS is how Windows calls the act of opening a directory handle.
         if !attributes.contains(FileAttributes::BACKUP_SEMANTICS) && is_dir {
@@ -219,13 +226,17 @@ fn CreateFileW(
         let desired_write = desired_access & generic_write != 0;
 
         let mut options = OpenOptions::new();
+        // The same options, in case we open the file in the in-memory file system.
+        let mut vfs_options = vfs::OpenOptions { mode: 0o666, ..Default::default() };
         if desired_read {
             desired_access &= !generic_read;
             options.read(true);
+            vfs_options.read = true;
         }
         if desired_write {
             desired_access &= !generic_write;
             options.write(true);
+            vfs_options.write = true;
         }
 
         if desired_access != 0 {
@@ -245,20 +256,35 @@ fn CreateFileW(
         // file exists but tells us it isn't new. Either we accept racing one way or another,
         // or we use an iffy heuristic like file creation time. This implementation prefers
         // to fail in the direction of erroring more often.
+        let exists = match &this.machine.vfs {
+            Some(vfs) => vfs.metadata(&file_name, true).is_ok(),
+            None => file_name.exists(),
+        };
         if let CreateAlways | OpenAlways = creation_disposition
-            && file_name.exists()
+            && exists
         {
             this.set_last_error(IoError::WindowsError("ERROR_ALREADY_EXISTS"))?;
         }
 
+        let now = vfs::now(this);
         let handle = if is_dir {
             // Open this as a directory.
-            let fd_num = this.machine.fds.insert_new(DirHandle { path: file_name });
-            Ok(Handle::File(fd_num))
+            let fd_num = match &mut this.machine.vfs {
+                Some(vfs) => {
+                    let options = vfs::OpenOptions { read: true, ..Default::default() };
+                    vfs.open(&file_name, &options, now).map(|dir| this.machine.fds.insert_new(dir))
+                }
+                None => Ok(this.machine.fds.insert_new(DirHandle { path: file_name })),
+            };
+            fd_num.map(Handle::File)
         } else if creation_disposition == OpenExisting && !(desired_read || desired_write) {
             // Windows supports handles with no permissions. These allow things such as reading
             // metadata, but not file content.
-            file_name.metadata().map(|meta| {
+            let meta = match &this.machine.vfs {
+                Some(vfs) => vfs.metadata(&file_name, true),
+                None => file_name.metadata().map(Metadata::from),
+            };
+            meta.map(|meta| {
                 let fd_num = this.machine.fds.insert_new(MetadataHandle { meta });
                 Handle::File(fd_num)
             })
@@ -267,30 +293,40 @@ fn CreateFileW(
             match creation_disposition {
                 CreateAlways | OpenAlways => {
                     options.create(true);
+                    vfs_options.create = true;
                     if creation_disposition == CreateAlways {
                         options.truncate(true);
+                        vfs_options.truncate = true;
                     }
                 }
                 CreateNew => {
                     options.create_new(true);
+                    vfs_options.create_new = true;
                     // Per `create_new` documentation:
                     // The file must be opened with write or append access in order to create a new file.
                     // https://doc.rust-lang.org/std/fs/struct.OpenOptions.html#method.create_new
                     if !desired_write {
                         options.append(true);
+                        vfs_options.append = true;
                     }
                 }
                 OpenExisting => {} // Default options
                 TruncateExisting => {
                     options.truncate(true);
+                    vfs_options.truncate = true;
                 }
             }
 
-            options.open(file_name).map(|file| {
-                let fd_num =
-                    this.machine.fds.insert_new(FileHandle { file, writable: desired_write });
-                Handle::File(fd_num)
-            })
+            let fd_num = match &mut this.machine.vfs {
+                Some(vfs) =>
+                    vfs.open(&file_name, &vfs_options, now)
+                        .map(|file| this.machine.fds.insert_new(file)),
+                None =>
+                    options.open(file_name).map(|file| {
+                        this.machine.fds.insert_new(FileHandle { file, writable: desired_write })
+                    }),
+            };
+            fd_num.map(Handle::File)
         };
 
         match handle {
@@ -310,7 +346,9 @@ fn GetFileInformationByHandle(
         // ^ Returns BOOL (i32 on Windows)
         let this = self.eval_context_mut();
         this.assert_target_os("windows", "GetFileInformationByHandle");
-        this.check_no_isolation("`GetFileInformationByHandle`")?;
+        if this.machine.vfs.is_none() {
+            this.check_no_isolation("`GetFileInformationByHandle`")?;
+        }
 
         let file = this.read_handle(file, "GetFileInformationByHandle")?;
         let file_information = this.deref_pointer_as(
@@ -336,23 +374,20 @@ fn GetFileInformationByHandle(
             }
         };
 
-        let size = metadata.len();
+        let size = metadata.len;
 
-        let file_type = metadata.file_type();
-        let attributes = if file_type.is_dir() {
-            this.eval_windows_u32("c", "FILE_ATTRIBUTE_DIRECTORY")
-        } else if file_type.is_file() {
-            this.eval_windows_u32("c", "FILE_ATTRIBUTE_NORMAL")
-        } else {
-            this.eval_windows_u32("c", "FILE_ATTRIBUTE_DEVICE")
+        let attributes = match metadata.kind {
+            FileKind::Dir => this.eval_windows_u32("c", "FILE_ATTRIBUTE_DIRECTORY"),
+            FileKind::File => this.eval_windows_u32("c", "FILE_ATTRIBUTE_NORMAL"),
+            _ => this.eval_windows_u32("c", "FILE_ATTRIBUTE_DEVICE"),
         };
 
         // Per the Windows documentation:
         // "If the underlying file system does not support the [...] time, this member is zero (0)."
         // https://learn.microsoft.com/en-us/windows/win32/api/fileapi/ns-fileapi-by_handle_file_information
-        let created = extract_windows_epoch(this, metadata.created())?.unwrap_or((0, 0));
-        let accessed = extract_windows_epoch(this, metadata.accessed())?.unwrap_or((0, 0));
-        let written = extract_windows_epoch(this, metadata.modified())?.unwrap_or((0, 0));
+        let created = extract_windows_epoch(this, metadata.created)?.unwrap_or((0, 0));
+        let accessed = extract_windows_epoch(this, metadata.accessed)?.unwrap_or((0, 0));
+        let written = extract_windows_epoch(this, metadata.modified)?.unwrap_or((0, 0));
 
         this.write_int_fields_named(&[("dwFileAttributes", attributes.into())], &file_information)?;
         write_filetime_field(this, &file_information, "ftCreationTime", created)?;
@@ -364,8 +399,8 @@ fn GetFileInformationByHandle(
                 ("nFileSizeHigh", (size >> 32).into()),
                 ("nFileSizeLow", (size & 0xFFFFFFFF).into()),
                 ("nNumberOfLinks", 1),
-                ("nFileIndexHigh", 0),
-                ("nFileIndexLow", 0),
+                ("nFileIndexHigh", (metadata.ino >> 32).into()),
+                ("nFileIndexLow", (metadata.ino & 0xFFFFFFFF).into()),
             ],
             &file_information,
         )?;
@@ -380,10 +415,17 @@ fn DeleteFileW(
         // ^ Returns BOOL (i32 on Windows)
         let this = self.eval_context_mut();
         this.assert_target_os("windows", "DeleteFileW");
-        this.check_no_isolation("`DeleteFileW`")?;
+        if this.machine.vfs.is_none() {
+            this.check_no_isolation("`DeleteFileW`")?;
+        }
 
         let file_name = this.read_path_from_wide_str(this.read_pointer(file_name)?)?;
-        match std::fs::remove_file(file_name) {
+        let now = vfs::now(this);
+        let result = match &mut this.machine.vfs {
+            Some(vfs) => vfs.remove_file(&file_name, now),
+            None => std::fs::remove_file(file_name),
+        };
+        match result {
             Ok(_) => interp_ok(this.eval_windows("c", "TRUE")),
             Err(e) => {
                 this.set_last_error(e)?;
@@ -661,9 +703,9 @@ fn SetFilePointerEx(
 /// Windows FILETIME is measured in 100-nanosecs since 1601
 fn extract_windows_epoch<'tcx>(
     ecx: &MiriInterpCx<'tcx>,
-    time: io::Result<SystemTime>,
+    time: Option<SystemTime>,
 ) -> InterpResult<'tcx, Option<(u32, u32)>> {
-    match time.ok() {
+    match time {
         Some(time) => {
             let duration = ecx.system_time_since_windows_epoch(&time)?;
             let duration_ticks = ecx.windows_ticks_for(duration)?;
@@ -0,0 +1,74 @@
+//! Runs `tests/pass/coverage.rs` with `-Zmiri-coverage`, and checks the lcov report.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+const PROGRAM: &str = "tests/pass/coverage.rs";
+
+fn miri_path() -> PathBuf {
+    PathBuf::from(env::var("MIRI").unwrap_or_else(|_| env!("CARGO_BIN_EXE_miri").into()))
+}
+
+/// Returns the line of `PROGRAM` that ends with `marker`.
+fn line_of(source: &str, marker: &str) -> usize {
+    source.lines().position(|line| line.ends_with(marker)).unwrap() + 1
+}
+
+#[test]
+fn lcov_report() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let mut miri = Command::new(miri_path());
+    miri.arg(format!(
+        "--sysroot={}",
+        env::var("MIRI_SYSROOT").expect("MIRI_SYSROOT must be set to run the coverage test")
+    ));
+    if let Ok(target) = env::var("MIRI_TEST_TARGET") {
+        miri.arg(format!("--target={target}"));
+    }
+    miri.arg("--edition=2021")
+        .arg(format!("-Zmiri-coverage={}", tmpdir.path().join("%p.info").display()))
+        .arg(PROGRAM);
+    let miri = miri.spawn().unwrap();
+    let pid = miri.id();
+    assert!(miri.wait_with_output().unwrap().status.success());
+
+    let report = std::fs::read_to_string(tmpdir.path().join(format!("{pid}.info"))).unwrap();
+    let record = report
+        .split_inclusive("end_of_record\n")
+        .find(|record| record.lines().next().unwrap().ends_with(PROGRAM))
+        .unwrap_or_else(|| panic!("no record for {PROGRAM}:\n{report}"));
+    let mut functions = BTreeMap::new();
+    let mut lines = BTreeMap::new();
+    let mut lines_hit = None;
+    for entry in record.lines() {
+        let (kind, value) = entry.split_once(':').unwrap_or((entry, ""));
+        match kind {
+            "FN" => {
+                let (line, name) = value.split_once(',').unwrap();
+                functions.insert(name, (line.parse::<usize>().unwrap(), None));
+            }
+            "FNDA" => {
+                let (calls, name) = value.split_once(',').unwrap();
+                functions.get_mut(name).unwrap().1 = Some(calls.parse::<u64>().unwrap());
+            }
+            "DA" => {
+                let (line, count) = value.split_once(',').unwrap();
+                lines.insert(line.parse::<usize>().unwrap(), count.parse::<u64>().unwrap());
+            }
+            "LH" => lines_hit = Some(value.parse::<usize>().unwrap()),
+            _ => {}
+        }
+    }
+
+    let source = std::fs::read_to_string(PROGRAM).unwrap();
+    let function = |name: &str| functions[format!("coverage::{name}").as_str()];
+    assert_eq!(function("branch"), (line_of(&source, "fn branch(x: u32) -> u32 {"), Some(1)));
+    assert_eq!(function("never_called"), (line_of(&source, "fn never_called() {"), Some(0)));
+    assert_eq!(function("main"), (line_of(&source, "fn main() {"), Some(1)));
+    assert_eq!(lines[&line_of(&source, "// taken")], 1);
+    assert_eq!(lines[&line_of(&source, "// untaken")], 0);
+    assert_eq!(lines[&line_of(&source, "// uncalled")], 0);
+    assert_eq!(lines_hit, Some(lines.values().filter(|&&count| count > 0).count()));
+}
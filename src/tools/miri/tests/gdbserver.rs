@@ -0,0 +1,150 @@
+//! Drives `-Zmiri-gdbserver` the way a debugger would, over the GDB remote serial protocol.
+
+use std::env;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+fn miri_path() -> PathBuf {
+    PathBuf::from(env::var("MIRI").unwrap_or_else(|_| env!("CARGO_BIN_EXE_miri").into()))
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn decode_hex(hex: &str) -> String {
+    let bytes = (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap());
+    String::from_utf8(bytes.collect()).unwrap()
+}
+
+/// A minimal debugger. It turns off acknowledgments right away, and does not check checksums.
+struct Client {
+    stream: BufReader<TcpStream>,
+}
+
+impl Client {
+    fn connect(addr: &str) -> Client {
+        let stream = TcpStream::connect(addr).unwrap();
+        // Miri can be slow, but not this slow.
+        stream.set_read_timeout(Some(Duration::from_secs(300))).unwrap();
+        let mut client = Client { stream: BufReader::new(stream) };
+        client.send("QStartNoAckMode");
+        assert_eq!(client.read_byte(), b'+');
+        assert_eq!(client.receive(), "OK");
+        client.stream.get_mut().write_all(b"+").unwrap();
+        client
+    }
+
+    fn read_byte(&mut self) -> u8 {
+        let mut buf = [0];
+        self.stream.read_exact(&mut buf).unwrap();
+        buf[0]
+    }
+
+    fn send(&mut self, data: &str) {
+        let sum = data.bytes().fold(0u8, |sum, byte| sum.wrapping_add(byte));
+        write!(self.stream.get_mut(), "${data}#{sum:02x}").unwrap();
+    }
+
+    fn receive(&mut self) -> String {
+        let mut packet = Vec::new();
+        self.stream.read_until(b'$', &mut packet).unwrap();
+        packet.clear();
+        self.stream.read_until(b'#', &mut packet).unwrap();
+        packet.pop();
+        // Skip the checksum.
+        self.read_byte();
+        self.read_byte();
+        String::from_utf8(packet).unwrap()
+    }
+
+    fn request(&mut self, data: &str) -> String {
+        self.send(data);
+        self.receive()
+    }
+
+    /// Runs a monitor command, and returns its output.
+    fn monitor(&mut self, command: &str) -> String {
+        self.send(&format!("qRcmd,{}", encode_hex(command.as_bytes())));
+        let mut output = String::new();
+        loop {
+            let packet = self.receive();
+            if packet == "OK" {
+                return output;
+            }
+            output.push_str(&decode_hex(packet.strip_prefix('O').unwrap()));
+        }
+    }
+}
+
+#[test]
+fn breakpoints() {
+    let mut miri = Command::new(miri_path());
+    miri.arg(format!(
+        "--sysroot={}",
+        env::var("MIRI_SYSROOT").expect("MIRI_SYSROOT must be set to run the gdbserver test")
+    ));
+    if let Ok(target) = env::var("MIRI_TEST_TARGET") {
+        miri.arg(format!("--target={target}"));
+    }
+    miri.args(["--edition=2021", "-Zmiri-gdbserver=0", "tests/gdbserver/debuggee.rs"]);
+    let mut miri = miri.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn().unwrap();
+    let mut stdout = BufReader::new(miri.stdout.take().unwrap());
+    let mut stderr = BufReader::new(miri.stderr.take().unwrap());
+
+    // Miri tells us where to connect.
+    let mut line = String::new();
+    let addr = loop {
+        line.clear();
+        assert_ne!(stderr.read_line(&mut line).unwrap(), 0, "Miri did not wait for a debugger");
+        if let Some(addr) = line.strip_prefix("Waiting for a debugger to connect to ") {
+            break addr.trim_end().strip_suffix("...").unwrap();
+        }
+    };
+    let mut client = Client::connect(addr);
+
+    // The program stops before it starts running.
+    assert_eq!(client.request("?"), "T05thread:1;");
+    assert_eq!(
+        client.monitor("break wait_for_debugger"),
+        "breakpoint 1 at function `wait_for_debugger`\n"
+    );
+    assert_eq!(client.request("c"), "T05thread:1;");
+    assert!(client.monitor("backtrace").starts_with("*#0 wait_for_debugger at "));
+
+    // Now the program told us where `callee` is, so we can insert a breakpoint there.
+    let mut callee = String::new();
+    stdout.read_line(&mut callee).unwrap();
+    let callee = callee.trim_end().strip_prefix("0x").unwrap();
+    assert_eq!(client.request(&format!("Z0,{callee},1")), "OK");
+    assert_eq!(
+        client.monitor("breakpoints"),
+        format!(
+            "breakpoint 1 at function `wait_for_debugger`\n\
+            breakpoint 2 at 0x{callee} (function `callee`)\n"
+        )
+    );
+    // Only functions have breakpoint addresses.
+    assert_eq!(client.request("Z0,0,1"), "E01");
+    assert_eq!(client.request("c"), "T05thread:1;");
+    let backtrace = client.monitor("backtrace");
+    let mut frames = backtrace.lines();
+    assert!(frames.next().unwrap().starts_with("*#0 callee at "), "{backtrace}");
+    assert!(frames.next().unwrap().starts_with(" #1 main at "), "{backtrace}");
+    assert!(client.monitor("locals").starts_with("x = 41"));
+
+    // The debugger can neither see registers nor modify the program.
+    assert_eq!(client.request("g"), "xxxxxxxxxxxxxxxx");
+    assert_eq!(client.request("M1000,1:00"), "E01");
+
+    assert_eq!(client.request(&format!("z0,{callee},1")), "OK");
+    assert_eq!(client.request("c"), "W00");
+
+    let status = miri.wait().unwrap();
+    let mut output = String::new();
+    stderr.read_to_string(&mut output).unwrap();
+    assert!(status.success(), "Miri failed:\n{output}");
+}
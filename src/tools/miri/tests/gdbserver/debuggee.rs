@@ -0,0 +1,15 @@
+//! The program that `tests/gdbserver.rs` debugs.
+
+fn callee(x: u32) -> u32 {
+    x + 1
+}
+
+fn wait_for_debugger() {}
+
+fn main() {
+    let f: fn(u32) -> u32 = callee;
+    // Tell the debugger where to find `callee`.
+    println!("{f:p}");
+    wait_for_debugger();
+    assert_eq!(f(41), 42);
+}
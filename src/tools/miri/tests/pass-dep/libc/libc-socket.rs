@@ -0,0 +1,156 @@
+//@only-target: linux android illumos
+// We use epoll to check readiness, which is not available on all Unixes.
+
+use std::io::{Read, Write};
+use std::mem::{MaybeUninit, size_of};
+use std::net::{TcpListener, TcpStream, UdpSocket};
+use std::os::fd::AsRawFd;
+
+fn main() {
+    test_epoll_listener();
+    test_epoll_stream();
+    test_epoll_udp();
+    test_sockaddr_truncation();
+    test_unconnected();
+}
+
+fn check_epoll_wait<const N: usize>(epfd: i32, expected_notifications: &[(u32, u64)]) {
+    let epoll_event = libc::epoll_event { events: 0, u64: 0 };
+    let mut array: [libc::epoll_event; N] = [epoll_event; N];
+    let maxsize = N;
+    let array_ptr = array.as_mut_ptr();
+    let res = unsafe { libc::epoll_wait(epfd, array_ptr, maxsize.try_into().unwrap(), 0) };
+    if res < 0 {
+        panic!("epoll_wait failed: {}", std::io::Error::last_os_error());
+    }
+    assert_eq!(
+        res,
+        expected_notifications.len().try_into().unwrap(),
+        "got wrong number of notifications"
+    );
+    let slice = unsafe { std::slice::from_raw_parts(array_ptr, res.try_into().unwrap()) };
+    for (return_event, expected_event) in slice.iter().zip(expected_notifications.iter()) {
+        let event = return_event.events;
+        let data = return_event.u64;
+        assert_eq!(event, expected_event.0, "got wrong events");
+        assert_eq!(data, expected_event.1, "got wrong data");
+    }
+}
+
+fn epoll_add(epfd: i32, fd: i32) {
+    let mut ev = libc::epoll_event {
+        events: (libc::EPOLLIN | libc::EPOLLOUT | libc::EPOLLET | libc::EPOLLRDHUP) as _,
+        u64: u64::try_from(fd).unwrap(),
+    };
+    let res = unsafe { libc::epoll_ctl(epfd, libc::EPOLL_CTL_ADD, fd, &mut ev) };
+    assert_eq!(res, 0);
+}
+
+fn test_epoll_listener() {
+    let epfd = unsafe { libc::epoll_create1(0) };
+    assert_ne!(epfd, -1);
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let fd = listener.as_raw_fd();
+    epoll_add(epfd, fd);
+    // Nothing to accept yet.
+    check_epoll_wait::<8>(epfd, &[]);
+
+    let _client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let expected_event = u32::try_from(libc::EPOLLIN).unwrap();
+    check_epoll_wait::<8>(epfd, &[(expected_event, u64::try_from(fd).unwrap())]);
+}
+
+fn test_epoll_stream() {
+    let epfd = unsafe { libc::epoll_create1(0) };
+    assert_ne!(epfd, -1);
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+    let fd = server.as_raw_fd();
+    let data = u64::try_from(fd).unwrap();
+
+    // A fresh connection is writable.
+    epoll_add(epfd, fd);
+    check_epoll_wait::<8>(epfd, &[(u32::try_from(libc::EPOLLOUT).unwrap(), data)]);
+
+    // Once the client sends something, it is readable too.
+    client.write_all(b"abc").unwrap();
+    let expected_event = u32::try_from(libc::EPOLLIN | libc::EPOLLOUT).unwrap();
+    check_epoll_wait::<8>(epfd, &[(expected_event, data)]);
+
+    // Once the client is gone, we read EOF and the connection is hung up.
+    drop(client);
+    let expected_event =
+        u32::try_from(libc::EPOLLIN | libc::EPOLLOUT | libc::EPOLLRDHUP | libc::EPOLLHUP).unwrap();
+    check_epoll_wait::<8>(epfd, &[(expected_event, data)]);
+    let mut buf = Vec::new();
+    server.read_to_end(&mut buf).unwrap();
+    assert_eq!(buf, b"abc");
+
+    // Writing to a connection without peer fails.
+    let res = unsafe { libc::send(fd, b"x".as_ptr().cast(), 1, libc::MSG_NOSIGNAL) };
+    assert_eq!(res, -1);
+    assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::EPIPE));
+}
+
+fn test_epoll_udp() {
+    let epfd = unsafe { libc::epoll_create1(0) };
+    assert_ne!(epfd, -1);
+
+    let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let fd = b.as_raw_fd();
+    let data = u64::try_from(fd).unwrap();
+
+    // Sending a datagram never blocks.
+    epoll_add(epfd, fd);
+    check_epoll_wait::<8>(epfd, &[(u32::try_from(libc::EPOLLOUT).unwrap(), data)]);
+
+    a.send_to(b"datagram", b.local_addr().unwrap()).unwrap();
+    let expected_event = u32::try_from(libc::EPOLLIN | libc::EPOLLOUT).unwrap();
+    check_epoll_wait::<8>(epfd, &[(expected_event, data)]);
+}
+
+fn test_sockaddr_truncation() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    // The address is truncated to the buffer, but the full length is reported.
+    let mut addr = MaybeUninit::<libc::sockaddr_in>::zeroed();
+    let mut len: libc::socklen_t = 4;
+    let res =
+        unsafe { libc::getsockname(listener.as_raw_fd(), addr.as_mut_ptr().cast(), &mut len) };
+    assert_eq!(res, 0);
+    assert_eq!(usize::try_from(len).unwrap(), size_of::<libc::sockaddr_in>());
+    let addr = unsafe { addr.assume_init() };
+    assert_eq!(addr.sin_family, libc::sa_family_t::try_from(libc::AF_INET).unwrap());
+    assert_eq!(u16::from_be(addr.sin_port), port);
+    // This part did not fit.
+    assert_eq!(addr.sin_addr.s_addr, 0);
+}
+
+fn test_unconnected() {
+    let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_STREAM, 0) };
+    assert_ne!(fd, -1);
+
+    let mut buf = [0u8; 4];
+    let res = unsafe { libc::recv(fd, buf.as_mut_ptr().cast(), buf.len(), 0) };
+    assert_eq!(res, -1);
+    assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::ENOTCONN));
+
+    let mut addr = MaybeUninit::<libc::sockaddr_in>::zeroed();
+    let mut len = libc::socklen_t::try_from(size_of::<libc::sockaddr_in>()).unwrap();
+    let res = unsafe { libc::getpeername(fd, addr.as_mut_ptr().cast(), &mut len) };
+    assert_eq!(res, -1);
+    assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::ENOTCONN));
+
+    // Not a socket.
+    let res = unsafe { libc::listen(libc::STDOUT_FILENO, 1) };
+    assert_eq!(res, -1);
+    assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::ENOTSOCK));
+
+    let res = unsafe { libc::close(fd) };
+    assert_eq!(res, 0);
+}
@@ -0,0 +1,184 @@
+//@only-target: windows # this directly tests windows-only functions
+//@compile-flags: -Zmiri-vfs
+#![allow(nonstandard_style)]
+
+use std::os::windows::ffi::OsStrExt;
+use std::path::Path;
+use std::ptr;
+
+use windows_sys::Wdk::Storage::FileSystem::{NtReadFile, NtWriteFile};
+use windows_sys::Win32::Foundation::{
+    CloseHandle, ERROR_ALREADY_EXISTS, ERROR_FILE_NOT_FOUND, GENERIC_READ, GENERIC_WRITE,
+    GetLastError, HANDLE, STATUS_SUCCESS,
+};
+use windows_sys::Win32::Storage::FileSystem::{
+    BY_HANDLE_FILE_INFORMATION, CREATE_ALWAYS, CREATE_NEW, CreateFileW, DeleteFileW,
+    FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_NORMAL, FILE_FLAG_BACKUP_SEMANTICS, FILE_SHARE_DELETE,
+    FILE_SHARE_READ, FILE_SHARE_WRITE, GetFileInformationByHandle, OPEN_EXISTING,
+};
+use windows_sys::Win32::System::IO::IO_STATUS_BLOCK;
+
+// Verbatim paths are not made absolute by the standard library, and map to `/tmp` in the
+// in-memory file system.
+const TMP: &str = r"\\?\tmp";
+
+fn main() {
+    unsafe {
+        test_create_read_write();
+        test_directory();
+        test_create_always();
+        test_delete_file();
+    }
+}
+
+unsafe fn test_create_read_write() {
+    let path = to_wide_cstr(&Path::new(TMP).join("miri_vfs_test_windows.txt"));
+    let text = b"Hello, World!";
+
+    // Nothing is there, not even on the host.
+    let handle = open(&path, GENERIC_READ, OPEN_EXISTING, 0);
+    assert_eq!(handle.addr(), usize::MAX);
+    assert_eq!(GetLastError(), ERROR_FILE_NOT_FOUND);
+
+    let handle = open(&path, GENERIC_READ | GENERIC_WRITE, CREATE_NEW, 0);
+    assert_ne!(handle.addr(), usize::MAX, "CreateFileW Failed: {}", GetLastError());
+    let mut status = std::mem::zeroed::<IO_STATUS_BLOCK>();
+    let out = NtWriteFile(
+        handle,
+        ptr::null_mut(),
+        None,
+        ptr::null_mut(),
+        &mut status,
+        text.as_ptr().cast(),
+        text.len() as u32,
+        ptr::null_mut(),
+        ptr::null_mut(),
+    );
+    assert_eq!(out, STATUS_SUCCESS);
+    let info = file_information(handle);
+    assert!(info.dwFileAttributes & FILE_ATTRIBUTE_NORMAL != 0);
+    assert_eq!((info.nFileSizeHigh, info.nFileSizeLow), (0, text.len() as u32));
+    close(handle);
+
+    let handle = open(&path, GENERIC_READ, OPEN_EXISTING, 0);
+    assert_ne!(handle.addr(), usize::MAX, "CreateFileW Failed: {}", GetLastError());
+    let mut buffer = [0; 13];
+    let out = NtReadFile(
+        handle,
+        ptr::null_mut(),
+        None,
+        ptr::null_mut(),
+        &mut status,
+        buffer.as_mut_ptr().cast(),
+        buffer.len() as u32,
+        ptr::null_mut(),
+        ptr::null_mut(),
+    );
+    assert_eq!(out, STATUS_SUCCESS);
+    assert_eq!(&buffer, text);
+    close(handle);
+
+    assert_ne!(DeleteFileW(path.as_ptr()), 0);
+}
+
+unsafe fn test_directory() {
+    let dir = to_wide_cstr(Path::new(TMP));
+    let file = to_wide_cstr(&Path::new(TMP).join("miri_vfs_test_windows_dir.txt"));
+
+    // Directories can only be opened with backup semantics.
+    let handle = open(&dir, GENERIC_READ, OPEN_EXISTING, FILE_FLAG_BACKUP_SEMANTICS);
+    assert_ne!(handle.addr(), usize::MAX, "CreateFileW Failed: {}", GetLastError());
+    let dir_info = file_information(handle);
+    assert!(dir_info.dwFileAttributes & FILE_ATTRIBUTE_DIRECTORY != 0);
+    close(handle);
+
+    // Every file has its own index.
+    let handle = open(&file, GENERIC_WRITE, CREATE_NEW, 0);
+    assert_ne!(handle.addr(), usize::MAX, "CreateFileW Failed: {}", GetLastError());
+    let file_info = file_information(handle);
+    assert_ne!(
+        (file_info.nFileIndexHigh, file_info.nFileIndexLow),
+        (dir_info.nFileIndexHigh, dir_info.nFileIndexLow)
+    );
+    close(handle);
+
+    assert_ne!(DeleteFileW(file.as_ptr()), 0);
+}
+
+/// Tests that CREATE_ALWAYS reports existing files, and truncates them.
+unsafe fn test_create_always() {
+    let path = to_wide_cstr(&Path::new(TMP).join("miri_vfs_test_windows_create_always.txt"));
+
+    let handle = open(&path, GENERIC_READ | GENERIC_WRITE, CREATE_ALWAYS, 0);
+    assert_ne!(handle.addr(), usize::MAX, "CreateFileW Failed: {}", GetLastError());
+    assert_eq!(GetLastError(), 0);
+    let mut status = std::mem::zeroed::<IO_STATUS_BLOCK>();
+    let text = b"abc";
+    let out = NtWriteFile(
+        handle,
+        ptr::null_mut(),
+        None,
+        ptr::null_mut(),
+        &mut status,
+        text.as_ptr().cast(),
+        text.len() as u32,
+        ptr::null_mut(),
+        ptr::null_mut(),
+    );
+    assert_eq!(out, STATUS_SUCCESS);
+    close(handle);
+
+    let handle = open(&path, GENERIC_READ | GENERIC_WRITE, CREATE_ALWAYS, 0);
+    assert_ne!(handle.addr(), usize::MAX, "CreateFileW Failed: {}", GetLastError());
+    assert_eq!(GetLastError(), ERROR_ALREADY_EXISTS);
+    let info = file_information(handle);
+    assert_eq!((info.nFileSizeHigh, info.nFileSizeLow), (0, 0));
+    close(handle);
+
+    assert_ne!(DeleteFileW(path.as_ptr()), 0);
+}
+
+unsafe fn test_delete_file() {
+    let path = to_wide_cstr(&Path::new(TMP).join("miri_vfs_test_windows_delete.txt"));
+    close(open(&path, GENERIC_WRITE, CREATE_NEW, 0));
+
+    assert_ne!(DeleteFileW(path.as_ptr()), 0);
+    let handle = open(&path, GENERIC_READ, OPEN_EXISTING, 0);
+    assert_eq!(handle.addr(), usize::MAX);
+    assert_eq!(GetLastError(), ERROR_FILE_NOT_FOUND);
+
+    assert_eq!(DeleteFileW(path.as_ptr()), 0);
+    assert_eq!(GetLastError(), ERROR_FILE_NOT_FOUND);
+}
+
+unsafe fn open(path: &[u16], access: u32, disposition: u32, flags: u32) -> HANDLE {
+    CreateFileW(
+        path.as_ptr(),
+        access,
+        FILE_SHARE_DELETE | FILE_SHARE_READ | FILE_SHARE_WRITE,
+        ptr::null_mut(),
+        disposition,
+        flags,
+        ptr::null_mut(),
+    )
+}
+
+unsafe fn file_information(handle: HANDLE) -> BY_HANDLE_FILE_INFORMATION {
+    let mut info = std::mem::zeroed::<BY_HANDLE_FILE_INFORMATION>();
+    if GetFileInformationByHandle(handle, &mut info) == 0 {
+        panic!("Failed to get file information: {}", GetLastError())
+    };
+    info
+}
+
+unsafe fn close(handle: HANDLE) {
+    if CloseHandle(handle) == 0 {
+        panic!("Failed to close file")
+    };
+}
+
+fn to_wide_cstr(path: &Path) -> Vec<u16> {
+    let mut raw_path = path.as_os_str().encode_wide().collect::<Vec<_>>();
+    raw_path.extend([0, 0]);
+    raw_path
+}
@@ -0,0 +1,109 @@
+// Synchronization that Miri cannot see through is not considered racy when it is annotated
+// with `miri_release` and `miri_acquire`.
+//@compile-flags: -Zmiri-deterministic-concurrency
+#[path = "../../utils/mod.rs"]
+mod utils;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::spawn;
+
+use utils::{miri_acquire, miri_release, miri_release_acquire};
+
+#[derive(Copy, Clone)]
+struct EvilSend<T>(pub T);
+
+unsafe impl<T> Send for EvilSend<T> {}
+unsafe impl<T> Sync for EvilSend<T> {}
+
+/// A flag handoff using only relaxed atomics, as if the real ordering came from elsewhere.
+fn test_handoff() {
+    static FLAG: AtomicUsize = AtomicUsize::new(0);
+
+    let mut var = 0u32;
+    let ptr = EvilSend(&mut var as *mut u32);
+
+    let j1 = spawn(move || {
+        let ptr = ptr; // avoid field capturing
+        unsafe { *ptr.0 = 1 };
+        unsafe { miri_release(FLAG.as_ptr().cast()) };
+        FLAG.store(1, Ordering::Relaxed);
+    });
+
+    let j2 = spawn(move || {
+        let ptr = ptr; // avoid field capturing
+        while FLAG.load(Ordering::Relaxed) == 0 {
+            std::hint::spin_loop();
+        }
+        unsafe { miri_acquire(FLAG.as_ptr().cast()) };
+        unsafe { *ptr.0 }
+    });
+
+    j1.join().unwrap();
+    assert_eq!(j2.join().unwrap(), 1);
+}
+
+/// `miri_release_acquire` chains synchronization through a middle thread.
+fn test_release_acquire_chain() {
+    static STEP: AtomicUsize = AtomicUsize::new(0);
+
+    let mut var = 0u32;
+    let ptr = EvilSend(&mut var as *mut u32);
+
+    let j1 = spawn(move || {
+        let ptr = ptr; // avoid field capturing
+        unsafe { *ptr.0 = 1 };
+        unsafe { miri_release(STEP.as_ptr().cast()) };
+        STEP.store(1, Ordering::Relaxed);
+    });
+
+    let j2 = spawn(move || {
+        while STEP.load(Ordering::Relaxed) != 1 {
+            std::hint::spin_loop();
+        }
+        unsafe { miri_release_acquire(STEP.as_ptr().cast()) };
+        STEP.store(2, Ordering::Relaxed);
+    });
+
+    let j3 = spawn(move || {
+        let ptr = ptr; // avoid field capturing
+        while STEP.load(Ordering::Relaxed) != 2 {
+            std::hint::spin_loop();
+        }
+        unsafe { miri_acquire(STEP.as_ptr().cast()) };
+        unsafe { *ptr.0 }
+    });
+
+    j1.join().unwrap();
+    j2.join().unwrap();
+    assert_eq!(j3.join().unwrap(), 1);
+}
+
+/// Annotations on a non-atomic location work just as well.
+fn test_plain_location() {
+    static FLAG: AtomicUsize = AtomicUsize::new(0);
+
+    let mut var = 0u32;
+    let mut sync = 0u8;
+    let ptr = EvilSend(&mut var as *mut u32);
+    let sync_ptr = EvilSend(&mut sync as *mut u8);
+
+    let j1 = spawn(move || {
+        let (ptr, sync_ptr) = (ptr, sync_ptr); // avoid field capturing
+        unsafe { *ptr.0 = 1 };
+        unsafe { miri_release(sync_ptr.0 as *const ()) };
+        FLAG.store(1, Ordering::Relaxed);
+    });
+
+    while FLAG.load(Ordering::Relaxed) == 0 {
+        std::hint::spin_loop();
+    }
+    unsafe { miri_acquire(sync_ptr.0 as *const ()) };
+    assert_eq!(unsafe { *ptr.0 }, 1);
+    j1.join().unwrap();
+}
+
+fn main() {
+    test_handoff();
+    test_release_acquire_chain();
+    test_plain_location();
+}
@@ -0,0 +1,18 @@
+// `tests/coverage.rs` runs this with `-Zmiri-coverage` and checks the report.
+
+fn branch(x: u32) -> u32 {
+    if x > 10 {
+        x - 10 // untaken
+    } else {
+        x + 10 // taken
+    }
+}
+
+#[allow(dead_code)]
+fn never_called() {
+    println!("never called"); // uncalled
+}
+
+fn main() {
+    assert_eq!(branch(1), 11);
+}
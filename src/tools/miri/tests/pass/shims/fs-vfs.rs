@@ -0,0 +1,217 @@
+//@ignore-target: windows # Uses Unix-specific APIs; see `pass-dep/shims/windows-fs-vfs.rs`
+//@compile-flags: -Zmiri-vfs
+
+#![feature(io_error_more)]
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::os::unix::fs::{FileExt, MetadataExt, PermissionsExt, symlink};
+use std::path::Path;
+
+fn main() {
+    test_file();
+    test_metadata();
+    test_permissions();
+    test_rename();
+    test_directory();
+    test_symlink();
+    test_pread_pwrite();
+    test_file_size_limit();
+}
+
+fn test_file() {
+    let path = Path::new("/tmp/miri_vfs_test_file.txt");
+    let bytes = b"Hello, World!\n";
+
+    // Nothing is there, not even on the host.
+    assert_eq!(File::open(path).unwrap_err().kind(), ErrorKind::NotFound);
+
+    let mut file = File::create(path).unwrap();
+    file.write_all(bytes).unwrap();
+    drop(file);
+
+    let mut contents = Vec::new();
+    File::open(path).unwrap().read_to_end(&mut contents).unwrap();
+    assert_eq!(contents, bytes);
+
+    // Appending writes at the end, no matter where the cursor is.
+    let mut file = OpenOptions::new().append(true).open(path).unwrap();
+    file.seek(SeekFrom::Start(0)).unwrap();
+    file.write_all(b"!").unwrap();
+    drop(file);
+    assert_eq!(fs::read(path).unwrap(), b"Hello, World!\n!");
+
+    // `create_new` refuses to open existing files.
+    assert_eq!(
+        OpenOptions::new().write(true).create_new(true).open(path).unwrap_err().kind(),
+        ErrorKind::AlreadyExists
+    );
+
+    // Truncating and extending.
+    let file = OpenOptions::new().write(true).open(path).unwrap();
+    file.set_len(5).unwrap();
+    assert_eq!(fs::read(path).unwrap(), b"Hello");
+    file.set_len(7).unwrap();
+    assert_eq!(fs::read(path).unwrap(), b"Hello\0\0");
+    drop(file);
+
+    // Relative paths are resolved against the root.
+    assert_eq!(fs::read("tmp/miri_vfs_test_file.txt").unwrap(), b"Hello\0\0");
+
+    fs::remove_file(path).unwrap();
+    assert_eq!(fs::remove_file(path).unwrap_err().kind(), ErrorKind::NotFound);
+}
+
+fn test_metadata() {
+    let path = Path::new("/tmp/miri_vfs_test_metadata.txt");
+    fs::write(path, b"abc").unwrap();
+
+    let metadata = fs::metadata(path).unwrap();
+    assert!(metadata.is_file());
+    assert_eq!(metadata.len(), 3);
+    // New files respect the umask.
+    assert_eq!(metadata.permissions().mode() & 0o777, 0o644);
+    // Every file has its own inode.
+    assert_ne!(metadata.ino(), fs::metadata("/tmp").unwrap().ino());
+    assert!(metadata.modified().unwrap() >= metadata.created().unwrap());
+
+    // The metadata of an open file is the same.
+    let file = File::open(path).unwrap();
+    assert_eq!(file.metadata().unwrap().ino(), metadata.ino());
+
+    let tmp = fs::metadata("/tmp").unwrap();
+    assert!(tmp.is_dir());
+
+    fs::remove_file(path).unwrap();
+}
+
+fn test_permissions() {
+    let path = Path::new("/tmp/miri_vfs_test_permissions.txt");
+    fs::write(path, b"abc").unwrap();
+
+    let mut permissions = fs::metadata(path).unwrap().permissions();
+    permissions.set_readonly(true);
+    fs::set_permissions(path, permissions).unwrap();
+    assert!(fs::metadata(path).unwrap().permissions().readonly());
+    assert_eq!(
+        OpenOptions::new().write(true).open(path).unwrap_err().kind(),
+        ErrorKind::PermissionDenied
+    );
+
+    // `fchmod` works as well.
+    let file = File::open(path).unwrap();
+    file.set_permissions(fs::Permissions::from_mode(0o600)).unwrap();
+    assert_eq!(fs::metadata(path).unwrap().permissions().mode() & 0o777, 0o600);
+    OpenOptions::new().write(true).open(path).unwrap();
+
+    fs::remove_file(path).unwrap();
+}
+
+fn test_rename() {
+    let from = Path::new("/tmp/miri_vfs_test_rename_from.txt");
+    let to = Path::new("/tmp/miri_vfs_test_rename_to.txt");
+    fs::write(from, b"abc").unwrap();
+
+    fs::rename(from, to).unwrap();
+    assert_eq!(fs::metadata(from).unwrap_err().kind(), ErrorKind::NotFound);
+    assert_eq!(fs::read(to).unwrap(), b"abc");
+
+    // Renaming replaces an existing file.
+    fs::write(from, b"def").unwrap();
+    fs::rename(from, to).unwrap();
+    assert_eq!(fs::read(to).unwrap(), b"def");
+
+    fs::remove_file(to).unwrap();
+}
+
+fn test_directory() {
+    let dir = Path::new("/tmp/miri_vfs_test_dir");
+    let nested = dir.join("a/b");
+
+    assert_eq!(fs::create_dir(&nested).unwrap_err().kind(), ErrorKind::NotFound);
+    fs::create_dir_all(&nested).unwrap();
+    assert!(nested.is_dir());
+    assert_eq!(fs::create_dir(&nested).unwrap_err().kind(), ErrorKind::AlreadyExists);
+
+    fs::write(dir.join("z.txt"), b"").unwrap();
+    fs::write(dir.join("m.txt"), b"").unwrap();
+    let mut names: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["a", "m.txt", "z.txt"]);
+
+    let entry = fs::read_dir(dir.join("a")).unwrap().next().unwrap().unwrap();
+    assert_eq!(entry.path(), dir.join("a/b"));
+    assert!(entry.file_type().unwrap().is_dir());
+
+    // Directories must be empty before they can be removed.
+    assert_eq!(fs::remove_dir(dir.join("a")).unwrap_err().kind(), ErrorKind::DirectoryNotEmpty);
+    assert_eq!(fs::remove_file(dir.join("a")).unwrap_err().kind(), ErrorKind::IsADirectory);
+    fs::remove_dir(&nested).unwrap();
+    fs::remove_dir(dir.join("a")).unwrap();
+    fs::remove_file(dir.join("z.txt")).unwrap();
+    fs::remove_file(dir.join("m.txt")).unwrap();
+    fs::remove_dir(dir).unwrap();
+    assert!(!dir.exists());
+}
+
+fn test_symlink() {
+    let dir = Path::new("/tmp/miri_vfs_test_symlink");
+    fs::create_dir(dir).unwrap();
+    let target = dir.join("target.txt");
+    let link = dir.join("link");
+    fs::write(&target, b"abc").unwrap();
+
+    // Relative targets are resolved against the directory of the link.
+    symlink("target.txt", &link).unwrap();
+    assert_eq!(fs::read_link(&link).unwrap(), Path::new("target.txt"));
+    assert_eq!(fs::read(&link).unwrap(), b"abc");
+    assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+    assert!(fs::metadata(&link).unwrap().is_file());
+    assert_eq!(fs::canonicalize(&link).unwrap(), target);
+    assert_eq!(fs::canonicalize(dir.join("../miri_vfs_test_symlink/./link")).unwrap(), target);
+
+    // Removing the link leaves the target alone.
+    fs::remove_file(&link).unwrap();
+    assert_eq!(fs::read(&target).unwrap(), b"abc");
+
+    // Symlink loops are detected.
+    symlink("loop", dir.join("loop")).unwrap();
+    assert_eq!(fs::read(dir.join("loop")).unwrap_err().kind(), ErrorKind::FilesystemLoop);
+
+    fs::remove_file(dir.join("loop")).unwrap();
+    fs::remove_file(&target).unwrap();
+    fs::remove_dir(dir).unwrap();
+}
+
+fn test_pread_pwrite() {
+    let path = Path::new("/tmp/miri_vfs_test_pread_pwrite.txt");
+    let file = OpenOptions::new().read(true).write(true).create(true).open(path).unwrap();
+
+    file.write_all_at(b"0123456789", 0).unwrap();
+    file.write_all_at(b"ab", 3).unwrap();
+    let mut buf = [0; 4];
+    file.read_exact_at(&mut buf, 2).unwrap();
+    assert_eq!(&buf, b"2ab5");
+    // Positioned I/O does not move the cursor.
+    let mut contents = Vec::new();
+    (&file).read_to_end(&mut contents).unwrap();
+    assert_eq!(contents, b"012ab56789");
+
+    fs::remove_file(path).unwrap();
+}
+
+fn test_file_size_limit() {
+    let path = Path::new("/tmp/miri_vfs_test_file_size_limit.txt");
+    let file = OpenOptions::new().read(true).write(true).create(true).open(path).unwrap();
+
+    // Growing a file to a size that does not fit in memory fails instead of aborting.
+    assert_eq!(file.set_len(1 << 40).unwrap_err().kind(), ErrorKind::FileTooLarge);
+    assert_eq!(file.write_at(b"x", 1 << 40).unwrap_err().kind(), ErrorKind::FileTooLarge);
+    assert_eq!(file.write_at(b"x", i64::MAX as u64).unwrap_err().kind(), ErrorKind::FileTooLarge);
+    assert_eq!(file.metadata().unwrap().len(), 0);
+
+    fs::remove_file(path).unwrap();
+}
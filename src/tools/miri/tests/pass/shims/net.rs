@@ -0,0 +1,240 @@
+//@ignore-target: windows # No socket support on Windows
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::thread;
+
+fn main() {
+    test_tcp();
+    test_tcp_threaded();
+    test_tcp_accept_synchronizes();
+    test_tcp_shutdown();
+    test_tcp_nonblocking();
+    test_tcp_options();
+    test_tcp_errors();
+    test_tcp_ipv6();
+    test_udp();
+    test_udp_connected();
+}
+
+fn test_tcp() {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let addr = listener.local_addr().unwrap();
+    assert_eq!(addr.ip(), Ipv4Addr::LOCALHOST);
+    assert_ne!(addr.port(), 0);
+
+    let mut client = TcpStream::connect(addr).unwrap();
+    let (mut server, client_addr) = listener.accept().unwrap();
+    assert_eq!(client.local_addr().unwrap(), client_addr);
+    assert_eq!(client.peer_addr().unwrap(), addr);
+    assert_eq!(server.local_addr().unwrap(), addr);
+    assert_eq!(server.peer_addr().unwrap(), client_addr);
+
+    client.write_all(b"ping").unwrap();
+    let mut buf = [0; 4];
+    server.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"ping");
+
+    server.write_all(b"pong").unwrap();
+    client.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"pong");
+
+    // Closing one end makes the other one read EOF.
+    drop(server);
+    assert_eq!(client.read(&mut buf).unwrap(), 0);
+}
+
+fn test_tcp_threaded() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    // The server blocks in `accept` and `read` until the client shows up.
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut data = Vec::new();
+        stream.read_to_end(&mut data).unwrap();
+        stream.write_all(&data).unwrap();
+    });
+
+    let mut client = TcpStream::connect(addr).unwrap();
+    // More than fits into the socket buffer, so the writer blocks until the reader catches up.
+    let data: Vec<u8> = (0..300_000u32).map(|i| i as u8).collect();
+    client.write_all(&data).unwrap();
+    client.shutdown(Shutdown::Write).unwrap();
+    let mut echo = Vec::new();
+    client.read_to_end(&mut echo).unwrap();
+    assert!(echo == data);
+
+    server.join().unwrap();
+}
+
+fn test_tcp_accept_synchronizes() {
+    static mut DATA: u32 = 0;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let client = thread::spawn(move || {
+        unsafe { DATA = 42 };
+        TcpStream::connect(addr).unwrap()
+    });
+
+    // `accept` synchronizes with the `connect`, so this is not a data race.
+    let _server = listener.accept().unwrap();
+    assert_eq!(unsafe { DATA }, 42);
+    client.join().unwrap();
+}
+
+fn test_tcp_shutdown() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+
+    client.shutdown(Shutdown::Write).unwrap();
+    let mut buf = [0; 4];
+    assert_eq!(server.read(&mut buf).unwrap(), 0);
+    assert_eq!(client.write(b"late").unwrap_err().kind(), ErrorKind::BrokenPipe);
+
+    // The other direction still works.
+    server.write_all(b"data").unwrap();
+    client.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"data");
+
+    client.shutdown(Shutdown::Read).unwrap();
+    assert_eq!(client.read(&mut buf).unwrap(), 0);
+}
+
+fn test_tcp_nonblocking() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_nonblocking(true).unwrap();
+    assert_eq!(listener.accept().unwrap_err().kind(), ErrorKind::WouldBlock);
+
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+    // Accepted sockets are blocking, regardless of the listener.
+    server.set_nonblocking(true).unwrap();
+    let mut buf = [0; 4];
+    assert_eq!(server.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+
+    client.write_all(b"abc").unwrap();
+    server.read_exact(&mut buf[..3]).unwrap();
+    assert_eq!(&buf[..3], b"abc");
+
+    // Fill up the buffer of the server.
+    client.set_nonblocking(true).unwrap();
+    let chunk = [0; 4096];
+    let mut written = 0;
+    loop {
+        match client.write(&chunk) {
+            Ok(n) => written += n,
+            Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+            Err(e) => panic!("unexpected error: {e}"),
+        }
+    }
+    assert!(written > 0);
+    server.set_nonblocking(false).unwrap();
+    let mut read = 0;
+    while read < written {
+        read += server.read(&mut [0; 4096]).unwrap();
+    }
+    assert_eq!(read, written);
+    client.write_all(&chunk[..1]).unwrap();
+}
+
+fn test_tcp_options() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+
+    assert!(!client.nodelay().unwrap());
+    client.set_nodelay(true).unwrap();
+    assert!(client.nodelay().unwrap());
+
+    client.set_ttl(23).unwrap();
+    assert_eq!(client.ttl().unwrap(), 23);
+
+    assert!(client.take_error().unwrap().is_none());
+}
+
+fn test_tcp_errors() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    // The address is still in use.
+    assert_eq!(TcpListener::bind(addr).unwrap_err().kind(), ErrorKind::AddrInUse);
+    assert_eq!(
+        TcpListener::bind((Ipv4Addr::UNSPECIFIED, addr.port())).unwrap_err().kind(),
+        ErrorKind::AddrInUse
+    );
+    // Only this host is on the network.
+    assert_eq!(TcpListener::bind("10.0.0.1:0").unwrap_err().kind(), ErrorKind::AddrNotAvailable);
+
+    // Once the listener is gone, nobody accepts connections on its port anymore.
+    drop(listener);
+    assert_eq!(TcpStream::connect(addr).unwrap_err().kind(), ErrorKind::ConnectionRefused);
+    // But it can be bound again.
+    let listener = TcpListener::bind(addr).unwrap();
+    assert_eq!(listener.local_addr().unwrap(), addr);
+}
+
+fn test_tcp_ipv6() {
+    // A listener on the unspecified address accepts connections to the loopback address.
+    let listener = TcpListener::bind((Ipv6Addr::UNSPECIFIED, 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let mut client = TcpStream::connect((Ipv6Addr::LOCALHOST, port)).unwrap();
+    let (mut server, client_addr) = listener.accept().unwrap();
+    assert_eq!(client_addr.ip(), Ipv6Addr::LOCALHOST);
+
+    client.write_all(b"v6").unwrap();
+    let mut buf = [0; 2];
+    server.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"v6");
+}
+
+fn test_udp() {
+    let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let a_addr = a.local_addr().unwrap();
+    let b_addr = b.local_addr().unwrap();
+
+    assert_eq!(a.send_to(b"hello", b_addr).unwrap(), 5);
+    assert_eq!(a.send_to(b"world!", b_addr).unwrap(), 6);
+
+    // Datagram boundaries are preserved.
+    let mut buf = [0; 16];
+    assert_eq!(b.recv_from(&mut buf).unwrap(), (5, a_addr));
+    assert_eq!(&buf[..5], b"hello");
+    // A datagram that does not fit is truncated.
+    assert_eq!(b.recv_from(&mut buf[..3]).unwrap(), (3, a_addr));
+    assert_eq!(&buf[..3], b"wor");
+
+    b.set_nonblocking(true).unwrap();
+    assert_eq!(b.recv_from(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+
+    // Datagrams to a port nobody is bound to get lost.
+    drop(b);
+    assert_eq!(a.send_to(b"lost", b_addr).unwrap(), 4);
+
+    // A socket bound to the unspecified address sends from the loopback address.
+    let c = UdpSocket::bind("0.0.0.0:0").unwrap();
+    let c_port = c.local_addr().unwrap().port();
+    c.send_to(b"hi", a_addr).unwrap();
+    let (len, from) = a.recv_from(&mut buf).unwrap();
+    assert_eq!(len, 2);
+    assert_eq!(from, SocketAddr::from((Ipv4Addr::LOCALHOST, c_port)));
+}
+
+fn test_udp_connected() {
+    let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+    a.connect(b.local_addr().unwrap()).unwrap();
+    assert_eq!(a.peer_addr().unwrap(), b.local_addr().unwrap());
+
+    // The receiver blocks until the datagram arrives.
+    let receiver = thread::spawn(move || {
+        let mut buf = [0; 8];
+        let (len, _) = b.recv_from(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"message");
+    });
+    a.send(b"message").unwrap();
+    receiver.join().unwrap();
+}
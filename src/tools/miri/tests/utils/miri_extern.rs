@@ -147,4 +147,22 @@ pub fn miri_host_to_target_path(
     /// "symbolic" alignment checks. Will fail if the pointer is not actually aligned or `align` is
     /// not a power of two. Has no effect when alignment checks are concrete (which is the default).
     pub fn miri_promise_symbolic_alignment(ptr: *const (), align: usize);
+
+    /// Miri-provided extern function to mark a release operation on the given address for the
+    /// data race detector, like TSan's `__tsan_release`. Every later `miri_acquire` of the same
+    /// address synchronizes with this point.
+    ///
+    /// This is meant for synchronization primitives Miri cannot see through, e.g. ones built on raw
+    /// syscalls or inline assembly. `ptr` must point to mutable memory; the annotation is tied to
+    /// that allocation. Has no effect when data race detection is disabled.
+    pub fn miri_release(ptr: *const ());
+
+    /// Miri-provided extern function to mark an acquire operation on the given address for the
+    /// data race detector, like TSan's `__tsan_acquire`. The current thread synchronizes with every
+    /// `miri_release` of the same address that happened before.
+    pub fn miri_acquire(ptr: *const ());
+
+    /// Combination of `miri_acquire` and `miri_release`, acting like an acquire-release
+    /// read-modify-write operation on the given address.
+    pub fn miri_release_acquire(ptr: *const ());
 }
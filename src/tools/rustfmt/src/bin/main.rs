@@ -19,7 +19,8 @@
 
 use crate::rustfmt::{
     CliOptions, Color, Config, Edition, EmitMode, FileLines, FileName,
-    FormatReportFormatterBuilder, Input, Session, StyleEdition, Verbosity, Version, load_config,
+    FormatReportFormatterBuilder, Input, Range, Session, StyleEdition, Verbosity, Version,
+    load_config,
 };
 
 const BUG_REPORT_URL: &str = "https://github.com/rust-lang/rustfmt/issues/new?labels=bug";
@@ -90,6 +91,9 @@ pub enum OperationError {
     /// supported with standard input.
     #[error("Emit mode {0} not supported with standard output.")]
     StdinBadEmit(EmitMode),
+    /// Attempt to use `--line-range` or `--byte-range` with files.
+    #[error("The `--line-range` and `--byte-range` options only work with standard input.")]
+    StdinRangeWithFiles,
 }
 
 impl From<IoError> for OperationError {
@@ -116,7 +120,7 @@ fn make_opts() -> Options {
     );
     let is_nightly = is_nightly();
     let emit_opts = if is_nightly {
-        "[files|stdout|coverage|checkstyle|json]"
+        "[files|stdout|coverage|checkstyle|json|sarif|github]"
     } else {
         "[files|stdout]"
     };
@@ -181,6 +185,20 @@ fn make_opts() -> Options {
              more detail (unstable).",
             "JSON",
         );
+        opts.optopt(
+            "",
+            "line-range",
+            "Format only the given lines of the input read from stdin. Lines are 1-based \
+             and inclusive of both end points (unstable).",
+            "START-END",
+        );
+        opts.optopt(
+            "",
+            "byte-range",
+            "Format only the lines overlapping the given byte range of the input read from \
+             stdin. The end is exclusive (unstable).",
+            "START-END",
+        );
         opts.optflag(
             "",
             "error-on-unformatted",
@@ -284,7 +302,11 @@ fn format_string(input: String, options: GetOptsOptions) -> Result<i32> {
                     .set()
                     .emit_mode(options.emit_mode.unwrap_or(EmitMode::Stdout));
             }
-            Some(EmitMode::Stdout) | Some(EmitMode::Checkstyle) | Some(EmitMode::Json) => {
+            Some(EmitMode::Stdout)
+            | Some(EmitMode::Checkstyle)
+            | Some(EmitMode::Json)
+            | Some(EmitMode::Sarif)
+            | Some(EmitMode::GitHub) => {
                 config
                     .set_cli()
                     .emit_mode(options.emit_mode.unwrap_or(EmitMode::Stdout));
@@ -310,6 +332,16 @@ fn format_string(input: String, options: GetOptsOptions) -> Result<i32> {
         }
     }
 
+    if let Some(range) = options.stdin_range {
+        if !config.file_lines().is_all() {
+            return Err(format_err!(
+                "Invalid to use `--file-lines` together with `--line-range` or `--byte-range`"
+            ));
+        }
+        let ranges = HashMap::from([(FileName::Stdin, vec![range.to_lines(&input)?])]);
+        config.set_cli().file_lines(FileLines::from_ranges(ranges));
+    }
+
     let out = &mut stdout();
     let mut session = Session::new(config, Some(out));
     format_and_emit_report(&mut session, Input::Text(input));
@@ -327,6 +359,9 @@ fn format(
     minimal_config_path: Option<String>,
     options: &GetOptsOptions,
 ) -> Result<i32> {
+    if options.stdin_range.is_some() {
+        return Err(OperationError::StdinRangeWithFiles.into());
+    }
     options.verify_file_lines(&files);
     let (config, config_path) = load_config(None, Some(options.clone()))?;
 
@@ -539,6 +574,7 @@ struct GetOptsOptions {
     style_edition: Option<StyleEdition>,
     color: Option<Color>,
     file_lines: FileLines, // Default is all lines in all files.
+    stdin_range: Option<StdinRange>,
     unstable_features: bool,
     error_on_unformatted: Option<bool>,
     print_misformatted_file_names: bool,
@@ -568,6 +604,24 @@ pub fn from_matches(matches: &Matches) -> Result<GetOptsOptions> {
                 if let Some(ref file_lines) = matches.opt_str("file-lines") {
                     options.file_lines = file_lines.parse()?;
                 }
+                if let Some(ref range) = matches.opt_str("line-range") {
+                    let (lo, hi) = parse_stdin_range(range, "--line-range")?;
+                    if lo == 0 {
+                        return Err(format_err!(
+                            "Invalid value for `--line-range`: lines are 1-based"
+                        ));
+                    }
+                    options.stdin_range = Some(StdinRange::Lines(lo, hi));
+                }
+                if let Some(ref range) = matches.opt_str("byte-range") {
+                    if options.stdin_range.is_some() {
+                        return Err(format_err!(
+                            "Invalid to use `--line-range` and `--byte-range`"
+                        ));
+                    }
+                    let (lo, hi) = parse_stdin_range(range, "--byte-range")?;
+                    options.stdin_range = Some(StdinRange::Bytes(lo, hi));
+                }
             } else {
                 let mut unstable_options = vec![];
                 if matches.opt_present("skip-children") {
@@ -579,6 +633,12 @@ pub fn from_matches(matches: &Matches) -> Result<GetOptsOptions> {
                 if matches.opt_present("file-lines") {
                     unstable_options.push("`--file-lines`");
                 }
+                if matches.opt_present("line-range") {
+                    unstable_options.push("`--line-range`");
+                }
+                if matches.opt_present("byte-range") {
+                    unstable_options.push("`--byte-range`");
+                }
                 if !unstable_options.is_empty() {
                     let s = if unstable_options.len() == 1 { "" } else { "s" };
                     return Err(format_err!(
@@ -674,6 +734,54 @@ fn verify_file_lines(&self, files: &[PathBuf]) {
     }
 }
 
+/// The part of the input read from stdin to format, given by `--line-range` or `--byte-range`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum StdinRange {
+    /// 1-based lines, inclusive of both end points.
+    Lines(usize, usize),
+    /// Byte offsets, exclusive of the end.
+    Bytes(usize, usize),
+}
+
+impl StdinRange {
+    /// Returns the lines of `input` covered by this range. Since rustfmt formats whole lines, a
+    /// byte range covers every line it overlaps.
+    fn to_lines(self, input: &str) -> Result<Range> {
+        match self {
+            StdinRange::Lines(lo, hi) => Ok(Range::new(lo, hi)),
+            StdinRange::Bytes(lo, hi) => {
+                if hi > input.len() {
+                    return Err(format_err!(
+                        "Invalid value for `--byte-range`: the input is only {} bytes long",
+                        input.len()
+                    ));
+                }
+                let line_of = |offset: usize| {
+                    input.as_bytes()[..offset]
+                        .iter()
+                        .filter(|&&b| b == b'\n')
+                        .count()
+                        + 1
+                };
+                Ok(Range::new(line_of(lo), line_of(hi.max(lo + 1) - 1)))
+            }
+        }
+    }
+}
+
+fn parse_stdin_range(range: &str, flag: &str) -> Result<(usize, usize)> {
+    let parsed = range
+        .split_once('-')
+        .and_then(|(lo, hi)| Some((lo.parse().ok()?, hi.parse().ok()?)));
+    match parsed {
+        Some((lo, hi)) if lo <= hi => Ok((lo, hi)),
+        _ => Err(format_err!(
+            "Invalid value for `{}`: expected `START-END`",
+            flag
+        )),
+    }
+}
+
 impl CliOptions for GetOptsOptions {
     fn apply_to(self, config: &mut Config) {
         if self.verbose {
@@ -778,6 +886,8 @@ fn emit_mode_from_emit_str(emit_str: &str) -> Result<EmitMode> {
         "coverage" => Ok(EmitMode::Coverage),
         "checkstyle" => Ok(EmitMode::Checkstyle),
         "json" => Ok(EmitMode::Json),
+        "sarif" => Ok(EmitMode::Sarif),
+        "github" => Ok(EmitMode::GitHub),
         _ => Err(format_err!("Invalid value for `--emit`")),
     }
 }
@@ -961,4 +1071,24 @@ fn style_edition_defaults_overridden_from_cli() {
         // `overflow_delimited_expr` is disabled by default in edition 2024.
         assert_eq!(config.overflow_delimited_expr(), false);
     }
+
+    #[test]
+    fn stdin_range_to_lines() {
+        let input = "fn a() {}\nfn b() {}\nfn c() {}\n";
+        assert_eq!(parse_stdin_range("2-3", "--line-range").unwrap(), (2, 3));
+        assert!(parse_stdin_range("3-2", "--line-range").is_err());
+        assert!(parse_stdin_range("2", "--line-range").is_err());
+
+        let range = StdinRange::Lines(2, 3).to_lines(input).unwrap();
+        assert_eq!(range, Range::new(2, 3));
+        // Bytes 12..15 lie within the second line.
+        let range = StdinRange::Bytes(12, 15).to_lines(input).unwrap();
+        assert_eq!(range, Range::new(2, 2));
+        // The newline ending the first line belongs to the first line.
+        let range = StdinRange::Bytes(9, 11).to_lines(input).unwrap();
+        assert_eq!(range, Range::new(1, 2));
+        let range = StdinRange::Bytes(10, 10).to_lines(input).unwrap();
+        assert_eq!(range, Range::new(2, 2));
+        assert!(StdinRange::Bytes(0, 31).to_lines(input).is_err());
+    }
 }
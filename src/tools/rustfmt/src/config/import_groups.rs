@@ -0,0 +1,236 @@
+//! This module contains types and functions to support user-defined import groups.
+
+use itertools::Itertools;
+use std::{fmt, str};
+
+use serde::{Deserialize, Serialize};
+use serde_json as json;
+use thiserror::Error;
+
+use crate::config::options::ImportGranularity;
+
+/// A group of imports, selected by the paths they start with.
+///
+/// A pattern is a path whose segments are separated by `::`, and matches all imports whose path
+/// starts with these segments. Within a segment, `*` matches any sequence of characters, e.g.
+/// `our_org_*` matches both `our_org_core::Foo` and `our_org_util`. A pattern consisting only of
+/// `*` matches every import, but only if it matches no other group.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(from = "RawImportGroup", into = "RawImportGroup")]
+pub struct ImportGroup {
+    pub patterns: Vec<String>,
+    /// Overrides `imports_granularity` for the imports in this group.
+    pub granularity: Option<ImportGranularity>,
+}
+
+/// An import group as written in the configuration: either a plain list of patterns, or a table
+/// that also specifies how to merge the imports of the group.
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum RawImportGroup {
+    Patterns(Vec<String>),
+    Table(ImportGroupTable),
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct ImportGroupTable {
+    patterns: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    granularity: Option<ImportGranularity>,
+}
+
+impl From<RawImportGroup> for ImportGroup {
+    fn from(raw: RawImportGroup) -> Self {
+        match raw {
+            RawImportGroup::Patterns(patterns) => ImportGroup {
+                patterns,
+                granularity: None,
+            },
+            RawImportGroup::Table(ImportGroupTable {
+                patterns,
+                granularity,
+            }) => ImportGroup {
+                patterns,
+                granularity,
+            },
+        }
+    }
+}
+
+impl From<ImportGroup> for RawImportGroup {
+    fn from(
+        ImportGroup {
+            patterns,
+            granularity,
+        }: ImportGroup,
+    ) -> Self {
+        match granularity {
+            None => RawImportGroup::Patterns(patterns),
+            granularity => RawImportGroup::Table(ImportGroupTable {
+                patterns,
+                granularity,
+            }),
+        }
+    }
+}
+
+impl ImportGroup {
+    fn is_fallback(pattern: &str) -> bool {
+        pattern == "*"
+    }
+
+    /// Whether any of the non-fallback patterns matches `path`.
+    fn matches(&self, path: &[Option<&str>]) -> bool {
+        self.patterns
+            .iter()
+            .filter(|pattern| !Self::is_fallback(pattern))
+            .any(|pattern| pattern_matches(pattern, path))
+    }
+
+    fn has_fallback(&self) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| Self::is_fallback(pattern))
+    }
+}
+
+impl fmt::Display for ImportGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}]", self.patterns.iter().format(", "))?;
+        if let Some(granularity) = self.granularity {
+            write!(f, " ({granularity})")?;
+        }
+        Ok(())
+    }
+}
+
+/// The import groups used by `group_imports = "Custom"`, in the order in which they are emitted.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ImportGroups(pub Vec<ImportGroup>);
+
+impl ImportGroups {
+    /// Returns the index of the group that an import with the given path belongs to. Nested lists
+    /// of imports are passed as `None`, and only match `*`.
+    ///
+    /// Imports are put into the first group with a matching pattern. Imports that do not match any
+    /// group belong to an implicit group after all configured groups, with index `self.0.len()`.
+    pub(crate) fn group_index(&self, path: &[Option<&str>]) -> usize {
+        self.0
+            .iter()
+            .position(|group| group.matches(path))
+            .or_else(|| self.0.iter().position(ImportGroup::has_fallback))
+            .unwrap_or(self.0.len())
+    }
+}
+
+fn pattern_matches(pattern: &str, path: &[Option<&str>]) -> bool {
+    let segments: Vec<_> = pattern.split("::").collect();
+    segments.len() <= path.len()
+        && segments
+            .iter()
+            .zip(path)
+            .all(|(&segment, name)| match name {
+                Some(name) => glob_matches(segment, name),
+                None => segment == "*",
+            })
+}
+
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => match name.strip_prefix(prefix) {
+            Some(name) => (0..=name.len())
+                .filter(|&i| name.is_char_boundary(i))
+                .any(|i| glob_matches(rest, &name[i..])),
+            None => false,
+        },
+    }
+}
+
+impl fmt::Display for ImportGroups {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.iter().format(", "))
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ImportGroupsError {
+    #[error("{0}")]
+    Json(json::Error),
+}
+
+// This impl is needed for `Config::override_value` to work for use in tests.
+impl str::FromStr for ImportGroups {
+    type Err = ImportGroupsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        json::from_str(s).map_err(ImportGroupsError::Json)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    fn group_index(groups: &ImportGroups, path: &str) -> usize {
+        let path: Vec<_> = path
+            .split("::")
+            .map(|segment| (segment != "{..}").then_some(segment))
+            .collect();
+        groups.group_index(&path)
+    }
+
+    #[test]
+    fn import_groups_from_str() {
+        let groups = ImportGroups::from_str(
+            r#"[["std", "core"], {"patterns": ["crate::*"], "granularity": "Module"}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            groups,
+            ImportGroups(vec![
+                ImportGroup {
+                    patterns: vec!["std".to_owned(), "core".to_owned()],
+                    granularity: None,
+                },
+                ImportGroup {
+                    patterns: vec!["crate::*".to_owned()],
+                    granularity: Some(ImportGranularity::Module),
+                },
+            ])
+        );
+        assert_eq!(format!("{groups}"), "[std, core], [crate::*] (Module)");
+        assert!(ImportGroups::from_str(r#"[{"patterns": [], "merge": true}]"#).is_err());
+    }
+
+    #[test]
+    fn import_groups_group_index() {
+        let groups = ImportGroups::from_str(
+            r#"[["std", "core", "alloc"], ["crate::*"], ["our_org_*"], ["*"], ["tokio"]]"#,
+        )
+        .unwrap();
+        assert_eq!(group_index(&groups, "std::io::Read"), 0);
+        assert_eq!(group_index(&groups, "alloc"), 0);
+        assert_eq!(group_index(&groups, "stdx::Foo"), 3);
+        assert_eq!(group_index(&groups, "crate::foo::Bar"), 1);
+        assert_eq!(group_index(&groups, "crate::{..}"), 1);
+        assert_eq!(group_index(&groups, "crate"), 3);
+        assert_eq!(group_index(&groups, "our_org_util::Foo"), 2);
+        assert_eq!(group_index(&groups, "our_org_"), 2);
+        // Specific patterns take precedence over the fallback, even in later groups.
+        assert_eq!(group_index(&groups, "tokio::spawn"), 4);
+        assert_eq!(group_index(&groups, "serde::Serialize"), 3);
+    }
+
+    #[test]
+    fn import_groups_implicit_group() {
+        let groups = ImportGroups::from_str(r#"[["std::*"], ["*_sys"]]"#).unwrap();
+        assert_eq!(group_index(&groups, "std::io"), 0);
+        assert_eq!(group_index(&groups, "std::{..}"), 0);
+        assert_eq!(group_index(&groups, "std"), 2);
+        assert_eq!(group_index(&groups, "libc_sys::{..}"), 1);
+        assert_eq!(group_index(&groups, "serde"), 2);
+    }
+}
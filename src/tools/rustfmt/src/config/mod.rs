@@ -10,6 +10,8 @@
 #[allow(unreachable_pub)]
 pub use crate::config::file_lines::{FileLines, FileName, Range};
 #[allow(unreachable_pub)]
+pub use crate::config::import_groups::ImportGroups;
+#[allow(unreachable_pub)]
 pub use crate::config::macro_names::MacroSelector;
 #[allow(unreachable_pub)]
 pub use crate::config::options::*;
@@ -21,6 +23,7 @@
 pub(crate) mod options;
 
 pub(crate) mod file_lines;
+pub(crate) mod import_groups;
 #[allow(unreachable_pub)]
 pub(crate) mod lists;
 pub(crate) mod macro_names;
@@ -76,8 +79,13 @@
         "Format the metavariable matching patterns in macros";
     format_macro_bodies: FormatMacroBodies, false,
         "Format the bodies of declarative macro definitions";
+    format_macro_repetitions: FormatMacroRepetitions, false,
+        "Format the bodies of declarative macro definitions containing repetitions";
     skip_macro_invocations: SkipMacroInvocations, false,
         "Skip formatting the bodies of macros invoked with the following names.";
+    format_macro_invocations: FormatMacroInvocations, false,
+        "Format the arguments of macros invoked with the following names as items or \
+        statements.";
     hex_literal_case: HexLiteralCaseConfig, false, "Format hexadecimal integer literals";
 
     // Single line expressions and items
@@ -95,6 +103,8 @@
         "Merge or split imports to the provided granularity";
     group_imports: GroupImportsTacticConfig, false,
         "Controls the strategy for how imports are grouped together";
+    import_groups: ImportGroupsConfig, false,
+        "Patterns selecting the import groups for `group_imports = Custom`";
     merge_imports: MergeImports, false, "(deprecated: use imports_granularity instead)";
 
     // Ordering
@@ -737,7 +747,9 @@ fn test_dump_default_config() {
 format_strings = false
 format_macro_matchers = false
 format_macro_bodies = true
+format_macro_repetitions = false
 skip_macro_invocations = []
+format_macro_invocations = []
 hex_literal_case = "Preserve"
 empty_item_single_line = true
 struct_lit_single_line = true
@@ -747,6 +759,7 @@ fn test_dump_default_config() {
 imports_layout = "Mixed"
 imports_granularity = "Preserve"
 group_imports = "Preserve"
+import_groups = []
 reorder_imports = true
 reorder_modules = true
 reorder_impl_items = false
@@ -827,7 +840,9 @@ fn test_dump_style_edition_2024_config() {
 format_strings = false
 format_macro_matchers = false
 format_macro_bodies = true
+format_macro_repetitions = false
 skip_macro_invocations = []
+format_macro_invocations = []
 hex_literal_case = "Preserve"
 empty_item_single_line = true
 struct_lit_single_line = true
@@ -837,6 +852,7 @@ fn test_dump_style_edition_2024_config() {
 imports_layout = "Mixed"
 imports_granularity = "Preserve"
 group_imports = "Preserve"
+import_groups = []
 reorder_imports = true
 reorder_modules = true
 reorder_impl_items = false
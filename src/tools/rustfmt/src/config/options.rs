@@ -13,6 +13,7 @@
 
 use crate::config::Config;
 use crate::config::file_lines::FileLines;
+use crate::config::import_groups::ImportGroups;
 use crate::config::lists::*;
 use crate::config::macro_names::MacroSelectors;
 
@@ -118,6 +119,8 @@ pub enum GroupImportsTactic {
     StdExternalCrate,
     /// Discard existing groups, and create a single group for everything
     One,
+    /// Discard existing groups, and create the groups configured in `import_groups`
+    Custom,
 }
 
 #[config_type]
@@ -168,6 +171,12 @@ pub enum EmitMode {
     /// Writes the resulting diffs in a JSON format. Returns an empty array
     /// `[]` if there were no diffs.
     Json,
+    /// Writes the resulting diffs as a SARIF log, with fixes replacing the
+    /// misformatted lines.
+    Sarif,
+    /// Writes the resulting diffs as GitHub Actions workflow commands, which
+    /// show up as annotations on the misformatted lines.
+    GitHub,
     /// Output the changed lines (for internal value only)
     ModifiedLines,
     /// Checks if a diff can be generated. If so, rustfmt outputs a diff and
@@ -595,7 +604,9 @@ macro_rules! config_option_with_style_edition_default {
     FormatStrings, bool, _ => false;
     FormatMacroMatchers, bool, _ => false;
     FormatMacroBodies, bool, _ => true;
+    FormatMacroRepetitions, bool, _ => false;
     SkipMacroInvocations, MacroSelectors, _ => MacroSelectors::default();
+    FormatMacroInvocations, MacroSelectors, _ => MacroSelectors::default();
     HexLiteralCaseConfig, HexLiteralCase, _ => HexLiteralCase::Preserve;
 
     // Single line expressions and items
@@ -609,6 +620,7 @@ macro_rules! config_option_with_style_edition_default {
     ImportsLayout, ListTactic, _ => ListTactic::Mixed;
     ImportsGranularityConfig, ImportGranularity, _ => ImportGranularity::Preserve;
     GroupImportsTacticConfig, GroupImportsTactic, _ => GroupImportsTactic::Preserve;
+    ImportGroupsConfig, ImportGroups, _ => ImportGroups::default();
     MergeImports, bool, _ => false;
 
     // Ordering
@@ -2,10 +2,13 @@
 pub(crate) use self::diff::*;
 pub(crate) use self::files::*;
 pub(crate) use self::files_with_backup::*;
+pub(crate) use self::github::*;
 pub(crate) use self::json::*;
 pub(crate) use self::modified_lines::*;
+pub(crate) use self::sarif::*;
 pub(crate) use self::stdout::*;
 use crate::FileName;
+use crate::rustfmt_diff::{DiffLine, Mismatch};
 use std::io::{self, Write};
 use std::path::Path;
 
@@ -13,8 +16,10 @@
 mod diff;
 mod files;
 mod files_with_backup;
+mod github;
 mod json;
 mod modified_lines;
+mod sarif;
 mod stdout;
 
 pub(crate) struct FormattedFile<'a> {
@@ -50,3 +55,51 @@ fn ensure_real_path(filename: &FileName) -> &Path {
         _ => panic!("cannot format `{filename}` and emit to files"),
     }
 }
+
+/// A range of misformatted lines of the original text, along with the formatted lines to
+/// replace them with.
+struct MisformattedLines {
+    /// The first line of the range, starting from 1.
+    begin_line: u32,
+    /// The number of original lines to replace, which is 0 if lines are only inserted.
+    original_lines: u32,
+    /// The formatted lines, each followed by a newline.
+    expected: String,
+}
+
+impl MisformattedLines {
+    /// The last line of the range, which is `begin_line` if lines are only inserted.
+    fn end_line(&self) -> u32 {
+        self.begin_line + self.original_lines.max(1) - 1
+    }
+
+    fn message(&self) -> String {
+        if self.expected.is_empty() {
+            String::from("Remove these lines")
+        } else {
+            format!("Should be formatted as:\n{}", self.expected)
+        }
+    }
+}
+
+impl From<Mismatch> for MisformattedLines {
+    fn from(mismatch: Mismatch) -> Self {
+        let mut original_lines = 0;
+        let mut expected = String::new();
+        for line in mismatch.lines {
+            match line {
+                DiffLine::Expected(msg) => {
+                    expected.push_str(&msg);
+                    expected.push('\n');
+                }
+                DiffLine::Resulting(_) => original_lines += 1,
+                DiffLine::Context(_) => continue,
+            }
+        }
+        MisformattedLines {
+            begin_line: mismatch.line_number_orig,
+            original_lines,
+            expected,
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use super::*;
+use crate::rustfmt_diff::{Mismatch, make_diff};
+use std::fmt;
+
+/// Emits a GitHub Actions workflow command (`::warning ...`) for every mismatch, so that the
+/// mismatches show up as annotations on the misformatted lines.
+#[derive(Debug, Default)]
+pub(crate) struct GitHubEmitter;
+
+impl Emitter for GitHubEmitter {
+    fn emit_formatted_file(
+        &mut self,
+        output: &mut dyn Write,
+        FormattedFile {
+            filename,
+            original_text,
+            formatted_text,
+        }: FormattedFile<'_>,
+    ) -> Result<EmitterResult, io::Error> {
+        const CONTEXT_SIZE: usize = 0;
+        let diff = make_diff(original_text, formatted_text, CONTEXT_SIZE);
+        let has_diff = !diff.is_empty();
+        output_github_annotations(output, filename, diff)?;
+        Ok(EmitterResult { has_diff })
+    }
+}
+
+fn output_github_annotations<T>(
+    mut writer: T,
+    filename: &FileName,
+    diff: Vec<Mismatch>,
+) -> Result<(), io::Error>
+where
+    T: Write,
+{
+    for mismatch in diff {
+        let lines = MisformattedLines::from(mismatch);
+        writeln!(
+            writer,
+            "::warning file={},line={},endLine={},title={}::{}",
+            Escaped(&filename.to_string(), true),
+            lines.begin_line,
+            lines.end_line(),
+            Escaped("rustfmt", true),
+            Escaped(&lines.message(), false),
+        )?;
+    }
+    Ok(())
+}
+
+/// Escapes a string for use in a workflow command. Property values need more characters escaped
+/// than the message.
+struct Escaped<'a>(&'a str, bool);
+
+impl fmt::Display for Escaped<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Escaped(s, is_property) = *self;
+        for c in s.chars() {
+            match c {
+                '%' => write!(formatter, "%25")?,
+                '\r' => write!(formatter, "%0D")?,
+                '\n' => write!(formatter, "%0A")?,
+                ':' if is_property => write!(formatter, "%3A")?,
+                ',' if is_property => write!(formatter, "%2C")?,
+                _ => write!(formatter, "{c}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn emits_nothing_on_file_with_no_mismatches() {
+        let mut writer = Vec::new();
+        let result = GitHubEmitter
+            .emit_formatted_file(
+                &mut writer,
+                FormattedFile {
+                    filename: &FileName::Real(PathBuf::from("src/lib.rs")),
+                    original_text: "fn empty() {}\n",
+                    formatted_text: "fn empty() {}\n",
+                },
+            )
+            .unwrap();
+        assert_eq!(result.has_diff, false);
+        assert!(writer.is_empty());
+    }
+
+    #[test]
+    fn emits_annotation_per_mismatch() {
+        let original = [
+            "fn main() {",
+            "println!(\"100%\");",
+            "}",
+            "fn foo(a: u8,b: u8) {}",
+        ];
+        let formatted = [
+            "fn main() {",
+            "    println!(\"100%\");",
+            "}",
+            "fn foo(a: u8, b: u8) {}",
+        ];
+        let mut writer = Vec::new();
+        let result = GitHubEmitter
+            .emit_formatted_file(
+                &mut writer,
+                FormattedFile {
+                    filename: &FileName::Real(PathBuf::from("src/a,b.rs")),
+                    original_text: &original.join("\n"),
+                    formatted_text: &formatted.join("\n"),
+                },
+            )
+            .unwrap();
+        assert_eq!(result.has_diff, true);
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            [
+                "::warning file=src/a%2Cb.rs,line=2,endLine=2,title=rustfmt::\
+                 Should be formatted as:%0A    println!(\"100%25\");%0A\n",
+                "::warning file=src/a%2Cb.rs,line=4,endLine=4,title=rustfmt::\
+                 Should be formatted as:%0Afn foo(a: u8, b: u8) {}%0A\n",
+            ]
+            .join(""),
+        );
+    }
+}
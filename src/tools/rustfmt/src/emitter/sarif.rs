@@ -0,0 +1,303 @@
+use super::*;
+use crate::rustfmt_diff::{Mismatch, make_diff};
+use serde::Serialize;
+use serde_json::to_string as to_json_string;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+const RULE_ID: &str = "rustfmt";
+
+/// Emits a [SARIF](https://sarifweb.azurewebsites.net/) log with one result per mismatch. Each
+/// result comes with a fix replacing the misformatted lines with the formatted ones.
+#[derive(Debug, Default)]
+pub(crate) struct SarifEmitter {
+    results: Vec<SarifResult>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Log<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: [Run<'a>; 1],
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Run<'a> {
+    tool: Tool,
+    results: &'a [SarifResult],
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    information_uri: &'static str,
+    rules: [Rule; 1],
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    id: &'static str,
+    short_description: Message,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: &'static str,
+    level: &'static str,
+    message: Message,
+    locations: [Location; 1],
+    fixes: [Fix; 1],
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_line: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_line: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    char_offset: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    char_length: Option<usize>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Fix {
+    description: Message,
+    artifact_changes: [ArtifactChange; 1],
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactChange {
+    artifact_location: ArtifactLocation,
+    replacements: [Replacement; 1],
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Replacement {
+    deleted_region: Region,
+    inserted_content: Message,
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_footer(&self, output: &mut dyn Write) -> Result<(), io::Error> {
+        let log = Log {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: [Run {
+                tool: Tool {
+                    driver: Driver {
+                        name: "rustfmt",
+                        information_uri: "https://github.com/rust-lang/rustfmt",
+                        rules: [Rule {
+                            id: RULE_ID,
+                            short_description: Message {
+                                text: String::from("Code is not formatted"),
+                            },
+                        }],
+                    },
+                },
+                results: &self.results,
+            }],
+        };
+        writeln!(output, "{}", &to_json_string(&log)?)
+    }
+
+    fn emit_formatted_file(
+        &mut self,
+        _output: &mut dyn Write,
+        FormattedFile {
+            filename,
+            original_text,
+            formatted_text,
+        }: FormattedFile<'_>,
+    ) -> Result<EmitterResult, io::Error> {
+        const CONTEXT_SIZE: usize = 0;
+        let diff = make_diff(original_text, formatted_text, CONTEXT_SIZE);
+        let has_diff = !diff.is_empty();
+
+        if has_diff {
+            self.add_misformatted_file(filename, original_text, diff);
+        }
+
+        Ok(EmitterResult { has_diff })
+    }
+}
+
+impl SarifEmitter {
+    fn add_misformatted_file(
+        &mut self,
+        filename: &FileName,
+        original_text: &str,
+        diff: Vec<Mismatch>,
+    ) {
+        let uri = match filename {
+            FileName::Real(path) => path.to_string_lossy().replace('\\', "/"),
+            FileName::Stdin => String::from("stdin"),
+        };
+        // The character offset at which each line of the original text starts, plus the
+        // length of the text, so that the end of the last line can be looked up as well.
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(original_text.split_inclusive('\n').scan(0, |offset, line| {
+                *offset += line.chars().count();
+                Some(*offset)
+            }))
+            .collect();
+        let char_offset =
+            |line: u32| line_starts[(line as usize).saturating_sub(1).min(line_starts.len() - 1)];
+
+        for mismatch in diff {
+            let lines = MisformattedLines::from(mismatch);
+            let deleted_offset = char_offset(lines.begin_line);
+            let deleted_length =
+                char_offset(lines.begin_line + lines.original_lines) - deleted_offset;
+            self.results.push(SarifResult {
+                rule_id: RULE_ID,
+                level: "warning",
+                message: Message {
+                    text: lines.message(),
+                },
+                locations: [Location {
+                    physical_location: PhysicalLocation {
+                        artifact_location: ArtifactLocation { uri: uri.clone() },
+                        region: Region {
+                            start_line: Some(lines.begin_line),
+                            end_line: Some(lines.end_line()),
+                            ..Region::default()
+                        },
+                    },
+                }],
+                fixes: [Fix {
+                    description: Message {
+                        text: String::from("Format the code"),
+                    },
+                    artifact_changes: [ArtifactChange {
+                        artifact_location: ArtifactLocation { uri: uri.clone() },
+                        replacements: [Replacement {
+                            deleted_region: Region {
+                                char_offset: Some(deleted_offset),
+                                char_length: Some(deleted_length),
+                                ..Region::default()
+                            },
+                            inserted_content: Message {
+                                text: lines.expected,
+                            },
+                        }],
+                    }],
+                }],
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use std::path::PathBuf;
+
+    fn emit(files: &[(&str, &[&str], &[&str])]) -> (bool, Value) {
+        let mut writer = Vec::new();
+        let mut emitter = SarifEmitter::default();
+        let _ = emitter.emit_header(&mut writer);
+        let mut has_diff = false;
+        for (file_name, original, formatted) in files {
+            let result = emitter
+                .emit_formatted_file(
+                    &mut writer,
+                    FormattedFile {
+                        filename: &FileName::Real(PathBuf::from(file_name)),
+                        original_text: &original.join("\n"),
+                        formatted_text: &formatted.join("\n"),
+                    },
+                )
+                .unwrap();
+            has_diff |= result.has_diff;
+        }
+        let _ = emitter.emit_footer(&mut writer);
+        (has_diff, serde_json::from_slice(&writer).unwrap())
+    }
+
+    #[test]
+    fn emits_no_results_on_no_diffs() {
+        let (has_diff, log) = emit(&[("src/lib.rs", &["fn empty() {}"], &["fn empty() {}"])]);
+        assert_eq!(has_diff, false);
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(log["runs"][0]["tool"]["driver"]["name"], "rustfmt");
+        assert_eq!(log["runs"][0]["results"], Value::Array(vec![]));
+    }
+
+    #[test]
+    fn emits_results_with_fixes() {
+        let original = ["fn main() {", "println!(\"Hello, world!\");", "}"];
+        let formatted = ["fn main() {", "    println!(\"Hello, world!\");", "}"];
+        let (has_diff, log) = emit(&[("src/bin.rs", &original, &formatted)]);
+        assert_eq!(has_diff, true);
+
+        let results = log["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/bin.rs");
+        assert_eq!(location["region"]["startLine"], 2);
+        assert_eq!(location["region"]["endLine"], 2);
+
+        let change = &results[0]["fixes"][0]["artifactChanges"][0];
+        assert_eq!(change["artifactLocation"]["uri"], "src/bin.rs");
+        let replacement = &change["replacements"][0];
+        assert_eq!(replacement["deletedRegion"]["charOffset"], 12);
+        assert_eq!(replacement["deletedRegion"]["charLength"], 27);
+        assert_eq!(
+            replacement["insertedContent"]["text"],
+            "    println!(\"Hello, world!\");\n"
+        );
+    }
+
+    #[test]
+    fn emits_insertions_as_empty_regions() {
+        let original = ["use a;", "use b;"];
+        let formatted = ["use a;", "", "use b;"];
+        let (_, log) = emit(&[("src/lib.rs", &original, &formatted)]);
+
+        let result = &log["runs"][0]["results"][0];
+        let replacement = &result["fixes"][0]["artifactChanges"][0]["replacements"][0];
+        assert_eq!(replacement["deletedRegion"]["charOffset"], 7);
+        assert_eq!(replacement["deletedRegion"]["charLength"], 0);
+        assert_eq!(replacement["insertedContent"]["text"], "\n");
+    }
+}
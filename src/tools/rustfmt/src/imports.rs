@@ -727,6 +727,46 @@ fn flatten(self, import_granularity: ImportGranularity) -> Vec<UseTree> {
         }
     }
 
+    /// Splits a use tree consisting only of a list, like `use {a, b};` or `use ::{a, b};`, into
+    /// one use tree per item of the list. Other use trees are returned as they are.
+    pub(crate) fn split_top_level_list(self) -> Vec<UseTree> {
+        let root_len = match self.path.first() {
+            Some(UseSegment {
+                kind: UseSegmentKind::Ident(name, None),
+                ..
+            }) if name.is_empty() => 1,
+            _ => 0,
+        };
+        if self.path.len() != root_len + 1 || self.contains_comment() {
+            return vec![self];
+        }
+        let UseSegmentKind::List(list) = &self.path[root_len].kind else {
+            return vec![self];
+        };
+        list.iter()
+            .map(|nested_use_tree| {
+                let mut path = nested_use_tree.path.clone();
+                // Like `from_ast`, keep a leading `::` as part of the first identifier.
+                if root_len > 0 {
+                    match path.first_mut() {
+                        Some(UseSegment {
+                            kind: UseSegmentKind::Ident(name, _),
+                            ..
+                        }) => name.insert_str(0, "::"),
+                        _ => path.insert(0, self.path[0].clone()),
+                    }
+                }
+                UseTree {
+                    path,
+                    span: self.span,
+                    list_item: None,
+                    visibility: self.visibility.clone(),
+                    attrs: self.attrs.clone(),
+                }
+            })
+            .collect()
+    }
+
     fn merge(&mut self, other: &UseTree, merge_by: SharedPrefix) {
         let mut prefix = 0;
         for (a, b) in self.path.iter().zip(other.path.iter()) {
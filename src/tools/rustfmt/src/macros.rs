@@ -19,16 +19,17 @@
 use rustc_span::{BytePos, DUMMY_SP, Ident, Span, Symbol};
 use tracing::debug;
 
+use crate::FormattedSnippet;
 use crate::comment::{
     CharClasses, FindUncommented, FullCodeCharKind, LineClasses, contains_comment,
 };
-use crate::config::StyleEdition;
 use crate::config::lists::*;
+use crate::config::{Config, MacroSelector, StyleEdition};
 use crate::expr::{RhsAssignKind, rewrite_array, rewrite_assign_rhs};
 use crate::lists::{ListFormatting, itemize_list, write_list};
 use crate::overflow;
 use crate::parse::macros::lazy_static::parse_lazy_static;
-use crate::parse::macros::{ParsedMacroArgs, parse_expr, parse_macro_args};
+use crate::parse::macros::{ParsedMacroArgs, count_statements, parse_expr, parse_macro_args};
 use crate::rewrite::{
     MacroErrorKind, Rewrite, RewriteContext, RewriteError, RewriteErrorExt, RewriteResult,
 };
@@ -37,7 +38,7 @@
 use crate::spanned::Spanned;
 use crate::utils::{
     NodeIdExt, filtered_str_fits, format_visibility, indent_next_line, is_empty_line, mk_sp,
-    remove_trailing_white_spaces, rewrite_ident, trim_left_preserve_layout,
+    remove_trailing_white_spaces, rewrite_ident, trim_left_preserve_layout, wrap_str,
 };
 use crate::visitor::FmtVisitor;
 
@@ -240,6 +241,14 @@ fn rewrite_macro_inner(
         }
     }
 
+    if is_format_macro_invocation(context, mac) {
+        if let Some(rw) =
+            rewrite_macro_with_statements(context, mac, &macro_name, shape, style, position)
+        {
+            return Ok(rw);
+        }
+    }
+
     let ParsedMacroArgs {
         args: arg_vec,
         vec_with_semi,
@@ -346,6 +355,65 @@ fn rewrite_macro_inner(
     }
 }
 
+// Whether the arguments of `mac` should be formatted as items or statements because it is
+// listed in `format_macro_invocations`.
+fn is_format_macro_invocation(context: &RewriteContext<'_>, mac: &ast::MacCall) -> bool {
+    let name = context.snippet(mac.path.span);
+    context
+        .config
+        .format_macro_invocations()
+        .0
+        .iter()
+        .any(|selector| match selector {
+            MacroSelector::Name(macro_name) => macro_name.to_string() == name,
+            MacroSelector::All => true,
+        })
+}
+
+// Formats the arguments of a macro invocation like the contents of a block. Returns `None`
+// if they are neither valid items nor valid statements, or if they are a single statement in
+// parentheses or brackets, like `println!("{}", x)`, which is better formatted as an expression.
+fn rewrite_macro_with_statements(
+    context: &RewriteContext<'_>,
+    mac: &ast::MacCall,
+    macro_name: &str,
+    shape: Shape,
+    style: Delimiter,
+    position: MacroPosition,
+) -> Option<String> {
+    let (opener, closer) = match style {
+        Delimiter::Parenthesis => ("(", ")"),
+        Delimiter::Bracket => ("[", "]"),
+        Delimiter::Brace => (" {", "}"),
+        _ => return None,
+    };
+    if style != Delimiter::Brace && count_statements(context, mac.args.tokens.clone())? < 2 {
+        return None;
+    }
+    let trailing_semicolon = match style {
+        Delimiter::Parenthesis | Delimiter::Bracket if position == MacroPosition::Item => ";",
+        _ => "",
+    };
+
+    let dspan = mac.args.dspan;
+    let snippet = context.snippet(mk_sp(dspan.open.hi(), dspan.close.lo()));
+
+    let mut config = context.config.clone();
+    config.set().show_parse_errors(false);
+    let body_indent = shape.indent.block_indent(&config);
+    let new_width = config.max_width().checked_sub(body_indent.width())?;
+    config.set().max_width(new_width);
+
+    // Items are formatted as a whole file, which would keep the leading newline.
+    let formatted = format_items_or_statements(snippet.trim_start(), &mut config)?;
+    let body = indent_formatted_snippet(&formatted, body_indent, &config);
+    let result = format!(
+        "{macro_name}{opener}\n{body}{}{closer}{trailing_semicolon}",
+        shape.indent.to_string(context.config)
+    );
+    wrap_str(result, context.config.max_width(), shape)
+}
+
 fn handle_vec_semi(
     context: &RewriteContext<'_>,
     shape: Shape,
@@ -520,14 +588,23 @@ fn register_metavariable(
 // Replaces `$foo` with `zfoo`. We must check for name overlap to ensure we
 // aren't causing problems.
 // This should also work for escaped `$` variables, where we leave earlier `$`s.
-fn replace_names(input: &str) -> Option<(String, HashMap<String, String>)> {
+// Repetitions (`$(...) sep op`) cannot be parsed as regular Rust code at all. If
+// `replace_repetitions` is set, we replace each of them with a placeholder macro call
+// (`z0! {}`), which is valid in any position, and put the repetition back verbatim after
+// formatting. Otherwise we give up.
+fn replace_names(
+    input: &str,
+    replace_repetitions: bool,
+) -> Option<(String, HashMap<String, String>, Vec<(String, String)>)> {
     // Each substitution will require five or six extra bytes.
     let mut result = String::with_capacity(input.len() + 64);
     let mut substs = HashMap::new();
+    let mut repetitions = Vec::new();
     let mut dollar_count = 0;
     let mut cur_name = String::new();
 
-    for (kind, c) in CharClasses::new(input.chars()) {
+    let mut chars = CharClasses::new(input.chars());
+    while let Some((kind, c)) = chars.next() {
         if kind != FullCodeCharKind::Normal {
             result.push(c);
         } else if c == '$' {
@@ -542,8 +619,14 @@ fn replace_names(input: &str) -> Option<(String, HashMap<String, String>)> {
             dollar_count = 0;
             cur_name.clear();
         } else if c == '(' && cur_name.is_empty() {
-            // FIXME: Support macro def with repeat.
-            return None;
+            if !replace_repetitions || dollar_count != 1 {
+                return None;
+            }
+            let repetition = take_repetition(&mut chars)?;
+            let placeholder = repetition_placeholder(repetitions.len(), &repetition);
+            result.push_str(&placeholder);
+            repetitions.push((placeholder, repetition));
+            dollar_count = 0;
         } else if c.is_alphanumeric() || c == '_' {
             cur_name.push(c);
         }
@@ -553,9 +636,91 @@ fn replace_names(input: &str) -> Option<(String, HashMap<String, String>)> {
         register_metavariable(&mut substs, &mut result, &cur_name, dollar_count);
     }
 
-    debug!("replace_names `{}` {:?}", result, substs);
+    debug!("replace_names `{}` {:?} {:?}", result, substs, repetitions);
+
+    Some((result, substs, repetitions))
+}
+
+// Consumes the rest of a repetition whose `$(` has already been consumed, i.e. its
+// contents, the closing parenthesis, the optional separator and the Kleene operator.
+// Returns the whole repetition including the `$(`.
+fn take_repetition(chars: &mut impl Iterator<Item = (FullCodeCharKind, char)>) -> Option<String> {
+    let mut repetition = String::from("$(");
+    let mut depth = 1;
+    while depth > 0 {
+        let (kind, c) = chars.next()?;
+        if kind == FullCodeCharKind::Normal {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+        }
+        repetition.push(c);
+    }
+
+    let is_op = |c: char| matches!(c, '*' | '+' | '?');
+    let mut separator_len = 0;
+    loop {
+        let (kind, c) = chars.next()?;
+        if kind != FullCodeCharKind::Normal {
+            return None;
+        }
+        repetition.push(c);
+        if is_op(c) {
+            return Some(repetition);
+        }
+        if c.is_whitespace() {
+            continue;
+        }
+        // Separators are single tokens, which are at most three characters long
+        // (e.g. `..=`). We do not support identifiers or literals as separators.
+        separator_len += 1;
+        if separator_len > 3 || c.is_alphanumeric() || "$()[]{}\"'".contains(c) {
+            return None;
+        }
+    }
+}
+
+// Returns a placeholder for the `index`th repetition, padded to the length of the first
+// line of the repetition so that formatting decisions depending on width are unaffected.
+fn repetition_placeholder(index: usize, repetition: &str) -> String {
+    const SUFFIX: &str = "! {}";
+    let name = format!("z{index}");
+    let width = repetition.lines().next().map_or(0, str::len);
+    let padding = width.saturating_sub(name.len() + SUFFIX.len());
+    format!("{name}{}{SUFFIX}", "_".repeat(padding))
+}
+
+// Replaces the placeholder in `body` with the original repetition. A repetition spanning
+// multiple lines is expected to have its closing parenthesis at the start of its last line;
+// its lines are re-indented to match the line the placeholder ended up on.
+fn restore_repetition(body: &str, placeholder: &str, repetition: &str) -> Option<String> {
+    let pos = body.find(placeholder)?;
+    let line_start = body[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let new_indent: String = body[line_start..]
+        .chars()
+        .take_while(|c| c.is_whitespace())
+        .collect();
+
+    let mut lines = repetition.lines();
+    let mut restored = String::with_capacity(repetition.len());
+    restored.push_str(lines.next()?);
+    let last_line = repetition.lines().last()?;
+    let old_indent = &last_line[..last_line.len() - last_line.trim_start().len()];
+    if repetition.contains('\n') && !last_line.trim_start().starts_with(')') {
+        return None;
+    }
+    for line in lines {
+        restored.push('\n');
+        if line.trim().is_empty() {
+            continue;
+        }
+        restored.push_str(&new_indent);
+        restored.push_str(line.strip_prefix(old_indent)?);
+    }
 
-    Some((result, substs))
+    Some(body.replacen(placeholder, &restored, 1))
 }
 
 #[derive(Debug, Clone)]
@@ -1318,8 +1483,9 @@ fn rewrite(
         // `$$`). We'll try and format like an AST node, but we'll substitute
         // variables for new names with the same length first.
 
-        let (body_str, substs) =
-            replace_names(old_body).macro_error(MacroErrorKind::ReplaceMacroVariable, self.span)?;
+        let (body_str, substs, repetitions) =
+            replace_names(old_body, context.config.format_macro_repetitions())
+                .macro_error(MacroErrorKind::ReplaceMacroVariable, self.span)?;
 
         let mut config = context.config.clone();
         config.set().show_parse_errors(false);
@@ -1334,23 +1500,8 @@ fn rewrite(
         let new_width = config.max_width() - body_indent.width();
         config.set().max_width(new_width);
 
-        // First try to format as items, then as statements.
-        let new_body_snippet = match crate::format_snippet(&body_str, &config, true) {
-            Some(new_body) => new_body,
-            None => {
-                let new_width = new_width + config.tab_spaces();
-                config.set().max_width(new_width);
-                match crate::format_code_block(&body_str, &config, true) {
-                    Some(new_body) => new_body,
-                    None => {
-                        return Err(RewriteError::MacroFailure {
-                            kind: MacroErrorKind::Unknown,
-                            span: self.span,
-                        });
-                    }
-                }
-            }
-        };
+        let new_body_snippet = format_items_or_statements(&body_str, &mut config)
+            .macro_error(MacroErrorKind::Unknown, self.span)?;
 
         if !filtered_str_fits(&new_body_snippet.snippet, config.max_width(), shape) {
             return Err(RewriteError::ExceedsMaxWidth {
@@ -1360,22 +1511,7 @@ fn rewrite(
         }
 
         // Indent the body since it is in a block.
-        let indent_str = body_indent.to_string(&config);
-        let mut new_body = LineClasses::new(new_body_snippet.snippet.trim_end())
-            .enumerate()
-            .fold(
-                (String::new(), true),
-                |(mut s, need_indent), (i, (kind, ref l))| {
-                    if !is_empty_line(l)
-                        && need_indent
-                        && !new_body_snippet.is_line_non_formatted(i + 1)
-                    {
-                        s += &indent_str;
-                    }
-                    (s + l + "\n", indent_next_line(kind, l, &config))
-                },
-            )
-            .0;
+        let mut new_body = indent_formatted_snippet(&new_body_snippet, body_indent, &config);
 
         // Undo our replacement of macro variables.
         // FIXME: this could be *much* more efficient.
@@ -1389,6 +1525,20 @@ fn rewrite(
             }
             new_body = new_body.replace(new, old);
         }
+        for (placeholder, repetition) in &repetitions {
+            if old_body.contains(placeholder) {
+                debug!(
+                    "rewrite_macro_def: bailing matching placeholder: `{}`",
+                    placeholder
+                );
+                return Err(RewriteError::MacroFailure {
+                    kind: MacroErrorKind::ReplaceMacroVariable,
+                    span: self.span,
+                });
+            }
+            new_body = restore_repetition(&new_body, placeholder, repetition)
+                .macro_error(MacroErrorKind::ReplaceMacroVariable, self.span)?;
+        }
 
         if has_block_body {
             result += new_body.trim();
@@ -1404,6 +1554,35 @@ fn rewrite(
     }
 }
 
+// First try to format as items, then as statements. Statements get wrapped in a function
+// before formatting, so they are given one more level of indentation to work with.
+fn format_items_or_statements(snippet: &str, config: &mut Config) -> Option<FormattedSnippet> {
+    if let Some(formatted) = crate::format_snippet(snippet, config, true) {
+        return Some(formatted);
+    }
+    let new_width = config.max_width() + config.tab_spaces();
+    config.set().max_width(new_width);
+    crate::format_code_block(snippet, config, true)
+}
+
+// Indents every line of a formatted snippet that is not empty and was not left unformatted.
+// Every line of the result ends with a newline.
+fn indent_formatted_snippet(snippet: &FormattedSnippet, indent: Indent, config: &Config) -> String {
+    let indent_str = indent.to_string(config);
+    LineClasses::new(snippet.snippet.trim_end())
+        .enumerate()
+        .fold(
+            (String::new(), true),
+            |(mut s, need_indent), (i, (kind, ref l))| {
+                if !is_empty_line(l) && need_indent && !snippet.is_line_non_formatted(i + 1) {
+                    s += &indent_str;
+                }
+                (s + l + "\n", indent_next_line(kind, l, config))
+            },
+        )
+        .0
+}
+
 /// Format `lazy_static!` from <https://crates.io/crates/lazy_static>.
 ///
 /// # Expected syntax
@@ -1515,3 +1694,46 @@ fn rewrite_macro_with_items(
     result.push_str(trailing_semicolon);
     Ok(result)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn replace_names_with_repetitions() {
+        assert!(replace_names("vec![$($x),*]; $y", false).is_none());
+
+        let (body, substs, repetitions) = replace_names("vec![$($x),*]; $y", true).unwrap();
+        assert_eq!(body, "vec![z0_! {}]; zy");
+        assert_eq!(substs.get("$y").map(String::as_str), Some("zy"));
+        assert_eq!(
+            repetitions,
+            vec![("z0_! {}".to_owned(), "$($x),*".to_owned())]
+        );
+
+        // Identifiers cannot be used as separators.
+        assert!(replace_names("$($x) and *", true).is_none());
+        // Escaped dollars cannot start a repetition.
+        assert!(replace_names("$$($x)*", true).is_none());
+
+        // As in rustc, a Kleene operator directly after the parenthesis is never a separator.
+        let (body, _, repetitions) = replace_names("$($x)++ 1", true).unwrap();
+        assert_eq!(body, "z0! {}+ 1");
+        assert_eq!(repetitions[0].1, "$($x)+");
+        let (_, _, repetitions) = replace_names("$($x)-*", true).unwrap();
+        assert_eq!(repetitions[0].1, "$($x)-*");
+    }
+
+    #[test]
+    fn restore_multi_line_repetition() {
+        let repetition = "$(\n        impl A for $t {}\n\n    )*";
+        assert_eq!(
+            restore_repetition("        z0! {}\n", "z0! {}", repetition).unwrap(),
+            "        $(\n            impl A for $t {}\n\n        )*\n"
+        );
+
+        // The closing parenthesis must start the last line.
+        let repetition = "$(\n        impl A for $t {})*";
+        assert!(restore_repetition("z0! {}", "z0! {}", repetition).is_none());
+    }
+}
@@ -161,6 +161,32 @@ pub(crate) fn parse_macro_args(
     })
 }
 
+// Returns the number of statements, including items, that `tokens` consist of, or `None` if they
+// cannot be parsed as statements.
+pub(crate) fn count_statements(context: &RewriteContext<'_>, tokens: TokenStream) -> Option<usize> {
+    let mut parser = build_parser(context, tokens);
+    let mut count = 0;
+    while parser.token.kind != TokenKind::Eof {
+        match parser.parse_stmt_without_recovery(true, ForceCollect::No, false) {
+            Ok(Some(_)) if parser.psess.dcx().has_errors().is_none() => count += 1,
+            Ok(_) => {
+                parser.psess.dcx().reset_err_count();
+                return None;
+            }
+            Err(e) => {
+                e.cancel();
+                parser.psess.dcx().reset_err_count();
+                return None;
+            }
+        }
+        // Statements can be separated by any number of semicolons.
+        while parser.token.kind == TokenKind::Semi {
+            parser.bump();
+        }
+    }
+    Some(count)
+}
+
 pub(crate) fn parse_expr(
     context: &RewriteContext<'_>,
     tokens: TokenStream,
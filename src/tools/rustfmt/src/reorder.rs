@@ -11,7 +11,7 @@
 use rustc_ast::{ast, attr};
 use rustc_span::{Span, symbol::sym};
 
-use crate::config::{Config, GroupImportsTactic};
+use crate::config::{Config, GroupImportsTactic, ImportGranularity, ImportGroups};
 use crate::imports::{UseSegmentKind, UseTree, normalize_use_trees_with_granularity};
 use crate::items::{is_mod_decl, rewrite_extern_crate, rewrite_mod};
 use crate::lists::{ListFormatting, ListItem, itemize_list, write_list};
@@ -110,16 +110,24 @@ fn rewrite_reorderable_or_regroupable_items(
             for (item, list_item) in normalized_items.iter_mut().zip(list_items) {
                 item.list_item = Some(list_item.clone());
             }
-            normalized_items = normalize_use_trees_with_granularity(
-                normalized_items,
-                context.config.imports_granularity(),
-            );
-
+            let granularity = context.config.imports_granularity();
             let mut regrouped_items = match context.config.group_imports() {
                 GroupImportsTactic::Preserve | GroupImportsTactic::One => {
-                    vec![normalized_items]
+                    vec![normalize_use_trees_with_granularity(
+                        normalized_items,
+                        granularity,
+                    )]
                 }
-                GroupImportsTactic::StdExternalCrate => group_imports(normalized_items),
+                GroupImportsTactic::StdExternalCrate => group_imports(
+                    normalize_use_trees_with_granularity(normalized_items, granularity),
+                ),
+                // Imports are grouped before merging, so that each group can be merged with its
+                // own granularity.
+                GroupImportsTactic::Custom => group_imports_custom(
+                    normalized_items,
+                    &context.config.import_groups(),
+                    granularity,
+                ),
             };
 
             if context.config.reorder_imports() {
@@ -208,6 +216,53 @@ fn group_imports(uts: Vec<UseTree>) -> Vec<Vec<UseTree>> {
     vec![std_imports, external_imports, local_imports]
 }
 
+/// Divides imports into the groups configured in `import_groups`, followed by a
+/// group for the imports not matching any of them. Lists at the top level of an
+/// import, like `use {a, b};`, are split up so that each item lands in its own
+/// group. Merges the imports of each group with the group's granularity, or
+/// `default_granularity` if it has none.
+fn group_imports_custom(
+    uts: Vec<UseTree>,
+    import_groups: &ImportGroups,
+    default_granularity: ImportGranularity,
+) -> Vec<Vec<UseTree>> {
+    let mut groups = vec![Vec::new(); import_groups.0.len() + 1];
+
+    for ut in uts.into_iter().flat_map(UseTree::split_top_level_list) {
+        let path: Vec<_> = ut
+            .path
+            .iter()
+            // A leading `::` is kept as an empty segment, or as part of the first segment.
+            .filter(
+                |segment| !matches!(&segment.kind, UseSegmentKind::Ident(id, _) if id.is_empty()),
+            )
+            .map(|segment| match &segment.kind {
+                UseSegmentKind::Ident(id, _) => Some(id.trim_start_matches("::")),
+                UseSegmentKind::Slf(_) => Some("self"),
+                UseSegmentKind::Super(_) => Some("super"),
+                UseSegmentKind::Crate(_) => Some("crate"),
+                UseSegmentKind::Glob => Some("*"),
+                UseSegmentKind::List(_) => None,
+            })
+            .collect();
+        let index = import_groups.group_index(&path);
+        groups[index].push(ut);
+    }
+
+    groups
+        .into_iter()
+        .enumerate()
+        .map(|(index, uts)| {
+            let granularity = import_groups
+                .0
+                .get(index)
+                .and_then(|group| group.granularity)
+                .unwrap_or(default_granularity);
+            normalize_use_trees_with_granularity(uts, granularity)
+        })
+        .collect()
+}
+
 /// A simplified version of `ast::ItemKind`.
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 enum ReorderableItemKind {
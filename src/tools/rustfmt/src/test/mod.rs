@@ -9,7 +9,7 @@
 use std::str::{Chars, FromStr};
 use std::thread;
 
-use crate::config::{Color, Config, EmitMode, FileName, NewlineStyle};
+use crate::config::{Color, Config, EmitMode, FileName, NewlineStyle, Verbosity};
 use crate::formatting::{ReportedErrors, SourceFile};
 use crate::rustfmt_diff::{DiffLine, Mismatch, ModifiedChunk, OutputWriter, make_diff, print_diff};
 use crate::source_file;
@@ -525,6 +525,30 @@ fn stdin_works_with_modified_lines() {
     assert_eq!(buf, output.as_bytes());
 }
 
+/// Ensures that only the given lines are formatted when the input is read from `stdin`, as with
+/// `--line-range`. Useful for editors formatting only part of an unsaved buffer.
+#[test]
+fn stdin_works_with_file_lines() {
+    init_log();
+    let input = "fn  a() {}\nfn  b() {}\nfn  c() {}\n";
+    let output = "fn  a() {}\nfn b() {}\nfn  c() {}\n";
+
+    let mut config = Config::default();
+    config.set().newline_style(NewlineStyle::Unix);
+    config.set().emit_mode(EmitMode::Stdout);
+    config.set().verbose(Verbosity::Quiet);
+    config
+        .set()
+        .file_lines(r#"[{"file":"stdin","range":[2,2]}]"#.parse().unwrap());
+    let mut buf: Vec<u8> = vec![];
+    {
+        let mut session = Session::new(config, Some(&mut buf));
+        session.format(Input::Text(input.to_owned())).unwrap();
+        assert!(session.has_no_errors());
+    }
+    assert_eq!(buf, output.as_bytes());
+}
+
 /// Ensures that `EmitMode::Json` works with input from `stdin`.
 #[test]
 fn stdin_works_with_json() {
@@ -573,6 +597,46 @@ fn stdin_disable_all_formatting_test() {
     assert_eq!(input, String::from_utf8(output.stdout).unwrap());
 }
 
+// Formats `input` read from stdin, passing `args` to rustfmt.
+fn format_stdin(input: &str, args: &[&str]) -> String {
+    let mut child = Command::new(rustfmt().to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .args(args)
+        .spawn()
+        .expect("failed to execute child");
+
+    {
+        let stdin = child.stdin.as_mut().expect("failed to get stdin");
+        stdin
+            .write_all(input.as_bytes())
+            .expect("failed to write stdin");
+    }
+
+    let output = child.wait_with_output().expect("failed to wait on child");
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[nightly_only_test]
+#[test]
+fn stdin_range_formats_only_given_lines() {
+    init_log();
+    let input = "fn a( ) {}\nfn b( ) {}\nfn c( ) {}\n";
+    let args = ["--unstable-features", "--config=newline_style=Unix"];
+
+    let output = format_stdin(input, &[&args[..], &["--line-range=2-3"]].concat());
+    assert_eq!(output, "fn a( ) {}\nfn b() {}\nfn c() {}\n");
+
+    // Byte 12 lies within the second line, and a byte range covers every line it overlaps.
+    let output = format_stdin(input, &[&args[..], &["--byte-range=12-13"]].concat());
+    assert_eq!(output, "fn a( ) {}\nfn b() {}\nfn c( ) {}\n");
+    // The newline ending the first line belongs to the first line.
+    let output = format_stdin(input, &[&args[..], &["--byte-range=10-12"]].concat());
+    assert_eq!(output, "fn a() {}\nfn b() {}\nfn c( ) {}\n");
+}
+
 #[test]
 fn stdin_generated_files_issue_5172() {
     init_log();
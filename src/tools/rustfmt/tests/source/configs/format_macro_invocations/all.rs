@@ -0,0 +1,13 @@
+// rustfmt-format_macro_invocations: ["*"]
+
+fn main() {
+    println!("hi");
+    assert!(x);
+    dbg!(  x);
+    println!("a very long format string that does not fit on one line {} {}", first_argument, second_argument);
+    foo!(let x=1; let y=x+1;);
+    bar! {
+        if x {y} else {z}
+    }
+    baz!(not valid as statements => x);
+}
@@ -0,0 +1,20 @@
+// rustfmt-format_macro_invocations: ["lorem"]
+
+lorem! {
+    struct   Foo;
+    fn bar( ) -> u32 { 1 }
+}
+
+fn main() {
+    lorem! {
+        let x=1;
+        println!("{}",x);
+    }
+    lorem!(let   y = 2; foo(y));
+    lorem!(foo(  1,2 ));
+    lorem![x+1];
+    ipsum! {
+        let x=1;
+    }
+    println!("{}",x);
+}
@@ -0,0 +1,28 @@
+// rustfmt-format_macro_repetitions: true
+
+macro_rules! print_all {
+    ($($x:expr),*) => {
+        let   values = [$($x),*];
+        for v in values { println!("{}", v); }
+    };
+}
+
+macro_rules! sum {
+    ($($x:expr)++) => {
+        let   total = add!($($x),+);
+        if total>0 { total } else { 0 }
+    };
+}
+
+macro_rules! stmts {
+    ($($s:stmt);*) => {
+        $($s;)*
+        let   done=true;
+    };
+}
+
+macro_rules! infix {
+    ($($x:expr),+) => {
+        let   total = 0 $(+$x)*;
+    };
+}
@@ -0,0 +1,15 @@
+// rustfmt-group_imports: Custom
+// rustfmt-import_groups: [["std","core","alloc"],{"patterns":["crate::*"],"granularity":"Module"},["our_org_*"],["*"]]
+// rustfmt-edition: 2018
+use our_org_util::Bar;
+use serde::Serialize;
+use crate::foo::{a, b};
+use std::io;
+use our_org_core::Foo;
+use core::fmt;
+use crate::foo::c;
+use our_org_core::Baz;
+use ::std::fmt::Write;
+use ::serde_json;
+use {alloc::vec::Vec, our_org_core::Qux};
+use ::{core::mem, tokio::spawn};
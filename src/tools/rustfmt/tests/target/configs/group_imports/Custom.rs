@@ -0,0 +1,19 @@
+// rustfmt-group_imports: Custom
+// rustfmt-import_groups: [["std","core","alloc"],{"patterns":["crate::*"],"granularity":"Module"},["our_org_*"],["*"]]
+// rustfmt-edition: 2018
+use ::core::mem;
+use ::std::fmt::Write;
+use alloc::vec::Vec;
+use core::fmt;
+use std::io;
+
+use crate::foo::{a, b, c};
+
+use our_org_core::Baz;
+use our_org_core::Foo;
+use our_org_core::Qux;
+use our_org_util::Bar;
+
+use ::serde_json;
+use ::tokio::spawn;
+use serde::Serialize;